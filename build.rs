@@ -0,0 +1,22 @@
+// 构建脚本
+//
+// assets/fonts/NotoSansSC-Subset.otf 存在时把它编译进程序（见 src/locale.rs），没有安装 CJK 字体的
+// 系统也能显示中文。字体文件的来源和裁剪方法见 assets/fonts/README.md；缺少字体文件时照常构建，
+// 只是中文界面依赖系统安装的 CJK 字体，构建时给出警告。
+
+use std::path::Path;
+
+const FONT_DIR: &str = "assets/fonts";
+const EMBEDDED_FONT: &str = "assets/fonts/NotoSansSC-Subset.otf";
+
+fn main() {
+    println!("cargo::rustc-check-cfg=cfg(embedded_font)");
+    println!("cargo::rerun-if-changed=build.rs");
+    // 监视目录而不是文件，字体文件放进来或删掉时都会重新检查
+    println!("cargo::rerun-if-changed={}", FONT_DIR);
+    if Path::new(EMBEDDED_FONT).is_file() {
+        println!("cargo::rustc-cfg=embedded_font");
+    } else {
+        println!("cargo::warning=缺少 {}，中文界面将依赖系统安装的 CJK 字体", EMBEDDED_FONT);
+    }
+}
@@ -0,0 +1,120 @@
+// 程序的运行状态
+//
+// 以前用「是否正在点击」和「是否正在捕捉坐标」两个互不相关的标志表示当前在做什么，
+// 可能出现执行中又开始捕捉坐标、停止后旧的执行线程还没退出就开始新一次执行之类的组合。
+// 现在只有一个状态，界面线程和执行线程共享：界面负责开始捕捉、开始执行和请求停止，
+// 执行线程在倒计时结束、因电量或 CPU 保护暂停和恢复、退出时更新。只有空闲时才能开始新的操作。
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::events::{self, Event};
+use crate::i18n::t;
+
+/// 程序当前在做什么
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AppState {
+    #[default]
+    Idle,
+    /// 等待用户点击鼠标按钮捕捉坐标
+    Picking,
+    /// 已开始执行，还在开始前的倒计时中
+    Countdown,
+    Running,
+    /// 电量或 CPU 保护暂停了执行，条件恢复后自动继续
+    Paused,
+    /// 已请求停止，等待执行线程退出
+    Stopping,
+}
+
+impl AppState {
+    /// 状态栏中显示的名称
+    pub fn label(self) -> &'static str {
+        match self {
+            AppState::Idle => t!("空闲"),
+            AppState::Picking => t!("捕捉坐标中"),
+            AppState::Countdown => t!("倒计时"),
+            AppState::Running => t!("正在执行"),
+            AppState::Paused => t!("已暂停"),
+            AppState::Stopping => t!("正在停止"),
+        }
+    }
+
+    /// 执行线程应当继续：倒计时、执行中或暂停中
+    pub fn is_running(self) -> bool {
+        matches!(self, AppState::Countdown | AppState::Running | AppState::Paused)
+    }
+}
+
+/// 界面线程和执行线程共享的状态
+#[derive(Debug, Clone, Default)]
+pub struct SharedState(Arc<Mutex<AppState>>);
+
+impl SharedState {
+    /// 以指定状态创建
+    pub fn new(state: AppState) -> Self {
+        Self(Arc::new(Mutex::new(state)))
+    }
+
+    // panic 时锁可能已中毒，状态仍然要能读写，否则停不下来
+    fn lock(&self) -> std::sync::MutexGuard<'_, AppState> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 当前状态
+    pub fn get(&self) -> AppState {
+        *self.lock()
+    }
+
+    /// 切换状态，状态有变化时广播 `Event::State`
+    pub fn set(&self, state: AppState) {
+        let mut current = self.lock();
+        if *current != state {
+            *current = state;
+            events::emit(Event::State { state });
+        }
+    }
+
+    /// 执行线程是否应当继续，见 `AppState::is_running`
+    pub fn is_running(&self) -> bool {
+        self.get().is_running()
+    }
+
+    /// 当前为 `from` 时切换到 `to` 并返回 true，否则不变。
+    /// 另一个线程可能刚刚改过状态，先读再写会把它覆盖掉
+    pub fn transition(&self, from: AppState, to: AppState) -> bool {
+        let mut state = self.lock();
+        if *state != from {
+            return false;
+        }
+        *state = to;
+        events::emit(Event::State { state: to });
+        true
+    }
+
+    /// 请求停止执行，没有在执行时返回 false
+    pub fn stop(&self) -> bool {
+        let mut state = self.lock();
+        if !state.is_running() {
+            return false;
+        }
+        *state = AppState::Stopping;
+        events::emit(Event::State { state: AppState::Stopping });
+        true
+    }
+
+    /// 在执行线程中等待倒计时结束，然后切换到执行中；期间被停止时返回 false
+    pub fn wait_countdown(&self, duration: Duration) -> bool {
+        let until = Instant::now() + duration;
+        while Instant::now() < until {
+            if !self.is_running() {
+                return false;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        self.transition(AppState::Countdown, AppState::Running);
+        self.is_running()
+    }
+}
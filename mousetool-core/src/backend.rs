@@ -0,0 +1,516 @@
+// 注入输入的后端
+//
+// 不同的程序接受的模拟输入方式不同：有的游戏只认扫描码，有的程序忽略 XTest，
+// Wayland 下只有内核层面的虚拟设备才能到达原生窗口。这里把注入抽象成 MouseBackend，
+// 默认使用 enigo，另外按平台提供：
+// - Windows：直接调用 SendInput，按键以扫描码发送，移动按整个虚拟桌面换算
+// - Linux：直接使用 X11 的 XTest 扩展，只使用现有的键盘映射，不像 enigo 那样临时改键
+// - Windows（可选功能 interception）：Interception 内核驱动，见 interception.rs
+// - Linux：uinput 虚拟设备，见 uinput.rs
+// 所有后端只负责注入，读取光标位置和按钮状态仍然通过 device_query。
+
+use enigo::{Axis, Button, Coordinate, Direction, Enigo, Key, Keyboard, Mouse};
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::t;
+
+/// 注入输入的后端。`MouseController` 把移动、按钮、按键和滚动交给当前的后端
+pub trait MouseBackend: Send {
+    /// 移动到全局坐标
+    fn move_to(&mut self, x: i32, y: i32) -> Result<(), String>;
+    /// 按下或松开鼠标按钮
+    fn button(&mut self, button: Button, pressed: bool) -> Result<(), String>;
+    /// 按下或松开按键
+    fn key(&mut self, key: Key, pressed: bool) -> Result<(), String>;
+    /// 垂直滚动，正数向下
+    fn scroll(&mut self, amount: i32) -> Result<(), String>;
+    /// 后端自己记录的光标全局坐标。真实的后端返回 None，由控制器从系统读取
+    fn position(&self) -> Option<(i32, i32)> {
+        None
+    }
+}
+
+/// 可选择的输入后端，保存在设置中
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Backend {
+    // 旧版本保存为 Default
+    #[default]
+    #[serde(alias = "Default")]
+    Enigo,
+    SendInput,
+    XTest,
+    Uinput,
+    Interception,
+}
+
+// Interception 后端按同样的方式换算坐标和按键
+#[cfg(all(target_os = "windows", feature = "interception"))]
+pub use platform::{key_vk, scan_code, virtual_desktop_position};
+
+impl Backend {
+    /// 当前平台可以选择的后端
+    pub fn available() -> &'static [Backend] {
+        platform::AVAILABLE
+    }
+
+    /// 设置中显示的名称
+    pub fn label(self) -> &'static str {
+        match self {
+            Backend::Enigo => t!("enigo（默认）"),
+            Backend::SendInput => t!("SendInput 扫描码"),
+            Backend::XTest => "XTest",
+            Backend::Uinput => "uinput",
+            Backend::Interception => t!("Interception 驱动"),
+        }
+    }
+
+    /// 设置中显示的说明
+    pub fn description(self) -> &'static str {
+        match self {
+            Backend::Enigo => t!("Windows 使用 SendInput 虚拟键码，macOS 使用 CGEvent，Linux 使用 XTest（必要时临时修改键盘映射）"),
+            Backend::SendInput => t!("直接调用 SendInput，按键以扫描码发送，通过 DirectInput 读取键盘的游戏也能收到"),
+            Backend::XTest => t!("直接使用 XTest 扩展，只使用现有的键盘映射，键盘布局里没有的字符无法输入"),
+            Backend::Uinput => t!("uinput 通过内核创建虚拟设备，在 Wayland 和忽略 XTest 的游戏中同样有效"),
+            Backend::Interception => t!("通过 Interception 内核驱动注入，输入和真实设备发出的一样，可以绕过过滤 SendInput 的反作弊"),
+        }
+    }
+
+    /// 创建后端。`desktop` 是所有显示器合起来的区域：左上角的全局坐标和宽高。
+    /// enigo 由鼠标控制器自己持有，这里不创建
+    pub fn create(self, desktop: (i32, i32, i32, i32)) -> Result<Box<dyn MouseBackend>, String> {
+        if !Self::available().contains(&self) || self == Backend::Enigo {
+            return Err(t!("当前平台不支持 {} 后端", self.label()));
+        }
+        platform::create(self, desktop)
+    }
+}
+
+impl MouseBackend for Enigo {
+    fn move_to(&mut self, x: i32, y: i32) -> Result<(), String> {
+        // enigo 在 Windows 上按主显示器换算绝对坐标，主显示器以外的位置改按整个虚拟桌面注入
+        #[cfg(target_os = "windows")]
+        {
+            let (width, height) = self.main_display().map_err(|e| e.to_string())?;
+            if !(0..width).contains(&x) || !(0..height).contains(&y) {
+                return platform::move_virtual_desktop(x, y);
+            }
+        }
+        self.move_mouse(x, y, Coordinate::Abs).map_err(|e| e.to_string())
+    }
+
+    fn button(&mut self, button: Button, pressed: bool) -> Result<(), String> {
+        let direction = if pressed { Direction::Press } else { Direction::Release };
+        Mouse::button(self, button, direction).map_err(|e| e.to_string())
+    }
+
+    fn key(&mut self, key: Key, pressed: bool) -> Result<(), String> {
+        let direction = if pressed { Direction::Press } else { Direction::Release };
+        Keyboard::key(self, key, direction).map_err(|e| e.to_string())
+    }
+
+    fn scroll(&mut self, amount: i32) -> Result<(), String> {
+        Mouse::scroll(self, amount, Axis::Vertical).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use enigo::{Button, Key};
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{
+        INPUT, INPUT_0, INPUT_KEYBOARD, INPUT_MOUSE, KEYBDINPUT, KEYEVENTF_EXTENDEDKEY, KEYEVENTF_KEYUP,
+        KEYEVENTF_SCANCODE, KEYEVENTF_UNICODE, MAPVK_VK_TO_VSC, MOUSEEVENTF_ABSOLUTE, MOUSEEVENTF_LEFTDOWN,
+        MOUSEEVENTF_LEFTUP, MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP, MOUSEEVENTF_MOVE, MOUSEEVENTF_RIGHTDOWN,
+        MOUSEEVENTF_RIGHTUP, MOUSEEVENTF_VIRTUALDESK, MOUSEEVENTF_WHEEL, MOUSEEVENTF_XDOWN, MOUSEEVENTF_XUP, MOUSEINPUT,
+        MapVirtualKeyW, SendInput, VIRTUAL_KEY, VK_BACK, VK_DELETE, VK_DOWN, VK_END, VK_ESCAPE, VK_F1, VK_HOME,
+        VK_LCONTROL, VK_LEFT, VK_LMENU, VK_LSHIFT, VK_NEXT, VK_PRIOR, VK_RETURN, VK_RIGHT, VK_SPACE, VK_TAB, VK_UP,
+        VkKeyScanW,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        GetSystemMetrics, SM_CXVIRTUALSCREEN, SM_CYVIRTUALSCREEN, SM_XVIRTUALSCREEN, SM_YVIRTUALSCREEN,
+    };
+
+    use super::{Backend, MouseBackend};
+    use crate::i18n::t;
+
+    #[cfg(not(feature = "interception"))]
+    pub const AVAILABLE: &[Backend] = &[Backend::Enigo, Backend::SendInput];
+    #[cfg(feature = "interception")]
+    pub const AVAILABLE: &[Backend] = &[Backend::Enigo, Backend::SendInput, Backend::Interception];
+
+    // 滚轮一格的 mouseData
+    const WHEEL_DELTA: i32 = 120;
+    // 侧键在 mouseData 中的编号
+    const XBUTTON1: u32 = 1;
+    const XBUTTON2: u32 = 2;
+
+    pub fn create(backend: Backend, _desktop: (i32, i32, i32, i32)) -> Result<Box<dyn MouseBackend>, String> {
+        match backend {
+            #[cfg(feature = "interception")]
+            Backend::Interception => Ok(Box::new(crate::interception::Device::new()?)),
+            _ => Ok(Box::new(SendInputBackend)),
+        }
+    }
+
+    fn send(inputs: &[INPUT]) -> Result<(), String> {
+        let sent = unsafe { SendInput(inputs.len() as u32, inputs.as_ptr(), std::mem::size_of::<INPUT>() as i32) };
+        if sent as usize != inputs.len() {
+            return Err(t!("SendInput 失败: {}", std::io::Error::last_os_error()));
+        }
+        Ok(())
+    }
+
+    fn mouse_input(dx: i32, dy: i32, data: u32, flags: u32) -> INPUT {
+        INPUT {
+            r#type: INPUT_MOUSE,
+            Anonymous: INPUT_0 {
+                mi: MOUSEINPUT { dx, dy, mouseData: data, dwFlags: flags, time: 0, dwExtraInfo: 0 },
+            },
+        }
+    }
+
+    fn key_input(vk: VIRTUAL_KEY, scan: u16, flags: u32) -> INPUT {
+        INPUT {
+            r#type: INPUT_KEYBOARD,
+            Anonymous: INPUT_0 {
+                ki: KEYBDINPUT { wVk: vk, wScan: scan, dwFlags: flags, time: 0, dwExtraInfo: 0 },
+            },
+        }
+    }
+
+    /// 全局坐标换算成整个虚拟桌面上 0 到 65535 的绝对坐标
+    pub fn virtual_desktop_position(x: i32, y: i32) -> (i32, i32) {
+        let (left, top, width, height) = unsafe {
+            (
+                GetSystemMetrics(SM_XVIRTUALSCREEN),
+                GetSystemMetrics(SM_YVIRTUALSCREEN),
+                GetSystemMetrics(SM_CXVIRTUALSCREEN).max(2) as i64 - 1,
+                GetSystemMetrics(SM_CYVIRTUALSCREEN).max(2) as i64 - 1,
+            )
+        };
+        // 0 到宽高减一映射到 0 到 65535，加上一半用于四舍五入
+        let dx = ((x - left) as i64 * 65535 + width / 2) / width;
+        let dy = ((y - top) as i64 * 65535 + height / 2) / height;
+        (dx as i32, dy as i32)
+    }
+
+    /// 按整个虚拟桌面换算绝对坐标后注入移动，可以到达任意显示器
+    pub fn move_virtual_desktop(x: i32, y: i32) -> Result<(), String> {
+        let (dx, dy) = virtual_desktop_position(x, y);
+        send(&[mouse_input(dx, dy, 0, MOUSEEVENTF_MOVE | MOUSEEVENTF_ABSOLUTE | MOUSEEVENTF_VIRTUALDESK)])
+    }
+
+    struct SendInputBackend;
+
+    impl MouseBackend for SendInputBackend {
+        fn move_to(&mut self, x: i32, y: i32) -> Result<(), String> {
+            move_virtual_desktop(x, y)
+        }
+
+        fn button(&mut self, button: Button, pressed: bool) -> Result<(), String> {
+            let (down, up, data) = match button {
+                Button::Left => (MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP, 0),
+                Button::Right => (MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP, 0),
+                Button::Middle => (MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP, 0),
+                Button::Back => (MOUSEEVENTF_XDOWN, MOUSEEVENTF_XUP, XBUTTON1),
+                Button::Forward => (MOUSEEVENTF_XDOWN, MOUSEEVENTF_XUP, XBUTTON2),
+                _ => return Err(t!("{} 后端不支持按钮 {}", "SendInput", format!("{:?}", button))),
+            };
+            send(&[mouse_input(0, 0, data, if pressed { down } else { up })])
+        }
+
+        fn key(&mut self, key: Key, pressed: bool) -> Result<(), String> {
+            let up = if pressed { 0 } else { KEYEVENTF_KEYUP };
+            let (vk, shift) = match (key_vk(key), key) {
+                (Some(found), _) => found,
+                // 当前布局里没有的字符只能以 Unicode 字符发送
+                (None, Key::Unicode(c)) => {
+                    let mut units = [0u16; 2];
+                    let inputs: Vec<INPUT> = c
+                        .encode_utf16(&mut units)
+                        .iter()
+                        .map(|&unit| key_input(0, unit, KEYEVENTF_UNICODE | up))
+                        .collect();
+                    return send(&inputs);
+                }
+                (None, other) => return Err(t!("{} 后端不支持按键 {}", "SendInput", format!("{:?}", other))),
+            };
+            let scan = |vk: VIRTUAL_KEY| {
+                let (scan, extended) = scan_code(vk);
+                let extended = if extended { KEYEVENTF_EXTENDEDKEY } else { 0 };
+                key_input(vk, scan, KEYEVENTF_SCANCODE | extended | up)
+            };
+            // 需要 Shift 的字符在按下前按住 Shift，松开后再松开
+            match (shift, pressed) {
+                (true, true) => send(&[scan(VK_LSHIFT), scan(vk)]),
+                (true, false) => send(&[scan(vk), scan(VK_LSHIFT)]),
+                (false, _) => send(&[scan(vk)]),
+            }
+        }
+
+        fn scroll(&mut self, amount: i32) -> Result<(), String> {
+            // 正数的 mouseData 向上滚动
+            send(&[mouse_input(0, 0, (-amount * WHEEL_DELTA) as u32, MOUSEEVENTF_WHEEL)])
+        }
+    }
+
+    /// 按键对应的虚拟键，以及是否需要 Shift；当前布局里没有的字符返回 None
+    pub fn key_vk(key: Key) -> Option<(VIRTUAL_KEY, bool)> {
+        match key {
+            Key::Unicode(c) => char_vk(c),
+            Key::Other(vk) => Some((vk as VIRTUAL_KEY, false)),
+            other => named_vk(other).map(|vk| (vk, false)),
+        }
+    }
+
+    /// 虚拟键在当前布局下的扫描码，以及是否是带 E0 前缀的扩展键
+    pub fn scan_code(vk: VIRTUAL_KEY) -> (u16, bool) {
+        (unsafe { MapVirtualKeyW(vk as u32, MAPVK_VK_TO_VSC) } as u16, is_extended(vk))
+    }
+
+    fn named_vk(key: Key) -> Option<VIRTUAL_KEY> {
+        let vk = match key {
+            Key::Escape => VK_ESCAPE,
+            Key::Backspace => VK_BACK,
+            Key::Tab => VK_TAB,
+            Key::Return => VK_RETURN,
+            Key::Space => VK_SPACE,
+            // 扫描码区分左右，统一使用左侧的修饰键
+            Key::Shift => VK_LSHIFT,
+            Key::Control => VK_LCONTROL,
+            Key::Alt => VK_LMENU,
+            Key::Home => VK_HOME,
+            Key::End => VK_END,
+            Key::PageUp => VK_PRIOR,
+            Key::PageDown => VK_NEXT,
+            Key::Delete => VK_DELETE,
+            Key::UpArrow => VK_UP,
+            Key::DownArrow => VK_DOWN,
+            Key::LeftArrow => VK_LEFT,
+            Key::RightArrow => VK_RIGHT,
+            Key::F1 => VK_F1,
+            Key::F2 => VK_F1 + 1,
+            Key::F3 => VK_F1 + 2,
+            Key::F4 => VK_F1 + 3,
+            Key::F5 => VK_F1 + 4,
+            Key::F6 => VK_F1 + 5,
+            Key::F7 => VK_F1 + 6,
+            Key::F8 => VK_F1 + 7,
+            Key::F9 => VK_F1 + 8,
+            Key::F10 => VK_F1 + 9,
+            Key::F11 => VK_F1 + 10,
+            Key::F12 => VK_F1 + 11,
+            _ => return None,
+        };
+        Some(vk)
+    }
+
+    // 当前键盘布局下字符所在的键，以及是否需要 Shift；需要 Ctrl 或 Alt 的字符按不在布局里处理
+    fn char_vk(c: char) -> Option<(VIRTUAL_KEY, bool)> {
+        let mut units = [0u16; 2];
+        let [unit] = c.encode_utf16(&mut units) else {
+            return None;
+        };
+        let result = unsafe { VkKeyScanW(*unit) };
+        if result == -1 {
+            return None;
+        }
+        let (vk, modifiers) = ((result & 0xff) as VIRTUAL_KEY, (result >> 8) & 0xff);
+        match modifiers {
+            0 => Some((vk, false)),
+            1 => Some((vk, true)),
+            _ => None,
+        }
+    }
+
+    // 这些键的扫描码带有 E0 前缀，需要加上扩展键标志
+    fn is_extended(vk: VIRTUAL_KEY) -> bool {
+        matches!(vk, VK_HOME | VK_END | VK_PRIOR | VK_NEXT | VK_DELETE | VK_UP | VK_DOWN | VK_LEFT | VK_RIGHT)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{Backend, MouseBackend};
+
+    pub const AVAILABLE: &[Backend] = &[Backend::Enigo];
+
+    pub fn create(_backend: Backend, _desktop: (i32, i32, i32, i32)) -> Result<Box<dyn MouseBackend>, String> {
+        unreachable!("macOS 只有 enigo 后端")
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    use enigo::{Button, Key};
+    use x11rb::connection::{Connection, RequestConnection};
+    use x11rb::protocol::xproto::{
+        BUTTON_PRESS_EVENT, BUTTON_RELEASE_EVENT, ConnectionExt as _, KEY_PRESS_EVENT, KEY_RELEASE_EVENT,
+        MOTION_NOTIFY_EVENT, Window,
+    };
+    use x11rb::protocol::xtest::{self, ConnectionExt as _};
+    use x11rb::rust_connection::RustConnection;
+
+    use super::{Backend, MouseBackend};
+    use crate::i18n::t;
+
+    pub const AVAILABLE: &[Backend] = &[Backend::Enigo, Backend::XTest, Backend::Uinput];
+
+    // X11 的 keysym
+    const XK_SHIFT_L: u32 = 0xffe1;
+
+    pub fn create(backend: Backend, desktop: (i32, i32, i32, i32)) -> Result<Box<dyn MouseBackend>, String> {
+        match backend {
+            Backend::Uinput => Ok(Box::new(crate::uinput::Device::new(desktop)?)),
+            _ => Ok(Box::new(XTestBackend::new()?)),
+        }
+    }
+
+    struct XTestBackend {
+        connection: RustConnection,
+        root: Window,
+        min_keycode: u8,
+        keysyms_per_keycode: u8,
+        /// 连接时的键盘映射，按键码顺序排列
+        keysyms: Vec<u32>,
+    }
+
+    impl XTestBackend {
+        fn new() -> Result<Self, String> {
+            let (connection, screen) = x11rb::connect(None).map_err(|e| t!("无法连接 X 服务器: {}", e))?;
+            let present = connection
+                .extension_information(xtest::X11_EXTENSION_NAME)
+                .map_err(|e| t!("无法连接 X 服务器: {}", e))?
+                .is_some();
+            if !present {
+                return Err(t!("X 服务器没有 XTest 扩展").to_string());
+            }
+            let setup = connection.setup();
+            let root = setup.roots[screen].root;
+            let (min_keycode, max_keycode) = (setup.min_keycode, setup.max_keycode);
+            let mapping = connection
+                .get_keyboard_mapping(min_keycode, max_keycode - min_keycode + 1)
+                .map_err(|e| t!("无法读取键盘映射: {}", e))?
+                .reply()
+                .map_err(|e| t!("无法读取键盘映射: {}", e))?;
+            Ok(Self {
+                connection,
+                root,
+                min_keycode,
+                keysyms_per_keycode: mapping.keysyms_per_keycode,
+                keysyms: mapping.keysyms,
+            })
+        }
+
+        fn fake(&self, kind: u8, detail: u8, x: i16, y: i16) -> Result<(), String> {
+            self.connection
+                // 设备号 0 表示核心设备
+                .xtest_fake_input(kind, detail, x11rb::CURRENT_TIME, self.root, x, y, 0)
+                .map_err(|e| t!("XTest 注入失败: {}", e))?;
+            Ok(())
+        }
+
+        fn flush(&self) -> Result<(), String> {
+            self.connection.flush().map_err(|e| t!("XTest 注入失败: {}", e))
+        }
+
+        // keysym 所在的键码，以及是否需要 Shift；只看每个键的前两列（无修饰和 Shift）
+        fn keycode(&self, keysym: u32) -> Option<(u8, bool)> {
+            let per = self.keysyms_per_keycode.max(1) as usize;
+            let index = self.keysyms.iter().enumerate().position(|(i, &sym)| sym == keysym && i % per < 2)?;
+            Some((self.min_keycode + (index / per) as u8, index % per == 1))
+        }
+    }
+
+    impl MouseBackend for XTestBackend {
+        fn move_to(&mut self, x: i32, y: i32) -> Result<(), String> {
+            let (Ok(x), Ok(y)) = (i16::try_from(x), i16::try_from(y)) else {
+                return Err(t!("坐标 ({}, {}) 超出 X11 的范围", x, y));
+            };
+            // detail 为 0 表示绝对坐标
+            self.fake(MOTION_NOTIFY_EVENT, 0, x, y)?;
+            self.flush()
+        }
+
+        fn button(&mut self, button: Button, pressed: bool) -> Result<(), String> {
+            let detail = match button {
+                Button::Left => 1,
+                Button::Middle => 2,
+                Button::Right => 3,
+                Button::Back => 8,
+                Button::Forward => 9,
+                _ => return Err(t!("{} 后端不支持按钮 {}", "XTest", format!("{:?}", button))),
+            };
+            self.fake(if pressed { BUTTON_PRESS_EVENT } else { BUTTON_RELEASE_EVENT }, detail, 0, 0)?;
+            self.flush()
+        }
+
+        fn key(&mut self, key: Key, pressed: bool) -> Result<(), String> {
+            let unsupported = || t!("{} 后端不支持按键 {}", "XTest", format!("{:?}", key));
+            let keysym = keysym(key).ok_or_else(unsupported)?;
+            let (code, shift) = self.keycode(keysym).ok_or_else(unsupported)?;
+            let kind = if pressed { KEY_PRESS_EVENT } else { KEY_RELEASE_EVENT };
+            let shift_code = match shift {
+                true => Some(self.keycode(XK_SHIFT_L).ok_or_else(unsupported)?.0),
+                false => None,
+            };
+            if let Some(shift_code) = shift_code.filter(|_| pressed) {
+                self.fake(KEY_PRESS_EVENT, shift_code, 0, 0)?;
+            }
+            self.fake(kind, code, 0, 0)?;
+            if let Some(shift_code) = shift_code.filter(|_| !pressed) {
+                self.fake(KEY_RELEASE_EVENT, shift_code, 0, 0)?;
+            }
+            self.flush()
+        }
+
+        fn scroll(&mut self, amount: i32) -> Result<(), String> {
+            // 滚轮是按钮 4（向上）和 5（向下），每一格按下并释放一次
+            let detail = if amount < 0 { 4 } else { 5 };
+            for _ in 0..amount.unsigned_abs() {
+                self.fake(BUTTON_PRESS_EVENT, detail, 0, 0)?;
+                self.fake(BUTTON_RELEASE_EVENT, detail, 0, 0)?;
+            }
+            self.flush()
+        }
+    }
+
+    fn keysym(key: Key) -> Option<u32> {
+        let keysym = match key {
+            Key::Escape => 0xff1b,
+            Key::Backspace => 0xff08,
+            Key::Tab => 0xff09,
+            Key::Return => 0xff0d,
+            Key::Space => 0x20,
+            Key::Shift => XK_SHIFT_L,
+            Key::Control => 0xffe3,
+            Key::Alt => 0xffe9,
+            Key::Home => 0xff50,
+            Key::LeftArrow => 0xff51,
+            Key::UpArrow => 0xff52,
+            Key::RightArrow => 0xff53,
+            Key::DownArrow => 0xff54,
+            Key::PageUp => 0xff55,
+            Key::PageDown => 0xff56,
+            Key::End => 0xff57,
+            Key::Delete => 0xffff,
+            Key::F1 => 0xffbe,
+            Key::F2 => 0xffbf,
+            Key::F3 => 0xffc0,
+            Key::F4 => 0xffc1,
+            Key::F5 => 0xffc2,
+            Key::F6 => 0xffc3,
+            Key::F7 => 0xffc4,
+            Key::F8 => 0xffc5,
+            Key::F9 => 0xffc6,
+            Key::F10 => 0xffc7,
+            Key::F11 => 0xffc8,
+            Key::F12 => 0xffc9,
+            // Latin-1 字符的 keysym 就是字符本身，其他字符加上 0x01000000
+            Key::Unicode(c) if matches!(c as u32, 0x20..=0x7e | 0xa0..=0xff) => c as u32,
+            Key::Unicode(c) => 0x0100_0000 | c as u32,
+            Key::Other(keysym) => keysym,
+            _ => return None,
+        };
+        Some(keysym)
+    }
+}
@@ -0,0 +1,115 @@
+// 鼠标按钮映射
+//
+// 设备查询库返回的按钮状态数组在不同平台和设备上的下标并不一致，
+// 默认的下标只是常见情况下的猜测。校准时依次让用户按一下左键、右键和中键，
+// 记下每次是哪个下标变为按下，之后判断按钮状态都按校准的结果。映射保存在本机。
+
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::t;
+
+/// 左、右、中键在按钮状态数组中的下标
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ButtonMapping {
+    pub left: usize,
+    pub right: usize,
+    pub middle: usize,
+}
+
+impl Default for ButtonMapping {
+    fn default() -> Self {
+        Self { left: 1, right: 2, middle: 3 }
+    }
+}
+
+impl ButtonMapping {
+    /// 按钮在按钮状态数组中的下标
+    pub fn index(&self, button: CalibrationButton) -> usize {
+        match button {
+            CalibrationButton::Left => self.left,
+            CalibrationButton::Right => self.right,
+            CalibrationButton::Middle => self.middle,
+        }
+    }
+
+    /// 按钮在 `buttons` 中是否为按下状态，下标越界时视为松开
+    pub fn is_pressed(&self, buttons: &[bool], button: CalibrationButton) -> bool {
+        buttons.get(self.index(button)).copied().unwrap_or(false)
+    }
+
+    /// 下标对应的按钮，没有映射到任何按钮时为 None
+    pub fn button_at(&self, index: usize) -> Option<CalibrationButton> {
+        CalibrationButton::ORDER.into_iter().find(|&button| self.index(button) == index)
+    }
+}
+
+/// 校准和捕捉坐标时区分的鼠标按钮
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalibrationButton {
+    Left,
+    Right,
+    Middle,
+}
+
+impl CalibrationButton {
+    /// 校准时依次提示按下的顺序
+    pub const ORDER: [CalibrationButton; 3] = [CalibrationButton::Left, CalibrationButton::Right, CalibrationButton::Middle];
+
+    /// 界面上显示的名称
+    pub fn label(self) -> &'static str {
+        match self {
+            CalibrationButton::Left => t!("左键"),
+            CalibrationButton::Right => t!("右键"),
+            CalibrationButton::Middle => t!("中键"),
+        }
+    }
+}
+
+/// 进行中的校准
+#[derive(Debug, Default)]
+pub struct Calibration {
+    found: Vec<usize>,
+    /// 上一次的按钮状态，用来找出新按下的下标
+    previous: Vec<bool>,
+    /// 记下一个按钮后要等所有按钮都松开再进行下一步
+    waiting_release: bool,
+}
+
+impl Calibration {
+    /// 开始校准。先等所有按钮都松开，避免把点击「校准按钮映射」的左键算进去
+    pub fn new() -> Self {
+        Self { waiting_release: true, ..Default::default() }
+    }
+
+    /// 当前等待按下的按钮，全部完成后为 None
+    pub fn current(&self) -> Option<CalibrationButton> {
+        CalibrationButton::ORDER.get(self.found.len()).copied()
+    }
+
+    /// 已记录的按钮和下标
+    pub fn found(&self) -> impl Iterator<Item = (CalibrationButton, usize)> + '_ {
+        CalibrationButton::ORDER.into_iter().zip(self.found.iter().copied())
+    }
+
+    /// 传入最新的按钮状态，全部记录完成时返回新的映射
+    pub fn poll(&mut self, buttons: &[bool]) -> Option<ButtonMapping> {
+        if self.waiting_release {
+            self.waiting_release = buttons.iter().any(|&pressed| pressed);
+        } else if self.current().is_some() {
+            // 只认从松开变为按下的下标，已经分配给其他按钮的不算
+            let pressed = buttons.iter().enumerate().find(|&(i, &pressed)| {
+                pressed && !self.previous.get(i).copied().unwrap_or(false) && !self.found.contains(&i)
+            });
+            if let Some((index, _)) = pressed {
+                self.found.push(index);
+                self.waiting_release = true;
+            }
+        }
+        self.previous = buttons.to_vec();
+
+        match self.found[..] {
+            [left, right, middle] if !self.waiting_release => Some(ButtonMapping { left, right, middle }),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,459 @@
+// 屏幕截图
+//
+// 截取虚拟桌面上任意矩形区域的画面，像素触发、找图和从截图选取坐标都建立在这里。
+// 区域使用虚拟桌面的全局坐标（见 `display`），可以跨越多个显示器，也可以只截某一个显示器。
+//
+// 判断画面是否变化时往往每隔几十毫秒就要看一次同一块区域，`cached` 在给定的时间内
+// 复用已有的截图：之前截取的画面只要完整包含所需的区域且足够新，就不再重新截取。
+// 一直等待画面变化时用 `Capturer`，多次截取之间保持与窗口系统的连接。
+//
+// Windows 通过 GDI 复制屏幕内容。Linux 在 X11 会话中直接读取根窗口的像素；Wayland 会话中
+// 通过 XWayland 只能读到 X 程序的窗口，所以改用 grim，没有 grim 时依次尝试 gnome-screenshot、
+// import（ImageMagick）和 scrot 截取整个屏幕再裁剪。macOS 使用系统自带的 screencapture，
+// 视网膜屏上截到的像素是坐标的两倍，缩小到以点为单位，与坐标一一对应。
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::display::{self, Display};
+use crate::i18n::t;
+
+// 缓存的截图数，超出时丢弃最早的
+const CACHE_SIZE: usize = 4;
+
+static CACHE: Mutex<Vec<Arc<Frame>>> = Mutex::new(Vec::new());
+
+/// 虚拟桌面上的矩形区域，左上角是全局坐标
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Region {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Region {
+    /// 左上角为全局坐标 (x, y)、指定宽高的区域
+    pub fn new(x: i32, y: i32, width: i32, height: i32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    /// 整个显示器
+    pub fn of(display: &Display) -> Self {
+        Self::new(display.x, display.y, display.width, display.height)
+    }
+
+    /// 宽或高不大于 0
+    pub fn is_empty(&self) -> bool {
+        self.width <= 0 || self.height <= 0
+    }
+
+    /// 完整包含另一个区域
+    pub fn contains(&self, other: &Region) -> bool {
+        other.x >= self.x
+            && other.y >= self.y
+            && other.x + other.width <= self.x + self.width
+            && other.y + other.height <= self.y + self.height
+    }
+
+    /// 两个区域重叠的部分，不重叠时为 None
+    pub fn intersect(&self, other: &Region) -> Option<Region> {
+        let left = self.x.max(other.x);
+        let top = self.y.max(other.y);
+        let right = (self.x + self.width).min(other.x + other.width);
+        let bottom = (self.y + self.height).min(other.y + other.height);
+        let region = Region::new(left, top, right - left, bottom - top);
+        (!region.is_empty()).then_some(region)
+    }
+}
+
+/// 一次截图
+#[derive(Debug, Clone)]
+pub struct Frame {
+    /// 左上角的全局坐标
+    pub x: i32,
+    pub y: i32,
+    pub width: usize,
+    pub height: usize,
+    /// 逐行排列的 RGBA 像素
+    pub rgba: Vec<u8>,
+    pub captured: Instant,
+}
+
+impl Frame {
+    fn new(region: Region, rgba: Vec<u8>) -> Self {
+        Self {
+            x: region.x,
+            y: region.y,
+            width: region.width as usize,
+            height: region.height as usize,
+            rgba,
+            captured: Instant::now(),
+        }
+    }
+
+    /// 截图覆盖的区域
+    pub fn region(&self) -> Region {
+        Region::new(self.x, self.y, self.width as i32, self.height as i32)
+    }
+
+    /// 全局坐标 (x, y) 处的 RGB 颜色，不在截图范围内时为 None
+    pub fn pixel(&self, x: i32, y: i32) -> Option<[u8; 3]> {
+        let (column, row) = (x.checked_sub(self.x)?, y.checked_sub(self.y)?);
+        if column < 0 || row < 0 || column as usize >= self.width || row as usize >= self.height {
+            return None;
+        }
+        let offset = (row as usize * self.width + column as usize) * 4;
+        Some([self.rgba[offset], self.rgba[offset + 1], self.rgba[offset + 2]])
+    }
+
+    /// 裁剪出截图中的一部分，区域超出截图范围时只保留重叠的部分
+    pub fn crop(&self, region: Region) -> Option<Frame> {
+        let region = self.region().intersect(&region)?;
+        let (left, top) = ((region.x - self.x) as usize, (region.y - self.y) as usize);
+        let width = region.width as usize;
+        let mut rgba = Vec::with_capacity(width * region.height as usize * 4);
+        for row in top..top + region.height as usize {
+            let start = (row * self.width + left) * 4;
+            rgba.extend_from_slice(&self.rgba[start..start + width * 4]);
+        }
+        Some(Frame { captured: self.captured, ..Frame::new(region, rgba) })
+    }
+}
+
+/// 截取一块区域
+pub fn capture_region(region: Region) -> Result<Frame, String> {
+    if region.is_empty() {
+        return Err(t!("截图区域的宽和高必须大于 0").to_string());
+    }
+    platform::capture(region)
+}
+
+/// 截取一个显示器的全部画面
+pub fn capture_display(display: &Display) -> Result<Frame, String> {
+    capture_region(Region::of(display))
+}
+
+/// 截取所有显示器合起来的外接矩形，显示器之间的空白为黑色
+pub fn capture_desktop() -> Result<Frame, String> {
+    let (x, y, width, height) = display::virtual_desktop(&display::enumerate()).ok_or_else(|| t!("找不到显示器").to_string())?;
+    capture_region(Region::new(x, y, width, height))
+}
+
+/// 截取主显示器；列不出显示器时截取系统报告的屏幕范围
+pub fn capture_primary() -> Result<Frame, String> {
+    match display::enumerate().first() {
+        Some(display) => capture_display(display),
+        None => capture_region(platform::screen().ok_or_else(|| t!("找不到显示器").to_string())?),
+    }
+}
+
+/// 反复截取屏幕时使用，Linux 上在多次截取之间保持与 X 服务器的连接，不必每次重新连接
+#[derive(Default)]
+pub struct Capturer(platform::Session);
+
+impl Capturer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 截取一块区域，与 `capture_region` 相同
+    pub fn capture(&mut self, region: Region) -> Result<Frame, String> {
+        if region.is_empty() {
+            return Err(t!("截图区域的宽和高必须大于 0").to_string());
+        }
+        self.0.capture(region)
+    }
+}
+
+/// 截取一块区域，`max_age` 之内截取过包含这块区域的画面时直接复用
+pub fn cached(region: Region, max_age: Duration) -> Result<Arc<Frame>, String> {
+    {
+        let cache = CACHE.lock().unwrap();
+        let reusable = cache.iter().find(|frame| frame.captured.elapsed() <= max_age && frame.region().contains(&region));
+        if let Some(frame) = reusable {
+            return Ok(frame.clone());
+        }
+    }
+    // 截图较慢，不要在持有锁的时候截取
+    let frame = Arc::new(capture_region(region)?);
+    let mut cache = CACHE.lock().unwrap();
+    cache.retain(|frame| frame.captured.elapsed() <= max_age);
+    cache.push(frame.clone());
+    if cache.len() > CACHE_SIZE {
+        cache.remove(0);
+    }
+    Ok(frame)
+}
+
+/// 丢弃缓存的截图，画面刚被自己改变（例如刚点击过）时调用
+pub fn clear_cache() {
+    CACHE.lock().unwrap().clear();
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{Frame, Region};
+    use crate::i18n::t;
+    use windows_sys::Win32::Graphics::Gdi::{
+        BI_RGB, BITMAPINFO, BITMAPINFOHEADER, BitBlt, CAPTUREBLT, CreateCompatibleBitmap, CreateCompatibleDC,
+        DIB_RGB_COLORS, DeleteDC, DeleteObject, GetDC, GetDIBits, ReleaseDC, SRCCOPY, SelectObject,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::{GetSystemMetrics, SM_CXSCREEN, SM_CYSCREEN};
+
+    // GDI 每次截取都要重新取得屏幕的 DC，没有可以保持的连接
+    #[derive(Default)]
+    pub struct Session;
+
+    impl Session {
+        pub fn capture(&mut self, region: Region) -> Result<Frame, String> {
+            capture(region)
+        }
+    }
+
+    pub fn screen() -> Option<Region> {
+        let (width, height) = unsafe { (GetSystemMetrics(SM_CXSCREEN), GetSystemMetrics(SM_CYSCREEN)) };
+        let region = Region::new(0, 0, width, height);
+        (!region.is_empty()).then_some(region)
+    }
+
+    pub fn capture(region: Region) -> Result<Frame, String> {
+        let Region { x, y, width, height } = region;
+        unsafe {
+            let screen = GetDC(std::ptr::null_mut());
+            if screen.is_null() {
+                return Err(t!("无法访问屏幕").to_string());
+            }
+            let memory = CreateCompatibleDC(screen);
+            let bitmap = CreateCompatibleBitmap(screen, width, height);
+            let previous = SelectObject(memory, bitmap);
+            // CAPTUREBLT 让分层窗口（如其他程序的半透明浮窗）也出现在截图中；屏幕外的部分为黑色
+            let copied = BitBlt(memory, 0, 0, width, height, screen, x, y, SRCCOPY | CAPTUREBLT) != 0;
+            SelectObject(memory, previous);
+
+            let mut info: BITMAPINFO = std::mem::zeroed();
+            info.bmiHeader = BITMAPINFOHEADER {
+                biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                biWidth: width,
+                // 高度为负数表示从上到下逐行排列
+                biHeight: -height,
+                biPlanes: 1,
+                biBitCount: 32,
+                biCompression: BI_RGB,
+                ..std::mem::zeroed()
+            };
+            let mut pixels = vec![0u8; width as usize * height as usize * 4];
+            let lines = if copied {
+                GetDIBits(memory, bitmap, 0, height as u32, pixels.as_mut_ptr().cast(), &mut info, DIB_RGB_COLORS)
+            } else {
+                0
+            };
+            DeleteObject(bitmap);
+            DeleteDC(memory);
+            ReleaseDC(std::ptr::null_mut(), screen);
+            if lines == 0 {
+                return Err(t!("复制屏幕内容失败").to_string());
+            }
+
+            // BGRX 转为 RGBA
+            for pixel in pixels.chunks_exact_mut(4) {
+                pixel.swap(0, 2);
+                pixel[3] = 255;
+            }
+            Ok(Frame::new(region, pixels))
+        }
+    }
+}
+
+// macOS 和 Linux 的截图工具把画面保存为 PNG 文件，读取后转换成所需的区域
+#[cfg(not(target_os = "windows"))]
+mod file {
+    use std::path::{Path, PathBuf};
+    use std::process::{Command, Stdio};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use super::{Frame, Region};
+    use crate::i18n::t;
+
+    // 取色器等界面功能和执行线程可能同时截图，每次截图用不同的文件，免得互相删除或覆盖
+    static NEXT_FILE: AtomicU64 = AtomicU64::new(0);
+
+    pub fn temp_path() -> PathBuf {
+        let serial = NEXT_FILE.fetch_add(1, Ordering::Relaxed);
+        std::env::temp_dir().join(format!("mousetool-capture-{}-{}.png", std::process::id(), serial))
+    }
+
+    /// 运行截图工具，成功生成文件时返回 true
+    pub fn run(program: &str, args: &[&str], path: &Path) -> bool {
+        let _ = std::fs::remove_file(path);
+        let status = Command::new(program).args(args).arg(path).stdout(Stdio::null()).stderr(Stdio::null()).status();
+        status.is_ok_and(|status| status.success()) && path.exists()
+    }
+
+    /// 读取截图文件，`source` 是文件覆盖的区域，返回其中 `region` 的部分。
+    /// 文件的像素数与区域不同时（视网膜屏）按比例取最近的像素
+    pub fn read(path: &Path, source: Region, region: Region) -> Result<Frame, String> {
+        let result = image::open(path);
+        let _ = std::fs::remove_file(path);
+        let image = result.map_err(|e| t!("无法读取截图: {}", e))?.to_rgba8();
+        let scale_x = image.width() as f64 / source.width.max(1) as f64;
+        let scale_y = image.height() as f64 / source.height.max(1) as f64;
+        let mut rgba = Vec::with_capacity(region.width as usize * region.height as usize * 4);
+        for y in region.y..region.y + region.height {
+            for x in region.x..region.x + region.width {
+                let px = ((x - source.x) as f64 * scale_x) as i64;
+                let py = ((y - source.y) as f64 * scale_y) as i64;
+                // 文件之外的部分填黑色
+                if px < 0 || py < 0 || px >= i64::from(image.width()) || py >= i64::from(image.height()) {
+                    rgba.extend([0, 0, 0, 255]);
+                } else {
+                    rgba.extend(image.get_pixel(px as u32, py as u32).0);
+                }
+            }
+        }
+        Ok(Frame::new(region, rgba))
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{Frame, Region, file};
+    use crate::i18n::t;
+
+    // 每次截取都运行一次 screencapture，没有可以保持的连接
+    #[derive(Default)]
+    pub struct Session;
+
+    impl Session {
+        pub fn capture(&mut self, region: Region) -> Result<Frame, String> {
+            capture(region)
+        }
+    }
+
+    // 列不出显示器时没有其他办法知道屏幕范围
+    pub fn screen() -> Option<Region> {
+        None
+    }
+
+    pub fn capture(region: Region) -> Result<Frame, String> {
+        let path = file::temp_path();
+        let rect = format!("-R{},{},{},{}", region.x, region.y, region.width, region.height);
+        if !file::run("screencapture", &["-x", &rect], &path) {
+            return Err(t!("screencapture 截图失败，请在「系统设置 → 隐私与安全性 → 屏幕录制」中允许本程序").to_string());
+        }
+        file::read(&path, region, region)
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::{ConnectionExt as _, ImageFormat, ImageOrder};
+    use x11rb::rust_connection::RustConnection;
+
+    use super::{Frame, Region, file};
+    use crate::i18n::t;
+    use crate::wayland;
+
+    // 截取整个屏幕的工具，grim 单独处理
+    const TOOLS: &[(&str, &[&str])] =
+        &[("gnome-screenshot", &["-f"]), ("import", &["-window", "root"]), ("scrot", &["-o"])];
+
+    // 根窗口的大小，也就是 X11 的整个屏幕
+    pub fn screen() -> Option<Region> {
+        let (conn, screen) = x11rb::connect(None).ok()?;
+        let root = &conn.setup().roots[screen];
+        Some(Region::new(0, 0, i32::from(root.width_in_pixels), i32::from(root.height_in_pixels)))
+    }
+
+    // 与 X 服务器的连接和屏幕序号，第一次截取时建立，读取失败后断开
+    #[derive(Default)]
+    pub struct Session(Option<(RustConnection, usize)>);
+
+    impl Session {
+        pub fn capture(&mut self, region: Region) -> Result<Frame, String> {
+            if !wayland::is_wayland() {
+                match self.read_root(region) {
+                    Ok(frame) => return Ok(frame),
+                    Err(e) => tracing::debug!(error = %e, "无法读取根窗口像素，改用截图工具"),
+                }
+            }
+            tools(region)
+        }
+
+        fn read_root(&mut self, region: Region) -> Result<Frame, String> {
+            if self.0.is_none() {
+                self.0 = Some(x11rb::connect(None).map_err(|e| t!("无法连接 X 服务器: {}", e))?);
+            }
+            let (conn, screen) = self.0.as_ref().expect("上面已经建立了连接");
+            let frame = x11(conn, *screen, region);
+            if frame.is_err() {
+                self.0 = None;
+            }
+            frame
+        }
+    }
+
+    pub fn capture(region: Region) -> Result<Frame, String> {
+        Session::default().capture(region)
+    }
+
+    // 读取根窗口的像素，区域超出屏幕的部分填黑色
+    fn x11(conn: &RustConnection, screen: usize, region: Region) -> Result<Frame, String> {
+        let setup = conn.setup();
+        let root = &setup.roots[screen];
+        let bounds = Region::new(0, 0, i32::from(root.width_in_pixels), i32::from(root.height_in_pixels));
+        let mut rgba = [0, 0, 0, 255].repeat(region.width as usize * region.height as usize);
+        let Some(visible) = bounds.intersect(&region) else {
+            return Ok(Frame::new(region, rgba));
+        };
+        // 只处理常见的每像素 32 位、低位在前的 BGRX 格式
+        let bits = setup.pixmap_formats.iter().find(|format| format.depth == root.root_depth).map(|format| format.bits_per_pixel);
+        if bits != Some(32) || setup.image_byte_order != ImageOrder::LSB_FIRST {
+            return Err(t!("不支持的屏幕像素格式").to_string());
+        }
+        let reply = conn
+            .get_image(
+                ImageFormat::Z_PIXMAP,
+                root.root,
+                visible.x as i16,
+                visible.y as i16,
+                visible.width as u16,
+                visible.height as u16,
+                !0,
+            )
+            .map_err(|e| e.to_string())?
+            .reply()
+            .map_err(|e| t!("读取屏幕像素失败: {}", e))?;
+        let width = visible.width as usize;
+        for row in 0..visible.height as usize {
+            let source = &reply.data[row * width * 4..(row + 1) * width * 4];
+            let top = (visible.y - region.y) as usize + row;
+            let start = (top * region.width as usize + (visible.x - region.x) as usize) * 4;
+            for (target, pixel) in rgba[start..start + width * 4].chunks_exact_mut(4).zip(source.chunks_exact(4)) {
+                target.copy_from_slice(&[pixel[2], pixel[1], pixel[0], 255]);
+            }
+        }
+        Ok(Frame::new(region, rgba))
+    }
+
+    fn tools(region: Region) -> Result<Frame, String> {
+        let path = file::temp_path();
+        // grim 可以只截取所需的区域
+        let geometry = format!("{},{} {}x{}", region.x, region.y, region.width, region.height);
+        if file::run("grim", &["-g", &geometry], &path) {
+            return file::read(&path, region, region);
+        }
+        for (program, args) in TOOLS {
+            if file::run(program, args, &path) {
+                // 截图从虚拟桌面的原点开始，大小由文件决定，不做缩放
+                let (width, height) = image::image_dimensions(&path).map_err(|e| t!("无法读取截图: {}", e))?;
+                return file::read(&path, Region::new(0, 0, width as i32, height as i32), region);
+            }
+        }
+        let names: Vec<&str> = std::iter::once("grim").chain(TOOLS.iter().map(|(program, _)| *program)).collect();
+        Err(t!("找不到可用的截图工具（{}）", names.join("、")))
+    }
+}
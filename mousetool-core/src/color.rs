@@ -0,0 +1,94 @@
+// 颜色匹配
+//
+// 像素触发判断屏幕上某一点是否是预期的颜色。按钮边缘的抗锯齿、渐变和呼吸动画会让颜色
+// 每次都略有不同，所以按容差匹配，容差是 0 到 100 的百分比：
+//
+// - RGB：三个通道各自的差不超过容差，适合颜色固定的目标
+// - HSV：只比较色相，色相差不超过容差对应的角度（100% 为 180°），亮度和饱和度随意变化，
+//   适合一闪一闪或明暗变化的目标。灰色、黑色、白色没有可靠的色相，任一方饱和度或亮度
+//   太低时改按 RGB 比较
+
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::t;
+
+// 饱和度或亮度低于这个值时色相不可靠
+const MIN_CHROMA: f32 = 0.15;
+
+/// 比较颜色时使用的颜色空间
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorSpace {
+    #[default]
+    Rgb,
+    Hsv,
+}
+
+impl ColorSpace {
+    /// 界面中按此顺序列出
+    pub const ALL: [ColorSpace; 2] = [ColorSpace::Rgb, ColorSpace::Hsv];
+
+    /// 界面上显示的名称
+    pub fn label(self) -> &'static str {
+        match self {
+            ColorSpace::Rgb => t!("RGB（逐通道）"),
+            ColorSpace::Hsv => t!("HSV（只看色相）"),
+        }
+    }
+}
+
+/// 预期的颜色和匹配方式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColorMatch {
+    pub color: [u8; 3],
+    /// 容差，0 到 100 的百分比
+    pub tolerance: u8,
+    pub space: ColorSpace,
+}
+
+impl Default for ColorMatch {
+    fn default() -> Self {
+        Self { color: [255, 255, 255], tolerance: 10, space: ColorSpace::Rgb }
+    }
+}
+
+impl ColorMatch {
+    /// 目标颜色，`#RRGGBB`
+    pub fn hex(&self) -> String {
+        let [r, g, b] = self.color;
+        format!("#{:02X}{:02X}{:02X}", r, g, b)
+    }
+
+    /// 像素的颜色是否在容差之内
+    pub fn matches(&self, pixel: [u8; 3]) -> bool {
+        let tolerance = f32::from(self.tolerance.min(100)) / 100.0;
+        if self.space == ColorSpace::Hsv {
+            let (expected, actual) = (hsv(self.color), hsv(pixel));
+            if expected.1.min(expected.2).min(actual.1).min(actual.2) >= MIN_CHROMA {
+                let difference = (expected.0 - actual.0).abs();
+                return difference.min(360.0 - difference) <= tolerance * 180.0;
+            }
+        }
+        let limit = (tolerance * 255.0).round() as i16;
+        self.color.iter().zip(pixel).all(|(&expected, actual)| (i16::from(expected) - i16::from(actual)).abs() <= limit)
+    }
+}
+
+// 色相（0 到 360 度）、饱和度和亮度（0 到 1）
+fn hsv([r, g, b]: [u8; 3]) -> (f32, f32, f32) {
+    let (r, g, b) = (f32::from(r) / 255.0, f32::from(g) / 255.0, f32::from(b) / 255.0);
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    let saturation = if max == 0.0 { 0.0 } else { delta / max };
+    (hue, saturation, max)
+}
@@ -0,0 +1,296 @@
+// 跨平台鼠标控制
+//
+// 通过 enigo 或界面中选择的输入后端注入鼠标和键盘事件，用 device_query 读取光标位置和按钮状态。
+// 记下本工具按下但尚未释放的按键和按钮，停止、退出或异常时统一释放。
+// 控制器只在输入引擎线程上使用，其他线程通过 `engine::Engine` 访问。
+
+use device_query::{DeviceQuery, DeviceState, Keycode};
+use enigo::{Enigo, Mouse, Key, Button, Settings};
+use std::time::Duration;
+
+use crate::button_map::{ButtonMapping, CalibrationButton};
+use crate::backend::{Backend, MouseBackend};
+use crate::error::{InputAction, InputError};
+use crate::i18n::t;
+
+/// 在当前线程中直接注入输入和读取输入状态的控制器，通常由 `Engine` 在自己的线程中持有
+pub struct MouseController {
+    // 用 `with_backend` 创建时两者都是 None，不连接真实的输入设备
+    enigo: Option<Enigo>,
+    device_state: Option<DeviceState>,
+    // 本工具按下但尚未释放的按键和鼠标按钮，用于紧急释放
+    held_keys: Vec<Key>,
+    held_buttons: Vec<Button>,
+    // 按钮状态数组中左、右、中键的下标
+    button_mapping: ButtonMapping,
+    // 选择了其他后端时用它注入，为 None 时通过 enigo 注入
+    backend: Option<Box<dyn MouseBackend>>,
+    // 正在使用的后端名称，写入注入失败的错误中
+    backend_label: &'static str,
+}
+
+impl MouseController {
+    /// 连接系统的输入设备，使用 enigo 后端
+    pub fn new() -> Result<Self, InputError> {
+        // 显式关闭 Windows 上相对移动受指针速度/加速度影响的选项，
+        // 保证注入的位移与系统鼠标设置无关
+        let settings = Settings {
+            windows_subject_to_mouse_speed_and_acceleration_level: false,
+            ..Settings::default()
+        };
+        let enigo = Enigo::new(&settings).map_err(|e| InputError::Connect(e.to_string()))?;
+        let device_state = DeviceState::new();
+
+        Ok(Self {
+            enigo: Some(enigo),
+            device_state: Some(device_state),
+            held_keys: Vec::new(),
+            held_buttons: Vec::new(),
+            button_mapping: ButtonMapping::default(),
+            backend: None,
+            backend_label: Backend::Enigo.label(),
+        })
+    }
+
+    /// 只通过 `backend` 注入、不连接真实设备的控制器，用于测试和模拟执行。
+    /// 光标位置由后端提供，后端不提供时为 (0, 0)；读取到的按键和按钮都是松开的
+    pub fn with_backend(backend: Box<dyn MouseBackend>) -> Self {
+        Self {
+            enigo: None,
+            device_state: None,
+            held_keys: Vec::new(),
+            held_buttons: Vec::new(),
+            button_mapping: ButtonMapping::default(),
+            backend: Some(backend),
+            backend_label: t!("自定义后端"),
+        }
+    }
+
+    /// 设置判断按钮状态时使用的下标
+    pub fn set_button_mapping(&mut self, mapping: ButtonMapping) {
+        self.button_mapping = mapping;
+    }
+
+    /// 切换注入输入的后端。无法创建时保持使用 enigo 并返回原因
+    pub fn set_backend(&mut self, backend: Backend) -> Result<(), InputError> {
+        if self.enigo.is_none() {
+            let reason = t!("这个控制器只能使用创建时指定的后端").to_string();
+            return Err(InputError::Backend { backend, reason });
+        }
+        // 切换前释放按住的输入，避免按下和释放经过不同的后端
+        self.release_all();
+        self.backend = None;
+        self.backend_label = Backend::Enigo.label();
+        if backend != Backend::Enigo {
+            let created = backend.create(self.desktop_bounds()).map_err(|reason| InputError::Backend { backend, reason })?;
+            self.backend = Some(created);
+            self.backend_label = backend.label();
+        }
+        Ok(())
+    }
+
+    /// 改用自定义的后端注入，例如在测试中记录注入的事件而不真的移动鼠标
+    pub fn set_custom_backend(&mut self, backend: Box<dyn MouseBackend>) {
+        self.release_all();
+        self.backend = Some(backend);
+        self.backend_label = t!("自定义后端");
+    }
+
+    // 把后端返回的原因包装成带动作和后端名称的错误
+    fn failed(&self, action: InputAction) -> impl FnOnce(String) -> InputError + use<> {
+        let backend = self.backend_label;
+        move |reason| InputError::Inject { action, backend, reason }
+    }
+
+    fn injector(&mut self) -> &mut dyn MouseBackend {
+        match (&mut self.backend, &mut self.enigo) {
+            (Some(backend), _) => backend.as_mut(),
+            (None, Some(enigo)) => enigo,
+            // with_backend 创建的控制器总是有后端，其他控制器总是有 enigo
+            (None, None) => unreachable!(),
+        }
+    }
+
+    // 所有显示器合起来的区域：左上角的全局坐标和宽高
+    fn desktop_bounds(&self) -> (i32, i32, i32, i32) {
+        crate::display::virtual_desktop(&crate::display::enumerate()).unwrap_or_else(|| {
+            let (width, height) =
+                self.enigo.as_ref().and_then(|enigo| enigo.main_display().ok()).unwrap_or((1920, 1080));
+            (0, 0, width, height)
+        })
+    }
+
+    /// 光标位置，相对于所选的显示器
+    pub fn get_mouse_position(&self) -> (i32, i32) {
+        let (x, y) = self
+            .backend
+            .as_ref()
+            .and_then(|backend| backend.position())
+            .or_else(|| self.device_state.as_ref().map(|state| state.get_mouse().coords))
+            .unwrap_or((0, 0));
+        crate::display::to_local(x, y)
+    }
+
+    /// 当前按下的键盘按键，用于全局快捷键
+    pub fn pressed_keys(&self) -> Vec<Keycode> {
+        self.device_state.as_ref().map(|state| state.get_keys()).unwrap_or_default()
+    }
+
+    /// 设备查询库返回的按钮状态，下标含义见 `ButtonMapping`
+    pub fn get_mouse_button_states(&self) -> Vec<bool> {
+        self.device_state.as_ref().map(|state| state.get_mouse().button_pressed).unwrap_or_default()
+    }
+
+    /// 按校准的下标判断中键是否按下
+    pub fn is_middle_button_pressed(&self) -> bool {
+        self.button_mapping.is_pressed(&self.get_mouse_button_states(), CalibrationButton::Middle)
+    }
+
+    /// 按校准的下标判断右键是否按下
+    pub fn is_right_button_pressed(&self) -> bool {
+        self.button_mapping.is_pressed(&self.get_mouse_button_states(), CalibrationButton::Right)
+    }
+
+    /// 移动到相对于所选显示器的坐标
+    pub fn move_mouse_to(&mut self, x: i32, y: i32) -> Result<(), InputError> {
+        let (x, y) = crate::display::to_global(x, y);
+        let failed = self.failed(InputAction::Move);
+        self.injector().move_to(x, y).map_err(failed)
+    }
+
+    /// 点击一次左键
+    pub fn click_left(&mut self) -> Result<(), InputError> {
+        self.click_button(Button::Left)
+    }
+
+    /// 点击一次右键
+    pub fn click_right(&mut self) -> Result<(), InputError> {
+        self.click_button(Button::Right)
+    }
+
+    /// 点击一次中键
+    pub fn click_middle(&mut self) -> Result<(), InputError> {
+        self.click_button(Button::Middle)
+    }
+
+    // 拆成按下和释放两步注入，释放失败时按钮仍留在登记表里，由紧急释放兜底
+    fn click_button(&mut self, button: Button) -> Result<(), InputError> {
+        self.press_button(button)?;
+        self.release_button(button)
+    }
+
+    /// 按下鼠标按钮并登记，直到 `release_button` 或紧急释放时才松开
+    pub fn press_button(&mut self, button: Button) -> Result<(), InputError> {
+        // 先登记再注入：即使注入报错，系统也可能已经收到了按下事件
+        if !self.held_buttons.contains(&button) {
+            self.held_buttons.push(button);
+        }
+        let failed = self.failed(InputAction::Press(button));
+        self.injector().button(button, true).map_err(failed)
+    }
+
+    /// 松开鼠标按钮并取消登记
+    pub fn release_button(&mut self, button: Button) -> Result<(), InputError> {
+        let failed = self.failed(InputAction::Release(button));
+        self.injector().button(button, false).map_err(failed)?;
+        self.held_buttons.retain(|b| *b != button);
+        Ok(())
+    }
+
+    /// 按下按键并登记，直到松开或紧急释放
+    pub fn press_key(&mut self, key: Key) -> Result<(), InputError> {
+        if !self.held_keys.contains(&key) {
+            self.held_keys.push(key);
+        }
+        let failed = self.failed(InputAction::Key);
+        self.injector().key(key, true).map_err(failed)
+    }
+
+    /// 按下并松开一个按键
+    pub fn tap_key(&mut self, key: Key) -> Result<(), InputError> {
+        self.press_key(key)?;
+        let failed = self.failed(InputAction::Key);
+        self.injector().key(key, false).map_err(failed)?;
+        self.held_keys.retain(|k| *k != key);
+        Ok(())
+    }
+
+    /// 垂直滚动，正数向下
+    pub fn scroll(&mut self, amount: i32) -> Result<(), InputError> {
+        let failed = self.failed(InputAction::Scroll);
+        self.injector().scroll(amount).map_err(failed)
+    }
+
+    /// 释放本工具按下的所有按键和鼠标按钮。
+    /// 单个释放失败不会中断其余的释放，返回所有失败的释放
+    pub fn release_all(&mut self) -> Vec<InputError> {
+        let mut errors = Vec::new();
+        for button in std::mem::take(&mut self.held_buttons) {
+            let failed = self.failed(InputAction::Release(button));
+            errors.extend(self.injector().button(button, false).map_err(failed).err());
+        }
+        for key in std::mem::take(&mut self.held_keys) {
+            let failed = self.failed(InputAction::Key);
+            errors.extend(self.injector().key(key, false).map_err(failed).err());
+        }
+        errors
+    }
+
+    /// 是否还有本工具按下但未松开的按键或按钮
+    pub fn has_held_inputs(&self) -> bool {
+        !self.held_keys.is_empty() || !self.held_buttons.is_empty()
+    }
+
+    /// 所选显示器的尺寸，没有选择时为主显示器的尺寸
+    pub fn get_screen_size(&self) -> Result<(i32, i32), InputError> {
+        if let Some(size) = crate::display::selected_size() {
+            return Ok(size);
+        }
+        let enigo = self.enigo.as_ref().ok_or_else(|| InputError::Screen(t!("这个控制器没有连接显示器").to_string()))?;
+        enigo.main_display().map_err(|e| InputError::Screen(e.to_string()))
+    }
+}
+
+impl Drop for MouseController {
+    fn drop(&mut self) {
+        for error in self.release_all() {
+            tracing::warn!(%error, "退出时释放输入失败");
+        }
+    }
+}
+
+/// 系统的双击时间：两次点击间隔超过它就会被识别为两次单击。
+/// 读取失败时返回 None，结果在首次读取后缓存。
+pub fn double_click_time() -> Option<Duration> {
+    static CACHE: std::sync::OnceLock<Option<Duration>> = std::sync::OnceLock::new();
+    *CACHE.get_or_init(query_double_click_time)
+}
+
+#[cfg(target_os = "windows")]
+fn query_double_click_time() -> Option<Duration> {
+    let ms = unsafe { windows_sys::Win32::UI::Input::KeyboardAndMouse::GetDoubleClickTime() };
+    (ms > 0).then(|| Duration::from_millis(ms as u64))
+}
+
+// macOS 的设置以秒为单位保存在全局偏好中
+#[cfg(target_os = "macos")]
+fn query_double_click_time() -> Option<Duration> {
+    let output = std::process::Command::new("defaults")
+        .args(["read", "-g", "com.apple.mouse.doubleClickThreshold"])
+        .output()
+        .ok()?;
+    let secs: f64 = String::from_utf8_lossy(&output.stdout).trim().parse().ok()?;
+    (secs > 0.0).then(|| Duration::from_secs_f64(secs))
+}
+
+// Linux 下没有统一的接口，读取 GNOME 的设置（毫秒）
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn query_double_click_time() -> Option<Duration> {
+    let output = std::process::Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.peripherals.mouse", "double-click"])
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let ms: u64 = text.trim().trim_start_matches("int32 ").parse().ok()?;
+    (ms > 0).then(|| Duration::from_millis(ms))
+}
@@ -0,0 +1,325 @@
+// 多显示器
+//
+// 列出所有显示器在虚拟桌面中的位置，每个配置可以选择坐标相对于哪个显示器：
+// 界面上的坐标以所选显示器的左上角为原点，移动鼠标、读取光标位置和读取屏幕像素时
+// 再换算成虚拟桌面的全局坐标。没有选择时坐标就是全局坐标，原点在主显示器左上角。
+// Windows 通过 EnumDisplayMonitors 获取，macOS 通过 CoreGraphics，Linux 解析 xrandr 的输出。
+//
+// 坐标的单位在所有地方保持一致：Windows 和 Linux 是物理像素，macOS 是点。
+// Windows 上程序必须声明按显示器感知 DPI，否则在缩放为 125%、150% 的显示器上，系统会把
+// 读到的光标位置和显示器区域换算成虚拟的 96 DPI 坐标，而注入的绝对位置仍按物理像素计算，
+// 捕捉到的坐标再点击时就会偏移。enable_dpi_awareness 在启动时最先调用，无界面运行时同样生效。
+
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::t;
+use crate::sequence::Sequence;
+
+/// 一个显示器
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Display {
+    /// 系统给出的显示器名称，用于在配置中记住选择
+    pub name: String,
+    /// 左上角在虚拟桌面中的位置
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    pub primary: bool,
+    /// 系统的显示缩放，1.0 表示 100%；Linux 上读取不到，总是 1.0
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+impl Display {
+    /// 界面上显示的名称：名称、是否为主显示器、分辨率、缩放和位置
+    pub fn label(&self) -> String {
+        let primary = if self.primary { t!("（主）") } else { "" };
+        let scale = if self.scale == 1.0 { String::new() } else { format!(" {:.0}%", self.scale * 100.0) };
+        format!("{}{} {}x{}{} @ ({}, {})", self.name, primary, self.width, self.height, scale, self.x, self.y)
+    }
+}
+
+/// 声明按显示器感知 DPI，使光标位置、注入的位置和显示器区域都使用物理像素。
+/// 必须在创建窗口和读取任何坐标之前调用，其他平台什么也不做
+pub fn enable_dpi_awareness() {
+    platform::enable_dpi_awareness();
+}
+
+/// 所有显示器，主显示器排在最前；读取失败时为空
+pub fn enumerate() -> Vec<Display> {
+    let mut displays = platform::enumerate();
+    displays.sort_by_key(|display| (!display.primary, display.x, display.y));
+    displays
+}
+
+/// 所有显示器合起来的外接矩形：左上角的全局坐标和宽高。主显示器左侧或上方的显示器
+/// 坐标为负数，左上角也就可能是负数。没有显示器时为 None
+pub fn virtual_desktop(displays: &[Display]) -> Option<(i32, i32, i32, i32)> {
+    let left = displays.iter().map(|d| d.x).min()?;
+    let top = displays.iter().map(|d| d.y).min()?;
+    let right = displays.iter().map(|d| d.x + d.width).max()?;
+    let bottom = displays.iter().map(|d| d.y + d.height).max()?;
+    Some((left, top, right - left, bottom - top))
+}
+
+/// 界面坐标 (x, y) 是否落在某个显示器上。显示器大小不一时外接矩形里会有空白，
+/// 所以逐个显示器判断。读取不到显示器时无法判断，总是返回 true
+pub fn contains(displays: &[Display], x: i32, y: i32) -> bool {
+    let (x, y) = to_global(x, y);
+    displays.is_empty()
+        || displays.iter().any(|d| (d.x..d.x + d.width).contains(&x) && (d.y..d.y + d.height).contains(&y))
+}
+
+/// 执行前检查：返回目标不在任何显示器上的步骤序号（从 0 开始）及原因。
+/// 坐标由表达式计算的步骤在执行前无法确定位置，不做检查
+pub fn preflight(sequence: &Sequence) -> Vec<(usize, String)> {
+    let displays = enumerate();
+    sequence
+        .steps
+        .iter()
+        .enumerate()
+        .filter(|(_, step)| step.kind.has_target() && step.x_expr.trim().is_empty() && step.y_expr.trim().is_empty())
+        .filter(|(_, step)| !contains(&displays, step.x, step.y))
+        .map(|(i, step)| (i, t!("({}, {}) 不在任何显示器上", step.x, step.y)))
+        .collect()
+}
+
+/// 全局坐标 (x, y) 换算成窗口逻辑坐标（点）时除以的比例，也就是该处显示器的缩放。
+/// macOS 的坐标本来就以点为单位；Linux 读不到显示器的缩放，使用窗口系统给出的 `native`
+pub fn scale_at(displays: &[Display], x: i32, y: i32, native: f32) -> f32 {
+    if cfg!(target_os = "macos") {
+        return 1.0;
+    }
+    if !cfg!(target_os = "windows") {
+        return native;
+    }
+    displays
+        .iter()
+        .find(|d| (d.x..d.x + d.width).contains(&x) && (d.y..d.y + d.height).contains(&y))
+        .map_or(native, |d| d.scale)
+}
+
+// 当前坐标原点和所选显示器的尺寸，None 表示使用全局坐标
+static SELECTED: Mutex<Option<Display>> = Mutex::new(None);
+
+/// 选择坐标相对的显示器
+pub fn select(display: Option<Display>) {
+    *SELECTED.lock().unwrap_or_else(|e| e.into_inner()) = display;
+}
+
+fn origin() -> (i32, i32) {
+    SELECTED.lock().unwrap_or_else(|e| e.into_inner()).as_ref().map_or((0, 0), |display| (display.x, display.y))
+}
+
+/// 所选显示器的尺寸，没有选择时为 None
+pub fn selected_size() -> Option<(i32, i32)> {
+    SELECTED.lock().unwrap_or_else(|e| e.into_inner()).as_ref().map(|display| (display.width, display.height))
+}
+
+/// 界面坐标换算成虚拟桌面的全局坐标
+pub fn to_global(x: i32, y: i32) -> (i32, i32) {
+    let (ox, oy) = origin();
+    (x + ox, y + oy)
+}
+
+/// 全局坐标换算成相对于所选显示器的坐标
+pub fn to_local(x: i32, y: i32) -> (i32, i32) {
+    let (ox, oy) = origin();
+    (x - ox, y - oy)
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::Display;
+    use windows_sys::Win32::Foundation::{BOOL, LPARAM, RECT};
+    use windows_sys::Win32::Graphics::Gdi::{
+        EnumDisplayMonitors, GetMonitorInfoW, HDC, HMONITOR, MONITORINFO, MONITORINFOEXW,
+    };
+    use windows_sys::Win32::UI::HiDpi::{
+        DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2, GetDpiForMonitor, MDT_EFFECTIVE_DPI, SetProcessDpiAwarenessContext,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::{MONITORINFOF_PRIMARY, SetProcessDPIAware};
+
+    // 100% 缩放对应的 DPI
+    const BASE_DPI: f32 = 96.0;
+
+    pub fn enable_dpi_awareness() {
+        unsafe {
+            // 每个显示器使用各自的缩放（Windows 10 1703 起）；更早的系统只能按主显示器感知
+            if SetProcessDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2) == 0 {
+                SetProcessDPIAware();
+            }
+        }
+    }
+
+    fn monitor_scale(monitor: HMONITOR) -> f32 {
+        let (mut dpi_x, mut dpi_y) = (0, 0);
+        let result = unsafe { GetDpiForMonitor(monitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y) };
+        if result != 0 || dpi_x == 0 {
+            return 1.0;
+        }
+        dpi_x as f32 / BASE_DPI
+    }
+
+    unsafe extern "system" fn collect(monitor: HMONITOR, _dc: HDC, _rect: *mut RECT, data: LPARAM) -> BOOL {
+        let displays = unsafe { &mut *(data as *mut Vec<Display>) };
+        let mut info: MONITORINFOEXW = unsafe { std::mem::zeroed() };
+        info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+        if unsafe { GetMonitorInfoW(monitor, &mut info as *mut MONITORINFOEXW as *mut MONITORINFO) } != 0 {
+            let rect = info.monitorInfo.rcMonitor;
+            let len = info.szDevice.iter().position(|&c| c == 0).unwrap_or(info.szDevice.len());
+            let name = String::from_utf16_lossy(&info.szDevice[..len]);
+            displays.push(Display {
+                // 设备名形如 \\.\DISPLAY1
+                name: name.trim_start_matches(r"\\.\").to_string(),
+                x: rect.left,
+                y: rect.top,
+                width: rect.right - rect.left,
+                height: rect.bottom - rect.top,
+                primary: info.monitorInfo.dwFlags & MONITORINFOF_PRIMARY != 0,
+                scale: monitor_scale(monitor),
+            });
+        }
+        1
+    }
+
+    pub fn enumerate() -> Vec<Display> {
+        let mut displays: Vec<Display> = Vec::new();
+        unsafe {
+            EnumDisplayMonitors(
+                std::ptr::null_mut(),
+                std::ptr::null(),
+                Some(collect),
+                &mut displays as *mut Vec<Display> as LPARAM,
+            );
+        }
+        displays
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::Display;
+
+    #[repr(C)]
+    struct CGPoint {
+        x: f64,
+        y: f64,
+    }
+
+    #[repr(C)]
+    struct CGSize {
+        width: f64,
+        height: f64,
+    }
+
+    #[repr(C)]
+    struct CGRect {
+        origin: CGPoint,
+        size: CGSize,
+    }
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    unsafe extern "C" {
+        fn CGGetActiveDisplayList(max: u32, displays: *mut u32, count: *mut u32) -> i32;
+        fn CGDisplayBounds(display: u32) -> CGRect;
+        fn CGMainDisplayID() -> u32;
+        fn CGDisplayCopyDisplayMode(display: u32) -> *mut std::ffi::c_void;
+        fn CGDisplayModeGetPixelWidth(mode: *mut std::ffi::c_void) -> usize;
+        fn CGDisplayModeRelease(mode: *mut std::ffi::c_void);
+    }
+
+    // macOS 的坐标本来就以点为单位，不需要声明
+    pub fn enable_dpi_awareness() {}
+
+    // 每个点对应的像素数，Retina 显示器为 2
+    fn backing_scale(id: u32, width: f64) -> f32 {
+        unsafe {
+            let mode = CGDisplayCopyDisplayMode(id);
+            if mode.is_null() || width <= 0.0 {
+                return 1.0;
+            }
+            let pixels = CGDisplayModeGetPixelWidth(mode) as f64;
+            CGDisplayModeRelease(mode);
+            (pixels / width) as f32
+        }
+    }
+
+    // 最多列出的显示器数
+    const MAX_DISPLAYS: usize = 16;
+
+    pub fn enumerate() -> Vec<Display> {
+        let mut ids = [0u32; MAX_DISPLAYS];
+        let mut count = 0u32;
+        unsafe {
+            if CGGetActiveDisplayList(MAX_DISPLAYS as u32, ids.as_mut_ptr(), &mut count) != 0 {
+                return Vec::new();
+            }
+            let main = CGMainDisplayID();
+            ids[..count as usize]
+                .iter()
+                .map(|&id| {
+                    // 坐标以点为单位，与光标位置和鼠标事件使用的坐标一致
+                    let bounds = CGDisplayBounds(id);
+                    let scale = backing_scale(id, bounds.size.width);
+                    Display {
+                        name: format!("Display {}", id),
+                        x: bounds.origin.x as i32,
+                        y: bounds.origin.y as i32,
+                        width: bounds.size.width as i32,
+                        height: bounds.size.height as i32,
+                        primary: id == main,
+                        scale,
+                    }
+                })
+                .collect()
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    use super::Display;
+    use std::process::Command;
+
+    // 每行形如 " 0: +*DP-1 2560/597x1440/336+0+0  DP-1"，星号表示主显示器
+    fn parse_monitor(line: &str) -> Option<Display> {
+        let mut fields = line.split_whitespace();
+        fields.next()?.strip_suffix(':')?;
+        let flags = fields.next()?;
+        let geometry = fields.next()?;
+        let name = fields.next().unwrap_or(flags.trim_start_matches(['+', '*']));
+
+        let (size, position) = geometry.split_once('+')?;
+        let (x, y) = position.split_once('+')?;
+        let (width, height) = size.split_once('x')?;
+        let pixels = |text: &str| text.split('/').next()?.parse::<i32>().ok();
+        Some(Display {
+            name: name.to_string(),
+            x: x.parse().ok()?,
+            y: y.parse().ok()?,
+            width: pixels(width)?,
+            height: pixels(height)?,
+            primary: flags.contains('*'),
+            // X11 没有按显示器的缩放，桌面环境的缩放只作用于各自的程序
+            scale: 1.0,
+        })
+    }
+
+    // X11 的坐标本来就是物理像素
+    pub fn enable_dpi_awareness() {}
+
+    pub fn enumerate() -> Vec<Display> {
+        let Ok(output) = Command::new("xrandr").arg("--listmonitors").output() else {
+            return Vec::new();
+        };
+        String::from_utf8_lossy(&output.stdout).lines().skip(1).filter_map(parse_monitor).collect()
+    }
+}
@@ -0,0 +1,251 @@
+// 输入引擎线程
+//
+// 以前界面和各个工作线程共享一个 `Arc<Mutex<MouseController>>`，执行线程在平滑移动、多击间隔和
+// 移动后的等待中一直持有锁，界面捕捉坐标时读不到光标位置，只能卡住等点击结束。
+// 现在控制器只属于一个长期运行的引擎线程，所有 enigo 和 device_query 的调用都在这个线程上完成：
+// - 其他线程通过 `Engine` 句柄把请求送进通道，引擎按顺序执行后把结果送回。每个请求只做一次注入或读取，
+//   平滑移动每一步之间、多击之间的等待都由调用方在自己的线程里完成，引擎不会被某个任务占住
+// - 是否还有本工具按住的输入由引擎在每个请求之后写入原子变量，界面每帧读取时不经过通道
+// - 注入失败时引擎把错误记入 `RecentErrors`，无论调用方是否检查返回值，界面都能显示出来
+// Linux 上 device_query 的连接不能跨线程传递，控制器在引擎线程里创建和销毁也解决了这个问题。
+// 所有句柄都被丢弃后引擎线程退出，控制器在退出时释放所有按住的输入。
+
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use device_query::Keycode;
+use enigo::{Button, Key};
+
+use crate::backend::{Backend, MouseBackend};
+use crate::button_map::ButtonMapping;
+use crate::cross_platform_mouse::MouseController;
+use crate::error::{InputError, RecentErrors, SharedErrors};
+use crate::timing;
+
+// 平滑移动时每一步之间的间隔（毫秒）
+const SMOOTH_STEP_MS: u64 = 8;
+// 平滑移动结束后最多修正的次数
+const SMOOTH_CORRECTION_ATTEMPTS: u32 = 3;
+
+type Job = Box<dyn FnOnce(&mut MouseController) + Send>;
+
+/// 引擎线程的句柄，可以克隆后交给其他线程
+#[derive(Clone)]
+pub struct Engine {
+    jobs: Sender<Job>,
+    held_inputs: Arc<AtomicBool>,
+    errors: SharedErrors,
+}
+
+impl Engine {
+    /// 启动引擎线程，在其中创建连接真实设备的控制器
+    pub fn start(mapping: ButtonMapping) -> Result<Self, InputError> {
+        let (created, result) = mpsc::sync_channel(1);
+        let engine = Self::spawn(move || {
+            let controller = MouseController::new().map(|mut controller| {
+                controller.set_button_mapping(mapping);
+                controller
+            });
+            match controller {
+                Ok(controller) => {
+                    tracing::info!("输入引擎已启动");
+                    let _ = created.send(Ok(()));
+                    Some(controller)
+                }
+                Err(e) => {
+                    tracing::error!(error = %e, "无法创建鼠标控制器");
+                    let _ = created.send(Err(e));
+                    None
+                }
+            }
+        });
+        result.recv().map_err(|_| InputError::EngineStopped)??;
+        Ok(engine)
+    }
+
+    /// 只通过 `backend` 注入、不连接真实设备的引擎，见 `MouseController::with_backend`
+    pub fn with_backend(backend: Box<dyn MouseBackend>) -> Self {
+        Self::spawn(move || Some(MouseController::with_backend(backend)))
+    }
+
+    fn spawn(create: impl FnOnce() -> Option<MouseController> + Send + 'static) -> Self {
+        let (jobs, receiver) = mpsc::channel::<Job>();
+        let held_inputs = Arc::new(AtomicBool::new(false));
+        let held = held_inputs.clone();
+        let errors = SharedErrors::default();
+        thread::spawn(move || {
+            let Some(mut controller) = create() else {
+                return;
+            };
+            for job in receiver {
+                // 单个请求 panic 时丢弃它的结果，调用方收到引擎已停止的错误，引擎继续服务其他请求
+                if catch_unwind(AssertUnwindSafe(|| job(&mut controller))).is_err() {
+                    tracing::error!("输入引擎的请求 panic");
+                }
+                held.store(controller.has_held_inputs(), Ordering::Relaxed);
+            }
+            tracing::debug!("输入引擎已退出");
+        });
+        Self { jobs, held_inputs, errors }
+    }
+
+    /// 在引擎线程上用控制器执行 `f` 并等待结果
+    pub fn call<R: Send + 'static>(
+        &self,
+        f: impl FnOnce(&mut MouseController) -> R + Send + 'static,
+    ) -> Result<R, InputError> {
+        let (reply, result) = mpsc::sync_channel(1);
+        self.jobs
+            .send(Box::new(move |controller| {
+                let _ = reply.send(f(controller));
+            }))
+            .map_err(|_| InputError::EngineStopped)?;
+        result.recv().map_err(|_| InputError::EngineStopped)
+    }
+
+    // 执行一次注入，失败时记入最近的错误
+    fn inject(
+        &self,
+        f: impl FnOnce(&mut MouseController) -> Result<(), InputError> + Send + 'static,
+    ) -> Result<(), InputError> {
+        let errors = self.errors.clone();
+        self.call(move |controller| {
+            f(controller).inspect_err(|error| {
+                tracing::warn!(%error, "注入失败");
+                errors.lock().unwrap_or_else(|e| e.into_inner()).push(error.clone());
+            })
+        })?
+    }
+
+    /// 最近的注入失败，界面据此显示错误面板
+    pub fn errors(&self) -> &Arc<Mutex<RecentErrors>> {
+        &self.errors
+    }
+
+    /// 光标位置，相对于所选的显示器
+    pub fn get_mouse_position(&self) -> Result<(i32, i32), InputError> {
+        self.call(|controller| controller.get_mouse_position())
+    }
+
+    /// 当前按下的键盘按键，用于全局快捷键
+    pub fn pressed_keys(&self) -> Result<Vec<Keycode>, InputError> {
+        self.call(|controller| controller.pressed_keys())
+    }
+
+    /// 设备查询库返回的按钮状态，下标含义见 `ButtonMapping`
+    pub fn get_mouse_button_states(&self) -> Result<Vec<bool>, InputError> {
+        self.call(|controller| controller.get_mouse_button_states())
+    }
+
+    /// 按校准的下标判断中键是否按下
+    pub fn is_middle_button_pressed(&self) -> Result<bool, InputError> {
+        self.call(|controller| controller.is_middle_button_pressed())
+    }
+
+    /// 按校准的下标判断右键是否按下
+    pub fn is_right_button_pressed(&self) -> Result<bool, InputError> {
+        self.call(|controller| controller.is_right_button_pressed())
+    }
+
+    /// 所选显示器的尺寸，没有选择时为主显示器的尺寸
+    pub fn get_screen_size(&self) -> Result<(i32, i32), InputError> {
+        self.call(|controller| controller.get_screen_size())?
+    }
+
+    /// 移动到相对于所选显示器的坐标
+    pub fn move_mouse_to(&self, x: i32, y: i32) -> Result<(), InputError> {
+        self.inject(move |controller| controller.move_mouse_to(x, y))
+    }
+
+    /// 平滑移动到目标位置。
+    ///
+    /// 轨迹上的每一步都使用绝对坐标注入，不经过系统的指针速度/加速度曲线
+    /// （Windows 的 SendInput 绝对模式、macOS 的 CGEvent 绝对位置、X11 的 XTest 绝对移动），
+    /// 因此无论用户的鼠标设置如何，路径都不会被放大或缩小。
+    /// 移动结束后再读取真实光标位置校验，若仍有偏差则用绝对移动修正。
+    /// 每一步单独发给引擎，步骤之间在调用方的线程里等待。
+    pub fn move_mouse_smooth(&self, x: i32, y: i32, duration: Duration) -> Result<(), InputError> {
+        let (start_x, start_y) = self.get_mouse_position()?;
+        let steps = (duration.as_millis() / SMOOTH_STEP_MS as u128).max(1) as u32;
+        let step_delay = duration / steps;
+
+        for i in 1..=steps {
+            let t = i as f64 / steps as f64;
+            // 缓入缓出，避免起止时的突变
+            let eased = t * t * (3.0 - 2.0 * t);
+            let cx = start_x + ((x - start_x) as f64 * eased).round() as i32;
+            let cy = start_y + ((y - start_y) as f64 * eased).round() as i32;
+            self.move_mouse_to(cx, cy)?;
+            timing::sleep(step_delay);
+        }
+
+        for _ in 0..SMOOTH_CORRECTION_ATTEMPTS {
+            if self.get_mouse_position()? == (x, y) {
+                break;
+            }
+            self.move_mouse_to(x, y)?;
+            timing::sleep(Duration::from_millis(SMOOTH_STEP_MS));
+        }
+        Ok(())
+    }
+
+    /// 按下并松开鼠标按钮
+    pub fn click(&self, button: Button) -> Result<(), InputError> {
+        self.inject(move |controller| match button {
+            Button::Right => controller.click_right(),
+            Button::Middle => controller.click_middle(),
+            _ => controller.click_left(),
+        })
+    }
+
+    /// 按下按键，直到松开或执行结束时释放
+    pub fn press_key(&self, key: Key) -> Result<(), InputError> {
+        self.inject(move |controller| controller.press_key(key))
+    }
+
+    /// 按下并松开一个按键
+    pub fn tap_key(&self, key: Key) -> Result<(), InputError> {
+        self.inject(move |controller| controller.tap_key(key))
+    }
+
+    /// 垂直滚动，正数向下
+    pub fn scroll(&self, amount: i32) -> Result<(), InputError> {
+        self.inject(move |controller| controller.scroll(amount))
+    }
+
+    /// 释放本工具按下的所有按键和鼠标按钮
+    pub fn release_all(&self) {
+        let errors = self.errors.clone();
+        let _ = self.call(move |controller| {
+            for error in controller.release_all() {
+                tracing::warn!(%error, "释放输入失败");
+                errors.lock().unwrap_or_else(|e| e.into_inner()).push(error);
+            }
+        });
+    }
+
+    /// 是否还有本工具按住的输入，不经过引擎线程
+    pub fn has_held_inputs(&self) -> bool {
+        self.held_inputs.load(Ordering::Relaxed)
+    }
+
+    /// 设置判断按钮状态时使用的下标
+    pub fn set_button_mapping(&self, mapping: ButtonMapping) {
+        let _ = self.call(move |controller| controller.set_button_mapping(mapping));
+    }
+
+    /// 切换注入输入的后端。无法创建时保持使用 enigo 并返回原因
+    pub fn set_backend(&self, backend: Backend) -> Result<(), InputError> {
+        tracing::info!(?backend, "切换输入后端");
+        self.call(move |controller| controller.set_backend(backend))?
+    }
+
+    /// 改用自定义的后端注入，见 `MouseController::set_custom_backend`
+    pub fn set_custom_backend(&self, backend: Box<dyn MouseBackend>) {
+        let _ = self.call(move |controller| controller.set_custom_backend(backend));
+    }
+}
@@ -0,0 +1,141 @@
+// 注入输入的错误
+//
+// 以前控制器的方法返回 `Box<dyn Error>`，调用方要么转换成文字，要么干脆丢弃结果，
+// 后端注入失败时界面上看起来和成功完全一样。现在所有注入和读取的错误都是 `InputError`，
+// 输入引擎在注入失败时把错误记入 `RecentErrors`，界面显示未读的失败次数和错误面板，
+// 即使调用方没有检查返回值也不会被吞掉。
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use enigo::Button;
+
+use crate::backend::Backend;
+use crate::i18n::t;
+use crate::timing;
+
+// 错误面板最多保留的条数，超出后丢弃最早的
+const MAX_RECORDS: usize = 100;
+
+/// 失败的注入动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputAction {
+    Move,
+    Press(Button),
+    Release(Button),
+    Key,
+    Scroll,
+}
+
+impl fmt::Display for InputAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let button = |button: &Button| match button {
+            Button::Right => t!("右键"),
+            Button::Middle => t!("中键"),
+            _ => t!("左键"),
+        };
+        match self {
+            InputAction::Move => f.write_str(t!("移动鼠标")),
+            InputAction::Press(b) => f.write_str(&t!("按下{}", button(b))),
+            InputAction::Release(b) => f.write_str(&t!("松开{}", button(b))),
+            InputAction::Key => f.write_str(t!("按键")),
+            InputAction::Scroll => f.write_str(t!("滚动")),
+        }
+    }
+}
+
+/// 注入或读取输入失败的原因
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum InputError {
+    /// 无法连接系统的输入设备，例如没有显示器或缺少权限
+    #[error("{}", t!("无法连接输入设备: {}", .0))]
+    Connect(String),
+    /// 后端拒绝了一次注入，`backend` 是注入时使用的后端名称
+    #[error("{}", t!("{}失败（{}）: {}", .action, .backend, .reason))]
+    Inject { action: InputAction, backend: &'static str, reason: String },
+    /// 选择的后端无法创建
+    #[error("{}", t!("无法使用 {} 后端: {}", .backend.label(), .reason))]
+    Backend { backend: Backend, reason: String },
+    #[error("{}", t!("无法获取屏幕尺寸: {}", .0))]
+    Screen(String),
+    #[error("{}", t!("输入引擎已停止"))]
+    EngineStopped,
+}
+
+// 界面和执行线程的错误仍以文字传递，`?` 可以直接转换
+impl From<InputError> for String {
+    fn from(error: InputError) -> Self {
+        error.to_string()
+    }
+}
+
+/// 错误面板中的一条，连续相同的错误合并计数
+#[derive(Debug, Clone)]
+pub struct ErrorRecord {
+    /// 最近一次发生的本地时间，`HH:MM:SS`
+    pub time: String,
+    pub error: InputError,
+    pub count: u32,
+}
+
+/// 最近的注入失败，由输入引擎写入，界面读取
+#[derive(Debug, Default)]
+pub struct RecentErrors {
+    records: VecDeque<ErrorRecord>,
+    /// 上次查看错误面板之后新增的失败次数
+    unseen: u32,
+}
+
+/// 输入引擎和界面共享的注入失败记录
+pub type SharedErrors = Arc<Mutex<RecentErrors>>;
+
+impl RecentErrors {
+    /// 记录一次失败，与上一条相同时只增加次数
+    pub fn push(&mut self, error: InputError) {
+        self.unseen += 1;
+        let time = timing::local_time();
+        if let Some(last) = self.records.back_mut()
+            && last.error == error
+        {
+            last.count += 1;
+            last.time = time;
+            return;
+        }
+        if self.records.len() >= MAX_RECORDS {
+            self.records.pop_front();
+        }
+        self.records.push_back(ErrorRecord { time, error, count: 1 });
+    }
+
+    /// 保留的记录，从旧到新
+    pub fn records(&self) -> impl DoubleEndedIterator<Item = &ErrorRecord> {
+        self.records.iter()
+    }
+
+    /// 最新的一条记录
+    pub fn last(&self) -> Option<&ErrorRecord> {
+        self.records.back()
+    }
+
+    /// 上次查看之后新增的失败次数
+    pub fn unseen(&self) -> u32 {
+        self.unseen
+    }
+
+    /// 打开错误面板后清零新增次数
+    pub fn mark_seen(&mut self) {
+        self.unseen = 0;
+    }
+
+    /// 清除所有记录
+    pub fn clear(&mut self) {
+        self.records.clear();
+        self.unseen = 0;
+    }
+
+    /// 每条一行的纯文本
+    pub fn to_text(&self) -> String {
+        self.records.iter().map(|record| format!("{} ×{} {}\n", record.time, record.count, record.error)).collect()
+    }
+}
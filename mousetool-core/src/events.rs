@@ -0,0 +1,44 @@
+// 引擎事件
+//
+// 执行中的点击、触发步骤的触发、执行结束和运行状态变化广播给所有订阅者，例如控制接口
+// 通过 WebSocket 把它们实时推送给仪表盘。没有订阅者时事件直接丢弃，不影响执行；
+// 订阅者处理得太慢时丢失最旧的事件，而不是拖慢执行线程。
+
+use std::sync::OnceLock;
+
+use tokio::sync::broadcast;
+
+use crate::app_state::AppState;
+
+// 每个订阅者最多积压的事件数
+const CAPACITY: usize = 256;
+
+/// 执行中广播的事件
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Event {
+    /// 执行了一次点击，`ok` 为 false 表示注入失败
+    Click { x: i32, y: i32, ok: bool },
+    /// 插件的触发步骤报告条件已经满足
+    Trigger { plugin: String, step: String },
+    /// 一次执行结束
+    RunFinished { clicks: u32, errors: u32, stopped: bool, aborted: Option<String> },
+    /// 运行状态变化
+    State { state: AppState },
+}
+
+fn sender() -> &'static broadcast::Sender<Event> {
+    static SENDER: OnceLock<broadcast::Sender<Event>> = OnceLock::new();
+    SENDER.get_or_init(|| broadcast::channel(CAPACITY).0)
+}
+
+/// 广播一个事件，没有订阅者时直接丢弃
+pub fn emit(event: Event) {
+    // 没有订阅者时返回错误，忽略即可
+    let _ = sender().send(event);
+}
+
+/// 订阅之后发生的事件
+pub fn subscribe() -> broadcast::Receiver<Event> {
+    sender().subscribe()
+}
@@ -0,0 +1,192 @@
+// 把点击序列导出为其他工具可以直接执行的脚本
+
+use std::fmt::Write as _;
+use std::time::Duration;
+
+use crate::ClickType;
+use crate::expr::Expr;
+use crate::i18n::t;
+use crate::sequence::{CLICKS_VAR, ITERATION_VAR, NAMED_KEYS, Sequence, StepKind};
+
+// xdotool 的按钮编号：1=左键, 2=中键, 3=右键
+fn xdotool_button(click_type: ClickType) -> u8 {
+    match click_type {
+        ClickType::Left => 1,
+        ClickType::Middle => 2,
+        ClickType::Right => 3,
+    }
+}
+
+// 按键步骤在 xdotool 中的 keysym 名称；字母和数字直接使用，其他字符写成 Unicode keysym（如 `U003B`）
+fn xdotool_key(name: &str) -> String {
+    let name = name.trim();
+    if let Some((_, _, keysym)) = NAMED_KEYS.iter().find(|(n, ..)| n.eq_ignore_ascii_case(name)) {
+        return keysym.to_string();
+    }
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_alphanumeric() => c.to_string(),
+        (Some(c), None) => format!("U{:04X}", c as u32),
+        // `compile` 已经拒绝了其他写法
+        _ => name.to_string(),
+    }
+}
+
+// 用单引号包住写入脚本的字符串，内容中的单引号写成 `'\''`
+fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
+
+// 坐标：没有表达式时直接写数字，否则交给 bash 算术展开
+fn shell_coordinate(expr: &Option<Expr>, literal: i32) -> String {
+    match expr {
+        Some(expr) => format!("$(( {} ))", expr.to_shell()),
+        None => literal.to_string(),
+    }
+}
+
+/// 生成等价的 bash + xdotool 脚本。
+///
+/// 与工具内的执行方式一致：序列循环执行，累计点击 `max_clicks` 次后结束。
+/// 序列中有找色、找图或插件步骤时返回错误。
+/// 变量和表达式转换为 bash 的整数运算，小数会被取整。变量名由 `Sequence::compile` 检查，
+/// 其他写入脚本的字符串都加上引号。
+/// `multi_click_gap` 是双击/三击中相邻两下的间隔。
+pub fn xdotool_script(sequence: &Sequence, max_clicks: u32, multi_click_gap: Duration) -> Result<String, String> {
+    let compiled = sequence.compile()?;
+
+    let mut script = String::new();
+    let _ = writeln!(script, "#!/usr/bin/env bash");
+    let _ = writeln!(script, "# 由 mouseTool 导出的点击序列，需要安装 xdotool");
+    let _ = writeln!(script, "set -e");
+    let _ = writeln!(script);
+    let _ = writeln!(script, "command -v xdotool >/dev/null || {{ echo \"xdotool not found\" >&2; exit 1; }}");
+    let _ = writeln!(script);
+    let _ = writeln!(script, "max_clicks={}", max_clicks);
+    let _ = writeln!(script, "{}=0", CLICKS_VAR);
+    let _ = writeln!(script, "{}=0", ITERATION_VAR);
+    for variable in &sequence.variables {
+        let _ = writeln!(script, "{}={}", variable.name, variable.value.round() as i64);
+    }
+    let _ = writeln!(script);
+
+    if sequence.steps.is_empty() {
+        return Ok(script);
+    }
+
+    let _ = writeln!(script, "while true; do");
+    let depths = sequence.depths();
+    for (i, (step, compiled)) in sequence.steps.iter().zip(&compiled).enumerate() {
+        let indent = "    ".repeat(depths[i] + 1);
+        match step.kind {
+            StepKind::LoopStart => {
+                // 每层循环用各自的计数器，避免嵌套时互相覆盖
+                let counter = format!("_loop{}", depths[i]);
+                let _ = writeln!(script, "{}# 步骤 {}: 重复 {} 次", indent, i + 1, step.repeat);
+                let _ = writeln!(
+                    script,
+                    "{0}for (( {1} = 0; {1} < {2}; {1}++ )); do",
+                    indent, counter, step.repeat
+                );
+                if !step.loop_var.is_empty() {
+                    let _ = writeln!(script, "{}    {}=${}", indent, step.loop_var, counter);
+                }
+            }
+            StepKind::LoopEnd => {
+                let _ = writeln!(script, "{}done", indent);
+            }
+            StepKind::Comment => {
+                let _ = writeln!(script, "{}# {}", indent, step.comment.replace(['\r', '\n'], " "));
+            }
+            _ => {
+                let _ = writeln!(script, "{}# 步骤 {}: {}", indent, i + 1, step.kind.label());
+                match step.kind {
+                    StepKind::Click => {
+                        let _ = writeln!(script, "{}[ \"${}\" -ge \"$max_clicks\" ] && exit 0", indent, CLICKS_VAR);
+                        let _ = writeln!(
+                            script,
+                            "{}xdotool mousemove {} {}",
+                            indent,
+                            shell_coordinate(&compiled.x, step.x),
+                            shell_coordinate(&compiled.y, step.y)
+                        );
+                        let _ = writeln!(script, "{}sleep 0.01", indent);
+                        let _ = writeln!(
+                            script,
+                            "{}xdotool click --repeat {} --delay {} {}",
+                            indent,
+                            step.multi_click.count(),
+                            multi_click_gap.as_millis(),
+                            xdotool_button(step.click_type)
+                        );
+                        let _ = writeln!(script, "{0}{1}=$(({1} + 1))", indent, CLICKS_VAR);
+                    }
+                    StepKind::Move => {
+                        let _ = writeln!(
+                            script,
+                            "{}xdotool mousemove {} {}",
+                            indent,
+                            shell_coordinate(&compiled.x, step.x),
+                            shell_coordinate(&compiled.y, step.y)
+                        );
+                    }
+                    // xdotool 中 4=向上滚动，5=向下滚动
+                    StepKind::Scroll if step.scroll != 0 => {
+                        let button = if step.scroll > 0 { 5 } else { 4 };
+                        let _ = writeln!(
+                            script,
+                            "{}xdotool click --repeat {} {}",
+                            indent,
+                            step.scroll.unsigned_abs(),
+                            button
+                        );
+                    }
+                    StepKind::Key => {
+                        let _ = writeln!(script, "{}xdotool key {}", indent, shell_quote(&xdotool_key(&step.key)));
+                    }
+                    StepKind::Wait | StepKind::Scroll => {}
+                    // 找色、找图和插件步骤没有对应的 xdotool 命令，不能悄悄跳过
+                    kind => return Err(t!("步骤 {}: xdotool 脚本不支持「{}」步骤", i + 1, kind.label())),
+                }
+                for assignment in &compiled.update {
+                    let _ = writeln!(script, "{}{}=$(( {} ))", indent, assignment.name, assignment.value.to_shell());
+                }
+                let _ = writeln!(script, "{}sleep {}", indent, step.dwell);
+            }
+        }
+    }
+    let _ = writeln!(script, "    {0}=$(({0} + 1))", ITERATION_VAR);
+    let _ = writeln!(script, "done");
+
+    Ok(script)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sequence::SequenceStep;
+
+    fn script(steps: Vec<SequenceStep>) -> Result<String, String> {
+        xdotool_script(&Sequence { steps, variables: Vec::new() }, 5, Duration::from_millis(50))
+    }
+
+    #[test]
+    fn writes_keys_as_quoted_keysyms() {
+        let key = |key: &str| SequenceStep { key: key.to_string(), ..SequenceStep::new(StepKind::Key, 0, 0) };
+        let script = script(vec![SequenceStep::new(StepKind::Click, 1, 2), key("enter"), key("'"), key("a")]).unwrap();
+        assert!(script.contains("xdotool key 'Return'\n"));
+        assert!(script.contains("xdotool key 'U0027'\n"));
+        assert!(script.contains("xdotool key 'a'\n"));
+    }
+
+    #[test]
+    fn refuses_steps_without_an_xdotool_equivalent() {
+        let steps = vec![SequenceStep::new(StepKind::Click, 1, 2), SequenceStep::new(StepKind::WaitColor, 1, 2)];
+        assert_eq!(script(steps).err(), Some(t!("步骤 {}: xdotool 脚本不支持「{}」步骤", 2, StepKind::WaitColor.label())));
+    }
+
+    #[test]
+    fn quotes_strings_for_the_shell() {
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+    }
+}
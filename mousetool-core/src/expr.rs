@@ -0,0 +1,274 @@
+// 序列步骤中使用的简单表达式
+//
+// 支持数字、变量名、四则运算和括号，例如 `x0 + 30 * i`。
+// 变量名只能使用 ASCII 字母、数字和下划线，且不以数字开头，导出的 bash 脚本中可以直接使用。
+// 步骤执行后的更新语句是用分号分隔的赋值，例如 `k = k + 1; y0 = y0 - 5`。
+
+use std::collections::HashMap;
+
+use crate::i18n::t;
+
+/// 变量名到当前值的映射
+pub type Variables = HashMap<String, f64>;
+
+/// 解析好的表达式
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    Var(String),
+    Neg(Box<Expr>),
+    Binary(Box<Expr>, char, Box<Expr>),
+}
+
+/// 一条 `变量 = 表达式` 赋值
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assignment {
+    pub name: String,
+    pub value: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Op(char),
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut number = String::new();
+            while let Some(&c) = chars.peek().filter(|c| c.is_ascii_digit() || **c == '.') {
+                number.push(c);
+                chars.next();
+            }
+            let value = number.parse().map_err(|_| t!("无效的数字: {}", number))?;
+            tokens.push(Token::Number(value));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let mut ident = String::new();
+            while let Some(&c) = chars.peek().filter(|c| c.is_ascii_alphanumeric() || **c == '_') {
+                ident.push(c);
+                chars.next();
+            }
+            tokens.push(Token::Ident(ident));
+        } else if "+-*/()=".contains(c) {
+            tokens.push(Token::Op(c));
+            chars.next();
+        } else {
+            return Err(t!("无法识别的字符: {}", c));
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat_op(&mut self, op: char) -> bool {
+        if self.peek() == Some(&Token::Op(op)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    // expr := term (('+' | '-') term)*
+    fn expr(&mut self) -> Result<Expr, String> {
+        let mut left = self.term()?;
+        loop {
+            let op = if self.eat_op('+') {
+                '+'
+            } else if self.eat_op('-') {
+                '-'
+            } else {
+                return Ok(left);
+            };
+            left = Expr::Binary(Box::new(left), op, Box::new(self.term()?));
+        }
+    }
+
+    // term := factor (('*' | '/') factor)*
+    fn term(&mut self) -> Result<Expr, String> {
+        let mut left = self.factor()?;
+        loop {
+            let op = if self.eat_op('*') {
+                '*'
+            } else if self.eat_op('/') {
+                '/'
+            } else {
+                return Ok(left);
+            };
+            left = Expr::Binary(Box::new(left), op, Box::new(self.factor()?));
+        }
+    }
+
+    // factor := number | ident | '-' factor | '(' expr ')'
+    fn factor(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(Expr::Number(value)),
+            Some(Token::Ident(name)) => Ok(Expr::Var(name)),
+            Some(Token::Op('-')) => Ok(Expr::Neg(Box::new(self.factor()?))),
+            Some(Token::Op('(')) => {
+                let inner = self.expr()?;
+                if !self.eat_op(')') {
+                    return Err(t!("缺少右括号").to_string());
+                }
+                Ok(inner)
+            }
+            Some(Token::Op(op)) => Err(t!("意外的符号: {}", op)),
+            None => Err(t!("表达式不完整").to_string()),
+        }
+    }
+
+    fn finish(&self) -> Result<(), String> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(t!("表达式末尾有多余内容").to_string()),
+        }
+    }
+}
+
+/// 是否是有效的变量名：`[A-Za-z_][A-Za-z0-9_]*`
+pub fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// 解析一个表达式，语法错误时返回原因
+pub fn parse(input: &str) -> Result<Expr, String> {
+    let mut parser = Parser { tokens: tokenize(input)?, pos: 0 };
+    let expr = parser.expr()?;
+    parser.finish()?;
+    Ok(expr)
+}
+
+/// 解析以分号分隔的多条赋值，空的部分会被忽略
+pub fn parse_assignments(input: &str) -> Result<Vec<Assignment>, String> {
+    input
+        .split(';')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut parser = Parser { tokens: tokenize(part)?, pos: 0 };
+            let Some(Token::Ident(name)) = parser.next() else {
+                return Err(t!("赋值语句必须以变量名开头: {}", part));
+            };
+            if !parser.eat_op('=') {
+                return Err(t!("赋值语句缺少 '=': {}", part));
+            }
+            let value = parser.expr()?;
+            parser.finish()?;
+            Ok(Assignment { name, value })
+        })
+        .collect()
+}
+
+impl Expr {
+    /// 用 `vars` 中的变量计算表达式的值，变量未定义或除以 0 时返回原因
+    pub fn eval(&self, vars: &Variables) -> Result<f64, String> {
+        match self {
+            Expr::Number(value) => Ok(*value),
+            Expr::Var(name) => vars.get(name).copied().ok_or_else(|| t!("未定义的变量: {}", name)),
+            Expr::Neg(inner) => Ok(-inner.eval(vars)?),
+            Expr::Binary(left, op, right) => {
+                let (l, r) = (left.eval(vars)?, right.eval(vars)?);
+                match op {
+                    '+' => Ok(l + r),
+                    '-' => Ok(l - r),
+                    '*' => Ok(l * r),
+                    _ if r == 0.0 => Err(t!("除数为零").to_string()),
+                    _ => Ok(l / r),
+                }
+            }
+        }
+    }
+
+    /// 转换为 bash 算术表达式 `$(( ... ))` 内的写法（整数运算）
+    pub fn to_shell(&self) -> String {
+        match self {
+            Expr::Number(value) => format!("{}", value.round() as i64),
+            Expr::Var(name) => name.clone(),
+            Expr::Neg(inner) => format!("-({})", inner.to_shell()),
+            Expr::Binary(left, op, right) => format!("({} {} {})", left.to_shell(), op, right.to_shell()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, f64)]) -> Variables {
+        pairs.iter().map(|(name, value)| (name.to_string(), *value)).collect()
+    }
+
+    #[test]
+    fn evaluates_with_precedence_and_parentheses() {
+        let vars = vars(&[("x0", 100.0), ("i", 2.0)]);
+        assert_eq!(parse("x0 + 30 * i").unwrap().eval(&vars), Ok(160.0));
+        assert_eq!(parse("(x0 + 30) * i").unwrap().eval(&vars), Ok(260.0));
+        assert_eq!(parse("-i - -3").unwrap().eval(&vars), Ok(1.0));
+        assert_eq!(parse("7 / 2").unwrap().eval(&vars), Ok(3.5));
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        for input in ["", "1 +", "(1 + 2", "1 2", "1 $ 2", "1..2"] {
+            assert!(parse(input).is_err(), "{:?} 应当解析失败", input);
+        }
+    }
+
+    #[test]
+    fn rejects_non_ascii_identifiers() {
+        assert!(parse("变量 + 1").is_err());
+        assert!(parse_assignments("计数 = 1").is_err());
+        assert!(is_identifier("_loop0"));
+        assert!(!is_identifier("0x"));
+        assert!(!is_identifier("a-b"));
+        assert!(!is_identifier(""));
+    }
+
+    #[test]
+    fn eval_reports_undefined_variables_and_division_by_zero() {
+        let vars = vars(&[("k", 0.0)]);
+        assert!(parse("missing + 1").unwrap().eval(&vars).is_err());
+        assert!(parse("1 / k").unwrap().eval(&vars).is_err());
+    }
+
+    #[test]
+    fn parses_assignments_and_skips_empty_parts() {
+        let assignments = parse_assignments("k = k + 1; ; y0 = y0 - 5;").unwrap();
+        assert_eq!(assignments.len(), 2);
+        assert_eq!(assignments[0].name, "k");
+        assert_eq!(assignments[1].value.eval(&vars(&[("y0", 20.0)])), Ok(15.0));
+        assert!(parse_assignments("1 = k").is_err());
+        assert!(parse_assignments("k + 1").is_err());
+    }
+
+    #[test]
+    fn converts_to_shell_arithmetic() {
+        assert_eq!(parse("x0 + 30 * -i").unwrap().to_shell(), "(x0 + (30 * -(i)))");
+        assert_eq!(parse("2.6").unwrap().to_shell(), "3");
+    }
+}
@@ -0,0 +1,130 @@
+// 自适应点击间隔
+//
+// 固定间隔要么太短（目标程序还没处理完上一下就点了下一下），要么太长（白白等待）。
+// 开启后，每次点击前记录反馈区域的像素，点击后等到区域发生变化，说明目标界面已经响应，
+// 再进行下一步；等待时间限制在设定的最短和最长时间之间，超过最长时间仍未变化时照常继续。
+// 读取屏幕像素目前只支持 Windows。
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::app_state::SharedState;
+use crate::display;
+
+// 检查反馈区域的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+/// 反馈区域边长的上限，逐像素读取，区域过大会拖慢检测
+pub const MAX_REGION_SIZE: u32 = 16;
+
+/// 当前平台无法读取屏幕像素时显示的提示
+pub const UNSUPPORTED_MESSAGE: &str = "当前平台不支持读取屏幕像素，无法使用自适应间隔";
+
+/// 自适应间隔的设置：点击后等待屏幕上的反馈区域发生变化
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdaptiveInterval {
+    pub enabled: bool,
+    /// 反馈区域的中心
+    pub x: i32,
+    pub y: i32,
+    /// 反馈区域的边长（像素）
+    pub size: u32,
+    pub min_ms: u64,
+    pub max_ms: u64,
+}
+
+impl Default for AdaptiveInterval {
+    fn default() -> Self {
+        Self { enabled: false, x: 100, y: 100, size: 5, min_ms: 50, max_ms: 2000 }
+    }
+}
+
+/// 读取屏幕上一块区域的像素（COLORREF 格式，逐行排列），无法读取时返回 None。
+/// 坐标相对于所选的显示器
+pub fn read_pixels(left: i32, top: i32, width: i32, height: i32) -> Option<Vec<u32>> {
+    let (left, top) = display::to_global(left, top);
+    platform::read_pixels(left, top, width, height)
+}
+
+/// 等待的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitOutcome {
+    /// 反馈区域发生了变化
+    Changed,
+    /// 到达最长等待时间仍未变化
+    TimedOut,
+    /// 等待期间执行被停止
+    Stopped,
+}
+
+impl AdaptiveInterval {
+    /// 当前平台是否能读取反馈区域的像素
+    pub fn is_supported() -> bool {
+        cfg!(target_os = "windows")
+    }
+
+    /// 读取反馈区域的像素，无法读取时返回 None
+    pub fn sample(&self) -> Option<Vec<u32>> {
+        let size = self.size.clamp(1, MAX_REGION_SIZE) as i32;
+        let left = self.x - size / 2;
+        let top = self.y - size / 2;
+        read_pixels(left, top, size, size)
+    }
+
+    /// 点击后等待反馈区域与 `baseline` 不同
+    pub fn wait_for_change(&self, baseline: &[u32], state: &SharedState) -> WaitOutcome {
+        let start = Instant::now();
+        let min = Duration::from_millis(self.min_ms);
+        let max = Duration::from_millis(self.max_ms.max(self.min_ms));
+        let mut changed = false;
+        loop {
+            if !state.is_running() {
+                return WaitOutcome::Stopped;
+            }
+            let elapsed = start.elapsed();
+            // 变化之后也要等够最短时间，避免界面还在过渡时就点击
+            if changed && elapsed >= min {
+                return WaitOutcome::Changed;
+            }
+            if elapsed >= max {
+                return WaitOutcome::TimedOut;
+            }
+            if !changed {
+                changed = self.sample().is_some_and(|pixels| pixels != baseline);
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows_sys::Win32::Graphics::Gdi::{CLR_INVALID, GetDC, GetPixel, ReleaseDC};
+
+    pub fn read_pixels(left: i32, top: i32, width: i32, height: i32) -> Option<Vec<u32>> {
+        unsafe {
+            let dc = GetDC(std::ptr::null_mut());
+            if dc.is_null() {
+                return None;
+            }
+            let mut pixels = Vec::with_capacity((width * height) as usize);
+            for y in top..top + height {
+                for x in left..left + width {
+                    pixels.push(GetPixel(dc, x, y));
+                }
+            }
+            ReleaseDC(std::ptr::null_mut(), dc);
+            // 区域在屏幕外时读不到像素
+            (!pixels.iter().all(|&p| p == CLR_INVALID)).then_some(pixels)
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    pub fn read_pixels(_left: i32, _top: i32, _width: i32, _height: i32) -> Option<Vec<u32>> {
+        None
+    }
+}
@@ -0,0 +1,350 @@
+// 界面语言
+//
+// 界面文字直接以中文原文作为键，通过 `t!` 查找当前语言的译文，没有译文时显示原文：
+//
+// ```ignore
+// ui.label(t!("点击间隔:"));
+// self.status_message = t!("已切换到配置「{}」", name);
+// ```
+//
+// 带参数时译文中的 `{}` 按顺序替换为参数，也可以用 `{0}`、`{1}` 指定参数的位置，
+// 以适应不同语言的语序。参数需要特殊格式（如保留一位小数）时先格式化成字符串再传入。
+// 控制台输出（命令行模式的提示和错误）不翻译。
+//
+// 这里只带有本库自己的文字的译文。使用本库的界面程序用 `register_english` 注册自己的译文表，
+// 之后它的 `t!` 也能查到。
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+/// 界面语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Language {
+    Chinese,
+    English,
+}
+
+impl Language {
+    /// 界面中按此顺序列出
+    pub const ALL: [Language; 2] = [Language::Chinese, Language::English];
+
+    /// 语言名称总是用该语言本身显示，切换错了也能找回来
+    pub fn label(self) -> &'static str {
+        match self {
+            Language::Chinese => "中文",
+            Language::English => "English",
+        }
+    }
+
+    /// 由系统语言标签选择界面语言：中文系统使用中文，其他使用英文
+    pub fn from_locale(locale: Option<&str>) -> Self {
+        match locale {
+            Some(locale) if !locale.to_ascii_lowercase().starts_with("zh") => Language::English,
+            _ => Language::Chinese,
+        }
+    }
+}
+
+static LANGUAGE: AtomicU8 = AtomicU8::new(0);
+// 调用方注册的译文，查找时优先于本库的译文
+static REGISTERED_ENGLISH: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+
+/// 设置 `tr` 和 `t!` 使用的界面语言
+pub fn set_language(language: Language) {
+    LANGUAGE.store(language as u8, Ordering::Relaxed);
+}
+
+/// 当前的界面语言
+pub fn language() -> Language {
+    match LANGUAGE.load(Ordering::Relaxed) {
+        1 => Language::English,
+        _ => Language::Chinese,
+    }
+}
+
+fn english() -> &'static HashMap<&'static str, &'static str> {
+    static TABLE: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+    TABLE.get_or_init(|| ENGLISH.iter().copied().collect())
+}
+
+/// 注册调用方自己的英文译文，键是中文原文。只能注册一次，重复调用时返回 false；
+/// 应在显示任何文字之前调用
+pub fn register_english(table: &'static [(&'static str, &'static str)]) -> bool {
+    REGISTERED_ENGLISH.set(table.iter().copied().collect()).is_ok()
+}
+
+/// 当前语言下的文字，没有译文时返回原文
+pub fn tr(text: &'static str) -> &'static str {
+    match language() {
+        Language::Chinese => text,
+        Language::English => REGISTERED_ENGLISH
+            .get()
+            .and_then(|table| table.get(text))
+            .or_else(|| english().get(text))
+            .copied()
+            .unwrap_or(text),
+    }
+}
+
+/// 把参数填入 `{}` 或 `{n}` 占位符，`{{` 和 `}}` 表示花括号本身
+pub fn fill(template: &str, args: &[String]) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut next = 0;
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                result.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                result.push('}');
+            }
+            '{' => {
+                let mut index = String::new();
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        break;
+                    }
+                    index.push(c);
+                }
+                let index = if index.is_empty() {
+                    next += 1;
+                    next - 1
+                } else {
+                    index.parse().unwrap_or(usize::MAX)
+                };
+                if let Some(arg) = args.get(index) {
+                    result.push_str(arg);
+                }
+            }
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// 翻译界面文字，带参数时返回填好参数的 `String`。
+/// 导出到库的根上供界面程序使用，通过 `i18n::t` 引用；界面程序的译文用 `register_english` 注册
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __t {
+    ($text:literal) => {
+        $crate::i18n::tr($text)
+    };
+    ($text:literal, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::fill($crate::i18n::tr($text), &[$($arg.to_string()),+])
+    };
+}
+pub use crate::__t as t;
+
+// 英文译文，按模块分组
+const ENGLISH: &[(&str, &str)] = &[
+    // lib.rs
+    ("单击", "Single"),
+    ("双击", "Double"),
+    ("三击", "Triple"),
+    // app_state.rs
+    ("捕捉坐标中", "Capturing"),
+    ("倒计时", "Countdown"),
+    ("正在停止", "Stopping"),
+    ("空闲", "Idle"),
+    ("正在执行", "Running"),
+    ("已暂停", "Paused"),
+    // backend.rs
+    ("enigo（默认）", "enigo (default)"),
+    ("SendInput 扫描码", "SendInput scan codes"),
+    ("Windows 使用 SendInput 虚拟键码，macOS 使用 CGEvent，Linux 使用 XTest（必要时临时修改键盘映射）", "Uses SendInput virtual-key codes on Windows, CGEvent on macOS and XTest on Linux (temporarily remapping keys when needed)"),
+    ("直接调用 SendInput，按键以扫描码发送，通过 DirectInput 读取键盘的游戏也能收到", "Calls SendInput directly and sends keys as scan codes, so games that read the keyboard through DirectInput receive them too"),
+    ("直接使用 XTest 扩展，只使用现有的键盘映射，键盘布局里没有的字符无法输入", "Uses the XTest extension directly with the existing keyboard mapping only; characters missing from the layout cannot be typed"),
+    ("uinput 通过内核创建虚拟设备，在 Wayland 和忽略 XTest 的游戏中同样有效", "uinput creates virtual devices in the kernel and also works under Wayland and in games that ignore XTest"),
+    ("Interception 驱动", "Interception driver"),
+    ("通过 Interception 内核驱动注入，输入和真实设备发出的一样，可以绕过过滤 SendInput 的反作弊", "Injects through the Interception kernel driver; input is indistinguishable from real devices and passes anti-cheat that filters SendInput"),
+    ("当前平台不支持 {} 后端", "The {} backend is not supported on this platform"),
+    ("SendInput 失败: {}", "SendInput failed: {}"),
+    ("{} 后端不支持按钮 {}", "The {} backend does not support the {} button"),
+    ("{} 后端不支持按键 {}", "The {} backend does not support the {} key"),
+    ("无法连接 X 服务器: {}", "Cannot connect to the X server: {}"),
+    ("X 服务器没有 XTest 扩展", "The X server has no XTest extension"),
+    ("无法读取键盘映射: {}", "Cannot read the keyboard mapping: {}"),
+    ("XTest 注入失败: {}", "XTest injection failed: {}"),
+    ("坐标 ({}, {}) 超出 X11 的范围", "Coordinates ({}, {}) are outside the X11 range"),
+    // button_map.rs
+    ("左键", "Left button"),
+    ("右键", "Right button"),
+    ("中键", "Middle button"),
+    // capture.rs
+    ("、", ", "),
+    ("截图区域的宽和高必须大于 0", "The capture region's width and height must be greater than 0"),
+    ("找不到显示器", "No display found"),
+    ("无法访问屏幕", "Cannot access the screen"),
+    ("复制屏幕内容失败", "Failed to copy the screen contents"),
+    ("无法读取截图: {}", "Cannot read the screenshot: {}"),
+    ("screencapture 截图失败，请在「系统设置 → 隐私与安全性 → 屏幕录制」中允许本程序", "screencapture failed; allow this program under System Settings → Privacy & Security → Screen Recording"),
+    ("不支持的屏幕像素格式", "Unsupported screen pixel format"),
+    ("读取屏幕像素失败: {}", "Failed to read screen pixels: {}"),
+    ("找不到可用的截图工具（{}）", "No screenshot tool found ({})"),
+    // color.rs
+    ("RGB（逐通道）", "RGB (per channel)"),
+    ("HSV（只看色相）", "HSV (hue only)"),
+    // cross_platform_mouse.rs
+    ("这个控制器只能使用创建时指定的后端", "This controller can only use the backend it was created with"),
+    ("这个控制器没有连接显示器", "This controller is not connected to a display"),
+    ("自定义后端", "custom backend"),
+    // display.rs
+    ("({}, {}) 不在任何显示器上", "({}, {}) is not on any display"),
+    ("（主）", " (primary)"),
+    // error.rs
+    ("输入引擎已停止", "The input engine has stopped"),
+    ("移动鼠标", "Move mouse"),
+    ("按下{}", "Press {}"),
+    ("松开{}", "Release {}"),
+    ("无法连接输入设备: {}", "Cannot connect to the input devices: {}"),
+    ("{}失败（{}）: {}", "{} failed ({}): {}"),
+    ("无法使用 {} 后端: {}", "Cannot use the {} backend: {}"),
+    ("无法获取屏幕尺寸: {}", "Cannot get the screen size: {}"),
+    // export.rs
+    ("步骤 {}: xdotool 脚本不支持「{}」步骤", "Step {}: xdotool scripts do not support \"{}\" steps"),
+    // expr.rs
+    ("无效的数字: {}", "Invalid number: {}"),
+    ("无法识别的字符: {}", "Unrecognized character: {}"),
+    ("缺少右括号", "Missing closing parenthesis"),
+    ("意外的符号: {}", "Unexpected token: {}"),
+    ("表达式不完整", "Incomplete expression"),
+    ("表达式末尾有多余内容", "Unexpected content at the end of the expression"),
+    ("赋值语句必须以变量名开头: {}", "An assignment must start with a variable name: {}"),
+    ("赋值语句缺少 '=': {}", "Assignment is missing '=': {}"),
+    ("未定义的变量: {}", "Undefined variable: {}"),
+    ("除数为零", "Division by zero"),
+    // feedback.rs
+    ("当前平台不支持读取屏幕像素，无法使用自适应间隔", "This platform cannot read screen pixels, so the adaptive interval is unavailable"),
+    // import.rs
+    ("无法读取文件: {}", "Cannot read the file: {}"),
+    // interception.rs
+    ("Interception 是第三方内核驱动：安装和卸载需要管理员权限并重启电脑，驱动出错时可能导致键盘和鼠标失灵；部分反作弊会检测这个驱动并封禁账号，请自行承担风险", "Interception is a third-party kernel driver: installing and removing it needs administrator rights and a reboot, a driver fault can leave the keyboard and mouse unusable, and some anti-cheat systems detect the driver and ban accounts. Use at your own risk"),
+    ("从 https://github.com/oblitum/Interception/releases 下载驱动，以管理员身份在命令提示符中运行 install-interception.exe /install，重启电脑，然后把 library 目录中与本程序位数相同的 interception.dll 复制到本程序所在的目录", "Download the driver from https://github.com/oblitum/Interception/releases, run install-interception.exe /install in an administrator command prompt, reboot, then copy the interception.dll matching this program's bitness from the library folder next to this program"),
+    ("找不到 interception.dll，请把它复制到本程序所在的目录", "interception.dll not found; copy it next to this program"),
+    ("interception.dll 缺少需要的函数，请使用驱动附带的版本", "interception.dll lacks the required functions; use the version shipped with the driver"),
+    ("Interception 驱动没有安装或没有运行，安装后需要重启电脑", "The Interception driver is not installed or not running; reboot after installing it"),
+    ("Interception 注入失败", "Interception injection failed"),
+    // jiggler.rs
+    ("微动鼠标", "Nudge the mouse"),
+    ("按 F15 键", "Press F15"),
+    // keep_awake.rs
+    ("SetThreadExecutionState 调用失败", "SetThreadExecutionState failed"),
+    ("mouseTOOL 正在执行自动点击", "mouseTOOL is auto-clicking"),
+    ("创建电源断言失败（错误码 {}）", "Failed to create the power assertion (error {})"),
+    ("无法连接到会话总线: {}", "Cannot connect to the session bus: {}"),
+    ("正在执行自动点击", "Auto-clicking in progress"),
+    ("桌面环境不支持 org.freedesktop.ScreenSaver: {}", "The desktop does not support org.freedesktop.ScreenSaver: {}"),
+    // logging.rs
+    ("错误", "Error"),
+    ("警告", "Warning"),
+    ("信息", "Info"),
+    ("调试", "Debug"),
+    ("全部", "Trace"),
+    // macro_file.rs
+    ("JSON 格式错误: {}", "Invalid JSON: {}"),
+    ("TOML 格式错误: {}", "Invalid TOML: {}"),
+    ("无法写入文件: {}", "Cannot write the file: {}"),
+    ("文件格式版本 {} 比当前程序支持的版本 {} 更新，请升级程序", "File format version {} is newer than the supported version {}; please update the program"),
+    ("不支持的文件格式版本: {}", "Unsupported file format version: {}"),
+    // paths.rs
+    ("跨平台鼠标点击工具", "Cross-platform mouse clicker"),
+    // permissions.rs
+    ("辅助功能", "Accessibility"),
+    ("输入监控", "Input Monitoring"),
+    ("模拟鼠标点击、移动和按键", "Simulating mouse clicks, movement and keys"),
+    ("全局快捷键和按键检测", "Global hotkeys and key detection"),
+    ("无法打开系统设置: {}", "Cannot open System Settings: {}"),
+    // plugins.rs
+    ("缺少函数 {}", "Missing function {}"),
+    ("插件没有提供清单", "The plugin provides no manifest"),
+    ("插件清单格式错误: {}", "Invalid plugin manifest: {}"),
+    ("插件接口版本 {} 与本程序支持的版本 {} 不同", "Plugin API version {} differs from the supported version {}"),
+    ("插件没有返回结果", "The plugin returned no result"),
+    ("插件返回的结果格式错误: {}", "Invalid plugin result: {}"),
+    ("插件执行失败", "The plugin failed"),
+    ("已经加载了同名的插件 {}", "A plugin named {} is already loaded"),
+    ("插件 {} 没有安装", "Plugin {} is not installed"),
+    ("插件 {} 没有步骤 {}", "Plugin {} has no step {}"),
+    // power.rs
+    ("电池电量 {}% 低于 {}%", "Battery {}% below {}%"),
+    ("CPU 占用 {}% 高于 {}%", "CPU usage {}% above {}%"),
+    // secure_desktop.rs
+    ("无法获取程序路径: {}", "Cannot get the program path: {}"),
+    ("({}, {}) 处的窗口以管理员权限运行，普通权限下无法向它发送点击，请以管理员身份运行本工具", "The window at ({}, {}) runs as administrator and cannot receive clicks from a normal process; run this tool as administrator"),
+    ("当前处于 Windows 安全桌面（UAC 提示、锁屏或 Ctrl+Alt+Del 界面），系统禁止在这里模拟鼠标和键盘", "Windows is on the secure desktop (UAC prompt, lock screen or Ctrl+Alt+Del screen), where simulating mouse and keyboard input is not allowed"),
+    ("已取消以管理员身份启动", "Restarting as administrator was cancelled"),
+    ("无法以管理员身份启动: {}", "Cannot start as administrator: {}"),
+    ("只有 Windows 支持以管理员身份重新启动", "Restarting as administrator is only supported on Windows"),
+    // sequence.rs
+    ("点击", "Click"),
+    ("等待", "Wait"),
+    ("移动", "Move"),
+    ("滚动", "Scroll"),
+    ("按键", "Key"),
+    ("等待颜色", "Wait for color"),
+    ("点击图片", "Click image"),
+    ("点击所有匹配", "Click all matches"),
+    ("等待图片", "Wait for image"),
+    ("跳过，继续下一步", "Skip to the next step"),
+    ("中止执行", "Abort the run"),
+    ("重试上一步", "Retry the previous step"),
+    ("注释", "Comment"),
+    ("插件", "Plugin"),
+    ("循环开始", "Loop start"),
+    ("循环结束", "Loop end"),
+    ("按键不能为空", "Key cannot be empty"),
+    ("无法识别的按键: {}", "Unrecognized key: {}"),
+    ("步骤 {}: {}", "Step {}: {}"),
+    ("停留时间", "Dwell time"),
+    ("点击间隔", "Click interval"),
+    ("超时", "Timeout"),
+    ("{}必须是不小于 0 的秒数，不能是 {}", "{} must be a number of seconds no less than 0, not {}"),
+    ("等待颜色: {}", "Wait for color: {}"),
+    ("找到图片 {}（相似度 {}）", "Found image {} (similarity {})"),
+    ("没有找到图片 {}", "Image {} not found"),
+    ("找到图片 {} {} 处", "Found image {} at {} places"),
+    ("找图失败: {}", "Image search failed: {}"),
+    ("等待图片 {} 超时", "Timed out waiting for image {}"),
+    ("等待图片 {} 重试 {} 次后仍然超时", "Still timed out waiting for image {} after {} retries"),
+    ("无法阻止系统睡眠: {}", "Could not keep the system awake: {}"),
+    ("无效的变量名: {}", "Invalid variable name: {}"),
+    ("步骤 {}: 循环结束没有对应的循环开始", "Step {}: loop end has no matching loop start"),
+    ("步骤 {}: 循环没有结束标记", "Step {}: loop has no end marker"),
+    ("序列中没有点击步骤", "The sequence has no click steps"),
+    ("插件 {}: {}", "Plugin {}: {}"),
+    // simulation.rs
+    ("按下", "down"),
+    ("松开", "up"),
+    ("移动到 ({}, {})", "Move to ({}, {})"),
+    ("{} 键{}", "{} button {}"),
+    ("按键 {}{}", "Key {} {}"),
+    ("滚轮 {}", "Scroll {}"),
+    // target_window.rs
+    ("开始执行时", "When a run starts"),
+    ("每次点击前", "Before every click"),
+    ("找不到目标窗口「{}」", "Target window \"{}\" not found"),
+    ("系统拒绝把「{}」切换到前台", "The system refused to bring \"{}\" to the front"),
+    ("无法运行 osascript: {}", "Cannot run osascript: {}"),
+    ("读取窗口失败: {}", "Failed to read windows: {}"),
+    ("无法切换到「{}」: {}", "Cannot switch to \"{}\": {}"),
+    // template.rs
+    ("没有选择图片", "No image selected"),
+    ("无法读取图片 {}: {}", "Cannot read the image {}: {}"),
+    // uinput.rs
+    ("找不到 {}，请先加载 uinput 内核模块", "Cannot find {}, load the uinput kernel module first"),
+    ("没有权限打开 {}，请按下面的方法设置后重新登录", "No permission to open {}, set it up as shown below and log in again"),
+    ("无法打开 {}: {}", "Cannot open {}: {}"),
+    ("uinput 设置失败: {}", "uinput setup failed: {}"),
+    ("uinput 写入失败: {}", "uinput write failed: {}"),
+    // wayland.rs
+    ("当前是 Wayland 会话：模拟的点击和读取的光标位置只对 XWayland 中运行的程序有效，原生 Wayland 窗口收不到点击。需要完整支持时请在登录界面选择 X11（Xorg）会话", "This is a Wayland session: simulated clicks and the read cursor position only work with programs running under XWayland, and native Wayland windows do not receive the clicks. For full support, choose an X11 (Xorg) session on the login screen"),
+    ("当前是 Wayland 会话且没有可用的 XWayland（未设置 DISPLAY），无法模拟鼠标和键盘。请启用 XWayland，或在登录界面选择 X11（Xorg）会话", "This is a Wayland session without XWayland (DISPLAY is not set), so the mouse and keyboard cannot be simulated. Enable XWayland or choose an X11 (Xorg) session on the login screen"),
+];
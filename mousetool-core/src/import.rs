@@ -0,0 +1,158 @@
+// 从文件导入点击序列
+//
+// 宏文件（.json / .toml）的格式见 `macro_file`。
+//
+// AutoHotkey 脚本只识别以下语句，其余行会被跳过：
+//
+// - `Click` / `Click, 100, 200` / `Click 100 200 Right`
+// - `MouseMove, 100, 200`（设置之后不带坐标的 Click 使用的位置）
+// - `Sleep, 500`（毫秒，累加到上一个点击步骤的停留时间上）
+//
+// 导入的步骤中停留时间等为负数或不是有限数时整个导入失败，错误信息中带步骤序号。
+
+use std::path::Path;
+
+use crate::ClickType;
+use crate::i18n::t;
+use crate::macro_file::{MacroFile, MacroMetadata};
+use crate::sequence::{Sequence, SequenceStep};
+
+/// 导入得到的序列
+pub struct ImportedSequence {
+    pub metadata: MacroMetadata,
+    pub sequence: Sequence,
+    /// 无法识别而被跳过的行数（仅 AutoHotkey 导入）
+    pub skipped_lines: usize,
+}
+
+/// 按扩展名选择解析方式，.ahk 按 AutoHotkey 解析，其余按宏文件解析
+pub fn load_file(path: impl AsRef<Path>) -> Result<ImportedSequence, String> {
+    let imported = parse_file(path.as_ref())?;
+    imported.sequence.validate()?;
+    Ok(imported)
+}
+
+fn parse_file(path: &Path) -> Result<ImportedSequence, String> {
+    let is_ahk = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("ahk"));
+
+    if is_ahk {
+        let content = std::fs::read_to_string(path).map_err(|e| t!("无法读取文件: {}", e))?;
+        Ok(from_ahk(&content))
+    } else {
+        let file = MacroFile::load(path)?;
+        Ok(ImportedSequence {
+            metadata: file.metadata,
+            sequence: file.sequence,
+            skipped_lines: 0,
+        })
+    }
+}
+
+/// 解析 AutoHotkey 脚本中的 Click、MouseMove 和 Sleep，其余的行跳过并计数
+pub fn from_ahk(content: &str) -> ImportedSequence {
+    let mut steps: Vec<SequenceStep> = Vec::new();
+    let mut skipped_lines = 0;
+    let mut position: Option<(i32, i32)> = None;
+
+    for line in content.lines() {
+        // 去掉行尾注释
+        let line = line.split(';').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let mut tokens = line
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|t| !t.is_empty());
+        let command = tokens.next().unwrap_or("").to_ascii_lowercase();
+        let args: Vec<&str> = tokens.collect();
+
+        match command.as_str() {
+            "click" => {
+                let mut numbers = args.iter().filter_map(|a| a.parse::<i32>().ok());
+                let click_position = match (numbers.next(), numbers.next()) {
+                    (Some(x), Some(y)) => Some((x, y)),
+                    _ => position,
+                };
+                let Some((x, y)) = click_position else {
+                    skipped_lines += 1;
+                    continue;
+                };
+
+                let click_type = args
+                    .iter()
+                    .find_map(|a| ahk_button(a))
+                    .unwrap_or(ClickType::Left);
+                steps.push(SequenceStep { x, y, click_type, dwell: 0.0, ..Default::default() });
+                position = Some((x, y));
+            }
+            "mousemove" => {
+                let mut numbers = args.iter().filter_map(|a| a.parse::<i32>().ok());
+                match (numbers.next(), numbers.next()) {
+                    (Some(x), Some(y)) => position = Some((x, y)),
+                    _ => skipped_lines += 1,
+                }
+            }
+            "sleep" => {
+                let millis = args.first().and_then(|a| a.parse::<f64>().ok());
+                match (millis, steps.last_mut()) {
+                    (Some(millis), Some(step)) => step.dwell += millis / 1000.0,
+                    _ => skipped_lines += 1,
+                }
+            }
+            _ => skipped_lines += 1,
+        }
+    }
+
+    ImportedSequence {
+        metadata: MacroMetadata::default(),
+        sequence: Sequence { steps, variables: Vec::new() },
+        skipped_lines,
+    }
+}
+
+fn ahk_button(arg: &str) -> Option<ClickType> {
+    match arg.to_ascii_lowercase().as_str() {
+        "left" | "l" => Some(ClickType::Left),
+        "right" | "r" => Some(ClickType::Right),
+        "middle" | "m" => Some(ClickType::Middle),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn imports_clicks_moves_and_sleeps() {
+        let imported = from_ahk(
+            "; 注释行\n\
+             Click, 100, 200\n\
+             Sleep, 500\n\
+             Sleep 250 ; 行尾注释\n\
+             MouseMove, 300, 400\n\
+             Click\n\
+             Click 10 20 Right\n\
+             Send, {Enter}\n",
+        );
+        let steps = &imported.sequence.steps;
+        assert_eq!(steps.len(), 3);
+        assert_eq!((steps[0].x, steps[0].y, steps[0].click_type), (100, 200, ClickType::Left));
+        assert_eq!(steps[0].dwell, 0.75);
+        assert_eq!((steps[1].x, steps[1].y), (300, 400));
+        assert_eq!((steps[2].x, steps[2].y, steps[2].click_type), (10, 20, ClickType::Right));
+        assert_eq!(imported.skipped_lines, 1);
+    }
+
+    #[test]
+    fn skips_lines_without_a_position() {
+        let imported = from_ahk("Sleep, 100\nClick\nMouseMove, 5\nclick, 1, 2, m\n");
+        assert_eq!(imported.sequence.steps.len(), 1);
+        assert_eq!(imported.sequence.steps[0].click_type, ClickType::Middle);
+        assert_eq!(imported.skipped_lines, 3);
+    }
+}
@@ -0,0 +1,201 @@
+// Windows Interception 驱动输入后端（可选功能 interception）
+//
+// 一些带反作弊的游戏会丢弃带有「注入」标记的 SendInput 事件。Interception 是一个键盘和鼠标的过滤驱动，
+// 由它发出的事件在系统看来来自真实设备。运行时需要已经安装驱动，并把驱动附带的 interception.dll
+// 放在程序所在的目录中；DLL 在选择这个后端时才加载，没有安装时其他后端不受影响。
+// 驱动工作在内核中，安装和卸载都需要管理员权限并重启，反作弊也可能把驱动本身视为作弊。
+
+/// 选择这个后端时显示的警告
+pub const WARNING: &str = "Interception 是第三方内核驱动：安装和卸载需要管理员权限并重启电脑，驱动出错时可能导致键盘和鼠标失灵；部分反作弊会检测这个驱动并封禁账号，请自行承担风险";
+
+/// 安装驱动的方法
+pub const INSTALL_HINT: &str = "从 https://github.com/oblitum/Interception/releases 下载驱动，以管理员身份在命令提示符中运行 install-interception.exe /install，重启电脑，然后把 library 目录中与本程序位数相同的 interception.dll 复制到本程序所在的目录";
+
+/// 本程序通过驱动注入的事件在 dwExtraInfo 中带有这个值，鼠标钩子据此忽略它们
+#[cfg(target_os = "windows")]
+pub const EXTRA_INFO: u32 = 0x6D54_4F4C;
+
+#[cfg(all(target_os = "windows", feature = "interception"))]
+pub use platform::Device;
+
+#[cfg(all(target_os = "windows", feature = "interception"))]
+mod platform {
+    use std::ffi::c_void;
+
+    use enigo::{Button, Key};
+    use windows_sys::Win32::Foundation::{FreeLibrary, HMODULE};
+    use windows_sys::Win32::System::LibraryLoader::{GetProcAddress, LoadLibraryW};
+
+    use super::EXTRA_INFO;
+    use crate::backend::{self, MouseBackend};
+    use crate::i18n::t;
+
+    // interception.h 中的设备编号：键盘是 1～10，鼠标是 11～20，注入时使用第一个设备
+    const KEYBOARD: i32 = 1;
+    const MOUSE: i32 = 11;
+
+    const MOUSE_LEFT_BUTTON_DOWN: u16 = 0x001;
+    const MOUSE_LEFT_BUTTON_UP: u16 = 0x002;
+    const MOUSE_RIGHT_BUTTON_DOWN: u16 = 0x004;
+    const MOUSE_RIGHT_BUTTON_UP: u16 = 0x008;
+    const MOUSE_MIDDLE_BUTTON_DOWN: u16 = 0x010;
+    const MOUSE_MIDDLE_BUTTON_UP: u16 = 0x020;
+    const MOUSE_BUTTON_4_DOWN: u16 = 0x040;
+    const MOUSE_BUTTON_4_UP: u16 = 0x080;
+    const MOUSE_BUTTON_5_DOWN: u16 = 0x100;
+    const MOUSE_BUTTON_5_UP: u16 = 0x200;
+    const MOUSE_WHEEL: u16 = 0x400;
+
+    const MOUSE_MOVE_ABSOLUTE: u16 = 0x001;
+    const MOUSE_VIRTUAL_DESKTOP: u16 = 0x002;
+
+    const KEY_UP: u16 = 0x01;
+    const KEY_E0: u16 = 0x02;
+
+    // 滚轮一格的 rolling
+    const WHEEL_DELTA: i32 = 120;
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct MouseStroke {
+        state: u16,
+        flags: u16,
+        rolling: i16,
+        x: i32,
+        y: i32,
+        information: u32,
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct KeyStroke {
+        code: u16,
+        state: u16,
+        information: u32,
+    }
+
+    // 驱动按鼠标事件的大小读取每一个事件，键盘事件也要占同样的空间
+    #[repr(C)]
+    union Stroke {
+        mouse: MouseStroke,
+        key: KeyStroke,
+    }
+
+    type CreateContext = unsafe extern "C" fn() -> *mut c_void;
+    type DestroyContext = unsafe extern "C" fn(*mut c_void);
+    type SendStrokes = unsafe extern "C" fn(*mut c_void, i32, *const Stroke, u32) -> i32;
+
+    pub struct Device {
+        library: HMODULE,
+        context: *mut c_void,
+        destroy: DestroyContext,
+        send: SendStrokes,
+    }
+
+    // 驱动上下文只在持有设备的鼠标控制器中使用，同一时间只有一个线程访问
+    unsafe impl Send for Device {}
+
+    fn wide(text: &str) -> Vec<u16> {
+        text.encode_utf16().chain(Some(0)).collect()
+    }
+
+    impl Device {
+        pub fn new() -> Result<Self, String> {
+            let library = unsafe { LoadLibraryW(wide("interception.dll").as_ptr()) };
+            if library.is_null() {
+                return Err(t!("找不到 interception.dll，请把它复制到本程序所在的目录").to_string());
+            }
+            let symbol = |name: &[u8]| unsafe { GetProcAddress(library, name.as_ptr()) };
+            let (Some(create), Some(destroy), Some(send)) = (
+                symbol(b"interception_create_context\0"),
+                symbol(b"interception_destroy_context\0"),
+                symbol(b"interception_send\0"),
+            ) else {
+                unsafe { FreeLibrary(library) };
+                return Err(t!("interception.dll 缺少需要的函数，请使用驱动附带的版本").to_string());
+            };
+            let (create, destroy, send) = unsafe {
+                (
+                    std::mem::transmute::<unsafe extern "system" fn() -> isize, CreateContext>(create),
+                    std::mem::transmute::<unsafe extern "system" fn() -> isize, DestroyContext>(destroy),
+                    std::mem::transmute::<unsafe extern "system" fn() -> isize, SendStrokes>(send),
+                )
+            };
+            // 驱动没有安装或没有运行时创建不了上下文
+            let context = unsafe { create() };
+            if context.is_null() {
+                unsafe { FreeLibrary(library) };
+                return Err(t!("Interception 驱动没有安装或没有运行，安装后需要重启电脑").to_string());
+            }
+            Ok(Self { library, context, destroy, send })
+        }
+
+        fn send(&self, device: i32, stroke: Stroke) -> Result<(), String> {
+            if unsafe { (self.send)(self.context, device, &stroke, 1) } != 1 {
+                return Err(t!("Interception 注入失败").to_string());
+            }
+            Ok(())
+        }
+
+        fn mouse(&self, state: u16, flags: u16, rolling: i16, (x, y): (i32, i32)) -> Result<(), String> {
+            let mouse = MouseStroke { state, flags, rolling, x, y, information: EXTRA_INFO };
+            self.send(MOUSE, Stroke { mouse })
+        }
+
+        fn scan(&self, (code, extended): (u16, bool), pressed: bool) -> Result<(), String> {
+            let state = (if pressed { 0 } else { KEY_UP }) | (if extended { KEY_E0 } else { 0 });
+            self.send(KEYBOARD, Stroke { key: KeyStroke { code, state, information: EXTRA_INFO } })
+        }
+    }
+
+    impl Drop for Device {
+        fn drop(&mut self) {
+            unsafe {
+                (self.destroy)(self.context);
+                FreeLibrary(self.library);
+            }
+        }
+    }
+
+    impl MouseBackend for Device {
+        fn move_to(&mut self, x: i32, y: i32) -> Result<(), String> {
+            self.mouse(0, MOUSE_MOVE_ABSOLUTE | MOUSE_VIRTUAL_DESKTOP, 0, backend::virtual_desktop_position(x, y))
+        }
+
+        fn button(&mut self, button: Button, pressed: bool) -> Result<(), String> {
+            let (down, up) = match button {
+                Button::Left => (MOUSE_LEFT_BUTTON_DOWN, MOUSE_LEFT_BUTTON_UP),
+                Button::Right => (MOUSE_RIGHT_BUTTON_DOWN, MOUSE_RIGHT_BUTTON_UP),
+                Button::Middle => (MOUSE_MIDDLE_BUTTON_DOWN, MOUSE_MIDDLE_BUTTON_UP),
+                Button::Back => (MOUSE_BUTTON_4_DOWN, MOUSE_BUTTON_4_UP),
+                Button::Forward => (MOUSE_BUTTON_5_DOWN, MOUSE_BUTTON_5_UP),
+                _ => return Err(t!("{} 后端不支持按钮 {}", "Interception", format!("{:?}", button))),
+            };
+            self.mouse(if pressed { down } else { up }, 0, 0, (0, 0))
+        }
+
+        fn key(&mut self, key: Key, pressed: bool) -> Result<(), String> {
+            // 驱动只能发送扫描码，当前布局里没有的字符无法输入
+            let unsupported = || t!("{} 后端不支持按键 {}", "Interception", format!("{:?}", key));
+            let (vk, shift) = backend::key_vk(key).ok_or_else(unsupported)?;
+            let code = backend::scan_code(vk);
+            let shift_code = match shift {
+                true => Some(backend::scan_code(backend::key_vk(Key::Shift).ok_or_else(unsupported)?.0)),
+                false => None,
+            };
+            if let Some(shift_code) = shift_code.filter(|_| pressed) {
+                self.scan(shift_code, true)?;
+            }
+            self.scan(code, pressed)?;
+            if let Some(shift_code) = shift_code.filter(|_| !pressed) {
+                self.scan(shift_code, false)?;
+            }
+            Ok(())
+        }
+
+        fn scroll(&mut self, amount: i32) -> Result<(), String> {
+            // 正数的 rolling 向上滚动
+            self.mouse(MOUSE_WHEEL, 0, (-amount * WHEEL_DELTA) as i16, (0, 0))
+        }
+    }
+}
@@ -0,0 +1,194 @@
+// 防离开（鼠标微动）
+//
+// 聊天和会议软件在一段时间没有输入后会把状态改为「离开」，远程桌面也会断开空闲的会话。
+// 开启后每隔一段时间把光标移动一个像素再移回原处，或者按一下没有任何作用的 F15 键，
+// 让系统和这些程序认为仍在使用。用户自己动过鼠标时跳过这一次，自动点击执行期间也不发送。
+// 可以限定只在每天的某个时间段内生效，例如只在上班时间。
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use enigo::Key;
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+
+use crate::app_state::{AppState, SharedState};
+use crate::engine::Engine;
+use crate::i18n::t;
+use crate::tasks::{self, Task};
+use crate::timing;
+
+// 微动时光标偏移的像素数
+const NUDGE_PIXELS: i32 = 1;
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+/// 防离开时执行的动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum JiggleAction {
+    /// 光标移动一个像素后移回原处
+    #[default]
+    Nudge,
+    /// 按一下 F15，几乎没有程序会响应它
+    Key,
+}
+
+impl JiggleAction {
+    /// 界面中按此顺序列出
+    pub const ALL: [JiggleAction; 2] = [JiggleAction::Nudge, JiggleAction::Key];
+
+    /// 界面上显示的名称
+    pub fn label(self) -> &'static str {
+        match self {
+            JiggleAction::Nudge => t!("微动鼠标"),
+            JiggleAction::Key => t!("按 F15 键"),
+        }
+    }
+}
+
+/// 防离开的设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JigglerSettings {
+    pub action: JiggleAction,
+    pub interval_secs: u64,
+    /// 只在每天 `start_minute` 到 `end_minute` 之间生效，结束早于开始时跨过午夜
+    pub schedule_enabled: bool,
+    pub start_minute: u32,
+    pub end_minute: u32,
+}
+
+impl Default for JigglerSettings {
+    fn default() -> Self {
+        Self { action: JiggleAction::Nudge, interval_secs: 60, schedule_enabled: false, start_minute: 9 * 60, end_minute: 18 * 60 }
+    }
+}
+
+impl JigglerSettings {
+    /// 当前的本地时间是否在生效时间段内，读取不到时间时按生效处理
+    pub fn in_schedule(&self) -> bool {
+        if !self.schedule_enabled {
+            return true;
+        }
+        let Some((hour, minute, _)) = timing::local_clock() else {
+            return true;
+        };
+        let now = hour * 60 + minute;
+        let (start, end) = (self.start_minute % MINUTES_PER_DAY, self.end_minute % MINUTES_PER_DAY);
+        match start <= end {
+            true => (start..end).contains(&now),
+            false => now >= start || now < end,
+        }
+    }
+}
+
+/// 一天中的分钟数显示为 `HH:MM`
+pub fn format_minute(minute: u32) -> String {
+    format!("{:02}:{:02}", minute / 60 % 24, minute % 60)
+}
+
+// 界面线程和微动任务共享的状态
+#[derive(Debug, Default)]
+struct Shared {
+    /// 上次发送的本地时间
+    last: Option<String>,
+    error: Option<String>,
+}
+
+/// 正在运行的防离开任务，析构时停止
+pub struct Jiggler {
+    shared: Arc<Mutex<Shared>>,
+    settings: watch::Sender<JigglerSettings>,
+    _task: Task,
+}
+
+impl Jiggler {
+    /// 在后台任务中开始防离开，只在程序空闲时动作
+    pub fn start(settings: JigglerSettings, engine: Engine, state: SharedState) -> Self {
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let (sender, receiver) = watch::channel(settings);
+        let task = {
+            let shared = shared.clone();
+            tasks::spawn(|token| run(shared, receiver, engine, state, token))
+        };
+        Self { shared, settings: sender, _task: task }
+    }
+
+    /// 界面中修改的设置立即生效，间隔从修改时重新计算
+    pub fn update(&self, settings: &JigglerSettings) {
+        self.settings.send_if_modified(|current| {
+            let changed = current != settings;
+            if changed {
+                *current = settings.clone();
+            }
+            changed
+        });
+    }
+
+    /// 上一次动作的本地时间，`HH:MM:SS`
+    pub fn last(&self) -> Option<String> {
+        self.shared.lock().unwrap().last.clone()
+    }
+
+    /// 上一次动作失败的原因
+    pub fn error(&self) -> Option<String> {
+        self.shared.lock().unwrap().error.clone()
+    }
+}
+
+async fn run(
+    shared: Arc<Mutex<Shared>>,
+    mut settings: watch::Receiver<JigglerSettings>,
+    engine: Engine,
+    state: SharedState,
+    token: CancellationToken,
+) {
+    let mut last_position = None;
+    loop {
+        let interval = Duration::from_secs(settings.borrow().interval_secs.max(1));
+        tokio::select! {
+            _ = token.cancelled() => return,
+            changed = settings.changed() => {
+                if changed.is_err() {
+                    return;
+                }
+                continue;
+            }
+            _ = tokio::time::sleep(interval) => {}
+        }
+
+        let settings = settings.borrow().clone();
+        if state.get() != AppState::Idle || !settings.in_schedule() {
+            last_position = None;
+            continue;
+        }
+        let engine = engine.clone();
+        let result = tasks::blocking(move || {
+            let position = engine.get_mouse_position()?;
+            // 光标和上次记录的位置不同说明用户正在使用，这一次不需要发送
+            if last_position.replace(position) != Some(position) {
+                return Ok((last_position, false));
+            }
+            let (x, y) = position;
+            match settings.action {
+                JiggleAction::Nudge => engine.move_mouse_to(x + NUDGE_PIXELS, y).and_then(|()| engine.move_mouse_to(x, y)),
+                JiggleAction::Key => engine.tap_key(Key::F15),
+            }
+            .map(|()| (last_position, true))
+        })
+        .await;
+
+        let mut shared = shared.lock().unwrap();
+        match result {
+            Some(Ok((position, sent))) => {
+                last_position = position;
+                if sent {
+                    shared.last = Some(timing::local_time());
+                    shared.error = None;
+                }
+            }
+            Some(Err(e)) => shared.error = Some(e.to_string()),
+            None => {}
+        }
+    }
+}
@@ -0,0 +1,139 @@
+// 执行期间阻止系统睡眠
+//
+// 挂机点击时间很长，系统空闲一段时间后会启动屏幕保护、关闭显示器或者睡眠，执行随之中断。
+// 模拟的输入不一定会重置空闲计时（Wayland 和部分驱动注入的事件就不会），而且步骤之间的长停留
+// 本身就没有输入。执行期间向系统声明需要保持唤醒，执行结束后撤销声明：
+// Windows 使用 SetThreadExecutionState，macOS 使用 IOKit 电源断言，
+// Linux 通过会话总线调用 org.freedesktop.ScreenSaver 的 Inhibit。
+
+/// 存在期间阻止系统睡眠、关闭显示器和启动屏幕保护，析构时撤销
+pub struct KeepAwake {
+    _inhibitor: platform::Inhibitor,
+}
+
+impl KeepAwake {
+    /// 向系统声明保持唤醒，无法声明时返回原因
+    pub fn new() -> Result<Self, String> {
+        platform::Inhibitor::new().map(|inhibitor| Self { _inhibitor: inhibitor })
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows_sys::Win32::System::Power::{
+        ES_CONTINUOUS, ES_DISPLAY_REQUIRED, ES_SYSTEM_REQUIRED, SetThreadExecutionState,
+    };
+
+    use crate::i18n::t;
+
+    // 执行状态属于调用的线程，创建和析构都在执行线程中进行
+    pub struct Inhibitor(());
+
+    impl Inhibitor {
+        pub fn new() -> Result<Self, String> {
+            if unsafe { SetThreadExecutionState(ES_CONTINUOUS | ES_SYSTEM_REQUIRED | ES_DISPLAY_REQUIRED) } == 0 {
+                return Err(t!("SetThreadExecutionState 调用失败").to_string());
+            }
+            Ok(Self(()))
+        }
+    }
+
+    impl Drop for Inhibitor {
+        fn drop(&mut self) {
+            unsafe { SetThreadExecutionState(ES_CONTINUOUS) };
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::ffi::{CString, c_char, c_void};
+
+    use crate::i18n::t;
+
+    #[link(name = "IOKit", kind = "framework")]
+    unsafe extern "C" {
+        fn IOPMAssertionCreateWithName(
+            assertion_type: *const c_void,
+            level: u32,
+            name: *const c_void,
+            id: *mut u32,
+        ) -> i32;
+        fn IOPMAssertionRelease(id: u32) -> i32;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    unsafe extern "C" {
+        fn CFStringCreateWithCString(allocator: *const c_void, text: *const c_char, encoding: u32) -> *const c_void;
+        fn CFRelease(object: *const c_void);
+    }
+
+    const STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+    const ASSERTION_LEVEL_ON: u32 = 255;
+    // 阻止空闲时关闭显示器，同时也阻止系统空闲睡眠
+    const PREVENT_USER_IDLE_DISPLAY_SLEEP: &str = "PreventUserIdleDisplaySleep";
+
+    fn cf_string(text: &str) -> *const c_void {
+        let text = CString::new(text).unwrap_or_default();
+        unsafe { CFStringCreateWithCString(std::ptr::null(), text.as_ptr(), STRING_ENCODING_UTF8) }
+    }
+
+    pub struct Inhibitor(u32);
+
+    impl Inhibitor {
+        pub fn new() -> Result<Self, String> {
+            let assertion_type = cf_string(PREVENT_USER_IDLE_DISPLAY_SLEEP);
+            let name = cf_string(t!("mouseTOOL 正在执行自动点击"));
+            let mut id = 0;
+            let result = unsafe { IOPMAssertionCreateWithName(assertion_type, ASSERTION_LEVEL_ON, name, &mut id) };
+            unsafe {
+                CFRelease(assertion_type);
+                CFRelease(name);
+            }
+            if result != 0 {
+                return Err(t!("创建电源断言失败（错误码 {}）", result));
+            }
+            Ok(Self(id))
+        }
+    }
+
+    impl Drop for Inhibitor {
+        fn drop(&mut self) {
+            unsafe { IOPMAssertionRelease(self.0) };
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    use zbus::blocking::Connection;
+
+    use crate::i18n::t;
+
+    const SERVICE: &str = "org.freedesktop.ScreenSaver";
+    const PATH: &str = "/org/freedesktop/ScreenSaver";
+
+    // 桌面环境在连接断开时也会撤销声明，所以连接要一直保持到析构
+    pub struct Inhibitor {
+        connection: Connection,
+        cookie: u32,
+    }
+
+    impl Inhibitor {
+        pub fn new() -> Result<Self, String> {
+            let connection =
+                Connection::session().map_err(|e| t!("无法连接到会话总线: {}", e.to_string()))?;
+            let reply = connection
+                .call_method(Some(SERVICE), PATH, Some(SERVICE), "Inhibit", &("mouseTOOL", t!("正在执行自动点击")))
+                .map_err(|e| t!("桌面环境不支持 org.freedesktop.ScreenSaver: {}", e.to_string()))?;
+            let cookie = reply.body().deserialize::<u32>().map_err(|e| e.to_string())?;
+            Ok(Self { connection, cookie })
+        }
+    }
+
+    impl Drop for Inhibitor {
+        fn drop(&mut self) {
+            let _ = self.connection.call_method(Some(SERVICE), PATH, Some(SERVICE), "UnInhibit", &(self.cookie,));
+        }
+    }
+}
@@ -0,0 +1,216 @@
+//! 鼠标自动化核心
+//!
+//! 序列模型、执行引擎、鼠标控制器和各平台的输入后端都在这个库里，不依赖 egui/eframe，
+//! 可以单独测试，也可以嵌入其他 Rust 程序。mouseTOOL 的界面程序负责显示和保存设置，
+//! 通过这里的 `sequence::run` 执行序列。
+//!
+//! - `sequence`：步骤、变量和循环组成的点击序列，`sequence::run` 按 `RunOptions` 执行，
+//!   执行中的每次移动、点击和提示交给 `RunObserver`
+//! - `engine`：在独立线程里注入输入的引擎，注入失败记录在 `Engine::errors` 中
+//! - `backend`：输入后端接口，接入新的输入方式时实现 `backend::MouseBackend`，
+//!   再用 `Engine::set_custom_backend` 交给输入引擎
+//! - `events`：执行中的点击、触发和执行结束等事件，可以订阅后实时处理
+//! - `runner`：不带界面执行序列的便捷函数，命令行程序也使用它
+//! - `capture`：截取屏幕上任意区域或某个显示器的画面，短时间内重复截取同一区域时复用缓存
+//!
+//! ```no_run
+//! use mousetool_core::sequence::{Sequence, SequenceStep, StepKind};
+//!
+//! let mut sequence = Sequence::default();
+//! sequence.steps.push(SequenceStep::new(StepKind::Click, 100, 200));
+//! sequence.steps.push(SequenceStep::new(StepKind::Click, 300, 200));
+//! // 执行 10 次点击，原速回放，瞬移到目标位置
+//! let report = mousetool_core::runner::execute(&sequence, 10, 1.0, None, None)?;
+//! println!("点击了 {} 次", report.clicks_performed);
+//! # Ok::<(), String>(())
+//! ```
+
+use std::time::Duration;
+
+/// 程序的运行状态
+pub mod app_state;
+/// 注入输入的后端
+pub mod backend;
+/// 鼠标按钮映射
+pub mod button_map;
+/// 屏幕截图
+pub mod capture;
+/// 颜色匹配
+pub mod color;
+/// 跨平台鼠标控制
+pub mod cross_platform_mouse;
+/// 多显示器
+pub mod display;
+/// 输入引擎线程
+pub mod engine;
+/// 注入输入的错误
+pub mod error;
+/// 引擎事件
+pub mod events;
+/// 把点击序列导出为其他工具可以直接执行的脚本
+pub mod export;
+/// 序列步骤中使用的简单表达式
+pub mod expr;
+/// 自适应点击间隔
+pub mod feedback;
+/// 界面语言
+pub mod i18n;
+/// 从文件导入点击序列
+pub mod import;
+/// Windows Interception 驱动输入后端（可选功能 interception）
+pub mod interception;
+/// 防离开（鼠标微动）
+pub mod jiggler;
+/// 执行期间阻止系统睡眠
+pub mod keep_awake;
+/// 诊断日志
+pub mod logging;
+/// 宏文件的磁盘格式
+pub mod macro_file;
+/// 程序数据的存放位置
+pub mod paths;
+/// macOS 的隐私权限检测（仅 macOS）
+pub mod permissions;
+/// 插件：第三方提供的序列步骤
+pub mod plugins;
+/// 电量和 CPU 保护
+pub mod power;
+/// 录制鼠标点击
+pub mod recorder;
+/// 无界面运行：不带界面直接执行宏文件或序列，命令行程序也使用它
+pub mod runner;
+/// 安全桌面和权限隔离检测（仅 Windows）
+pub mod secure_desktop;
+/// 点击序列：自动点击的执行引擎
+pub mod sequence;
+/// 模拟执行（试运行）
+pub mod simulation;
+/// 找图
+pub mod template;
+/// 目标窗口：列出打开的窗口，在开始执行或每次点击前把选中的窗口切换到前台
+pub mod target_window;
+/// 后台任务的异步运行时
+pub mod tasks;
+/// 点击间隔统计和高精度等待
+pub mod timing;
+/// Linux uinput 输入后端
+pub mod uinput;
+/// Wayland 会话检测（仅 Linux）
+pub mod wayland;
+
+use enigo::Button;
+
+use engine::Engine;
+use error::InputError;
+use i18n::t;
+
+/// 点击使用的鼠标按钮
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClickType {
+    #[default]
+    Left,
+    Right,
+    Middle,
+}
+
+impl ClickType {
+    /// 界面中按此顺序列出
+    pub const ALL: [ClickType; 3] = [ClickType::Left, ClickType::Right, ClickType::Middle];
+
+    /// 界面上显示的名称
+    pub fn label(self) -> &'static str {
+        match self {
+            ClickType::Left => t!("左键"),
+            ClickType::Right => t!("右键"),
+            ClickType::Middle => t!("中键"),
+        }
+    }
+}
+
+/// 每次点击动作连续点几下
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClickMultiplicity {
+    #[default]
+    Single,
+    Double,
+    Triple,
+}
+
+impl ClickMultiplicity {
+    /// 界面中按此顺序列出
+    pub const ALL: [ClickMultiplicity; 3] = [ClickMultiplicity::Single, ClickMultiplicity::Double, ClickMultiplicity::Triple];
+
+    /// 界面上显示的名称
+    pub fn label(self) -> &'static str {
+        match self {
+            ClickMultiplicity::Single => t!("单击"),
+            ClickMultiplicity::Double => t!("双击"),
+            ClickMultiplicity::Triple => t!("三击"),
+        }
+    }
+
+    /// 每次点击动作按下的次数
+    pub fn count(self) -> u32 {
+        match self {
+            ClickMultiplicity::Single => 1,
+            ClickMultiplicity::Double => 2,
+            ClickMultiplicity::Triple => 3,
+        }
+    }
+}
+
+/// 系统双击时间读取失败时假定的值（Windows 默认值）
+pub const FALLBACK_DOUBLE_CLICK_TIME: Duration = Duration::from_millis(500);
+// 跟随系统时多击之间的最大间隔
+const DEFAULT_MULTI_CLICK_GAP: Duration = Duration::from_millis(50);
+
+/// 双击/三击中相邻两下之间的间隔。
+///
+/// 指定了 `override_ms` 时直接使用；否则取系统双击时间的五分之一（最多 50 毫秒），
+/// 保证连续的几下一定落在系统的双击判定时间之内。
+pub fn multi_click_gap(override_ms: Option<u64>) -> Duration {
+    match override_ms {
+        Some(ms) => Duration::from_millis(ms),
+        None => {
+            let threshold = cross_platform_mouse::double_click_time().unwrap_or(FALLBACK_DOUBLE_CLICK_TIME);
+            (threshold / 5).min(DEFAULT_MULTI_CLICK_GAP)
+        }
+    }
+}
+
+/// 移动到目标位置的方式
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum MoveMode {
+    Teleport,
+    Smooth,
+}
+
+/// 按移动方式把鼠标移到目标位置
+pub fn move_to_target(engine: &Engine, x: i32, y: i32, move_mode: MoveMode, smooth_move_ms: u64) -> Result<(), InputError> {
+    match move_mode {
+        MoveMode::Teleport => engine.move_mouse_to(x, y),
+        MoveMode::Smooth => engine.move_mouse_smooth(x, y, Duration::from_millis(smooth_move_ms)),
+    }
+}
+
+/// 在光标当前位置点击一次
+pub fn click_with(engine: &Engine, click_type: ClickType) -> Result<(), InputError> {
+    engine.click(match click_type {
+        ClickType::Left => Button::Left,
+        ClickType::Right => Button::Right,
+        ClickType::Middle => Button::Middle,
+    })
+}
+
+/// 按多击设置连续点击，相邻两下之间间隔 `gap`，间隔期间不占用输入引擎
+pub fn click_times(engine: &Engine, click_type: ClickType, multiplicity: ClickMultiplicity, gap: Duration) -> Result<(), InputError> {
+    for i in 0..multiplicity.count() {
+        if i > 0 {
+            timing::sleep(gap);
+        }
+        click_with(engine, click_type)?;
+    }
+    Ok(())
+}
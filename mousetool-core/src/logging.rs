@@ -0,0 +1,118 @@
+// 诊断日志
+//
+// 事件日志只在界面里保留最近的消息，程序退出后就没有了，用户报告问题时说不清发生了什么。
+// 这里用 tracing 把状态消息、输入引擎的事件、注入失败和平台诊断信息写入数据目录下的 `logs` 文件夹，
+// 每天一个文件，只保留最近几天。报告问题时附上日志文件即可。
+// 日志级别可以在界面中修改，立即生效；默认只记录 info 及以上。
+// 没有数据目录或无法创建日志文件时写到标准错误。
+
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{Registry, fmt, reload};
+
+use crate::i18n::t;
+use crate::paths;
+
+// 数据目录下存放日志的文件夹
+const LOGS_DIR: &str = "logs";
+// 日志文件名为 `mousetool.<日期>.log`
+const FILE_PREFIX: &str = "mousetool";
+const FILE_SUFFIX: &str = "log";
+// 最多保留的日志文件数（天数）
+const MAX_LOG_FILES: usize = 7;
+
+/// 写入日志文件的最低级别
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    /// 界面中按此顺序列出
+    pub const ALL: [LogLevel; 5] = [LogLevel::Error, LogLevel::Warn, LogLevel::Info, LogLevel::Debug, LogLevel::Trace];
+
+    /// 界面上显示的名称
+    pub fn label(self) -> &'static str {
+        match self {
+            LogLevel::Error => t!("错误"),
+            LogLevel::Warn => t!("警告"),
+            LogLevel::Info => t!("信息"),
+            LogLevel::Debug => t!("调试"),
+            LogLevel::Trace => t!("全部"),
+        }
+    }
+
+    fn filter(self) -> LevelFilter {
+        match self {
+            LogLevel::Error => LevelFilter::ERROR,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+static LEVEL: OnceLock<reload::Handle<LevelFilter, Registry>> = OnceLock::new();
+
+/// 存放日志文件的文件夹
+pub fn logs_dir() -> Option<PathBuf> {
+    paths::data_dir().map(|dir| dir.join(LOGS_DIR))
+}
+
+fn file_appender() -> Option<RollingFileAppender> {
+    let dir = logs_dir()?;
+    std::fs::create_dir_all(&dir).ok()?;
+    RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(FILE_PREFIX)
+        .filename_suffix(FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(dir)
+        .ok()
+}
+
+/// 启动时在 `paths::init` 之后调用一次，之后的日志写入日志文件
+pub fn init(level: LogLevel) {
+    let (filter, handle) = reload::Layer::new(level.filter());
+    let file = file_appender();
+    let stderr = file.is_none().then(|| fmt::layer().with_writer(std::io::stderr));
+    let file = file.map(|appender| fmt::layer().with_ansi(false).with_writer(appender));
+    if tracing_subscriber::registry().with(filter).with(file).with(stderr).try_init().is_err() {
+        return;
+    }
+    let _ = LEVEL.set(handle);
+
+    // panic 的位置和原因也写入日志，再交给默认的处理（打印到标准错误）
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        tracing::error!(%info, "panic");
+        default_hook(info);
+    }));
+
+    tracing::info!(
+        version = env!("CARGO_PKG_VERSION"),
+        os = std::env::consts::OS,
+        arch = std::env::consts::ARCH,
+        portable = paths::is_portable(),
+        "启动"
+    );
+}
+
+/// 修改日志级别，立即生效
+pub fn set_level(level: LogLevel) {
+    if let Some(handle) = LEVEL.get() {
+        let _ = handle.modify(|filter| *filter = level.filter());
+    }
+}
@@ -0,0 +1,201 @@
+// 宏文件的磁盘格式
+//
+// 宏以 JSON（.json）或 TOML（.toml）保存，两种格式的结构相同：
+//
+// ```json
+// {
+//   "version": 7,
+//   "metadata": {
+//     "name": "示例",
+//     "description": "在两个按钮之间来回点击",
+//     "app_version": "0.1.0"
+//   },
+//   "variables": [
+//     { "name": "x0", "value": 300 }
+//   ],
+//   "steps": [
+//     { "x": 100, "y": 200, "click_type": "left", "multi_click": "double", "dwell": 0.5 },
+//     { "kind": "loop_start", "repeat": 3, "loop_var": "j" },
+//     { "x": 0, "y": 200, "click_type": "right", "dwell": 1.0, "x_expr": "x0 + 30 * j" },
+//     { "kind": "loop_end" },
+//     { "kind": "comment", "comment": "提交表单" },
+//     { "kind": "key", "key": "Enter", "dwell": 0.5 },
+//     { "kind": "wait", "dwell": 2.0 }
+//   ]
+// }
+// ```
+//
+// - `version`：格式版本。缺省视为 1，兼容早期没有版本号的导入文件；
+//   比当前程序支持的版本更新的文件会被拒绝，而不是按错误的结构解析
+// - `metadata`：可省略
+// - `variables`：序列变量及初始值，可省略
+// - `steps`：步骤字段说明见 `sequence::SequenceStep`，
+//   `x_expr` / `y_expr` / `update` 的写法见 `expr`
+//
+// 版本历史：
+// - 1：步骤只有固定坐标
+// - 2：增加变量和坐标表达式
+// - 3：增加步骤类型 `kind`，支持嵌套循环
+// - 4：增加等待、移动、滚动、按键和注释步骤
+// - 5：点击步骤增加 `multi_click`（双击、三击）
+// - 6：增加插件步骤（`plugin`、`plugin_step`、`params`）
+// - 7：增加等待颜色、点击图片、点击所有匹配和等待图片步骤
+//   （`color`、`image`、`matching`、`click_delay`、`timeout`、`on_timeout`）
+//
+// 以后修改结构时必须提升 `FORMAT_VERSION`，并在 `upgrade` 中把旧版本转换为新版本。
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::t;
+use crate::sequence::Sequence;
+
+/// 当前的宏文件格式版本，读取旧版本时先升级
+pub const FORMAT_VERSION: u32 = 7;
+
+/// 宏文件的名称、说明和保存它的程序版本
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MacroMetadata {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// 保存该文件的程序版本
+    #[serde(default)]
+    pub app_version: String,
+}
+
+/// 宏文件：元数据和序列，按扩展名保存为 JSON 或 TOML
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MacroFile {
+    #[serde(default = "legacy_version")]
+    pub version: u32,
+    #[serde(default)]
+    pub metadata: MacroMetadata,
+    #[serde(flatten)]
+    pub sequence: Sequence,
+}
+
+fn legacy_version() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Json,
+    Toml,
+}
+
+impl Format {
+    fn from_path(path: &Path) -> Self {
+        let is_toml = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"));
+        if is_toml { Format::Toml } else { Format::Json }
+    }
+}
+
+impl MacroFile {
+    /// 用当前格式版本和程序版本创建
+    pub fn new(metadata: MacroMetadata, sequence: Sequence) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            metadata: MacroMetadata {
+                app_version: env!("CARGO_PKG_VERSION").to_string(),
+                ..metadata
+            },
+            sequence,
+        }
+    }
+
+    /// 解析 JSON 宏文件，旧版本的文件升级为当前格式
+    pub fn from_json(content: &str) -> Result<Self, String> {
+        let file: Self = serde_json::from_str(content).map_err(|e| t!("JSON 格式错误: {}", e))?;
+        file.upgrade()
+    }
+
+    /// 解析 TOML 宏文件，旧版本的文件升级为当前格式
+    pub fn from_toml(content: &str) -> Result<Self, String> {
+        let file: Self = toml::from_str(content).map_err(|e| t!("TOML 格式错误: {}", e))?;
+        file.upgrade()
+    }
+
+    /// 读取宏文件，`.toml` 按 TOML 解析，其余按 JSON 解析
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| t!("无法读取文件: {}", e))?;
+        match Format::from_path(path) {
+            Format::Json => Self::from_json(&content),
+            Format::Toml => Self::from_toml(&content),
+        }
+    }
+
+    /// 按扩展名保存为 JSON 或 TOML
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        let content = match Format::from_path(path) {
+            Format::Json => serde_json::to_string_pretty(self).map_err(|e| e.to_string())?,
+            Format::Toml => toml::to_string_pretty(self).map_err(|e| e.to_string())?,
+        };
+        std::fs::write(path, content).map_err(|e| t!("无法写入文件: {}", e))
+    }
+
+    // 把旧版本的文件转换为当前版本
+    fn upgrade(self) -> Result<Self, String> {
+        match self.version {
+            FORMAT_VERSION => Ok(self),
+            // 旧版本都是新版本的子集，缺省字段的默认值即为旧行为
+            1..=6 => Ok(Self { version: FORMAT_VERSION, ..self }),
+            v if v > FORMAT_VERSION => Err(t!(
+                "文件格式版本 {} 比当前程序支持的版本 {} 更新，请升级程序",
+                v, FORMAT_VERSION
+            )),
+            v => Err(t!("不支持的文件格式版本: {}", v)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upgrades_every_older_version() {
+        for version in 1..FORMAT_VERSION {
+            let content = format!(r#"{{ "version": {}, "steps": [{{ "x": 1, "y": 2, "dwell": 0.5 }}] }}"#, version);
+            let file = MacroFile::from_json(&content).unwrap();
+            assert_eq!(file.version, FORMAT_VERSION, "版本 {}", version);
+            assert_eq!(file.sequence.steps.len(), 1);
+            assert_eq!((file.sequence.steps[0].x, file.sequence.steps[0].y), (1, 2));
+        }
+    }
+
+    #[test]
+    fn treats_files_without_a_version_as_version_1() {
+        let file = MacroFile::from_json(r#"{ "steps": [{ "x": 1, "y": 2, "delay": 0.5 }] }"#).unwrap();
+        assert_eq!(file.version, FORMAT_VERSION);
+        assert_eq!(file.sequence.steps[0].dwell, 0.5);
+    }
+
+    #[test]
+    fn rejects_newer_and_invalid_versions() {
+        let newer = format!(r#"{{ "version": {}, "steps": [] }}"#, FORMAT_VERSION + 1);
+        assert!(MacroFile::from_json(&newer).is_err());
+        assert!(MacroFile::from_json(r#"{ "version": 0, "steps": [] }"#).is_err());
+    }
+
+    #[test]
+    fn round_trips_through_json_and_toml() {
+        let mut sequence = Sequence::single_point(10, 20, crate::ClickType::Right, 0.25);
+        sequence.steps.push(crate::sequence::SequenceStep::loop_start(3));
+        sequence.steps.push(crate::sequence::SequenceStep::loop_end());
+        let file = MacroFile::new(MacroMetadata { name: "示例".to_string(), ..Default::default() }, sequence);
+
+        let json = serde_json::to_string(&file).unwrap();
+        assert_eq!(MacroFile::from_json(&json).unwrap(), file);
+        let toml = toml::to_string(&file).unwrap();
+        assert_eq!(MacroFile::from_toml(&toml).unwrap(), file);
+    }
+}
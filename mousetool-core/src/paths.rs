@@ -0,0 +1,78 @@
+// 程序数据的存放位置
+//
+// 默认放在系统的用户数据目录。便携模式下所有设置、配置、历史记录都放在程序旁边的
+// data 目录中，方便装在 U 盘里带着走：程序旁边有 portable.flag 文件，或者命令行带
+// `--portable` 时启用。
+// 用户数据目录与界面程序中 eframe 保存设置的目录相同，这里自行计算，核心库不依赖 eframe。
+
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// 应用名称，同时决定 eframe 持久化存储的目录
+pub const APP_NAME: &str = "跨平台鼠标点击工具";
+
+/// 放在程序旁边即启用便携模式的文件
+pub const PORTABLE_FLAG_FILE: &str = "portable.flag";
+// 便携模式下程序旁边的数据目录
+const PORTABLE_DATA_DIR: &str = "data";
+// eframe 的设置存储文件名，与 eframe 默认使用的一致
+const STORAGE_FILE: &str = "app.ron";
+
+static PORTABLE: OnceLock<bool> = OnceLock::new();
+
+fn exe_dir() -> Option<PathBuf> {
+    std::env::current_exe().ok()?.parent().map(|dir| dir.to_path_buf())
+}
+
+fn has_portable_flag() -> bool {
+    exe_dir().is_some_and(|dir| dir.join(PORTABLE_FLAG_FILE).exists())
+}
+
+/// 启动时调用一次，`portable_arg` 表示命令行是否带了 `--portable`
+pub fn init(portable_arg: bool) {
+    let _ = PORTABLE.set(portable_arg || has_portable_flag());
+}
+
+/// 是否为便携模式：程序旁边有标记文件，或启动时带有 `--portable`
+pub fn is_portable() -> bool {
+    *PORTABLE.get_or_init(has_portable_flag)
+}
+
+// 系统的用户数据目录下本程序的目录，规则与 `eframe::storage_dir` 一致：
+// - Windows：%APPDATA%\<应用名称>\data
+// - macOS：~/Library/Application Support/<应用名称，空白换成 ->
+// - Linux：$XDG_DATA_HOME（没有时为 ~/.local/share）/<应用名称小写并去掉空白>
+fn user_data_dir() -> Option<PathBuf> {
+    let var = |name: &str| std::env::var_os(name).map(PathBuf::from).filter(|path| path.is_absolute());
+    if cfg!(target_os = "windows") {
+        var("APPDATA").map(|dir| dir.join(APP_NAME).join("data"))
+    } else if cfg!(target_os = "macos") {
+        var("HOME").map(|home| {
+            home.join("Library")
+                .join("Application Support")
+                .join(APP_NAME.replace(|c: char| c.is_ascii_whitespace(), "-"))
+        })
+    } else {
+        var("XDG_DATA_HOME")
+            .or_else(|| var("HOME").map(|home| home.join(".local").join("share")))
+            .map(|dir| dir.join(APP_NAME.to_lowercase().replace(|c: char| c.is_ascii_whitespace(), "")))
+    }
+}
+
+/// 历史记录等数据文件所在目录，与 eframe 的设置存储放在一起
+pub fn data_dir() -> Option<PathBuf> {
+    if is_portable() {
+        exe_dir().map(|dir| dir.join(PORTABLE_DATA_DIR))
+    } else {
+        user_data_dir()
+    }
+}
+
+/// 便携模式下 eframe 设置存储的文件，其他情况下返回 None，由 eframe 使用默认位置
+pub fn storage_file() -> Option<PathBuf> {
+    if is_portable() {
+        data_dir().map(|dir| dir.join(STORAGE_FILE))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,120 @@
+// macOS 的隐私权限检测（仅 macOS）
+//
+// macOS 要求模拟鼠标和键盘的程序拥有「辅助功能」权限，读取全局按键（快捷键、按键捕捉）
+// 需要「输入监控」权限。没有权限时注入的事件和读取的按键都会被系统静默丢弃，不会报错，
+// 因此启动时检测权限，缺少时引导用户打开系统设置中对应的页面，并持续重新检测直到获得权限。
+// 其他平台没有对应的权限，检测总是通过。
+
+use crate::i18n::t;
+
+/// macOS 上模拟和读取输入需要的权限
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    Accessibility,
+    InputMonitoring,
+}
+
+impl Permission {
+    /// 需要检查的全部权限
+    pub const ALL: [Permission; 2] = [Permission::Accessibility, Permission::InputMonitoring];
+
+    /// 系统设置中的名称
+    pub fn label(self) -> &'static str {
+        match self {
+            Permission::Accessibility => t!("辅助功能"),
+            Permission::InputMonitoring => t!("输入监控"),
+        }
+    }
+
+    /// 缺少这项权限时无法使用的功能
+    pub fn purpose(self) -> &'static str {
+        match self {
+            Permission::Accessibility => t!("模拟鼠标点击、移动和按键"),
+            Permission::InputMonitoring => t!("全局快捷键和按键检测"),
+        }
+    }
+
+    /// 是否已经获得该权限，其他平台总是 true
+    pub fn is_granted(self) -> bool {
+        platform::is_granted(self)
+    }
+
+    /// 请求权限：系统会弹出提示并把本程序加入设置中的列表，已经请求过时不再提示
+    pub fn request(self) {
+        platform::request(self);
+    }
+
+    /// 打开系统设置中这项权限的页面
+    pub fn open_settings(self) -> Result<(), String> {
+        platform::open_settings(self)
+    }
+}
+
+/// 尚未获得的权限
+pub fn missing() -> Vec<Permission> {
+    Permission::ALL.into_iter().filter(|permission| !permission.is_granted()).collect()
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::Permission;
+    use crate::i18n::t;
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    unsafe extern "C" {
+        fn AXIsProcessTrusted() -> bool;
+    }
+
+    // macOS 10.15 起可用
+    #[link(name = "CoreGraphics", kind = "framework")]
+    unsafe extern "C" {
+        fn CGPreflightListenEventAccess() -> bool;
+        fn CGRequestListenEventAccess() -> bool;
+        fn CGRequestPostEventAccess() -> bool;
+    }
+
+    pub fn is_granted(permission: Permission) -> bool {
+        unsafe {
+            match permission {
+                Permission::Accessibility => AXIsProcessTrusted(),
+                Permission::InputMonitoring => CGPreflightListenEventAccess(),
+            }
+        }
+    }
+
+    pub fn request(permission: Permission) {
+        unsafe {
+            match permission {
+                Permission::Accessibility => CGRequestPostEventAccess(),
+                Permission::InputMonitoring => CGRequestListenEventAccess(),
+            };
+        }
+    }
+
+    pub fn open_settings(permission: Permission) -> Result<(), String> {
+        let pane = match permission {
+            Permission::Accessibility => "Privacy_Accessibility",
+            Permission::InputMonitoring => "Privacy_ListenEvent",
+        };
+        std::process::Command::new("open")
+            .arg(format!("x-apple.systempreferences:com.apple.preference.security?{}", pane))
+            .spawn()
+            .map(drop)
+            .map_err(|e| t!("无法打开系统设置: {}", e))
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod platform {
+    use super::Permission;
+
+    pub fn is_granted(_permission: Permission) -> bool {
+        true
+    }
+
+    pub fn request(_permission: Permission) {}
+
+    pub fn open_settings(_permission: Permission) -> Result<(), String> {
+        Ok(())
+    }
+}
@@ -0,0 +1,270 @@
+// 插件：第三方提供的序列步骤
+//
+// 插件是放在数据目录 `plugins` 文件夹中的动态库（Windows 的 .dll、macOS 的 .dylib、Linux 的 .so），
+// 启动时全部加载。每个插件可以提供若干种步骤，编辑器按插件声明的参数自动生成输入框，
+// 执行到插件步骤时把参数和当前变量交给插件，插件返回的变量写回序列，供后面的坐标表达式使用。
+// 步骤分两种：
+// - 动作：执行一次，例如从串口读取一个数值
+// - 触发：反复询问插件，直到插件报告条件满足（或执行被停止）才继续，例如等串口收到某个信号
+//
+// 插件导出三个 C 调用约定的函数，字符串都是以 NUL 结尾的 UTF-8：
+//
+// ```c
+// // 插件清单（JSON），插件卸载前一直有效
+// const char *mousetool_plugin_manifest(void);
+// // 执行一次请求，请求和回复都是 JSON；回复由插件分配
+// char *mousetool_plugin_call(const char *request);
+// // 释放 mousetool_plugin_call 返回的回复
+// void mousetool_plugin_free(char *reply);
+// ```
+//
+// 清单、请求和回复的结构见 `Manifest`、`Request` 和 `Reply`，例如：
+//
+// ```json
+// { "api_version": 1, "name": "serial", "description": "串口读写",
+//   "steps": [{ "id": "read", "label": "读取串口", "trigger": false,
+//               "params": [{ "name": "port", "label": "端口", "kind": "text", "default": "COM3" }] }] }
+// 请求: { "step": "read", "params": { "port": "COM3" }, "variables": { "i": 0, "n": 12 } }
+// 回复: { "ok": true, "fired": true, "variables": { "value": 42 } }
+// ```
+//
+// 同一个插件的调用不会同时进行，插件不需要考虑线程安全。插件与本程序运行在同一个进程中，
+// 只应安装信任的插件。
+
+use std::collections::BTreeMap;
+use std::ffi::{CStr, CString, c_char};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use libloading::Library;
+use serde::{Deserialize, Serialize};
+
+use crate::expr::Variables;
+use crate::i18n::t;
+use crate::paths;
+
+/// 本程序支持的插件接口版本
+pub const API_VERSION: u32 = 1;
+// 数据目录下存放插件的文件夹
+const PLUGINS_DIR: &str = "plugins";
+
+/// 插件步骤参数的类型，决定编辑器中使用的控件
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParamKind {
+    #[default]
+    Text,
+    Number,
+    Bool,
+}
+
+/// 插件步骤的一个参数，值总是以文字保存
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParamSpec {
+    pub name: String,
+    #[serde(default)]
+    pub label: String,
+    #[serde(default)]
+    pub kind: ParamKind,
+    #[serde(default)]
+    pub default: String,
+}
+
+/// 插件提供的一种步骤
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StepSpec {
+    pub id: String,
+    #[serde(default)]
+    pub label: String,
+    /// 触发步骤：反复询问直到插件报告 `fired`
+    #[serde(default)]
+    pub trigger: bool,
+    #[serde(default)]
+    pub params: Vec<ParamSpec>,
+}
+
+impl StepSpec {
+    /// 编辑器中显示的名称，没有设置时使用 id
+    pub fn label(&self) -> &str {
+        if self.label.is_empty() { &self.id } else { &self.label }
+    }
+
+    /// 新建步骤时各参数的默认值
+    pub fn default_params(&self) -> BTreeMap<String, String> {
+        self.params.iter().map(|param| (param.name.clone(), param.default.clone())).collect()
+    }
+}
+
+/// 插件清单
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub api_version: u32,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub steps: Vec<StepSpec>,
+}
+
+#[derive(Debug, Serialize)]
+struct Request<'a> {
+    step: &'a str,
+    params: &'a BTreeMap<String, String>,
+    variables: &'a Variables,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Reply {
+    ok: bool,
+    /// 触发步骤的条件是否已经满足，动作步骤忽略
+    fired: bool,
+    variables: Variables,
+    error: Option<String>,
+}
+
+/// 插件步骤执行一次的结果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Outcome {
+    pub fired: bool,
+    /// 要写回序列的变量
+    pub variables: Variables,
+}
+
+type ManifestFn = unsafe extern "C" fn() -> *const c_char;
+type CallFn = unsafe extern "C" fn(*const c_char) -> *mut c_char;
+type FreeFn = unsafe extern "C" fn(*mut c_char);
+
+struct Functions {
+    call: CallFn,
+    free: FreeFn,
+}
+
+/// 已加载的插件
+pub struct Plugin {
+    pub manifest: Manifest,
+    pub path: PathBuf,
+    // 同一时间只有一个调用，插件不需要考虑线程安全
+    functions: Mutex<Functions>,
+    // 函数指针来自这个库，必须和插件一起保留
+    _library: Library,
+}
+
+impl Plugin {
+    fn load(path: &Path) -> Result<Self, String> {
+        // SAFETY: 加载动态库会执行其初始化代码，用户把插件放进插件目录即表示信任它
+        let library = unsafe { Library::new(path) }.map_err(|e| e.to_string())?;
+        let symbol = |name: &[u8]| t!("缺少函数 {}", String::from_utf8_lossy(&name[..name.len() - 1]));
+        // SAFETY: 函数签名由插件接口约定
+        let (manifest, call, free) = unsafe {
+            (
+                *library.get::<ManifestFn>(b"mousetool_plugin_manifest\0").map_err(|_| symbol(b"mousetool_plugin_manifest\0"))?,
+                *library.get::<CallFn>(b"mousetool_plugin_call\0").map_err(|_| symbol(b"mousetool_plugin_call\0"))?,
+                *library.get::<FreeFn>(b"mousetool_plugin_free\0").map_err(|_| symbol(b"mousetool_plugin_free\0"))?,
+            )
+        };
+        // SAFETY: 清单是插件持有的以 NUL 结尾的字符串
+        let manifest = unsafe {
+            let text = manifest();
+            if text.is_null() {
+                return Err(t!("插件没有提供清单").to_string());
+            }
+            CStr::from_ptr(text).to_string_lossy().into_owned()
+        };
+        let manifest: Manifest = serde_json::from_str(&manifest).map_err(|e| t!("插件清单格式错误: {}", e))?;
+        if manifest.api_version != API_VERSION {
+            return Err(t!("插件接口版本 {} 与本程序支持的版本 {} 不同", manifest.api_version, API_VERSION));
+        }
+        Ok(Self { manifest, path: path.to_path_buf(), functions: Mutex::new(Functions { call, free }), _library: library })
+    }
+
+    /// 按 id 查找插件提供的步骤
+    pub fn step(&self, id: &str) -> Option<&StepSpec> {
+        self.manifest.steps.iter().find(|step| step.id == id)
+    }
+
+    /// 执行一次插件步骤
+    pub fn call(&self, step: &str, params: &BTreeMap<String, String>, variables: &Variables) -> Result<Outcome, String> {
+        let request = serde_json::to_string(&Request { step, params, variables }).map_err(|e| e.to_string())?;
+        let request = CString::new(request).map_err(|e| e.to_string())?;
+        let functions = self.functions.lock().unwrap_or_else(|e| e.into_inner());
+        // SAFETY: 回复是插件分配的以 NUL 结尾的字符串，读取后交还插件释放
+        let reply = unsafe {
+            let reply = (functions.call)(request.as_ptr());
+            if reply.is_null() {
+                return Err(t!("插件没有返回结果").to_string());
+            }
+            let text = CStr::from_ptr(reply).to_string_lossy().into_owned();
+            (functions.free)(reply);
+            text
+        };
+        drop(functions);
+        let reply: Reply = serde_json::from_str(&reply).map_err(|e| t!("插件返回的结果格式错误: {}", e))?;
+        match reply.ok {
+            true => Ok(Outcome { fired: reply.fired, variables: reply.variables }),
+            false => Err(reply.error.unwrap_or_else(|| t!("插件执行失败").to_string())),
+        }
+    }
+}
+
+/// 已加载的插件和加载失败的文件
+#[derive(Default)]
+pub struct Registry {
+    pub plugins: Vec<Plugin>,
+    pub errors: Vec<(PathBuf, String)>,
+}
+
+static REGISTRY: OnceLock<Registry> = OnceLock::new();
+
+/// 存放插件的文件夹
+pub fn plugins_dir() -> Option<PathBuf> {
+    paths::data_dir().map(|dir| dir.join(PLUGINS_DIR))
+}
+
+fn is_library(path: &Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()).is_some_and(|ext| ext == std::env::consts::DLL_EXTENSION)
+}
+
+fn load_all() -> Registry {
+    let mut registry = Registry::default();
+    let Some(entries) = plugins_dir().and_then(|dir| std::fs::read_dir(dir).ok()) else {
+        return registry;
+    };
+    let mut paths: Vec<PathBuf> = entries.filter_map(|entry| entry.ok().map(|entry| entry.path())).filter(|path| is_library(path)).collect();
+    paths.sort();
+    for path in paths {
+        match Plugin::load(&path) {
+            Ok(plugin) if registry.find(&plugin.manifest.name).is_some() => {
+                registry.errors.push((path, t!("已经加载了同名的插件 {}", plugin.manifest.name)));
+            }
+            Ok(plugin) => {
+                tracing::info!(name = %plugin.manifest.name, path = %path.display(), "已加载插件");
+                registry.plugins.push(plugin);
+            }
+            Err(e) => {
+                tracing::warn!(path = %path.display(), error = %e, "无法加载插件");
+                registry.errors.push((path, e));
+            }
+        }
+    }
+    registry
+}
+
+/// 插件在第一次使用时加载，之后不再变化
+pub fn registry() -> &'static Registry {
+    REGISTRY.get_or_init(load_all)
+}
+
+impl Registry {
+    /// 按名称查找已加载的插件
+    pub fn find(&self, name: &str) -> Option<&Plugin> {
+        self.plugins.iter().find(|plugin| plugin.manifest.name == name)
+    }
+
+    /// 插件名称和步骤，找不到时返回原因
+    pub fn step(&self, plugin: &str, step: &str) -> Result<(&Plugin, &StepSpec), String> {
+        let found = self.find(plugin).ok_or_else(|| t!("插件 {} 没有安装", plugin))?;
+        let spec = found.step(step).ok_or_else(|| t!("插件 {} 没有步骤 {}", plugin, step))?;
+        Ok((found, spec))
+    }
+}
@@ -0,0 +1,235 @@
+// 电量和 CPU 保护
+//
+// 笔记本用电池运行且电量低于设定值，或者系统 CPU 占用持续高于设定值一段时间时，
+// 自动暂停执行，条件恢复后继续，避免挂机耗尽电池或拖慢正在编译的机器。
+// 每个配置分别设置。
+
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::app_state::{AppState, SharedState};
+use crate::i18n::t;
+
+// 两次读取 CPU 占用的最短间隔，间隔太短时计算出的占用率波动很大
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 电量和 CPU 保护的设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PowerGuard {
+    /// 使用电池且电量低于 `battery_min_percent` 时暂停
+    pub battery_enabled: bool,
+    pub battery_min_percent: u8,
+    /// CPU 占用连续 `cpu_secs` 秒高于 `cpu_max_percent` 时暂停
+    pub cpu_enabled: bool,
+    pub cpu_max_percent: u8,
+    pub cpu_secs: u64,
+}
+
+impl Default for PowerGuard {
+    fn default() -> Self {
+        Self { battery_enabled: false, battery_min_percent: 20, cpu_enabled: false, cpu_max_percent: 90, cpu_secs: 10 }
+    }
+}
+
+impl PowerGuard {
+    /// 是否启用了任何一项保护
+    pub fn is_enabled(&self) -> bool {
+        self.battery_enabled || self.cpu_enabled
+    }
+}
+
+/// 电池状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatteryStatus {
+    /// 没有接通电源，正在使用电池
+    pub on_battery: bool,
+    pub percent: u8,
+}
+
+/// 当前的电池状态，没有电池或无法读取时返回 None
+pub fn battery() -> Option<BatteryStatus> {
+    platform::battery()
+}
+
+/// 当前平台是否能读取 CPU 占用
+pub fn cpu_supported() -> bool {
+    platform::cpu_times().is_some()
+}
+
+/// 跟踪 CPU 占用和高占用持续的时间
+#[derive(Debug, Default)]
+pub struct Monitor {
+    last_sample: Option<(Instant, u64, u64)>,
+    cpu_percent: Option<f64>,
+    cpu_high_since: Option<Instant>,
+}
+
+impl Monitor {
+    fn sample_cpu(&mut self) {
+        if self.last_sample.is_some_and(|(at, ..)| at.elapsed() < SAMPLE_INTERVAL) {
+            return;
+        }
+        let Some((idle, total)) = platform::cpu_times() else {
+            return;
+        };
+        if let Some((_, last_idle, last_total)) = self.last_sample
+            && total > last_total
+        {
+            let busy = 1.0 - (idle.saturating_sub(last_idle)) as f64 / (total - last_total) as f64;
+            self.cpu_percent = Some(busy.clamp(0.0, 1.0) * 100.0);
+        }
+        self.last_sample = Some((Instant::now(), idle, total));
+    }
+
+    /// 需要暂停时返回原因
+    pub fn pause_reason(&mut self, guard: &PowerGuard) -> Option<String> {
+        if guard.battery_enabled
+            && let Some(status) = battery()
+            && status.on_battery
+            && status.percent < guard.battery_min_percent
+        {
+            return Some(t!("电池电量 {}% 低于 {}%", status.percent, guard.battery_min_percent));
+        }
+
+        if guard.cpu_enabled {
+            self.sample_cpu();
+            match self.cpu_percent {
+                Some(percent) if percent > guard.cpu_max_percent as f64 => {
+                    let since = *self.cpu_high_since.get_or_insert_with(Instant::now);
+                    if since.elapsed() >= Duration::from_secs(guard.cpu_secs) {
+                        return Some(t!("CPU 占用 {}% 高于 {}%", format!("{:.0}", percent), guard.cpu_max_percent));
+                    }
+                }
+                _ => self.cpu_high_since = None,
+            }
+        }
+        None
+    }
+
+    /// 条件不满足时一直等待，期间把原因写入 `paused` 并把状态切换为暂停，供界面显示。
+    /// 等待期间执行被停止时返回 false
+    pub fn wait_until_clear(&mut self, guard: &PowerGuard, paused: &Mutex<Option<String>>, state: &SharedState) -> bool {
+        while let Some(reason) = self.pause_reason(guard) {
+            *paused.lock().unwrap() = Some(reason);
+            state.transition(AppState::Running, AppState::Paused);
+            let until = Instant::now() + SAMPLE_INTERVAL;
+            while Instant::now() < until {
+                if !state.is_running() {
+                    *paused.lock().unwrap() = None;
+                    return false;
+                }
+                thread::sleep(Duration::from_millis(50));
+            }
+        }
+        *paused.lock().unwrap() = None;
+        state.transition(AppState::Paused, AppState::Running);
+        true
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::BatteryStatus;
+    use windows_sys::Win32::Foundation::FILETIME;
+    use windows_sys::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+    use windows_sys::Win32::System::Threading::GetSystemTimes;
+
+    // BatteryFlag 中表示没有电池的位
+    const NO_SYSTEM_BATTERY: u8 = 128;
+    const UNKNOWN_PERCENT: u8 = 255;
+
+    pub fn battery() -> Option<BatteryStatus> {
+        let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+        if unsafe { GetSystemPowerStatus(&mut status) } == 0
+            || status.BatteryFlag & NO_SYSTEM_BATTERY != 0
+            || status.BatteryLifePercent == UNKNOWN_PERCENT
+        {
+            return None;
+        }
+        Some(BatteryStatus { on_battery: status.ACLineStatus == 0, percent: status.BatteryLifePercent })
+    }
+
+    fn to_u64(time: FILETIME) -> u64 {
+        ((time.dwHighDateTime as u64) << 32) | time.dwLowDateTime as u64
+    }
+
+    pub fn cpu_times() -> Option<(u64, u64)> {
+        let empty = FILETIME { dwLowDateTime: 0, dwHighDateTime: 0 };
+        let (mut idle, mut kernel, mut user) = (empty, empty, empty);
+        if unsafe { GetSystemTimes(&mut idle, &mut kernel, &mut user) } == 0 {
+            return None;
+        }
+        // 内核时间已经包含空闲时间
+        Some((to_u64(idle), to_u64(kernel) + to_u64(user)))
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::BatteryStatus;
+
+    // `pmset -g batt` 的输出形如:
+    // Now drawing from 'Battery Power'
+    //  -InternalBattery-0 (id=1234)	85%; discharging; 4:10 remaining present: true
+    pub fn battery() -> Option<BatteryStatus> {
+        let output = std::process::Command::new("pmset").args(["-g", "batt"]).output().ok()?;
+        let text = String::from_utf8_lossy(&output.stdout);
+        let percent = text
+            .split_whitespace()
+            .find_map(|word| word.strip_suffix("%;").and_then(|n| n.parse().ok()))?;
+        Some(BatteryStatus { on_battery: text.contains("'Battery Power'"), percent })
+    }
+
+    pub fn cpu_times() -> Option<(u64, u64)> {
+        None
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    use super::BatteryStatus;
+    use std::fs;
+
+    fn read(path: &std::path::Path, name: &str) -> Option<String> {
+        fs::read_to_string(path.join(name)).ok().map(|value| value.trim().to_string())
+    }
+
+    pub fn battery() -> Option<BatteryStatus> {
+        let mut battery = None;
+        let mut on_mains = false;
+        for entry in fs::read_dir("/sys/class/power_supply").ok()?.flatten() {
+            let path = entry.path();
+            match read(&path, "type").as_deref() {
+                Some("Mains") => on_mains |= read(&path, "online").as_deref() == Some("1"),
+                Some("Battery") if battery.is_none() => {
+                    let percent = read(&path, "capacity").and_then(|value| value.parse().ok());
+                    let discharging = read(&path, "status").as_deref() == Some("Discharging");
+                    battery = percent.map(|percent| (percent, discharging));
+                }
+                _ => {}
+            }
+        }
+        let (percent, discharging) = battery?;
+        Some(BatteryStatus { on_battery: discharging && !on_mains, percent })
+    }
+
+    // /proc/stat 第一行: cpu user nice system idle iowait irq softirq steal ...
+    pub fn cpu_times() -> Option<(u64, u64)> {
+        let stat = fs::read_to_string("/proc/stat").ok()?;
+        let fields: Vec<u64> = stat
+            .lines()
+            .next()?
+            .split_whitespace()
+            .skip(1)
+            // 之后的 guest 时间已经计入 user，不再重复累加
+            .take(8)
+            .filter_map(|value| value.parse().ok())
+            .collect();
+        let idle = fields.get(3)? + fields.get(4).unwrap_or(&0);
+        Some((idle, fields.iter().sum()))
+    }
+}
@@ -0,0 +1,75 @@
+// 录制鼠标点击
+//
+// 反复查询鼠标按钮状态，左键、右键或中键从松开变为按下时在当前位置记下一个点击步骤，
+// 到下一次点击之间经过的时间作为这个步骤的停留时间。录制结果就是普通的序列，可以直接保存为宏文件。
+// 录制时只按 `POLL_INTERVAL` 查询，比它更快的两次点击会被合并成一次。
+
+use std::time::{Duration, Instant};
+
+use crate::ClickType;
+use crate::button_map::{ButtonMapping, CalibrationButton};
+use crate::sequence::{Sequence, SequenceStep};
+
+/// 查询按钮状态的间隔
+pub const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// 录制鼠标点击：反复调用 `poll`，新按下的按钮记为一个点击步骤
+#[derive(Debug)]
+pub struct Recorder {
+    mapping: ButtonMapping,
+    steps: Vec<SequenceStep>,
+    /// 上一次的按钮状态，用来找出新按下的按钮
+    previous: Vec<bool>,
+    last_click: Option<Instant>,
+}
+
+impl Recorder {
+    /// 开始录制。开始时已经按住的按钮要先松开才算一次点击
+    pub fn new(mapping: ButtonMapping, buttons: &[bool]) -> Self {
+        Self { mapping, steps: Vec::new(), previous: buttons.to_vec(), last_click: None }
+    }
+
+    /// 传入最新的按钮状态和光标位置，有新的点击时返回记下的步骤
+    pub fn poll(&mut self, buttons: &[bool], position: (i32, i32)) -> Option<&SequenceStep> {
+        let pressed = CalibrationButton::ORDER.into_iter().find(|&button| {
+            self.mapping.is_pressed(buttons, button) && !self.mapping.is_pressed(&self.previous, button)
+        });
+        self.previous = buttons.to_vec();
+
+        let click_type = match pressed? {
+            CalibrationButton::Left => ClickType::Left,
+            CalibrationButton::Right => ClickType::Right,
+            CalibrationButton::Middle => ClickType::Middle,
+        };
+        let now = Instant::now();
+        if let (Some(last), Some(step)) = (self.last_click, self.steps.last_mut()) {
+            step.dwell = round_secs(now - last);
+        }
+        self.last_click = Some(now);
+        self.steps.push(SequenceStep { x: position.0, y: position.1, click_type, ..Default::default() });
+        self.steps.last()
+    }
+
+    /// 已录制的步骤数
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// 是否还没有录制到任何步骤
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// 结束录制。最后一个步骤停留到结束录制的时刻
+    pub fn finish(mut self) -> Sequence {
+        if let (Some(last), Some(step)) = (self.last_click, self.steps.last_mut()) {
+            step.dwell = round_secs(last.elapsed());
+        }
+        Sequence { steps: self.steps, variables: Vec::new() }
+    }
+}
+
+// 停留时间保留到毫秒，保存的宏文件更易读
+fn round_secs(duration: Duration) -> f64 {
+    (duration.as_secs_f64() * 1000.0).round() / 1000.0
+}
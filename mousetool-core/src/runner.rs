@@ -0,0 +1,153 @@
+// 无界面运行：直接执行一个宏文件，供运行包中的启动脚本调用。命令行程序 mousetool-cli 也通过这里执行
+//
+// 用法: mouseTOOL --run <宏文件> [--count <次数>] [--speed <倍率>] [--smooth <毫秒>] [--display <显示器名称>]
+//
+// `--display` 与界面中选择的显示器相同，宏中的坐标相对于该显示器的左上角；省略时使用全局坐标。
+
+use std::sync::{Arc, Mutex};
+
+use crate::MoveMode;
+use crate::app_state::{AppState, SharedState};
+use crate::button_map::ButtonMapping;
+use crate::engine::Engine;
+use crate::display;
+use crate::macro_file::MacroFile;
+use crate::permissions::Permission;
+use crate::secure_desktop;
+use crate::sequence::{self, RunReport, Sequence};
+use crate::wayland;
+
+/// 无界面执行宏文件的参数
+pub struct HeadlessOptions {
+    pub macro_path: String,
+    /// 点击次数上限
+    pub count: u32,
+    /// 回放速度倍率
+    pub speed: f64,
+    /// 为 Some 时使用平滑移动，值为移动耗时（毫秒）
+    pub smooth_move_ms: Option<u64>,
+    /// 坐标相对的显示器名称，为 None 时使用全局坐标
+    pub display: Option<String>,
+}
+
+/// 解析 `--run` 及其参数。没有 `--run` 参数时返回 Ok(None)，按正常方式启动界面；
+/// 带 `--run` 时不认识的参数都是错误，`--portable` 由启动时的数据目录选择处理
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Option<HeadlessOptions>, String> {
+    let args: Vec<String> = args.into_iter().collect();
+    // 其余参数属于界面程序，由它自己解析
+    if !args.iter().any(|arg| arg == "--run") {
+        return Ok(None);
+    }
+
+    let mut macro_path = None;
+    let mut count = 10;
+    let mut speed = 1.0;
+    let mut smooth_move_ms = None;
+    let mut display = None;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("{} 缺少参数值", name));
+        match arg.as_str() {
+            "--run" => macro_path = Some(value("--run")?),
+            "--count" => count = value("--count")?.parse().map_err(|_| "--count 必须是正整数")?,
+            "--speed" => speed = value("--speed")?.parse().map_err(|_| "--speed 必须是数字")?,
+            "--smooth" => smooth_move_ms = Some(value("--smooth")?.parse().map_err(|_| "--smooth 必须是毫秒数")?),
+            "--display" => display = Some(value("--display")?),
+            "--portable" => {}
+            other => return Err(format!("未知参数: {}", other)),
+        }
+    }
+
+    if count == 0 {
+        return Err("--count 必须是正整数".to_string());
+    }
+    if !(0.25..=4.0).contains(&speed) {
+        return Err("--speed 的范围是 0.25 到 4".to_string());
+    }
+
+    Ok(macro_path.map(|macro_path| HeadlessOptions {
+        macro_path,
+        count,
+        speed,
+        smooth_move_ms,
+        display,
+    }))
+}
+
+/// 加载宏文件并在当前线程执行。执行中途中止时原因在 `RunReport::aborted` 中，由调用方决定如何报告
+pub fn run(options: &HeadlessOptions) -> Result<RunReport, String> {
+    let file = MacroFile::load(&options.macro_path)?;
+    execute(&file.sequence, options.count, options.speed, options.smooth_move_ms, options.display.as_deref())
+}
+
+/// 检查序列和运行环境后在当前线程执行，供无界面运行和命令行程序使用。
+/// `smooth_move_ms` 为 Some 时使用平滑移动；`display` 为 Some 时坐标相对于该显示器
+pub fn execute(
+    sequence: &Sequence,
+    count: u32,
+    speed: f64,
+    smooth_move_ms: Option<u64>,
+    display: Option<&str>,
+) -> Result<RunReport, String> {
+    sequence.compile()?;
+    select_display(display)?;
+    let mut issues = secure_desktop::preflight(sequence);
+    issues.extend(display::preflight(sequence));
+    if let Some((i, reason)) = issues.into_iter().next() {
+        return Err(format!("步骤 {}: {}", i + 1, reason));
+    }
+
+    let engine = engine()?;
+    let state = SharedState::new(AppState::Running);
+    let total_clicks = Arc::new(Mutex::new(0));
+
+    let run_options = sequence::RunOptions {
+        max_clicks: count,
+        move_mode: if smooth_move_ms.is_some() { MoveMode::Smooth } else { MoveMode::Teleport },
+        smooth_move_ms: smooth_move_ms.unwrap_or(0),
+        hold_key: None,
+        speed,
+        multi_click_gap: crate::multi_click_gap(None),
+        adaptive: None,
+        power_guard: None,
+        resume: None,
+        timing: None,
+        observer: None,
+        focus_window: None,
+        keep_awake: true,
+        plugins: true,
+        events: true,
+        screen: true,
+    };
+    Ok(sequence::run(sequence, &run_options, &engine, &state, &total_clicks))
+}
+
+// 与界面不同，找不到指定的显示器时不退回全局坐标，以免点到别的地方
+fn select_display(name: Option<&str>) -> Result<(), String> {
+    let selected = match name {
+        Some(name) => Some(
+            display::enumerate()
+                .into_iter()
+                .find(|display| display.name == name)
+                .ok_or_else(|| format!("找不到显示器 {}", name))?,
+        ),
+        None => None,
+    };
+    display::select(selected);
+    Ok(())
+}
+
+/// 检查会话类型和权限后启动输入引擎，模拟的输入注定会被丢弃时返回原因。
+/// 在 Wayland 会话中通过 XWayland 注入时只记录警告，需要提示用户时调用方可以读取 `wayland::diagnostic`
+pub fn engine() -> Result<Engine, String> {
+    match wayland::diagnostic() {
+        Some(message) if !wayland::has_xwayland() => return Err(message.to_string()),
+        Some(message) => tracing::warn!("{}", message),
+        None => {}
+    }
+    if !Permission::Accessibility.is_granted() {
+        return Err("缺少 macOS「辅助功能」权限，模拟的输入会被系统丢弃。请在「系统设置 > 隐私与安全性 > 辅助功能」中允许运行本工具的终端或程序".to_string());
+    }
+    Engine::start(ButtonMapping::default()).map_err(|e| format!("无法初始化鼠标控制器: {}", e))
+}
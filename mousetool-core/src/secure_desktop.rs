@@ -0,0 +1,193 @@
+// 安全桌面和权限隔离检测（仅 Windows）
+//
+// Windows 的 UAC 提示、Ctrl+Alt+Del 界面和锁屏运行在单独的安全桌面上，
+// 普通程序既读不到那里的鼠标位置，也无法向其注入输入；
+// 以管理员权限运行的窗口同样会拒绝来自普通权限程序的输入（UIPI）。
+// 这些情况下注入会静默失败，因此在捕捉、执行前和执行中主动检测并给出说明；
+// 目标窗口以管理员权限运行时还可以直接以管理员身份重新启动本工具。
+// 其他平台没有对应的限制，检测函数总是返回「正常」。
+
+use crate::display;
+use crate::i18n;
+use crate::sequence::{Sequence, StepKind};
+
+/// 处于安全桌面时的提示
+pub const SECURE_DESKTOP_MESSAGE: &str =
+    "当前处于 Windows 安全桌面（UAC 提示、锁屏或 Ctrl+Alt+Del 界面），系统禁止在这里模拟鼠标和键盘";
+
+/// 当前接收输入的是否为安全桌面
+pub fn input_desktop_is_secure() -> bool {
+    platform::input_desktop_is_secure()
+}
+
+/// 坐标处的窗口是否会拒绝本程序注入的输入，返回原因
+pub fn target_blocked(x: i32, y: i32) -> Option<String> {
+    let (x, y) = display::to_global(x, y);
+    platform::target_blocked(x, y)
+}
+
+/// 本程序是否以管理员权限运行
+pub fn is_elevated() -> bool {
+    platform::is_current_elevated()
+}
+
+/// 通过 UAC 以管理员身份重新启动本程序，保留命令行参数。成功后调用方应退出当前进程
+pub fn relaunch_elevated() -> Result<(), String> {
+    let exe = std::env::current_exe().map_err(|e| i18n::t!("无法获取程序路径: {}", e))?;
+    // Windows 的命令行参数按空格分隔，带空格或引号的参数需要加引号
+    // 新实例带上 --replace，等当前实例退出后接替它，不会把参数转交回来
+    let args: Vec<String> = std::env::args()
+        .skip(1)
+        .filter(|arg| arg != "--replace")
+        .chain(std::iter::once("--replace".to_string()))
+        .map(|arg| if arg.contains([' ', '\t', '"']) { format!("\"{}\"", arg.replace('"', "\\\"")) } else { arg })
+        .collect();
+    platform::relaunch_elevated(&exe.to_string_lossy(), &args.join(" "))
+}
+
+/// 执行前检查：返回无法执行的步骤序号（从 0 开始）及原因。
+/// 坐标由表达式计算的步骤在执行前无法确定位置，不做检查。
+pub fn preflight(sequence: &Sequence) -> Vec<(usize, String)> {
+    if input_desktop_is_secure() {
+        return vec![(0, i18n::tr(SECURE_DESKTOP_MESSAGE).to_string())];
+    }
+    sequence
+        .steps
+        .iter()
+        .enumerate()
+        // 等待颜色只读取像素，目标窗口的权限不影响
+        .filter(|(_, step)| matches!(step.kind, StepKind::Click | StepKind::Move))
+        .filter(|(_, step)| step.x_expr.trim().is_empty() && step.y_expr.trim().is_empty())
+        .filter_map(|(i, step)| target_blocked(step.x, step.y).map(|reason| (i, reason)))
+        .collect()
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use crate::i18n::t;
+    use windows_sys::Win32::Foundation::{CloseHandle, ERROR_CANCELLED, HANDLE, POINT};
+    use windows_sys::Win32::Security::{GetTokenInformation, TOKEN_ELEVATION, TOKEN_QUERY, TokenElevation};
+    use windows_sys::Win32::System::StationsAndDesktops::{
+        CloseDesktop, DESKTOP_READOBJECTS, GetUserObjectInformationW, OpenInputDesktop, UOI_NAME,
+    };
+    use windows_sys::Win32::System::Threading::{
+        GetCurrentProcess, OpenProcess, OpenProcessToken, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows_sys::Win32::UI::Shell::ShellExecuteW;
+    use windows_sys::Win32::UI::WindowsAndMessaging::{GetWindowThreadProcessId, SW_SHOWNORMAL, WindowFromPoint};
+
+    pub fn input_desktop_is_secure() -> bool {
+        unsafe {
+            // 普通权限的程序打不开安全桌面，打开失败本身就说明输入桌面不是当前桌面
+            let desktop = OpenInputDesktop(0, 0, DESKTOP_READOBJECTS);
+            if desktop.is_null() {
+                return true;
+            }
+            let mut name = [0u16; 64];
+            let mut needed = 0;
+            let ok = GetUserObjectInformationW(
+                desktop,
+                UOI_NAME,
+                name.as_mut_ptr().cast(),
+                (name.len() * 2) as u32,
+                &mut needed,
+            );
+            CloseDesktop(desktop);
+            if ok == 0 {
+                return false;
+            }
+            let len = name.iter().position(|&c| c == 0).unwrap_or(name.len());
+            !String::from_utf16_lossy(&name[..len]).eq_ignore_ascii_case("Default")
+        }
+    }
+
+    // 进程是否以管理员权限（提升后的令牌）运行；无法查询时返回 None
+    fn is_elevated(process: HANDLE) -> Option<bool> {
+        unsafe {
+            let mut token = std::ptr::null_mut();
+            if OpenProcessToken(process, TOKEN_QUERY, &mut token) == 0 {
+                return None;
+            }
+            let mut elevation = TOKEN_ELEVATION { TokenIsElevated: 0 };
+            let mut len = 0;
+            let ok = GetTokenInformation(
+                token,
+                TokenElevation,
+                (&mut elevation as *mut TOKEN_ELEVATION).cast(),
+                std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+                &mut len,
+            );
+            CloseHandle(token);
+            (ok != 0).then_some(elevation.TokenIsElevated != 0)
+        }
+    }
+
+    pub fn is_current_elevated() -> bool {
+        is_elevated(unsafe { GetCurrentProcess() }) == Some(true)
+    }
+
+    fn wide(text: &str) -> Vec<u16> {
+        text.encode_utf16().chain(Some(0)).collect()
+    }
+
+    pub fn relaunch_elevated(exe: &str, args: &str) -> Result<(), String> {
+        let (verb, exe, args) = (wide("runas"), wide(exe), wide(args));
+        let result = unsafe {
+            ShellExecuteW(std::ptr::null_mut(), verb.as_ptr(), exe.as_ptr(), args.as_ptr(), std::ptr::null(), SW_SHOWNORMAL)
+        };
+        // 返回值大于 32 表示成功，否则原因在 GetLastError 中
+        if result as isize > 32 {
+            return Ok(());
+        }
+        let error = std::io::Error::last_os_error();
+        if error.raw_os_error() == Some(ERROR_CANCELLED as i32) {
+            return Err(t!("已取消以管理员身份启动").to_string());
+        }
+        Err(t!("无法以管理员身份启动: {}", error))
+    }
+
+    pub fn target_blocked(x: i32, y: i32) -> Option<String> {
+        if is_current_elevated() {
+            return None;
+        }
+        unsafe {
+            let window = WindowFromPoint(POINT { x, y });
+            if window.is_null() {
+                return None;
+            }
+            let mut pid = 0;
+            GetWindowThreadProcessId(window, &mut pid);
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+            if process.is_null() {
+                return None;
+            }
+            let elevated = is_elevated(process);
+            CloseHandle(process);
+            (elevated == Some(true)).then(|| {
+                t!(
+                    "({}, {}) 处的窗口以管理员权限运行，普通权限下无法向它发送点击，请以管理员身份运行本工具",
+                    x, y
+                )
+            })
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    pub fn input_desktop_is_secure() -> bool {
+        false
+    }
+
+    pub fn target_blocked(_x: i32, _y: i32) -> Option<String> {
+        None
+    }
+
+    pub fn is_current_elevated() -> bool {
+        false
+    }
+
+    pub fn relaunch_elevated(_exe: &str, _args: &str) -> Result<(), String> {
+        Err(crate::i18n::t!("只有 Windows 支持以管理员身份重新启动").to_string())
+    }
+}
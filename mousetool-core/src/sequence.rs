@@ -0,0 +1,1156 @@
+// 点击序列：自动点击的执行引擎
+//
+// 单点自动点击就是只有一个步骤的序列，A/B 两点交替是两个步骤的序列，
+// 后续的多步骤流程都在这里统一执行。
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use enigo::Key;
+
+use crate::app_state::{AppState, SharedState};
+use crate::capture::{self, Region};
+use crate::color::ColorMatch;
+use crate::display;
+use crate::engine::Engine;
+use crate::error::InputError;
+use crate::events::{self, Event};
+use crate::expr::{self, Assignment, Expr, Variables};
+use crate::feedback::{AdaptiveInterval, WaitOutcome};
+use crate::i18n::{self, t};
+use crate::keep_awake::KeepAwake;
+use crate::plugins;
+use crate::power::{self, PowerGuard};
+use crate::secure_desktop;
+use crate::template::{self, MatchOptions, Template};
+use crate::target_window::{self, FocusMode, WindowTarget};
+use crate::timing::{self, SharedTiming};
+use crate::{ClickMultiplicity, ClickType, MoveMode, click_times, move_to_target};
+
+// 移动到目标后、点击之前的等待
+const SETTLE_TIME: Duration = Duration::from_millis(10);
+// 触发步骤（插件触发、等待颜色和等待图片）两次检查之间的间隔
+const TRIGGER_POLL_INTERVAL: Duration = Duration::from_millis(100);
+// 等待图片超时后连续重试上一步的最多次数，用完后中止执行
+const MAX_WAIT_RETRIES: u32 = 10;
+
+/// 内置变量：当前是第几轮循环（从 0 开始）
+pub const ITERATION_VAR: &str = "i";
+/// 内置变量：到目前为止成功点击的次数
+pub const CLICKS_VAR: &str = "n";
+
+/// 步骤类型。循环由成对的 LoopStart / LoopEnd 包围，可以嵌套
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepKind {
+    #[default]
+    Click,
+    /// 只等待 `dwell` 秒
+    Wait,
+    /// 移动到目标位置但不点击
+    Move,
+    /// 在当前位置滚动滚轮
+    Scroll,
+    /// 按下并松开一个按键
+    Key,
+    /// 等到坐标处的像素变成预期的颜色
+    WaitColor,
+    /// 在屏幕上查找图片并点击它的中心
+    ClickImage,
+    /// 点击屏幕上图片出现的每一处，相邻两次点击间隔 `click_delay` 秒
+    ClickAllImages,
+    /// 等到屏幕上出现图片，超时后按 `on_timeout` 处理
+    WaitImage,
+    /// 注释，执行时跳过
+    Comment,
+    /// 由插件提供的步骤，见 `plugins`
+    Plugin,
+    LoopStart,
+    LoopEnd,
+}
+
+impl StepKind {
+    /// 编辑器中可以直接添加的步骤类型（循环成对添加，不在其中）
+    pub const ACTIONS: [StepKind; 10] = [
+        StepKind::Click,
+        StepKind::Wait,
+        StepKind::Move,
+        StepKind::Scroll,
+        StepKind::Key,
+        StepKind::WaitColor,
+        StepKind::ClickImage,
+        StepKind::ClickAllImages,
+        StepKind::WaitImage,
+        StepKind::Comment,
+    ];
+
+    /// 编辑器中显示的名称
+    pub fn label(self) -> &'static str {
+        match self {
+            StepKind::Click => t!("点击"),
+            StepKind::Wait => t!("等待"),
+            StepKind::Move => t!("移动"),
+            StepKind::Scroll => t!("滚动"),
+            StepKind::Key => t!("按键"),
+            StepKind::WaitColor => t!("等待颜色"),
+            StepKind::ClickImage => t!("点击图片"),
+            StepKind::ClickAllImages => t!("点击所有匹配"),
+            StepKind::WaitImage => t!("等待图片"),
+            StepKind::Comment => t!("注释"),
+            StepKind::Plugin => t!("插件"),
+            StepKind::LoopStart => t!("循环开始"),
+            StepKind::LoopEnd => t!("循环结束"),
+        }
+    }
+
+    /// 是否点击鼠标
+    pub fn clicks(self) -> bool {
+        matches!(self, StepKind::Click | StepKind::ClickImage | StepKind::ClickAllImages)
+    }
+
+    /// 是否在屏幕上查找模板图片
+    pub fn uses_image(self) -> bool {
+        matches!(self, StepKind::ClickImage | StepKind::ClickAllImages | StepKind::WaitImage)
+    }
+
+    /// 是否使用步骤的坐标
+    pub fn has_target(self) -> bool {
+        matches!(self, StepKind::Click | StepKind::Move | StepKind::WaitColor)
+    }
+}
+
+/// 等待图片超时后的处理
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeoutAction {
+    /// 不再等待，继续执行下一步
+    #[default]
+    Skip,
+    /// 中止整个执行
+    Abort,
+    /// 重新执行上一步（例如再点一次打开窗口的按钮），然后继续等待；连续重试 10 次仍然超时时中止执行
+    Retry,
+}
+
+impl TimeoutAction {
+    /// 界面中按此顺序列出
+    pub const ALL: [TimeoutAction; 3] = [TimeoutAction::Skip, TimeoutAction::Abort, TimeoutAction::Retry];
+
+    /// 界面上显示的名称
+    pub fn label(self) -> &'static str {
+        match self {
+            TimeoutAction::Skip => t!("跳过，继续下一步"),
+            TimeoutAction::Abort => t!("中止执行"),
+            TimeoutAction::Retry => t!("重试上一步"),
+        }
+    }
+}
+
+/// 按键步骤支持的特殊按键：名称、对应的按键、xdotool 中的写法。
+/// 不在表中的单个字符按字符输入。
+pub const NAMED_KEYS: &[(&str, Key, &str)] = &[
+    ("Enter", Key::Return, "Return"),
+    ("Tab", Key::Tab, "Tab"),
+    ("Space", Key::Space, "space"),
+    ("Backspace", Key::Backspace, "BackSpace"),
+    ("Delete", Key::Delete, "Delete"),
+    ("Escape", Key::Escape, "Escape"),
+    ("Up", Key::UpArrow, "Up"),
+    ("Down", Key::DownArrow, "Down"),
+    ("Left", Key::LeftArrow, "Left"),
+    ("Right", Key::RightArrow, "Right"),
+    ("Home", Key::Home, "Home"),
+    ("End", Key::End, "End"),
+    ("PageUp", Key::PageUp, "Prior"),
+    ("PageDown", Key::PageDown, "Next"),
+    ("F1", Key::F1, "F1"),
+    ("F2", Key::F2, "F2"),
+    ("F3", Key::F3, "F3"),
+    ("F4", Key::F4, "F4"),
+    ("F5", Key::F5, "F5"),
+    ("F6", Key::F6, "F6"),
+    ("F7", Key::F7, "F7"),
+    ("F8", Key::F8, "F8"),
+    ("F9", Key::F9, "F9"),
+    ("F10", Key::F10, "F10"),
+    ("F11", Key::F11, "F11"),
+    ("F12", Key::F12, "F12"),
+];
+
+/// 解析按键步骤中的按键名称（不区分大小写），或单个字符
+pub fn parse_key(name: &str) -> Result<Key, String> {
+    let name = name.trim();
+    if let Some((_, key, _)) = NAMED_KEYS.iter().find(|(n, ..)| n.eq_ignore_ascii_case(name)) {
+        return Ok(*key);
+    }
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(Key::Unicode(c)),
+        (None, _) => Err(t!("按键不能为空").to_string()),
+        _ => Err(t!("无法识别的按键: {}", name)),
+    }
+}
+
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+/// 序列中的一个步骤。字段是否有效取决于步骤类型，默认值在保存时省略
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SequenceStep {
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub kind: StepKind,
+    #[serde(default)]
+    pub x: i32,
+    #[serde(default)]
+    pub y: i32,
+    #[serde(default, alias = "button")]
+    pub click_type: ClickType,
+    /// 单击、双击或三击（仅 Click）
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub multi_click: ClickMultiplicity,
+    /// 执行后停留的时间（秒），等待步骤的等待时长
+    #[serde(default, alias = "delay")]
+    pub dwell: f64,
+    /// X 坐标表达式，为空时使用 `x`
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub x_expr: String,
+    /// Y 坐标表达式，为空时使用 `y`
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub y_expr: String,
+    /// 点击后执行的变量更新，例如 `k = k + 1`
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub update: String,
+    /// 循环次数（仅 LoopStart）
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub repeat: u32,
+    /// 保存当前循环轮次（从 0 开始）的变量名，为空时不保存（仅 LoopStart）
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub loop_var: String,
+    /// 滚动的格数，正数向下、负数向上（仅 Scroll）
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub scroll: i32,
+    /// 按键名称，见 `NAMED_KEYS`（仅 Key）
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub key: String,
+    /// 等待的颜色和匹配方式（仅 WaitColor）
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub color: ColorMatch,
+    /// 要查找的模板图片路径（仅 ClickImage、ClickAllImages 和 WaitImage）
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub image: String,
+    /// 找图的阈值、缩放和搜索区域（仅 ClickImage、ClickAllImages 和 WaitImage）
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub matching: MatchOptions,
+    /// 相邻两次点击之间的间隔（秒）（仅 ClickAllImages）
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub click_delay: f64,
+    /// 最长等待时间（秒），0 表示一直等下去（仅 WaitImage）
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub timeout: f64,
+    /// 超时后的处理（仅 WaitImage）
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub on_timeout: TimeoutAction,
+    /// 注释内容（仅 Comment）
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub comment: String,
+    /// 提供步骤的插件名称（仅 Plugin）
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub plugin: String,
+    /// 插件中的步骤标识（仅 Plugin）
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub plugin_step: String,
+    /// 插件步骤的参数，按插件声明的名称保存（仅 Plugin）
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, String>,
+}
+
+impl SequenceStep {
+    /// 重复 `repeat` 次的循环开始标记
+    pub fn loop_start(repeat: u32) -> Self {
+        Self { kind: StepKind::LoopStart, repeat, ..Default::default() }
+    }
+
+    /// 循环结束标记
+    pub fn loop_end() -> Self {
+        Self { kind: StepKind::LoopEnd, ..Default::default() }
+    }
+
+    /// 编辑器中新建步骤时的默认值
+    pub fn new(kind: StepKind, x: i32, y: i32) -> Self {
+        match kind {
+            StepKind::Wait => Self { kind, dwell: 1.0, ..Default::default() },
+            StepKind::Scroll => Self { kind, scroll: 3, dwell: 0.5, ..Default::default() },
+            StepKind::Key => Self { kind, key: "Enter".to_string(), dwell: 0.5, ..Default::default() },
+            StepKind::WaitColor => Self { kind, x, y, dwell: 0.5, ..Default::default() },
+            StepKind::ClickImage => Self { kind, dwell: 1.0, ..Default::default() },
+            StepKind::ClickAllImages => Self { kind, click_delay: 0.3, dwell: 1.0, ..Default::default() },
+            StepKind::WaitImage => Self { kind, timeout: 10.0, dwell: 0.5, ..Default::default() },
+            _ => Self { kind, x, y, dwell: 1.0, ..Default::default() },
+        }
+    }
+
+    // 停留时间、点击间隔和超时都要换算成时长，负数或不是有限数时执行线程会出错
+    fn check_durations(&self) -> Result<(), String> {
+        for (name, seconds) in [(t!("停留时间"), self.dwell), (t!("点击间隔"), self.click_delay), (t!("超时"), self.timeout)] {
+            if !seconds.is_finite() || seconds < 0.0 {
+                return Err(t!("{}必须是不小于 0 的秒数，不能是 {}", name, seconds));
+            }
+        }
+        Ok(())
+    }
+
+    /// 插件步骤，参数取插件声明的默认值
+    pub fn plugin(plugin: &str, spec: &plugins::StepSpec) -> Self {
+        Self {
+            kind: StepKind::Plugin,
+            plugin: plugin.to_string(),
+            plugin_step: spec.id.clone(),
+            params: spec.default_params(),
+            dwell: 0.5,
+            ..Default::default()
+        }
+    }
+}
+
+/// 序列中的用户变量及其初始值
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Variable {
+    pub name: String,
+    pub value: f64,
+}
+
+/// 点击序列：步骤和用户变量
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Sequence {
+    pub steps: Vec<SequenceStep>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variables: Vec<Variable>,
+}
+
+/// 解析好表达式的步骤
+pub struct CompiledStep {
+    pub x: Option<Expr>,
+    pub y: Option<Expr>,
+    pub update: Vec<Assignment>,
+    /// 按键步骤要按的键
+    pub key: Option<Key>,
+    /// 插件步骤是否是触发步骤
+    pub trigger: bool,
+    /// 点击图片和等待图片步骤要查找的模板
+    pub template: Option<Arc<Template>>,
+    /// 循环标记对应的另一端的下标
+    pub pair: usize,
+}
+
+impl CompiledStep {
+    /// 计算本次执行的目标坐标
+    pub fn target(&self, step: &SequenceStep, vars: &Variables) -> Result<(i32, i32), String> {
+        let eval = |expr: &Option<Expr>, literal: i32| match expr {
+            Some(expr) => expr.eval(vars).map(|v| v.round() as i32),
+            None => Ok(literal),
+        };
+        Ok((eval(&self.x, step.x)?, eval(&self.y, step.y)?))
+    }
+
+    /// 执行步骤中的赋值，更新 `vars`
+    pub fn apply_update(&self, vars: &mut Variables) -> Result<(), String> {
+        for assignment in &self.update {
+            let value = assignment.value.eval(vars)?;
+            vars.insert(assignment.name.clone(), value);
+        }
+        Ok(())
+    }
+}
+
+impl Sequence {
+    /// 在同一位置反复点击
+    pub fn single_point(x: i32, y: i32, click_type: ClickType, interval: f64) -> Self {
+        Self {
+            steps: vec![SequenceStep { x, y, click_type, dwell: interval, ..Default::default() }],
+            variables: Vec::new(),
+        }
+    }
+
+    /// A/B 两点交替，每个点有各自的停留时间
+    pub fn two_point(a: SequenceStep, b: SequenceStep) -> Self {
+        Self { steps: vec![a, b], variables: Vec::new() }
+    }
+
+    /// 检查各步骤的时间是否有效，出错时返回带步骤序号的错误信息。导入时检查，编译时也会检查
+    pub fn validate(&self) -> Result<(), String> {
+        for (i, step) in self.steps.iter().enumerate() {
+            step.check_durations().map_err(|e| t!("步骤 {}: {}", i + 1, e))?;
+        }
+        Ok(())
+    }
+
+    /// 解析所有步骤中的表达式、检查时间是否有效以及循环是否配对，出错时返回带步骤序号的错误信息
+    pub fn compile(&self) -> Result<Vec<CompiledStep>, String> {
+        let optional = |input: &str| -> Result<Option<Expr>, String> {
+            if input.trim().is_empty() { Ok(None) } else { expr::parse(input).map(Some) }
+        };
+        if let Some(variable) = self.variables.iter().find(|variable| !expr::is_identifier(&variable.name)) {
+            return Err(t!("无效的变量名: {}", variable.name));
+        }
+
+        let mut compiled = self
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(i, step)| {
+                let compile = || -> Result<CompiledStep, String> {
+                    step.check_durations()?;
+                    if step.kind == StepKind::LoopStart
+                        && !step.loop_var.is_empty()
+                        && !expr::is_identifier(&step.loop_var)
+                    {
+                        return Err(t!("无效的变量名: {}", step.loop_var));
+                    }
+                    Ok(CompiledStep {
+                        x: optional(&step.x_expr)?,
+                        y: optional(&step.y_expr)?,
+                        update: expr::parse_assignments(&step.update)?,
+                        key: match step.kind {
+                            StepKind::Key => Some(parse_key(&step.key)?),
+                            _ => None,
+                        },
+                        trigger: match step.kind {
+                            StepKind::Plugin => plugins::registry().step(&step.plugin, &step.plugin_step)?.1.trigger,
+                            _ => false,
+                        },
+                        template: match step.kind.uses_image() {
+                            true => Some(template::load(Path::new(&step.image))?),
+                            false => None,
+                        },
+                        pair: i,
+                    })
+                };
+                compile().map_err(|e| t!("步骤 {}: {}", i + 1, e))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let mut open_loops = Vec::new();
+        for (i, step) in self.steps.iter().enumerate() {
+            match step.kind {
+                StepKind::LoopStart => open_loops.push(i),
+                StepKind::LoopEnd => {
+                    let start = open_loops
+                        .pop()
+                        .ok_or_else(|| t!("步骤 {}: 循环结束没有对应的循环开始", i + 1))?;
+                    compiled[start].pair = i;
+                    compiled[i].pair = start;
+                }
+                _ => {}
+            }
+        }
+        if let Some(start) = open_loops.pop() {
+            return Err(t!("步骤 {}: 循环没有结束标记", start + 1));
+        }
+
+        if !self.steps.iter().any(|step| step.kind.clicks()) {
+            return Err(t!("序列中没有点击步骤").to_string());
+        }
+
+        Ok(compiled)
+    }
+
+    /// 每个步骤所在的循环嵌套深度，用于编辑器缩进
+    pub fn depths(&self) -> Vec<usize> {
+        let mut depth = 0usize;
+        self.steps
+            .iter()
+            .map(|step| match step.kind {
+                StepKind::LoopStart => {
+                    depth += 1;
+                    depth - 1
+                }
+                StepKind::LoopEnd => {
+                    depth = depth.saturating_sub(1);
+                    depth
+                }
+                _ => depth,
+            })
+            .collect()
+    }
+
+    /// 按停留时间估算的每秒点击数（多击按实际按下的次数计算），不计移动耗时，没有点击步骤时为 None
+    pub fn estimated_rate(&self, speed: f64) -> Option<f64> {
+        // 每层循环的重复次数相乘，得到步骤在一轮中执行的次数
+        let mut repeats: Vec<f64> = Vec::new();
+        let (mut clicks, mut seconds) = (0.0, 0.0);
+        for step in &self.steps {
+            let weight: f64 = repeats.iter().product();
+            match step.kind {
+                StepKind::LoopStart => repeats.push(step.repeat as f64),
+                StepKind::LoopEnd => {
+                    repeats.pop();
+                }
+                // 点击所有匹配按找到一处估算
+                StepKind::Click | StepKind::ClickImage | StepKind::ClickAllImages => {
+                    clicks += weight * step.multi_click.count() as f64;
+                    // 执行时点击前固定等待 10 毫秒
+                    seconds += weight * (step.dwell / speed + 0.01);
+                }
+                StepKind::Comment => {}
+                _ => seconds += weight * step.dwell / speed,
+            }
+        }
+        (clicks > 0.0).then(|| clicks / seconds.max(0.001))
+    }
+
+    /// 与 `index` 处的循环开始配对的循环结束位置，没有配对时为 None
+    fn loop_end_of(&self, index: usize) -> Option<usize> {
+        let mut depth = 0usize;
+        for (i, step) in self.steps.iter().enumerate().skip(index) {
+            match step.kind {
+                StepKind::LoopStart => depth += 1,
+                StepKind::LoopEnd => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(i);
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// 把步骤移动到 `to` 之前（`to` 等于步骤数时移到末尾）
+    pub fn move_step(&mut self, from: usize, to: usize) {
+        if from >= self.steps.len() || to > self.steps.len() || from == to || from + 1 == to {
+            return;
+        }
+        let step = self.steps.remove(from);
+        self.steps.insert(if to > from { to - 1 } else { to }, step);
+    }
+
+    /// 在后面插入一份副本。复制循环开始时连同整个循环一起复制，保持循环配对
+    pub fn duplicate_step(&mut self, index: usize) {
+        let end = match self.steps[index].kind {
+            StepKind::LoopStart => self.loop_end_of(index).unwrap_or(index),
+            _ => index,
+        };
+        let copy: Vec<SequenceStep> = self.steps[index..=end].to_vec();
+        self.steps.splice(end + 1..end + 1, copy);
+    }
+
+    /// 执行开始时的变量：用户变量的初始值，以及内置变量
+    pub fn initial_variables(&self) -> Variables {
+        let mut vars: Variables = self
+            .variables
+            .iter()
+            .map(|v| (v.name.clone(), v.value))
+            .collect();
+        vars.insert(ITERATION_VAR.to_string(), 0.0);
+        vars.insert(CLICKS_VAR.to_string(), 0.0);
+        vars
+    }
+}
+
+/// 执行过程的观察者：界面用它记录事件日志、回放轨迹和热力图，播放点击提示音，定期保存执行位置。
+///
+/// 所有方法都在执行线程上调用，应尽快返回；默认实现什么也不做
+pub trait RunObserver: Send + Sync {
+    /// 移动步骤把光标移到了 (x, y)
+    fn moved(&self, _x: i32, _y: i32) {}
+
+    /// 点击步骤在 (x, y) 点击了一次，注入失败时带有错误
+    fn clicked(&self, _x: i32, _y: i32, _result: Result<(), &InputError>) {}
+
+    /// 执行中需要告诉用户的消息，如找图结果、插件错误和等待超时
+    fn status(&self, _text: String) {}
+
+    /// 是否该保存执行位置了；返回 true 时随后会调用 `checkpoint`
+    fn checkpoint_due(&self) -> bool {
+        false
+    }
+
+    /// 即将执行 `position` 处的步骤，程序意外退出后可以从这里继续
+    fn checkpoint(&self, _position: RunPosition) {}
+}
+
+/// `sequence::run` 的执行参数
+pub struct RunOptions {
+    pub max_clicks: u32,
+    pub move_mode: MoveMode,
+    pub smooth_move_ms: u64,
+    /// 整个执行期间持续按住的按键
+    pub hold_key: Option<Key>,
+    /// 回放速度倍率，所有停留时间都除以该值
+    pub speed: f64,
+    /// 双击/三击中相邻两下之间的间隔，不受回放速度影响
+    pub multi_click_gap: Duration,
+    /// 自适应间隔：点击步骤等待反馈区域变化，代替固定的停留时间
+    pub adaptive: Option<AdaptiveInterval>,
+    /// 电量和 CPU 保护，以及暂停时写入原因供界面显示的位置
+    pub power_guard: Option<(PowerGuard, Arc<Mutex<Option<String>>>)>,
+    /// 从上次中断的位置继续执行
+    pub resume: Option<RunPosition>,
+    /// 统计相邻两次点击的实际间隔
+    pub timing: Option<SharedTiming>,
+    /// 接收执行中的移动、点击和状态消息
+    pub observer: Option<Arc<dyn RunObserver>>,
+    /// 开始时或每次点击前切换到前台的窗口
+    pub focus_window: Option<(WindowTarget, FocusMode)>,
+    /// 执行期间阻止系统睡眠和屏幕保护
+    pub keep_awake: bool,
+    /// 执行插件步骤；模拟执行时跳过，插件可能访问真实设备
+    pub plugins: bool,
+    /// 把点击、触发和执行结束广播为 `events` 中的事件；模拟执行时关闭
+    pub events: bool,
+    /// 读取屏幕：等待颜色、点击图片和等待图片；模拟执行时关闭，
+    /// 等待的画面视为已经出现，要点击的图片视为没有找到
+    pub screen: bool,
+}
+
+/// 执行到的位置：第几轮、第几个步骤、各层循环的进度、变量和已完成的点击数
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RunPosition {
+    pub iteration: u64,
+    pub step: usize,
+    pub loops: Vec<u32>,
+    pub variables: Variables,
+    pub clicks: u32,
+}
+
+// 执行期间的输入保护。
+// 无论执行是正常结束、被停止还是中途 panic，离开作用域时都会释放本次执行
+// 按下的所有按键和按钮，并把状态恢复为空闲。
+struct RunGuard<'a> {
+    engine: &'a Engine,
+    state: &'a SharedState,
+}
+
+impl Drop for RunGuard<'_> {
+    fn drop(&mut self) {
+        self.engine.release_all();
+        self.state.set(AppState::Idle);
+    }
+}
+
+/// 单个目标位置上的点击统计
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TargetStats {
+    pub x: i32,
+    pub y: i32,
+    pub clicks: u32,
+    pub errors: u32,
+}
+
+/// 一次执行的结果
+#[derive(Debug, Clone, Default)]
+pub struct RunReport {
+    pub clicks_performed: u32,
+    /// 移动或点击注入失败的次数
+    pub errors: u32,
+    pub targets: Vec<TargetStats>,
+    /// 是否在完成前被手动停止
+    pub stopped: bool,
+    /// 因无法继续注入输入而中止时的原因
+    pub aborted: Option<String>,
+    /// 自适应间隔等到最长时间仍未看到反馈区域变化的次数
+    pub adaptive_timeouts: u32,
+}
+
+impl RunReport {
+    /// 记录一次点击的结果
+    pub fn record(&mut self, x: i32, y: i32, ok: bool) {
+        if ok {
+            self.clicks_performed += 1;
+        } else {
+            self.errors += 1;
+        }
+
+        let index = match self.targets.iter().position(|t| t.x == x && t.y == y) {
+            Some(index) => index,
+            None => {
+                self.targets.push(TargetStats { x, y, clicks: 0, errors: 0 });
+                self.targets.len() - 1
+            }
+        };
+        let target = &mut self.targets[index];
+        if ok {
+            target.clicks += 1;
+        } else {
+            target.errors += 1;
+        }
+    }
+}
+
+// 点击或移动的目标：坐标步骤按表达式计算，点击图片步骤在屏幕上查找模板，点击所有匹配
+// 步骤找出模板出现的每一处；找不到时为空
+fn locate(
+    step: &SequenceStep,
+    compiled: &CompiledStep,
+    vars: &Variables,
+    options: &RunOptions,
+) -> Result<Vec<(i32, i32)>, String> {
+    let Some(template) = compiled.template.as_ref().filter(|_| step.kind.clicks()) else {
+        return compiled.target(step, vars).map(|target| vec![target]);
+    };
+    if !options.screen {
+        return Ok(Vec::new());
+    }
+    let result = match step.kind {
+        StepKind::ClickAllImages => template::find_all(template, &step.matching),
+        _ => template::find(template, &step.matching).map(Vec::from_iter),
+    };
+    if let Some(observer) = &options.observer {
+        let text = match &result {
+            Ok(found) if found.is_empty() => t!("没有找到图片 {}", step.image),
+            Ok(found) if step.kind == StepKind::ClickAllImages => t!("找到图片 {} {} 处", step.image, found.len()),
+            Ok(found) => t!("找到图片 {}（相似度 {}）", step.image, format!("{:.2}", found[0].confidence)),
+            Err(reason) => t!("找图失败: {}", reason),
+        };
+        observer.status(text);
+    }
+    result.map(|found| found.iter().map(|found| found.center()).collect())
+}
+
+/// 循环执行序列，直到达到点击次数上限或被停止
+pub fn run(
+    sequence: &Sequence,
+    options: &RunOptions,
+    engine: &Engine,
+    state: &SharedState,
+    total_clicks: &Arc<Mutex<u32>>,
+) -> RunReport {
+    let mut report = RunReport::default();
+    if sequence.steps.is_empty() {
+        return report;
+    }
+
+    let _guard = RunGuard { engine, state };
+    // 先检查序列，有错时还没有切换窗口或按下按键
+    let compiled = match sequence.compile() {
+        Ok(compiled) => compiled,
+        Err(reason) => {
+            report.aborted = Some(reason);
+            return report;
+        }
+    };
+    let _timer = timing::HighResolutionTimer::begin();
+    // 声明失败时照常执行，只在日志中提示
+    let _awake = match options.keep_awake.then(KeepAwake::new) {
+        Some(Err(reason)) => {
+            if let Some(observer) = &options.observer {
+                observer.status(t!("无法阻止系统睡眠: {}", reason));
+            }
+            None
+        }
+        awake => awake,
+    };
+    // 按住的按键也要发给目标窗口，先切换再按下
+    if let Some((target, _)) = &options.focus_window
+        && let Err(reason) = target_window::bring_to_front(target)
+    {
+        report.aborted = Some(reason);
+        return report;
+    }
+    // 按不下去时照常执行，失败计入错误并显示在错误面板中
+    if let Some(key) = options.hold_key
+        && engine.press_key(key).is_err()
+    {
+        report.errors += 1;
+    }
+
+    let mut vars = sequence.initial_variables();
+
+    // 正在执行的各层循环已完成的轮数，最内层在最后
+    let mut loops: Vec<u32> = Vec::new();
+    let mut power_monitor = power::Monitor::default();
+
+    // 等待图片超时后已经连续重试的次数，等到图片时清零
+    let mut wait_retries = 0;
+
+    let mut resume = options.resume.clone();
+    // 中断前已经完成的点击，计入点击次数上限和内置变量 n
+    let clicks_before = resume.as_ref().map_or(0, |position| position.clicks);
+
+    'run: for iteration in resume.as_ref().map_or(0, |position| position.iteration).. {
+        vars.insert(ITERATION_VAR.to_string(), iteration as f64);
+        loops.clear();
+        let mut pc = 0;
+        if let Some(position) = resume.take() {
+            pc = position.step;
+            loops = position.loops;
+            vars.extend(position.variables);
+        }
+
+        while pc < sequence.steps.len() {
+            let clicks = clicks_before + report.clicks_performed;
+            if clicks >= options.max_clicks {
+                break 'run;
+            }
+            if let Some(observer) = &options.observer
+                && observer.checkpoint_due()
+            {
+                observer.checkpoint(RunPosition { iteration, step: pc, loops: loops.clone(), variables: vars.clone(), clicks });
+            }
+            if !state.is_running() {
+                report.stopped = true;
+                break 'run;
+            }
+            if let Some((guard, paused)) = &options.power_guard
+                && !power_monitor.wait_until_clear(guard, paused, state)
+            {
+                report.stopped = true;
+                break 'run;
+            }
+
+            let step = &sequence.steps[pc];
+            let compiled = &compiled[pc];
+            // 点击前的反馈区域；读取成功时本步骤等待区域变化，不再按停留时间等待
+            let mut baseline = None;
+
+            match step.kind {
+                StepKind::LoopStart => {
+                    if step.repeat == 0 {
+                        pc = compiled.pair + 1;
+                        continue;
+                    }
+                    loops.push(0);
+                    if !step.loop_var.is_empty() {
+                        vars.insert(step.loop_var.clone(), 0.0);
+                    }
+                    pc += 1;
+                    continue;
+                }
+                StepKind::LoopEnd => {
+                    let start = compiled.pair;
+                    if let Some(done) = loops.last_mut() {
+                        *done += 1;
+                        let loop_step = &sequence.steps[start];
+                        if *done < loop_step.repeat {
+                            if !loop_step.loop_var.is_empty() {
+                                vars.insert(loop_step.loop_var.clone(), *done as f64);
+                            }
+                            pc = start + 1;
+                            continue;
+                        }
+                        loops.pop();
+                    }
+                    pc += 1;
+                    continue;
+                }
+                StepKind::Comment => {
+                    pc += 1;
+                    continue;
+                }
+                // 安全桌面上注入会静默失败，与其空跑不如立即中止
+                _ if secure_desktop::input_desktop_is_secure() => {
+                    report.aborted = Some(i18n::tr(secure_desktop::SECURE_DESKTOP_MESSAGE).to_string());
+                    break 'run;
+                }
+                StepKind::Wait => {}
+                StepKind::Click | StepKind::Move | StepKind::ClickImage | StepKind::ClickAllImages => match locate(step, compiled, &vars, options) {
+                    // 没有找到图片时为空，跳过这一步
+                    Ok(targets) => {
+                        for (index, (x, y)) in targets.into_iter().enumerate() {
+                            // 点击所有匹配：每两次点击之间停顿，期间也要能停止、不超过点击次数上限
+                            if index > 0 {
+                                if !state.is_running() {
+                                    report.stopped = true;
+                                    break 'run;
+                                }
+                                if clicks_before + report.clicks_performed >= options.max_clicks {
+                                    break 'run;
+                                }
+                                timing::sleep(Duration::from_secs_f64(step.click_delay / options.speed));
+                            }
+                            // 目标窗口找不到或切换不过去时，点击会落在别的窗口上，直接中止
+                            if step.kind.clicks()
+                                && let Some((target, FocusMode::BeforeEachClick)) = &options.focus_window
+                                && let Err(reason) = target_window::bring_to_front(target)
+                            {
+                                report.aborted = Some(reason);
+                                break 'run;
+                            }
+                            // 读不到光标位置时按需要移动处理，不再跳过这一步
+                            let already_there = engine.get_mouse_position().is_ok_and(|position| position == (x, y));
+                            let moved = move_to_target(engine, x, y, options.move_mode, options.smooth_move_ms);
+                            if step.kind == StepKind::Move {
+                                if moved.is_err() {
+                                    report.errors += 1;
+                                }
+                                if let Some(observer) = &options.observer {
+                                    observer.moved(x, y);
+                                }
+                            } else {
+                                baseline = options.adaptive.as_ref().and_then(AdaptiveInterval::sample);
+                                // 移动后给目标程序一点时间处理；光标本来就在目标上时不用等，连续点击同一点才能更快
+                                if !already_there {
+                                    timing::sleep(SETTLE_TIME);
+                                }
+                                let result = moved
+                                    .and_then(|()| click_times(engine, step.click_type, step.multi_click, options.multi_click_gap));
+                                let clicked = result.is_ok();
+                                report.record(x, y, clicked);
+                                if options.events {
+                                    events::emit(Event::Click { x, y, ok: clicked });
+                                }
+                                if clicked && let Ok(mut count) = total_clicks.lock() {
+                                    *count += 1;
+                                }
+                                if clicked && let Some(timing) = &options.timing {
+                                    timing.lock().unwrap().record(Instant::now());
+                                }
+                                if let Some(observer) = &options.observer {
+                                    observer.clicked(x, y, result.as_ref().copied());
+                                }
+                            }
+                        }
+                    }
+                    Err(_) => report.errors += 1,
+                },
+                StepKind::Scroll => {
+                    if engine.scroll(step.scroll).is_err() {
+                        report.errors += 1;
+                    }
+                }
+                StepKind::Key => {
+                    if let Some(key) = compiled.key
+                        && engine.tap_key(key).is_err()
+                    {
+                        report.errors += 1;
+                    }
+                }
+                StepKind::WaitColor | StepKind::WaitImage if !options.screen => {}
+                StepKind::WaitColor => match compiled.target(step, &vars) {
+                    Ok((x, y)) => {
+                        let (gx, gy) = display::to_global(x, y);
+                        // 像素触发：反复截取这一点，直到颜色在容差之内；整个步骤共用一个截图连接
+                        let mut capturer = capture::Capturer::new();
+                        loop {
+                            match capturer.capture(Region::new(gx, gy, 1, 1)) {
+                                Ok(frame) if frame.pixel(gx, gy).is_some_and(|pixel| step.color.matches(pixel)) => break,
+                                Ok(_) => {}
+                                Err(reason) => {
+                                    report.errors += 1;
+                                    if let Some(observer) = &options.observer {
+                                        observer.status(t!("等待颜色: {}", reason));
+                                    }
+                                    break;
+                                }
+                            }
+                            if !state.is_running() {
+                                report.stopped = true;
+                                break 'run;
+                            }
+                            timing::sleep(TRIGGER_POLL_INTERVAL);
+                        }
+                    }
+                    Err(_) => report.errors += 1,
+                },
+                StepKind::WaitImage => {
+                    let Some(template) = &compiled.template else {
+                        pc += 1;
+                        continue;
+                    };
+                    let started = Instant::now();
+                    let mut timed_out = false;
+                    loop {
+                        match template::find(template, &step.matching) {
+                            Ok(Some(_)) => break,
+                            Ok(None) => {}
+                            Err(reason) => {
+                                report.errors += 1;
+                                if let Some(observer) = &options.observer {
+                                    observer.status(t!("找图失败: {}", reason));
+                                }
+                                break;
+                            }
+                        }
+                        if !state.is_running() {
+                            report.stopped = true;
+                            break 'run;
+                        }
+                        // 等的是外部程序，超时不受回放速度影响
+                        if step.timeout > 0.0 && started.elapsed().as_secs_f64() >= step.timeout {
+                            timed_out = true;
+                            break;
+                        }
+                        timing::sleep(TRIGGER_POLL_INTERVAL);
+                    }
+                    if timed_out {
+                        let reason = t!("等待图片 {} 超时", step.image);
+                        if let Some(observer) = &options.observer {
+                            observer.status(reason.clone());
+                        }
+                        match step.on_timeout {
+                            TimeoutAction::Skip => {}
+                            TimeoutAction::Abort => {
+                                report.aborted = Some(reason);
+                                break 'run;
+                            }
+                            TimeoutAction::Retry if wait_retries >= MAX_WAIT_RETRIES => {
+                                report.aborted = Some(t!("等待图片 {} 重试 {} 次后仍然超时", step.image, MAX_WAIT_RETRIES));
+                                break 'run;
+                            }
+                            TimeoutAction::Retry => {
+                                wait_retries += 1;
+                                // 上一步是循环标记时没有可重试的动作，只重新等待
+                                if pc > 0 && !matches!(sequence.steps[pc - 1].kind, StepKind::LoopStart | StepKind::LoopEnd) {
+                                    pc -= 1;
+                                }
+                                continue;
+                            }
+                        }
+                    } else {
+                        wait_retries = 0;
+                    }
+                }
+                StepKind::Plugin if !options.plugins => {}
+                StepKind::Plugin => {
+                    // 编译时已经确认插件和步骤存在
+                    let Ok((plugin, _)) = plugins::registry().step(&step.plugin, &step.plugin_step) else {
+                        pc += 1;
+                        continue;
+                    };
+                    // 触发步骤反复询问，直到插件报告条件满足
+                    loop {
+                        match plugin.call(&step.plugin_step, &step.params, &vars) {
+                            Ok(outcome) => {
+                                vars.extend(outcome.variables);
+                                if compiled.trigger && outcome.fired && options.events {
+                                    events::emit(Event::Trigger { plugin: step.plugin.clone(), step: step.plugin_step.clone() });
+                                }
+                                if outcome.fired || !compiled.trigger {
+                                    break;
+                                }
+                            }
+                            Err(reason) => {
+                                report.errors += 1;
+                                if let Some(observer) = &options.observer {
+                                    observer.status(t!("插件 {}: {}", step.plugin, reason));
+                                }
+                                break;
+                            }
+                        }
+                        if !state.is_running() {
+                            report.stopped = true;
+                            break 'run;
+                        }
+                        timing::sleep(TRIGGER_POLL_INTERVAL);
+                    }
+                }
+            }
+
+            vars.insert(CLICKS_VAR.to_string(), (clicks_before + report.clicks_performed) as f64);
+            if compiled.apply_update(&mut vars).is_err() {
+                report.errors += 1;
+            }
+
+            match (&options.adaptive, &baseline) {
+                (Some(adaptive), Some(baseline)) => match adaptive.wait_for_change(baseline, state) {
+                    WaitOutcome::Changed => {}
+                    WaitOutcome::TimedOut => report.adaptive_timeouts += 1,
+                    WaitOutcome::Stopped => {
+                        report.stopped = true;
+                        break 'run;
+                    }
+                },
+                _ => timing::sleep(Duration::from_secs_f64(step.dwell / options.speed)),
+            }
+            pc += 1;
+        }
+    }
+
+    tracing::info!(
+        clicks = report.clicks_performed,
+        errors = report.errors,
+        stopped = report.stopped,
+        aborted = report.aborted.as_deref(),
+        "序列执行结束"
+    );
+    if options.events {
+        events::emit(Event::RunFinished {
+            clicks: report.clicks_performed,
+            errors: report.errors,
+            stopped: report.stopped,
+            aborted: report.aborted.clone(),
+        });
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn click(x: i32, y: i32) -> SequenceStep {
+        SequenceStep::new(StepKind::Click, x, y)
+    }
+
+    fn sequence(steps: Vec<SequenceStep>) -> Sequence {
+        Sequence { steps, variables: Vec::new() }
+    }
+
+    #[test]
+    fn pairs_nested_loops() {
+        let compiled = sequence(vec![
+            SequenceStep::loop_start(2),
+            SequenceStep::loop_start(3),
+            click(1, 1),
+            SequenceStep::loop_end(),
+            SequenceStep::loop_end(),
+        ])
+        .compile()
+        .unwrap();
+        let pairs: Vec<usize> = compiled.iter().map(|step| step.pair).collect();
+        assert_eq!(pairs, [4, 3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn rejects_unpaired_loops() {
+        let unmatched_end = sequence(vec![click(1, 1), SequenceStep::loop_end()]);
+        assert_eq!(unmatched_end.compile().err(), Some(t!("步骤 {}: 循环结束没有对应的循环开始", 2)));
+
+        let unclosed = sequence(vec![SequenceStep::loop_start(2), click(1, 1)]);
+        assert_eq!(unclosed.compile().err(), Some(t!("步骤 {}: 循环没有结束标记", 1)));
+    }
+
+    #[test]
+    fn rejects_invalid_durations() {
+        for (dwell, click_delay, timeout) in [(-1.0, 0.0, 0.0), (f64::NAN, 0.0, 0.0), (0.0, f64::INFINITY, 0.0), (0.0, 0.0, -0.5)] {
+            let step = SequenceStep { dwell, click_delay, timeout, ..click(1, 1) };
+            assert!(sequence(vec![step.clone()]).compile().is_err(), "{:?}", (dwell, click_delay, timeout));
+            assert!(sequence(vec![step]).validate().is_err());
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_variable_names() {
+        let mut bad_variable = sequence(vec![click(1, 1)]);
+        bad_variable.variables.push(Variable { name: "x; rm -rf ~".to_string(), value: 0.0 });
+        assert!(bad_variable.compile().is_err());
+
+        let bad_loop_var = sequence(vec![
+            SequenceStep { loop_var: "$(id)".to_string(), ..SequenceStep::loop_start(2) },
+            click(1, 1),
+            SequenceStep::loop_end(),
+        ]);
+        assert!(bad_loop_var.compile().is_err());
+
+        let bad_update = sequence(vec![SequenceStep { update: "k = k +".to_string(), ..click(1, 1) }]);
+        assert!(bad_update.compile().is_err());
+    }
+
+    #[test]
+    fn requires_a_click_step() {
+        let no_clicks = sequence(vec![SequenceStep::new(StepKind::Wait, 0, 0)]);
+        assert_eq!(no_clicks.compile().err(), Some(t!("序列中没有点击步骤").to_string()));
+    }
+
+    #[test]
+    fn parses_named_and_single_character_keys() {
+        assert_eq!(parse_key("enter"), Ok(Key::Return));
+        assert_eq!(parse_key(" a "), Ok(Key::Unicode('a')));
+        assert!(parse_key("").is_err());
+        assert!(parse_key("ab").is_err());
+    }
+}
@@ -0,0 +1,293 @@
+// 模拟执行（试运行）
+//
+// `MockMouseBackend` 实现 `MouseBackend`，只记下收到的移动、按钮、按键和滚轮，不注入任何输入。
+// `simulate` 用它和虚拟时钟把序列完整跑一遍：不会移动鼠标，也不用真的等待，
+// 得到每个动作会在什么时刻发生、一共会点击多少次、会不会出错。上线前可以先预览有风险的自动化，
+// 测试中也可以用它检查执行引擎的行为。
+//
+// 模拟时不切换目标窗口、不阻止系统睡眠、不播放提示音，也不检查电量和 CPU；
+// 自适应间隔依赖屏幕上的真实变化，模拟时按固定的停留时间等待；等待颜色和等待图片视为
+// 画面已经出现，点击图片视为没有找到。
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use enigo::{Button, Key};
+
+use crate::app_state::SharedState;
+use crate::backend::MouseBackend;
+use crate::engine::Engine;
+use crate::i18n::t;
+use crate::sequence::{self, RunOptions, RunReport, Sequence};
+use crate::timing::{self, VirtualClock};
+
+/// 最多记录的动作数，超过后停止模拟，避免点击永远达不到上限的序列一直运行
+pub const MAX_ACTIONS: usize = 100_000;
+
+/// 模拟后端记录的动作
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    /// 移动到全局坐标
+    Move { x: i32, y: i32 },
+    Button { button: Button, pressed: bool },
+    Key { key: Key, pressed: bool },
+    Scroll(i32),
+}
+
+impl Action {
+    /// 模拟结果列表中显示的文字
+    pub fn describe(&self) -> String {
+        let pressed = |pressed: bool| if pressed { t!("按下") } else { t!("松开") };
+        match *self {
+            Action::Move { x, y } => t!("移动到 ({}, {})", x, y),
+            Action::Button { button, pressed: down } => t!("{} 键{}", format!("{:?}", button), pressed(down)),
+            Action::Key { key, pressed: down } => t!("按键 {}{}", format!("{:?}", key), pressed(down)),
+            Action::Scroll(amount) => t!("滚轮 {}", amount),
+        }
+    }
+}
+
+/// 记录下来的一个动作，`at` 是从开始执行算起的时间
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Recorded {
+    pub at: Duration,
+    pub action: Action,
+}
+
+#[derive(Debug)]
+struct Recording {
+    actions: Vec<Recorded>,
+    position: (i32, i32),
+    started: Instant,
+    // 模拟执行时按虚拟时钟记录动作发生的时刻
+    clock: Option<VirtualClock>,
+    // 记录满 `MAX_ACTIONS` 个动作时请求停止的状态
+    stop: Option<SharedState>,
+}
+
+/// 只记录动作的后端。克隆出的副本共享同一份记录，交给控制器后仍能从另一份读取
+#[derive(Debug, Clone)]
+pub struct MockMouseBackend(Arc<Mutex<Recording>>);
+
+impl Default for MockMouseBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockMouseBackend {
+    /// 创建空的记录，光标位于 (0, 0)
+    pub fn new() -> Self {
+        Self(Arc::new(Mutex::new(Recording {
+            actions: Vec::new(),
+            position: (0, 0),
+            started: Instant::now(),
+            clock: None,
+            stop: None,
+        })))
+    }
+
+    /// 记录满 `MAX_ACTIONS` 个动作时把 `state` 设为正在停止
+    pub fn stop_when_full(&self, state: SharedState) {
+        self.0.lock().unwrap().stop = Some(state);
+    }
+
+    /// 按 `clock` 的时间记录动作，不再按真实经过的时间
+    pub fn use_clock(&self, clock: VirtualClock) {
+        self.0.lock().unwrap().clock = Some(clock);
+    }
+
+    /// 光标的起始位置（全局坐标）
+    pub fn set_position(&self, x: i32, y: i32) {
+        self.0.lock().unwrap().position = (x, y);
+    }
+
+    /// 已记录的动作
+    pub fn actions(&self) -> Vec<Recorded> {
+        self.0.lock().unwrap().actions.clone()
+    }
+
+    /// 记录是否已满，满了之后不再记录新的动作
+    pub fn is_full(&self) -> bool {
+        self.0.lock().unwrap().actions.len() >= MAX_ACTIONS
+    }
+
+    fn record(&self, action: Action) -> Result<(), String> {
+        let mut recording = self.0.lock().unwrap();
+        if recording.actions.len() >= MAX_ACTIONS {
+            return Ok(());
+        }
+        let at = match &recording.clock {
+            Some(clock) => clock.elapsed(),
+            None => recording.started.elapsed(),
+        };
+        recording.actions.push(Recorded { at, action });
+        if recording.actions.len() >= MAX_ACTIONS
+            && let Some(state) = &recording.stop
+        {
+            state.stop();
+        }
+        Ok(())
+    }
+}
+
+impl MouseBackend for MockMouseBackend {
+    fn move_to(&mut self, x: i32, y: i32) -> Result<(), String> {
+        self.0.lock().unwrap().position = (x, y);
+        self.record(Action::Move { x, y })
+    }
+
+    fn button(&mut self, button: Button, pressed: bool) -> Result<(), String> {
+        self.record(Action::Button { button, pressed })
+    }
+
+    fn key(&mut self, key: Key, pressed: bool) -> Result<(), String> {
+        self.record(Action::Key { key, pressed })
+    }
+
+    fn scroll(&mut self, amount: i32) -> Result<(), String> {
+        self.record(Action::Scroll(amount))
+    }
+
+    fn position(&self) -> Option<(i32, i32)> {
+        Some(self.0.lock().unwrap().position)
+    }
+}
+
+/// 模拟执行的结果
+#[derive(Debug, Clone, Default)]
+pub struct Simulation {
+    pub report: RunReport,
+    pub actions: Vec<Recorded>,
+    /// 真实执行大约需要的时间
+    pub duration: Duration,
+    /// 动作太多，只模拟了前 `MAX_ACTIONS` 个
+    pub truncated: bool,
+}
+
+/// 用模拟后端和虚拟时钟执行序列。`start` 是光标的起始位置（全局坐标），
+/// `state` 应当处于执行中，停止它时模拟提前结束。只有 `options` 中的点击次数、移动方式、
+/// 按住的按键、速度和多击间隔会被使用，其余选项在模拟时都不起作用
+pub fn simulate(sequence: &Sequence, options: RunOptions, start: (i32, i32), state: &SharedState) -> Simulation {
+    let mock = MockMouseBackend::new();
+    mock.set_position(start.0, start.1);
+    mock.stop_when_full(state.clone());
+    let engine = Engine::with_backend(Box::new(mock.clone()));
+    let options = RunOptions {
+        adaptive: None,
+        power_guard: None,
+        resume: None,
+        timing: None,
+        observer: None,
+        focus_window: None,
+        keep_awake: false,
+        plugins: false,
+        events: false,
+        screen: false,
+        ..options
+    };
+    let total_clicks = Arc::new(Mutex::new(0));
+    let clock = VirtualClock::default();
+    mock.use_clock(clock.clone());
+    let report = timing::with_virtual_clock(&clock, || sequence::run(sequence, &options, &engine, state, &total_clicks));
+    Simulation { report, truncated: mock.is_full(), actions: mock.actions(), duration: clock.elapsed() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::AppState;
+    use crate::sequence::{SequenceStep, StepKind};
+    use crate::{ClickMultiplicity, MoveMode};
+
+    fn options(max_clicks: u32) -> RunOptions {
+        RunOptions {
+            max_clicks,
+            move_mode: MoveMode::Teleport,
+            smooth_move_ms: 0,
+            hold_key: None,
+            speed: 1.0,
+            multi_click_gap: Duration::from_millis(20),
+            adaptive: None,
+            power_guard: None,
+            resume: None,
+            timing: None,
+            observer: None,
+            focus_window: None,
+            keep_awake: false,
+            plugins: false,
+            events: false,
+            screen: false,
+        }
+    }
+
+    fn run(sequence: &Sequence, max_clicks: u32) -> Simulation {
+        simulate(sequence, options(max_clicks), (0, 0), &SharedState::new(AppState::Running))
+    }
+
+    // 每次按下左键时光标所在的位置
+    fn clicked_positions(simulation: &Simulation) -> Vec<(i32, i32)> {
+        let mut position = (0, 0);
+        let mut clicks = Vec::new();
+        for recorded in &simulation.actions {
+            match recorded.action {
+                Action::Move { x, y } => position = (x, y),
+                Action::Button { button: Button::Left, pressed: true } => clicks.push(position),
+                _ => {}
+            }
+        }
+        clicks
+    }
+
+    #[test]
+    fn runs_nested_loops_with_loop_variables() {
+        let sequence = Sequence {
+            steps: vec![
+                SequenceStep { loop_var: "a".to_string(), ..SequenceStep::loop_start(2) },
+                SequenceStep { loop_var: "b".to_string(), ..SequenceStep::loop_start(3) },
+                SequenceStep { x_expr: "100 + a * 10 + b".to_string(), dwell: 0.1, ..SequenceStep::new(StepKind::Click, 0, 50) },
+                SequenceStep::loop_end(),
+                SequenceStep::loop_end(),
+            ],
+            variables: Vec::new(),
+        };
+        let simulation = run(&sequence, 6);
+        assert_eq!(simulation.report.clicks_performed, 6);
+        assert_eq!(simulation.report.errors, 0);
+        assert_eq!(
+            clicked_positions(&simulation),
+            [(100, 50), (101, 50), (102, 50), (110, 50), (111, 50), (112, 50)]
+        );
+    }
+
+    #[test]
+    fn stops_at_the_click_limit() {
+        let sequence = Sequence::single_point(5, 5, crate::ClickType::Left, 0.5);
+        let simulation = run(&sequence, 4);
+        assert_eq!(simulation.report.clicks_performed, 4);
+        assert_eq!(clicked_positions(&simulation).len(), 4);
+        assert!(!simulation.truncated);
+        // 每次点击后停留 0.5 秒，另有第一次移动后的短暂等待
+        assert!(simulation.duration >= Duration::from_secs(2) && simulation.duration < Duration::from_secs(3));
+    }
+
+    #[test]
+    fn counts_a_double_click_as_one_click() {
+        let mut sequence = Sequence::single_point(5, 5, crate::ClickType::Left, 0.1);
+        sequence.steps[0].multi_click = ClickMultiplicity::Double;
+        let simulation = run(&sequence, 3);
+        assert_eq!(simulation.report.clicks_performed, 3);
+        assert_eq!(clicked_positions(&simulation).len(), 6);
+    }
+
+    #[test]
+    fn aborts_before_any_input_when_the_sequence_does_not_compile() {
+        let sequence = Sequence {
+            steps: vec![SequenceStep::loop_start(2), SequenceStep::new(StepKind::Click, 5, 5)],
+            variables: Vec::new(),
+        };
+        let simulation = run(&sequence, 3);
+        assert!(simulation.report.aborted.is_some());
+        assert!(simulation.actions.is_empty());
+    }
+}
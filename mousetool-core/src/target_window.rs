@@ -0,0 +1,339 @@
+// 目标窗口：列出打开的窗口，在开始执行或每次点击前把选中的窗口切换到前台
+//
+// 切换到其他窗口后，点击会落在挡在目标前面的窗口上。选定目标窗口后，执行前先把它切到前台，
+// 这样中途切走也能继续。窗口按进程名和标题保存，下次启动时按同样的条件重新查找；
+// 标题变了（例如切换了文档）时退而使用同一进程的第一个窗口。
+// - Windows：EnumWindows 列出顶层窗口，切换时临时共享前台线程的输入状态，绕过前台切换限制
+// - macOS：通过 System Events 的脚本列出和切换窗口，需要「辅助功能」权限，每次切换约需 0.1 秒
+// - Linux：读取窗口管理器维护的 _NET_CLIENT_LIST，通过 _NET_ACTIVE_WINDOW 请求切换
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::t;
+
+// 切换后等待窗口完成重绘再点击
+const ACTIVATION_DELAY: Duration = Duration::from_millis(150);
+
+/// 执行前切换到前台的窗口，按标题和进程名查找
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowTarget {
+    pub title: String,
+    /// 进程名，不含路径
+    pub process: String,
+    /// 本次运行中的窗口句柄，不保存；macOS 上没有
+    #[serde(skip)]
+    pub id: u64,
+}
+
+impl WindowTarget {
+    /// 界面上显示的名称：标题和进程名
+    pub fn label(&self) -> String {
+        match (self.title.is_empty(), self.process.is_empty()) {
+            (false, false) => format!("{} — {}", self.title, self.process),
+            (false, true) => self.title.clone(),
+            _ => self.process.clone(),
+        }
+    }
+}
+
+/// 什么时候切换到目标窗口
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FocusMode {
+    #[default]
+    BeforeRun,
+    BeforeEachClick,
+}
+
+impl FocusMode {
+    /// 界面中按此顺序列出
+    pub const ALL: [FocusMode; 2] = [FocusMode::BeforeRun, FocusMode::BeforeEachClick];
+
+    /// 界面上显示的名称
+    pub fn label(self) -> &'static str {
+        match self {
+            FocusMode::BeforeRun => t!("开始执行时"),
+            FocusMode::BeforeEachClick => t!("每次点击前"),
+        }
+    }
+}
+
+/// 当前打开的窗口，不含没有标题的窗口
+pub fn list() -> Vec<WindowTarget> {
+    platform::list()
+}
+
+/// 把目标窗口切换到前台；刚切换过时等待窗口重绘
+pub fn bring_to_front(target: &WindowTarget) -> Result<(), String> {
+    if platform::focus(target)? {
+        std::thread::sleep(ACTIVATION_DELAY);
+    }
+    Ok(())
+}
+
+// 优先使用本次运行中选中的同一个窗口，其次是进程和标题都相同的窗口，最后是同一进程的窗口
+#[cfg(not(target_os = "macos"))]
+fn find<'a>(target: &WindowTarget, windows: &'a [WindowTarget]) -> Result<&'a WindowTarget, String> {
+    let same_process = |window: &&WindowTarget| window.process == target.process;
+    windows
+        .iter()
+        .filter(same_process)
+        .find(|window| target.id != 0 && window.id == target.id)
+        .or_else(|| windows.iter().filter(same_process).find(|window| window.title == target.title))
+        .or_else(|| windows.iter().find(same_process))
+        .ok_or_else(|| t!("找不到目标窗口「{}」", target.label()))
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::WindowTarget;
+    use crate::i18n::t;
+    use windows_sys::Win32::Foundation::{BOOL, CloseHandle, HWND, LPARAM};
+    use windows_sys::Win32::System::Threading::{
+        AttachThreadInput, GetCurrentThreadId, OpenProcess, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+        QueryFullProcessImageNameW,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        BringWindowToTop, EnumWindows, GW_OWNER, GWL_EXSTYLE, GetForegroundWindow, GetWindow, GetWindowLongW,
+        GetWindowTextLengthW, GetWindowTextW, GetWindowThreadProcessId, IsIconic, IsWindowVisible, SW_RESTORE,
+        SetForegroundWindow, ShowWindow, WS_EX_TOOLWINDOW,
+    };
+
+    pub fn list() -> Vec<WindowTarget> {
+        let mut windows: Vec<WindowTarget> = Vec::new();
+        unsafe { EnumWindows(Some(collect), &mut windows as *mut Vec<WindowTarget> as LPARAM) };
+        windows
+    }
+
+    // 只列出任务栏上会出现的窗口：可见、没有所有者、不是工具窗口
+    unsafe extern "system" fn collect(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let windows = unsafe { &mut *(lparam as *mut Vec<WindowTarget>) };
+        unsafe {
+            if IsWindowVisible(hwnd) == 0
+                || !GetWindow(hwnd, GW_OWNER).is_null()
+                || GetWindowLongW(hwnd, GWL_EXSTYLE) as u32 & WS_EX_TOOLWINDOW != 0
+            {
+                return 1;
+            }
+            let len = GetWindowTextLengthW(hwnd);
+            if len == 0 {
+                return 1;
+            }
+            let mut title = vec![0u16; len as usize + 1];
+            let copied = GetWindowTextW(hwnd, title.as_mut_ptr(), title.len() as i32).max(0) as usize;
+            let mut pid = 0;
+            GetWindowThreadProcessId(hwnd, &mut pid);
+            windows.push(WindowTarget {
+                title: String::from_utf16_lossy(&title[..copied]),
+                process: process_name(pid).unwrap_or_default(),
+                id: hwnd as usize as u64,
+            });
+        }
+        1
+    }
+
+    fn process_name(pid: u32) -> Option<String> {
+        unsafe {
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+            if process.is_null() {
+                return None;
+            }
+            let mut path = [0u16; 1024];
+            let mut len = path.len() as u32;
+            let ok = QueryFullProcessImageNameW(process, PROCESS_NAME_WIN32, path.as_mut_ptr(), &mut len);
+            CloseHandle(process);
+            if ok == 0 {
+                return None;
+            }
+            let path = String::from_utf16_lossy(&path[..len as usize]);
+            Some(path.rsplit('\\').next().unwrap_or(&path).to_string())
+        }
+    }
+
+    pub fn focus(target: &WindowTarget) -> Result<bool, String> {
+        let windows = list();
+        let window = super::find(target, &windows)?;
+        let hwnd = window.id as usize as HWND;
+        unsafe {
+            let foreground = GetForegroundWindow();
+            if foreground == hwnd {
+                return Ok(false);
+            }
+            if IsIconic(hwnd) != 0 {
+                ShowWindow(hwnd, SW_RESTORE);
+            }
+            // 系统只允许前台程序切换前台窗口，临时把本线程的输入状态挂到前台窗口的线程上
+            let foreground_thread = GetWindowThreadProcessId(foreground, std::ptr::null_mut());
+            let current_thread = GetCurrentThreadId();
+            let attached = foreground_thread != 0
+                && foreground_thread != current_thread
+                && AttachThreadInput(current_thread, foreground_thread, 1) != 0;
+            BringWindowToTop(hwnd);
+            let switched = SetForegroundWindow(hwnd) != 0;
+            if attached {
+                AttachThreadInput(current_thread, foreground_thread, 0);
+            }
+            if !switched {
+                return Err(t!("系统拒绝把「{}」切换到前台", window.label()));
+            }
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::WindowTarget;
+    use crate::i18n::t;
+    use std::process::Command;
+
+    // JavaScript for Automation 脚本，结果以 JSON 输出
+    const LIST_SCRIPT: &str = "function run() {
+    var windows = [];
+    Application('System Events').processes.whose({ backgroundOnly: false })().forEach(function (process) {
+        try {
+            var name = process.name();
+            process.windows().forEach(function (window) {
+                windows.push({ process: name, title: window.name() || '' });
+            });
+        } catch (e) {}
+    });
+    return JSON.stringify(windows);
+}";
+
+    // 参数是进程名和窗口标题；已经在前台时输出 front，找不到进程时输出 missing
+    const FOCUS_SCRIPT: &str = "function run(argv) {
+    var processes = Application('System Events').processes.whose({ name: argv[0] })();
+    if (processes.length === 0) return 'missing';
+    var process = processes[0];
+    if (process.frontmost()) return 'front';
+    process.frontmost = true;
+    var windows = process.windows.whose({ name: argv[1] })();
+    if (windows.length > 0) windows[0].actions.byName('AXRaise').perform();
+    return 'switched';
+}";
+
+    fn osascript(script: &str, args: &[&str]) -> Result<String, String> {
+        let output = Command::new("osascript")
+            .args(["-l", "JavaScript", "-e", script])
+            .args(args)
+            .output()
+            .map_err(|e| t!("无法运行 osascript: {}", e))?;
+        if !output.status.success() {
+            return Err(t!("读取窗口失败: {}", String::from_utf8_lossy(&output.stderr).trim()));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    pub fn list() -> Vec<WindowTarget> {
+        osascript(LIST_SCRIPT, &[])
+            .ok()
+            .and_then(|json| serde_json::from_str::<Vec<WindowTarget>>(&json).ok())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|window| !window.title.is_empty())
+            .collect()
+    }
+
+    pub fn focus(target: &WindowTarget) -> Result<bool, String> {
+        match osascript(FOCUS_SCRIPT, &[&target.process, &target.title])?.as_str() {
+            "missing" => Err(t!("找不到目标窗口「{}」", target.label())),
+            "front" => Ok(false),
+            _ => Ok(true),
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    use super::WindowTarget;
+    use crate::i18n::t;
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::{AtomEnum, ClientMessageEvent, ConnectionExt as _, EventMask, Window};
+    use x11rb::rust_connection::RustConnection;
+
+    // _NET_ACTIVE_WINDOW 的请求来源：2 表示任务栏之类的工具，窗口管理器不会当作抢焦点而拒绝
+    const SOURCE_PAGER: u32 = 2;
+
+    struct Atoms {
+        client_list: u32,
+        active_window: u32,
+        wm_name: u32,
+        wm_pid: u32,
+        utf8_string: u32,
+    }
+
+    fn connect() -> Result<(RustConnection, Window, Atoms), String> {
+        let (conn, screen) = x11rb::connect(None).map_err(|e| t!("无法连接 X 服务器: {}", e))?;
+        let root = conn.setup().roots[screen].root;
+        let atom = |name: &str| -> Result<u32, String> {
+            let failed = |e: &dyn std::fmt::Display| t!("无法连接 X 服务器: {}", e);
+            Ok(conn
+                .intern_atom(false, name.as_bytes())
+                .map_err(|e| failed(&e))?
+                .reply()
+                .map_err(|e| failed(&e))?
+                .atom)
+        };
+        let atoms = Atoms {
+            client_list: atom("_NET_CLIENT_LIST")?,
+            active_window: atom("_NET_ACTIVE_WINDOW")?,
+            wm_name: atom("_NET_WM_NAME")?,
+            wm_pid: atom("_NET_WM_PID")?,
+            utf8_string: atom("UTF8_STRING")?,
+        };
+        Ok((conn, root, atoms))
+    }
+
+    fn property(conn: &RustConnection, window: Window, property: u32, kind: u32) -> Option<x11rb::protocol::xproto::GetPropertyReply> {
+        conn.get_property(false, window, property, kind, 0, u32::MAX / 4).ok()?.reply().ok()
+    }
+
+    fn property32(conn: &RustConnection, window: Window, name: u32, kind: impl Into<u32>) -> Vec<u32> {
+        property(conn, window, name, kind.into())
+            .and_then(|reply| reply.value32().map(|values| values.collect()))
+            .unwrap_or_default()
+    }
+
+    fn windows(conn: &RustConnection, root: Window, atoms: &Atoms) -> Vec<WindowTarget> {
+        property32(conn, root, atoms.client_list, AtomEnum::WINDOW)
+            .into_iter()
+            .filter_map(|id| {
+                let title = property(conn, id, atoms.wm_name, atoms.utf8_string)
+                    .filter(|reply| !reply.value.is_empty())
+                    .or_else(|| property(conn, id, AtomEnum::WM_NAME.into(), AtomEnum::STRING.into()))
+                    .map(|reply| String::from_utf8_lossy(&reply.value).into_owned())
+                    .filter(|title| !title.is_empty())?;
+                let process = property32(conn, id, atoms.wm_pid, AtomEnum::CARDINAL)
+                    .first()
+                    .and_then(|pid| std::fs::read_to_string(format!("/proc/{}/comm", pid)).ok())
+                    .map(|name| name.trim().to_string())
+                    .unwrap_or_default();
+                Some(WindowTarget { title, process, id: id.into() })
+            })
+            .collect()
+    }
+
+    pub fn list() -> Vec<WindowTarget> {
+        connect().map(|(conn, root, atoms)| windows(&conn, root, &atoms)).unwrap_or_default()
+    }
+
+    pub fn focus(target: &WindowTarget) -> Result<bool, String> {
+        let (conn, root, atoms) = connect()?;
+        let windows = windows(&conn, root, &atoms);
+        let window = super::find(target, &windows)?;
+        let id = window.id as Window;
+        if property32(&conn, root, atoms.active_window, AtomEnum::WINDOW).first() == Some(&id) {
+            return Ok(false);
+        }
+        let failed = |e: &dyn std::fmt::Display| t!("无法切换到「{}」: {}", window.label(), e);
+        let event = ClientMessageEvent::new(32, id, atoms.active_window, [SOURCE_PAGER, x11rb::CURRENT_TIME, 0, 0, 0]);
+        conn.send_event(false, root, EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY, event)
+            .map_err(|e| failed(&e))?;
+        conn.flush().map_err(|e| failed(&e))?;
+        Ok(true)
+    }
+}
@@ -0,0 +1,104 @@
+// 后台任务的异步运行时
+//
+// 防离开、定时计划、屏幕监视和远程控制这类功能大部分时间都在等待：等下一个时间点、等像素变化、
+// 等连接上的命令。以前每个功能各开一个线程，用一个 AtomicBool 请求停止，线程要定期醒来检查，
+// 停止时最多还要等一个检查周期。现在它们都作为异步任务运行在同一个 tokio 运行时上，
+// 定时器和网络 IO 不占用线程；每个任务有一个 `CancellationToken`，取消时正在等待的任务立即醒来退出。
+//
+// 运行时在第一次使用时创建，整个程序共用。注入输入仍然经过输入引擎，
+// 任务中用 `blocking` 等待引擎的回复，不会阻塞运行时的工作线程。
+// 执行序列的工作线程、托盘和系统钩子的消息循环需要独占线程，不在这里运行。
+// 退出时 `shutdown` 取消全部任务并等待它们真正结束。
+
+use std::future::Future;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use tokio::runtime::Runtime;
+use tokio::task::JoinHandle;
+pub use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
+
+// 运行时的工作线程数，后台任务都很轻，不需要每个核心一个
+const WORKER_THREADS: usize = 2;
+
+struct Tasks {
+    runtime: Runtime,
+    /// 所有任务的令牌都从它派生，取消它即停止全部任务
+    root: CancellationToken,
+    /// 记录所有启动过的任务，退出时等待它们结束
+    tracker: TaskTracker,
+}
+
+static TASKS: OnceLock<Tasks> = OnceLock::new();
+
+fn tasks() -> &'static Tasks {
+    TASKS.get_or_init(|| Tasks {
+        runtime: tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(WORKER_THREADS)
+            .thread_name("mousetool-tasks")
+            .enable_time()
+            .enable_io()
+            .build()
+            .expect("无法创建后台任务运行时"),
+        root: CancellationToken::new(),
+        tracker: TaskTracker::new(),
+    })
+}
+
+/// 正在运行的后台任务，析构时取消
+pub struct Task {
+    token: CancellationToken,
+    handle: JoinHandle<()>,
+}
+
+impl Task {
+    /// 请求任务停止，任务在下一个等待点退出
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+
+    /// 任务是否已经结束
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+}
+
+impl Drop for Task {
+    fn drop(&mut self) {
+        self.token.cancel();
+    }
+}
+
+/// 启动后台任务。`task` 收到这个任务的取消令牌，应当在每个等待点同时等待它
+pub fn spawn<F>(task: impl FnOnce(CancellationToken) -> F) -> Task
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let tasks = tasks();
+    let token = tasks.root.child_token();
+    let handle = tasks.tracker.spawn_on(task(token.clone()), tasks.runtime.handle());
+    Task { token, handle }
+}
+
+/// 在运行时的阻塞线程池中执行 `f`，例如等待输入引擎或读取屏幕。任务 panic 时返回 None
+pub async fn blocking<R: Send + 'static>(f: impl FnOnce() -> R + Send + 'static) -> Option<R> {
+    tokio::task::spawn_blocking(f).await.ok()
+}
+
+/// 取消所有后台任务
+pub fn cancel_all() {
+    tasks().root.cancel();
+}
+
+/// 取消所有后台任务并等待它们退出，`timeout` 内没有全部退出时返回 false。
+/// 不能在后台任务中调用
+pub fn shutdown(timeout: Duration) -> bool {
+    // 从没启动过任务时不必为此创建运行时
+    let Some(tasks) = TASKS.get() else {
+        return true;
+    };
+    tasks.root.cancel();
+    tasks.tracker.close();
+    tasks.runtime.block_on(async { tokio::time::timeout(timeout, tasks.tracker.wait()).await.is_ok() })
+}
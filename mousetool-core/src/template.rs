@@ -0,0 +1,310 @@
+// 找图
+//
+// 在屏幕上查找一张模板图片（PNG 等常见格式），点击图片步骤用它定位要点击的按钮。
+// 比较的是灰度，相似度为 1 减去逐像素平均差（0 到 1），达到阈值才算找到。
+//
+// 4K 屏幕有八百多万个位置，逐个完整比较太慢，所以先把屏幕和模板同时缩小到 1/2 或 1/4
+// 粗找，只在粗找得分最高的几处附近按原始大小细找；每个位置累计的差一旦超出阈值允许的
+// 范围就放弃这个位置。限定搜索区域能进一步缩短耗时。
+//
+// 模板在缩放比例不同的显示器上截取时，开启多尺度搜索会再按常见的缩放比例缩放模板查找，
+// 耗时成倍增加。坐标与步骤的坐标一样相对所选显示器。
+//
+// 点击所有匹配要找出模板出现的每一处，同一处附近错开几个像素的位置只保留相似度最高的一个。
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::capture::{self, Frame, Region};
+use crate::display;
+use crate::i18n::t;
+
+// 多尺度搜索时尝试的模板缩放比例，对应 100%、125%、150% 显示缩放之间的换算
+const SCALES: [f32; 7] = [1.0, 0.67, 0.75, 0.8, 1.25, 1.33, 1.5];
+// 粗找时缩小后的模板边长至少要有这么多像素，太小的模板直接按原始大小查找
+const MIN_COARSE_SIDE: usize = 8;
+// 粗找的阈值比设定的低这么多，缩小会抹掉细节，得分偏低
+const COARSE_MARGIN: f32 = 0.1;
+// 粗找后细找的位置数
+const MAX_CANDIDATES: usize = 32;
+// 一处匹配的周围往往有好几个错开一两个像素的位置也达到阈值，查找多处时每处多留这么多个
+const CLUSTER: usize = 16;
+/// 查找所有位置时最多返回的个数
+pub const MAX_MATCHES: usize = 64;
+
+// 读过的模板和读取时文件的修改时间
+type TemplateCache = BTreeMap<PathBuf, (Option<SystemTime>, Arc<Template>)>;
+
+static TEMPLATES: Mutex<TemplateCache> = Mutex::new(BTreeMap::new());
+
+/// 找图的参数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MatchOptions {
+    /// 相似度阈值，0 到 1
+    pub threshold: f32,
+    /// 同时按常见的显示缩放比例缩放模板查找
+    pub multi_scale: bool,
+    /// 只在这块区域中查找，坐标相对所选显示器；为 None 时查找整个显示器
+    pub region: Option<Region>,
+}
+
+impl Default for MatchOptions {
+    fn default() -> Self {
+        Self { threshold: 0.9, multi_scale: false, region: None }
+    }
+}
+
+/// 找到的位置，坐标相对所选显示器
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Match {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    pub confidence: f32,
+}
+
+impl Match {
+    /// 匹配位置的中心，坐标相对所选显示器
+    pub fn center(&self) -> (i32, i32) {
+        (self.x + self.width / 2, self.y + self.height / 2)
+    }
+}
+
+// 逐行排列的灰度像素
+#[derive(Debug, Clone)]
+struct Gray {
+    width: usize,
+    height: usize,
+    data: Vec<u8>,
+}
+
+impl Gray {
+    fn from_rgba(width: usize, height: usize, rgba: &[u8]) -> Self {
+        let data = rgba
+            .chunks_exact(4)
+            .map(|pixel| ((u32::from(pixel[0]) * 299 + u32::from(pixel[1]) * 587 + u32::from(pixel[2]) * 114) / 1000) as u8)
+            .collect();
+        Self { width, height, data }
+    }
+
+    fn from_frame(frame: &Frame) -> Self {
+        Self::from_rgba(frame.width, frame.height, &frame.rgba)
+    }
+
+    // 每 factor × factor 个像素取平均
+    fn shrink(&self, factor: usize) -> Self {
+        let (width, height) = (self.width / factor, self.height / factor);
+        let mut data = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let mut sum = 0u32;
+                for row in y * factor..(y + 1) * factor {
+                    let start = row * self.width + x * factor;
+                    sum += self.data[start..start + factor].iter().map(|&v| u32::from(v)).sum::<u32>();
+                }
+                data.push((sum / (factor * factor) as u32) as u8);
+            }
+        }
+        Self { width, height, data }
+    }
+
+    // 按比例缩放，取最近的像素
+    fn resize(&self, scale: f32) -> Self {
+        let width = ((self.width as f32 * scale).round() as usize).max(1);
+        let height = ((self.height as f32 * scale).round() as usize).max(1);
+        let mut data = Vec::with_capacity(width * height);
+        for y in 0..height {
+            let source_y = ((y as f32 / scale) as usize).min(self.height - 1);
+            for x in 0..width {
+                let source_x = ((x as f32 / scale) as usize).min(self.width - 1);
+                data.push(self.data[source_y * self.width + source_x]);
+            }
+        }
+        Self { width, height, data }
+    }
+
+    // 模板放在 (x, y) 处的差的总和，超过 budget 时提前放弃
+    fn difference(&self, template: &Gray, x: usize, y: usize, budget: u64) -> Option<u64> {
+        let mut sum = 0u64;
+        for row in 0..template.height {
+            let screen = &self.data[(y + row) * self.width + x..][..template.width];
+            let expected = &template.data[row * template.width..][..template.width];
+            sum += screen.iter().zip(expected).map(|(&a, &b)| u64::from(a.abs_diff(b))).sum::<u64>();
+            if sum > budget {
+                return None;
+            }
+        }
+        Some(sum)
+    }
+}
+
+/// 读取好的模板图片
+#[derive(Debug)]
+pub struct Template {
+    pub path: PathBuf,
+    pub width: usize,
+    pub height: usize,
+    gray: Gray,
+}
+
+/// 读取模板图片。读过的图片按路径缓存，文件修改后重新读取
+pub fn load(path: &Path) -> Result<Arc<Template>, String> {
+    if path.as_os_str().is_empty() {
+        return Err(t!("没有选择图片").to_string());
+    }
+    let modified = std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok();
+    if let Some((cached, template)) = TEMPLATES.lock().unwrap().get(path)
+        && *cached == modified
+    {
+        return Ok(template.clone());
+    }
+    let image = image::open(path).map_err(|e| t!("无法读取图片 {}: {}", path.display(), e))?.to_rgba8();
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    let gray = Gray::from_rgba(width, height, image.as_raw());
+    let template = Arc::new(Template { path: path.to_path_buf(), width, height, gray });
+    TEMPLATES.lock().unwrap().insert(path.to_path_buf(), (modified, template.clone()));
+    Ok(template)
+}
+
+// 要查找的区域，换算成全局坐标
+fn search_region(options: &MatchOptions) -> Result<Region, String> {
+    if let Some(region) = options.region {
+        let (x, y) = display::to_global(region.x, region.y);
+        return Ok(Region::new(x, y, region.width, region.height));
+    }
+    if let Some((width, height)) = display::selected_size() {
+        let (x, y) = display::to_global(0, 0);
+        return Ok(Region::new(x, y, width, height));
+    }
+    let (x, y, width, height) = display::virtual_desktop(&display::enumerate()).ok_or_else(|| t!("找不到显示器").to_string())?;
+    Ok(Region::new(x, y, width, height))
+}
+
+/// 截取屏幕并查找模板，返回相似度最高且达到阈值的位置
+pub fn find(template: &Template, options: &MatchOptions) -> Result<Option<Match>, String> {
+    Ok(search(template, options, 1)?.into_iter().next())
+}
+
+/// 截取屏幕并查找模板出现的所有位置，相互重叠超过一半的只算一处，最多 MAX_MATCHES 个，
+/// 从上到下、从左到右排列
+pub fn find_all(template: &Template, options: &MatchOptions) -> Result<Vec<Match>, String> {
+    let mut matches = search(template, options, MAX_MATCHES)?;
+    matches.sort_by_key(|found| (found.y, found.x));
+    Ok(matches)
+}
+
+// 按各个缩放比例查找，返回最佳匹配最像的那个比例下找到的位置，从高到低排列
+fn search(template: &Template, options: &MatchOptions, limit: usize) -> Result<Vec<Match>, String> {
+    let started = Instant::now();
+    let frame = capture::capture_region(search_region(options)?)?;
+    let screen = Gray::from_frame(&frame);
+    let scales: &[f32] = if options.multi_scale { &SCALES } else { &SCALES[..1] };
+    let mut best: Vec<Match> = Vec::new();
+    for &scale in scales {
+        let scaled = if scale == 1.0 { template.gray.clone() } else { template.gray.resize(scale) };
+        let found = positions(&screen, &scaled, options.threshold, limit);
+        if found.first().is_some_and(|first| best.first().is_none_or(|best| first.2 > best.confidence)) {
+            best = found
+                .into_iter()
+                .map(|(x, y, confidence)| {
+                    let (x, y) = display::to_local(frame.x + x as i32, frame.y + y as i32);
+                    Match { x, y, width: scaled.width as i32, height: scaled.height as i32, confidence }
+                })
+                .collect();
+        }
+    }
+    tracing::debug!(elapsed = ?started.elapsed(), found = best.len(), "找图");
+    Ok(best)
+}
+
+// 相似度达到阈值且相互重叠不超过一半的位置，最多 limit 个，从高到低排列
+fn positions(screen: &Gray, template: &Gray, threshold: f32, limit: usize) -> Vec<(usize, usize, f32)> {
+    if template.width > screen.width || template.height > screen.height || template.data.is_empty() {
+        return Vec::new();
+    }
+    let (max_x, max_y) = (screen.width - template.width, screen.height - template.height);
+    let factor = [4, 2]
+        .into_iter()
+        .find(|&factor| template.width / factor >= MIN_COARSE_SIDE && template.height / factor >= MIN_COARSE_SIDE);
+    let Some(factor) = factor else {
+        let found = scan(screen, template, threshold, 0..=max_x, 0..=max_y, limit * CLUSTER);
+        return distinct(found, template, limit);
+    };
+
+    // 粗找
+    let (small_screen, small_template) = (screen.shrink(factor), template.shrink(factor));
+    let candidates = scan(
+        &small_screen,
+        &small_template,
+        threshold - COARSE_MARGIN,
+        0..=small_screen.width.saturating_sub(small_template.width),
+        0..=small_screen.height.saturating_sub(small_template.height),
+        (limit * CLUSTER).max(MAX_CANDIDATES),
+    );
+
+    // 在每个候选位置附近细找
+    let found = candidates
+        .into_iter()
+        .filter_map(|(x, y, _)| {
+            let (x, y) = (x * factor, y * factor);
+            let columns = x.saturating_sub(factor)..=(x + factor).min(max_x);
+            let rows = y.saturating_sub(factor)..=(y + factor).min(max_y);
+            scan(screen, template, threshold, columns, rows, 1).pop()
+        })
+        .collect();
+    distinct(found, template, limit)
+}
+
+// 从相似度高的开始挑，和已挑出的位置重叠超过一半的跳过
+fn distinct(mut found: Vec<(usize, usize, f32)>, template: &Gray, limit: usize) -> Vec<(usize, usize, f32)> {
+    found.sort_by(|a, b| b.2.total_cmp(&a.2));
+    let mut kept: Vec<(usize, usize, f32)> = Vec::new();
+    for (x, y, score) in found {
+        if kept.len() >= limit {
+            break;
+        }
+        let overlaps = kept
+            .iter()
+            .any(|&(kept_x, kept_y, _)| kept_x.abs_diff(x) <= template.width / 2 && kept_y.abs_diff(y) <= template.height / 2);
+        if !overlaps {
+            kept.push((x, y, score));
+        }
+    }
+    kept
+}
+
+// 在给定范围内逐个位置比较，返回相似度达到阈值的位置中最高的 limit 个，从高到低排列。
+// 已经找到 limit 个之后，比其中最差的还差的位置不必比较完
+fn scan(
+    screen: &Gray,
+    template: &Gray,
+    threshold: f32,
+    columns: std::ops::RangeInclusive<usize>,
+    rows: std::ops::RangeInclusive<usize>,
+    limit: usize,
+) -> Vec<(usize, usize, f32)> {
+    let worst = 255 * template.data.len() as u64;
+    let mut budget = ((1.0 - threshold.clamp(0.0, 1.0)) * worst as f32) as u64;
+    let mut found: Vec<(usize, usize, u64)> = Vec::new();
+    for y in rows {
+        for x in columns.clone() {
+            if let Some(difference) = screen.difference(template, x, y, budget) {
+                found.push((x, y, difference));
+                if found.len() >= limit * 2 {
+                    found.sort_by_key(|&(_, _, difference)| difference);
+                    found.truncate(limit);
+                    budget = found[limit - 1].2;
+                }
+            }
+        }
+    }
+    found.sort_by_key(|&(_, _, difference)| difference);
+    found.truncate(limit);
+    found.into_iter().map(|(x, y, difference)| (x, y, 1.0 - difference as f32 / worst as f32)).collect()
+}
@@ -0,0 +1,217 @@
+// 点击间隔统计和高精度等待
+//
+// 记录执行中相邻两次点击实际相隔的时间，计算平均值、最短、最长和抖动（标准差），
+// 用来确认设定的间隔是否真的被执行：系统计时器精度、平滑移动和自适应间隔都会让
+// 实际间隔偏离设定值。每次开始执行时重新统计。
+//
+// thread::sleep 至少睡一个系统计时器周期，Windows 默认约 15.6 ms，低于它的间隔都会被拉长。
+// 执行期间把 Windows 的计时器精度提高到 1 ms，等待时先睡到离截止时间只剩一点余量，
+// 剩下的时间让出处理器并反复检查，这样几毫秒的间隔也能准确执行，同时不会一直占满一个核心。
+//
+// 模拟执行时在虚拟时钟中运行：等待只把虚拟时间往前推，不真的睡眠，整个序列立即跑完，
+// 同时仍能算出每个动作在真实执行中发生的时刻。
+
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// 睡眠可能超出的时间，剩下这么多时改为自旋等待
+#[cfg(target_os = "windows")]
+const SPIN_MARGIN: Duration = Duration::from_millis(2);
+#[cfg(not(target_os = "windows"))]
+const SPIN_MARGIN: Duration = Duration::from_micros(300);
+
+/// 模拟执行用的虚拟时钟。克隆出的副本共享同一个时间，
+/// 执行线程等待时推进它，输入引擎线程上的模拟后端按它记录动作发生的时刻
+#[derive(Debug, Clone, Default)]
+pub struct VirtualClock(Arc<Mutex<Duration>>);
+
+impl VirtualClock {
+    /// 虚拟时钟已经走过的时间
+    pub fn elapsed(&self) -> Duration {
+        *self.0.lock().unwrap()
+    }
+
+    fn advance(&self, duration: Duration) {
+        *self.0.lock().unwrap() += duration;
+    }
+}
+
+thread_local! {
+    // 当前线程使用的虚拟时钟，不在虚拟时钟中运行时为 None
+    static VIRTUAL_CLOCK: RefCell<Option<VirtualClock>> = const { RefCell::new(None) };
+}
+
+fn virtual_clock() -> Option<VirtualClock> {
+    VIRTUAL_CLOCK.with_borrow(Clone::clone)
+}
+
+/// 在当前线程上用虚拟时钟 `clock` 运行 `f`
+pub fn with_virtual_clock<R>(clock: &VirtualClock, f: impl FnOnce() -> R) -> R {
+    VIRTUAL_CLOCK.set(Some(clock.clone()));
+    let result = f();
+    VIRTUAL_CLOCK.set(None);
+    result
+}
+
+/// 比 thread::sleep 更准确的等待
+pub fn sleep(duration: Duration) {
+    if let Some(clock) = virtual_clock() {
+        clock.advance(duration);
+        return;
+    }
+    sleep_until(Instant::now() + duration);
+}
+
+/// 等待到 `deadline`；在虚拟时钟中运行时只推进虚拟时间
+pub fn sleep_until(deadline: Instant) {
+    if let Some(clock) = virtual_clock() {
+        clock.advance(deadline.saturating_duration_since(Instant::now()));
+        return;
+    }
+    loop {
+        let now = Instant::now();
+        if now >= deadline {
+            return;
+        }
+        let remaining = deadline - now;
+        if remaining > SPIN_MARGIN {
+            thread::sleep(remaining - SPIN_MARGIN);
+        } else {
+            thread::yield_now();
+        }
+    }
+}
+
+/// 存在期间提高系统计时器精度，只在 Windows 上有作用
+pub struct HighResolutionTimer(());
+
+impl HighResolutionTimer {
+    /// 提高系统计时器精度，直到返回值被丢弃
+    pub fn begin() -> Self {
+        platform::begin();
+        Self(())
+    }
+}
+
+impl Drop for HighResolutionTimer {
+    fn drop(&mut self) {
+        platform::end();
+    }
+}
+
+/// 本地时间的时、分、秒，读取失败时返回 None
+pub fn local_clock() -> Option<(u32, u32, u32)> {
+    platform::local_clock()
+}
+
+/// 本地时间，`HH:MM:SS`
+pub fn local_time() -> String {
+    match local_clock() {
+        Some((hour, minute, second)) => format!("{:02}:{:02}:{:02}", hour, minute, second),
+        None => "--:--:--".to_string(),
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows_sys::Win32::Foundation::SYSTEMTIME;
+    use windows_sys::Win32::Media::{timeBeginPeriod, timeEndPeriod};
+    use windows_sys::Win32::System::SystemInformation::GetLocalTime;
+
+    // 计时器精度是整个系统共享的，必须与 begin 成对调用
+    pub fn begin() {
+        unsafe { timeBeginPeriod(1) };
+    }
+
+    pub fn end() {
+        unsafe { timeEndPeriod(1) };
+    }
+
+    pub fn local_clock() -> Option<(u32, u32, u32)> {
+        let mut time: SYSTEMTIME = unsafe { std::mem::zeroed() };
+        unsafe { GetLocalTime(&mut time) };
+        Some((time.wHour as u32, time.wMinute as u32, time.wSecond as u32))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    pub fn begin() {}
+
+    pub fn end() {}
+
+    pub fn local_clock() -> Option<(u32, u32, u32)> {
+        unsafe {
+            let now = libc::time(std::ptr::null_mut());
+            let mut time: libc::tm = std::mem::zeroed();
+            if libc::localtime_r(&now, &mut time).is_null() {
+                return None;
+            }
+            Some((time.tm_hour as u32, time.tm_min as u32, time.tm_sec as u32))
+        }
+    }
+}
+
+/// 相邻两次点击实际间隔的统计
+#[derive(Debug, Default)]
+pub struct TimingStats {
+    last_click: Option<Instant>,
+    count: u32,
+    /// 间隔的总和与平方和（毫秒），用于计算平均值和标准差
+    sum: f64,
+    sum_squares: f64,
+    min: f64,
+    max: f64,
+}
+
+/// 执行线程和界面共享的间隔统计
+pub type SharedTiming = Arc<Mutex<TimingStats>>;
+
+/// 间隔统计的结果（毫秒）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimingSummary {
+    /// 统计到的间隔个数，比点击次数少一
+    pub count: u32,
+    pub mean_ms: f64,
+    pub min_ms: f64,
+    pub max_ms: f64,
+    /// 标准差
+    pub jitter_ms: f64,
+}
+
+impl TimingStats {
+    /// 清空统计，每次开始执行时调用
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// 记录一次点击的时刻
+    pub fn record(&mut self, at: Instant) {
+        if let Some(last) = self.last_click.replace(at) {
+            let ms = at.saturating_duration_since(last).as_secs_f64() * 1000.0;
+            if self.count == 0 {
+                self.min = ms;
+                self.max = ms;
+            } else {
+                self.min = self.min.min(ms);
+                self.max = self.max.max(ms);
+            }
+            self.count += 1;
+            self.sum += ms;
+            self.sum_squares += ms * ms;
+        }
+    }
+
+    /// 至少有一个间隔时返回统计结果
+    pub fn summary(&self) -> Option<TimingSummary> {
+        if self.count == 0 {
+            return None;
+        }
+        let count = self.count as f64;
+        let mean_ms = self.sum / count;
+        let variance = (self.sum_squares / count - mean_ms * mean_ms).max(0.0);
+        Some(TimingSummary { count: self.count, mean_ms, min_ms: self.min, max_ms: self.max, jitter_ms: variance.sqrt() })
+    }
+}
@@ -0,0 +1,294 @@
+// Linux uinput 输入后端
+//
+// 通过 /dev/uinput 创建虚拟的鼠标和键盘，由内核把事件直接送入输入子系统。
+// 这些事件和真实设备发出的没有区别，Wayland 合成器和忽略 XTest 模拟输入的游戏都能收到。
+// 移动使用绝对定位设备（与虚拟机的 USB 数位板相同），坐标范围是所有显示器合起来的区域，
+// 由合成器映射到整个桌面；多显示器时的映射方式取决于合成器。
+// 读取光标位置和按钮状态仍然通过 X11。打开 /dev/uinput 通常需要 root，
+// 或者把用户加入 input 组并添加 udev 规则，打不开时界面上给出设置方法。
+
+/// 让当前用户可以使用 /dev/uinput 的命令
+pub const SETUP_COMMANDS: &str = "sudo modprobe uinput
+echo uinput | sudo tee /etc/modules-load.d/uinput.conf
+echo 'KERNEL==\"uinput\", GROUP=\"input\", MODE=\"0660\", OPTIONS+=\"static_node=uinput\"' | sudo tee /etc/udev/rules.d/99-uinput.rules
+sudo udevadm control --reload-rules && sudo udevadm trigger
+sudo usermod -aG input $USER";
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub use platform::Device;
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    use std::fs::{File, OpenOptions};
+    use std::io::{ErrorKind, Write};
+    use std::os::fd::AsRawFd;
+    use std::os::unix::fs::OpenOptionsExt;
+    use std::time::Duration;
+
+    use enigo::{Button, Key};
+
+    use crate::backend::MouseBackend;
+    use crate::i18n::t;
+
+    const UINPUT_PATH: &str = "/dev/uinput";
+
+    // linux/uinput.h 中的 ioctl
+    const fn io(nr: u64) -> u64 {
+        ((b'U' as u64) << 8) | nr
+    }
+    const fn iow(nr: u64, size: usize) -> u64 {
+        (1 << 30) | ((size as u64) << 16) | io(nr)
+    }
+    const UI_DEV_CREATE: u64 = io(1);
+    const UI_DEV_DESTROY: u64 = io(2);
+    const UI_DEV_SETUP: u64 = iow(3, std::mem::size_of::<UinputSetup>());
+    const UI_ABS_SETUP: u64 = iow(4, std::mem::size_of::<UinputAbsSetup>());
+    const UI_SET_EVBIT: u64 = iow(100, std::mem::size_of::<libc::c_int>());
+    const UI_SET_KEYBIT: u64 = iow(101, std::mem::size_of::<libc::c_int>());
+    const UI_SET_RELBIT: u64 = iow(102, std::mem::size_of::<libc::c_int>());
+    const UI_SET_ABSBIT: u64 = iow(103, std::mem::size_of::<libc::c_int>());
+
+    // linux/input-event-codes.h
+    const EV_SYN: u16 = 0x00;
+    const EV_KEY: u16 = 0x01;
+    const EV_REL: u16 = 0x02;
+    const EV_ABS: u16 = 0x03;
+    const SYN_REPORT: u16 = 0;
+    const REL_WHEEL: u16 = 0x08;
+    const ABS_X: u16 = 0x00;
+    const ABS_Y: u16 = 0x01;
+    const BTN_LEFT: u16 = 0x110;
+    const BTN_RIGHT: u16 = 0x111;
+    const BTN_MIDDLE: u16 = 0x112;
+    const BTN_SIDE: u16 = 0x113;
+    const BTN_EXTRA: u16 = 0x114;
+    const KEY_LEFTSHIFT: u16 = 42;
+    // 键盘设备声明的按键范围：KEY_ESC 到 KEY_DELETE，包含下面映射到的所有按键
+    const KEY_CODES: std::ops::RangeInclusive<u16> = 1..=111;
+    const BUS_VIRTUAL: u16 = 0x06;
+
+    #[repr(C)]
+    struct InputId {
+        bustype: u16,
+        vendor: u16,
+        product: u16,
+        version: u16,
+    }
+
+    #[repr(C)]
+    struct UinputSetup {
+        id: InputId,
+        name: [u8; 80],
+        ff_effects_max: u32,
+    }
+
+    #[repr(C)]
+    struct AbsInfo {
+        value: i32,
+        minimum: i32,
+        maximum: i32,
+        fuzz: i32,
+        flat: i32,
+        resolution: i32,
+    }
+
+    #[repr(C)]
+    struct UinputAbsSetup {
+        code: u16,
+        absinfo: AbsInfo,
+    }
+
+    #[repr(C)]
+    struct InputEvent {
+        time: libc::timeval,
+        kind: u16,
+        code: u16,
+        value: i32,
+    }
+
+    fn open() -> Result<File, String> {
+        OpenOptions::new()
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(UINPUT_PATH)
+            .map_err(|e| match e.kind() {
+                ErrorKind::NotFound => t!("找不到 {}，请先加载 uinput 内核模块", UINPUT_PATH),
+                ErrorKind::PermissionDenied => t!("没有权限打开 {}，请按下面的方法设置后重新登录", UINPUT_PATH),
+                _ => t!("无法打开 {}: {}", UINPUT_PATH, e),
+            })
+    }
+
+    fn ioctl<T>(file: &File, request: u64, arg: T) -> Result<(), String> {
+        // SAFETY: 请求号和参数按 linux/uinput.h 的定义传入
+        let result = unsafe { libc::ioctl(file.as_raw_fd(), request as _, arg) };
+        if result < 0 {
+            return Err(t!("uinput 设置失败: {}", std::io::Error::last_os_error()));
+        }
+        Ok(())
+    }
+
+    fn create(file: &File, name: &str) -> Result<(), String> {
+        let mut setup = UinputSetup {
+            id: InputId { bustype: BUS_VIRTUAL, vendor: 0x1234, product: 0x5678, version: 1 },
+            name: [0; 80],
+            ff_effects_max: 0,
+        };
+        setup.name[..name.len()].copy_from_slice(name.as_bytes());
+        ioctl(file, UI_DEV_SETUP, &setup as *const UinputSetup)?;
+        ioctl(file, UI_DEV_CREATE, 0)
+    }
+
+    fn write_events(file: &mut File, events: &[(u16, u16, i32)]) -> Result<(), String> {
+        let report = [(EV_SYN, SYN_REPORT, 0)];
+        for &(kind, code, value) in events.iter().chain(&report) {
+            let event = InputEvent { time: libc::timeval { tv_sec: 0, tv_usec: 0 }, kind, code, value };
+            // SAFETY: InputEvent 是按 struct input_event 布局的普通数据
+            let bytes = unsafe {
+                std::slice::from_raw_parts(&event as *const InputEvent as *const u8, std::mem::size_of::<InputEvent>())
+            };
+            file.write_all(bytes).map_err(|e| t!("uinput 写入失败: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// 虚拟的绝对定位鼠标和键盘，销毁时自动移除
+    pub struct Device {
+        pointer: File,
+        keyboard: File,
+        /// 桌面区域左上角的全局坐标
+        origin: (i32, i32),
+    }
+
+    impl Device {
+        /// `desktop` 是所有显示器合起来的区域：左上角的全局坐标和宽高
+        pub fn new(desktop: (i32, i32, i32, i32)) -> Result<Self, String> {
+            let (x, y, width, height) = desktop;
+            let pointer = open()?;
+            ioctl(&pointer, UI_SET_EVBIT, EV_KEY as libc::c_int)?;
+            for button in [BTN_LEFT, BTN_RIGHT, BTN_MIDDLE, BTN_SIDE, BTN_EXTRA] {
+                ioctl(&pointer, UI_SET_KEYBIT, button as libc::c_int)?;
+            }
+            ioctl(&pointer, UI_SET_EVBIT, EV_REL as libc::c_int)?;
+            ioctl(&pointer, UI_SET_RELBIT, REL_WHEEL as libc::c_int)?;
+            ioctl(&pointer, UI_SET_EVBIT, EV_ABS as libc::c_int)?;
+            for (code, size) in [(ABS_X, width), (ABS_Y, height)] {
+                ioctl(&pointer, UI_SET_ABSBIT, code as libc::c_int)?;
+                let abs = UinputAbsSetup {
+                    code,
+                    absinfo: AbsInfo { value: 0, minimum: 0, maximum: (size - 1).max(1), fuzz: 0, flat: 0, resolution: 0 },
+                };
+                ioctl(&pointer, UI_ABS_SETUP, &abs as *const UinputAbsSetup)?;
+            }
+            create(&pointer, "mouseTOOL pointer")?;
+
+            let keyboard = open()?;
+            ioctl(&keyboard, UI_SET_EVBIT, EV_KEY as libc::c_int)?;
+            for code in KEY_CODES {
+                ioctl(&keyboard, UI_SET_KEYBIT, code as libc::c_int)?;
+            }
+            create(&keyboard, "mouseTOOL keyboard")?;
+
+            // 合成器发现新设备需要一点时间，立即发出的事件可能被丢弃
+            std::thread::sleep(Duration::from_millis(300));
+            Ok(Self { pointer, keyboard, origin: (x, y) })
+        }
+    }
+
+    impl MouseBackend for Device {
+        fn move_to(&mut self, x: i32, y: i32) -> Result<(), String> {
+            let (x, y) = ((x - self.origin.0).max(0), (y - self.origin.1).max(0));
+            write_events(&mut self.pointer, &[(EV_ABS, ABS_X, x), (EV_ABS, ABS_Y, y)])
+        }
+
+        fn button(&mut self, button: Button, pressed: bool) -> Result<(), String> {
+            let code = match button {
+                Button::Left => BTN_LEFT,
+                Button::Right => BTN_RIGHT,
+                Button::Middle => BTN_MIDDLE,
+                Button::Back => BTN_SIDE,
+                Button::Forward => BTN_EXTRA,
+                _ => return Err(t!("{} 后端不支持按钮 {}", "uinput", format!("{:?}", button))),
+            };
+            write_events(&mut self.pointer, &[(EV_KEY, code, pressed as i32)])
+        }
+
+        fn scroll(&mut self, amount: i32) -> Result<(), String> {
+            write_events(&mut self.pointer, &[(EV_REL, REL_WHEEL, -amount)])
+        }
+
+        fn key(&mut self, key: Key, pressed: bool) -> Result<(), String> {
+            let Some((code, shift)) = key_code(key) else {
+                return Err(t!("{} 后端不支持按键 {}", "uinput", format!("{:?}", key)));
+            };
+            let value = pressed as i32;
+            // 大写字母和需要 Shift 的符号按美式键盘布局加上 Shift
+            match (shift, pressed) {
+                (true, true) => write_events(&mut self.keyboard, &[(EV_KEY, KEY_LEFTSHIFT, 1), (EV_KEY, code, value)]),
+                (true, false) => write_events(&mut self.keyboard, &[(EV_KEY, code, value), (EV_KEY, KEY_LEFTSHIFT, 0)]),
+                (false, _) => write_events(&mut self.keyboard, &[(EV_KEY, code, value)]),
+            }
+        }
+    }
+
+    impl Drop for Device {
+        fn drop(&mut self) {
+            let _ = ioctl(&self.pointer, UI_DEV_DESTROY, 0);
+            let _ = ioctl(&self.keyboard, UI_DEV_DESTROY, 0);
+        }
+    }
+
+    // 按键对应的键码，以及是否需要同时按住 Shift
+    fn key_code(key: Key) -> Option<(u16, bool)> {
+        let code = match key {
+            Key::Escape => 1,
+            Key::Backspace => 14,
+            Key::Tab => 15,
+            Key::Return => 28,
+            Key::Control => 29,
+            Key::Shift => KEY_LEFTSHIFT,
+            Key::Alt => 56,
+            Key::Space => 57,
+            Key::F1 => 59,
+            Key::F2 => 60,
+            Key::F3 => 61,
+            Key::F4 => 62,
+            Key::F5 => 63,
+            Key::F6 => 64,
+            Key::F7 => 65,
+            Key::F8 => 66,
+            Key::F9 => 67,
+            Key::F10 => 68,
+            Key::F11 => 87,
+            Key::F12 => 88,
+            Key::Home => 102,
+            Key::UpArrow => 103,
+            Key::PageUp => 104,
+            Key::LeftArrow => 105,
+            Key::RightArrow => 106,
+            Key::End => 107,
+            Key::DownArrow => 108,
+            Key::PageDown => 109,
+            Key::Delete => 111,
+            Key::Unicode(c) => return char_code(c),
+            _ => return None,
+        };
+        Some((code, false))
+    }
+
+    // 美式键盘布局下字符所在的键
+    fn char_code(c: char) -> Option<(u16, bool)> {
+        const ROWS: [(&str, &str, u16); 4] = [
+            ("1234567890-=", "!@#$%^&*()_+", 2),
+            ("qwertyuiop[]", "QWERTYUIOP{}", 16),
+            ("asdfghjkl;'`", "ASDFGHJKL:\"~", 30),
+            ("\\zxcvbnm,./", "|ZXCVBNM<>?", 43),
+        ];
+        if c == ' ' {
+            return Some((57, false));
+        }
+        ROWS.iter().find_map(|(plain, shifted, first)| {
+            let position = |row: &str| row.chars().position(|k| k == c).map(|i| first + i as u16);
+            position(plain).map(|code| (code, false)).or_else(|| position(shifted).map(|code| (code, true)))
+        })
+    }
+}
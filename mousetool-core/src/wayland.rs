@@ -0,0 +1,62 @@
+// Wayland 会话检测（仅 Linux）
+//
+// GNOME、KDE 等桌面的 Wayland 会话不允许普通程序读取全局光标位置或注入输入。
+// enigo 和 device_query 使用 X11 接口，在 Wayland 下只对 XWayland 中运行的窗口有效：
+// 对原生 Wayland 窗口的点击会被忽略，光标在这些窗口上时读到的位置也不会更新，而且不会报错。
+// 这里检测 Wayland 会话并在界面上给出说明；有 XWayland 时让本程序的窗口也运行在 XWayland 中，
+// 这样坐标捕捉、点击速度测试和对 XWayland 程序的点击都能正常工作。
+// 通过 libei 或远程桌面门户注入输入需要额外的依赖，暂未实现。
+
+use std::sync::OnceLock;
+
+/// 有 XWayland 时的提示：只有 X11 程序能收到模拟的输入
+pub const WAYLAND_MESSAGE: &str = "当前是 Wayland 会话：模拟的点击和读取的光标位置只对 XWayland 中运行的程序有效，原生 Wayland 窗口收不到点击。需要完整支持时请在登录界面选择 X11（Xorg）会话";
+/// 没有 XWayland 时的提示：无法模拟输入
+pub const NO_XWAYLAND_MESSAGE: &str = "当前是 Wayland 会话且没有可用的 XWayland（未设置 DISPLAY），无法模拟鼠标和键盘。请启用 XWayland，或在登录界面选择 X11（Xorg）会话";
+
+/// 当前是否为 Wayland 会话，其他平台总是 false
+pub fn is_wayland() -> bool {
+    // 切换到 XWayland 时会清除 WAYLAND_DISPLAY，结果要在那之前记下
+    static WAYLAND: OnceLock<bool> = OnceLock::new();
+    *WAYLAND.get_or_init(platform::is_wayland)
+}
+
+/// 是否可以通过 XWayland 使用 X11 接口
+pub fn has_xwayland() -> bool {
+    std::env::var_os("DISPLAY").is_some_and(|display| !display.is_empty())
+}
+
+/// 当前会话对模拟输入的限制，没有限制时为 None
+pub fn diagnostic() -> Option<&'static str> {
+    if !is_wayland() {
+        None
+    } else if has_xwayland() {
+        Some(WAYLAND_MESSAGE)
+    } else {
+        Some(NO_XWAYLAND_MESSAGE)
+    }
+}
+
+/// 有 XWayland 时让本程序的窗口运行在 XWayland 中，与模拟的输入处在同一个 X 服务器上。
+/// 修改环境变量，必须在创建窗口和启动其他线程之前调用
+pub fn prefer_xwayland() {
+    if is_wayland() && has_xwayland() {
+        // SAFETY: 在 main 开头调用，此时还没有其他线程读写环境变量
+        unsafe { std::env::remove_var("WAYLAND_DISPLAY") };
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    pub fn is_wayland() -> bool {
+        std::env::var("XDG_SESSION_TYPE").is_ok_and(|session| session.eq_ignore_ascii_case("wayland"))
+            || std::env::var_os("WAYLAND_DISPLAY").is_some_and(|display| !display.is_empty())
+    }
+}
+
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+mod platform {
+    pub fn is_wayland() -> bool {
+        false
+    }
+}
@@ -0,0 +1,193 @@
+// 登录时自动启动
+//
+// Windows 写入当前用户的注册表 Run 键，macOS 在 ~/Library/LaunchAgents 中放置 LaunchAgent，
+// Linux 在 ~/.config/autostart 中放置 .desktop 文件。都只影响当前用户，不需要管理员权限。
+
+use crate::i18n::t;
+use crate::paths;
+
+// 注册表值名、LaunchAgent 标签和 .desktop 文件名中使用的标识
+const AUTOSTART_ID: &str = env!("CARGO_PKG_NAME");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AutostartStatus {
+    /// 以最小化方式启动
+    pub minimized: bool,
+}
+
+// 登录时执行的命令：本程序的路径和参数
+fn launch_args(minimized: bool) -> Result<Vec<String>, String> {
+    let exe = std::env::current_exe().map_err(|e| t!("无法获取程序路径: {}", e))?;
+    let mut args = vec![exe.display().to_string()];
+    // 通过命令行启用的便携模式也要保留，否则登录启动后会读写另一套数据
+    if paths::is_portable() {
+        args.push("--portable".to_string());
+    }
+    if minimized {
+        args.push("--minimized".to_string());
+    }
+    Ok(args)
+}
+
+/// 当前是否已注册自动启动，未注册时返回 None
+pub fn status() -> Option<AutostartStatus> {
+    platform::registered().map(|command| AutostartStatus { minimized: command.contains("--minimized") })
+}
+
+pub fn enable(minimized: bool) -> Result<(), String> {
+    platform::register(&launch_args(minimized)?)
+}
+
+pub fn disable() -> Result<(), String> {
+    platform::unregister()
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::AUTOSTART_ID;
+    use crate::i18n::t;
+    use windows_sys::Win32::Foundation::{ERROR_FILE_NOT_FOUND, ERROR_SUCCESS};
+    use windows_sys::Win32::System::Registry::{
+        HKEY_CURRENT_USER, REG_SZ, RRF_RT_REG_SZ, RegDeleteKeyValueW, RegGetValueW, RegSetKeyValueW,
+    };
+
+    const RUN_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Run";
+
+    fn wide(text: &str) -> Vec<u16> {
+        text.encode_utf16().chain(Some(0)).collect()
+    }
+
+    pub fn registered() -> Option<String> {
+        let (key, name) = (wide(RUN_KEY), wide(AUTOSTART_ID));
+        let mut size = 0u32;
+        unsafe {
+            let get = |data: *mut u16, size: &mut u32| {
+                RegGetValueW(
+                    HKEY_CURRENT_USER,
+                    key.as_ptr(),
+                    name.as_ptr(),
+                    RRF_RT_REG_SZ,
+                    std::ptr::null_mut(),
+                    data.cast(),
+                    size,
+                )
+            };
+            if get(std::ptr::null_mut(), &mut size) != ERROR_SUCCESS {
+                return None;
+            }
+            let mut buffer = vec![0u16; size as usize / 2];
+            if get(buffer.as_mut_ptr(), &mut size) != ERROR_SUCCESS {
+                return None;
+            }
+            let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+            Some(String::from_utf16_lossy(&buffer[..len]))
+        }
+    }
+
+    pub fn register(args: &[String]) -> Result<(), String> {
+        // 路径中可能有空格，每个参数都加上引号
+        let command = args.iter().map(|arg| format!("\"{}\"", arg)).collect::<Vec<_>>().join(" ");
+        let (key, name, value) = (wide(RUN_KEY), wide(AUTOSTART_ID), wide(&command));
+        let result = unsafe {
+            RegSetKeyValueW(
+                HKEY_CURRENT_USER,
+                key.as_ptr(),
+                name.as_ptr(),
+                REG_SZ,
+                value.as_ptr().cast(),
+                (value.len() * 2) as u32,
+            )
+        };
+        if result != ERROR_SUCCESS {
+            return Err(t!("无法写入注册表（错误码 {}）", result));
+        }
+        Ok(())
+    }
+
+    pub fn unregister() -> Result<(), String> {
+        let (key, name) = (wide(RUN_KEY), wide(AUTOSTART_ID));
+        let result = unsafe { RegDeleteKeyValueW(HKEY_CURRENT_USER, key.as_ptr(), name.as_ptr()) };
+        if result != ERROR_SUCCESS && result != ERROR_FILE_NOT_FOUND {
+            return Err(t!("无法删除注册表项（错误码 {}）", result));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    use super::AUTOSTART_ID;
+    use crate::i18n::t;
+    use std::path::PathBuf;
+
+    fn home() -> Option<PathBuf> {
+        std::env::var_os("HOME").map(PathBuf::from)
+    }
+
+    #[cfg(target_os = "macos")]
+    fn entry_path() -> Option<PathBuf> {
+        home().map(|home| home.join("Library/LaunchAgents").join(format!("com.{}.plist", AUTOSTART_ID.to_lowercase())))
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    fn entry_path() -> Option<PathBuf> {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| home().map(|home| home.join(".config")))
+            .map(|dir| dir.join("autostart").join(format!("{}.desktop", AUTOSTART_ID)))
+    }
+
+    #[cfg(target_os = "macos")]
+    fn entry_content(args: &[String]) -> String {
+        let escape = |text: &str| text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+        let arguments: String = args.iter().map(|arg| format!("        <string>{}</string>\n", escape(arg))).collect();
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+             <plist version=\"1.0\">\n\
+             <dict>\n    <key>Label</key>\n    <string>com.{}</string>\n    <key>ProgramArguments</key>\n    <array>\n{}    </array>\n    <key>RunAtLoad</key>\n    <true/>\n</dict>\n\
+             </plist>\n",
+            AUTOSTART_ID.to_lowercase(),
+            arguments
+        )
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    fn entry_content(args: &[String]) -> String {
+        // .desktop 文件的 Exec 中，含空格或特殊字符的参数需要用双引号包围
+        let quote = |arg: &String| {
+            if arg.chars().any(|c| c.is_whitespace() || "\"'\\$`".contains(c)) {
+                format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\"").replace('$', "\\$").replace('`', "\\`"))
+            } else {
+                arg.clone()
+            }
+        };
+        format!(
+            "[Desktop Entry]\nType=Application\nName={}\nExec={}\nX-GNOME-Autostart-enabled=true\n",
+            crate::paths::APP_NAME,
+            args.iter().map(quote).collect::<Vec<_>>().join(" ")
+        )
+    }
+
+    pub fn registered() -> Option<String> {
+        std::fs::read_to_string(entry_path()?).ok()
+    }
+
+    pub fn register(args: &[String]) -> Result<(), String> {
+        let path = entry_path().ok_or(t!("找不到用户目录"))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(&path, entry_content(args)).map_err(|e| t!("无法写入 {}: {}", path.display(), e))
+    }
+
+    pub fn unregister() -> Result<(), String> {
+        match entry_path() {
+            Some(path) if path.exists() => {
+                std::fs::remove_file(&path).map_err(|e| t!("无法删除 {}: {}", path.display(), e))
+            }
+            _ => Ok(()),
+        }
+    }
+}
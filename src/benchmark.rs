@@ -0,0 +1,203 @@
+// 点击速度测试
+//
+// 在窗口里放一个测试目标，执行线程把鼠标移到目标中心后不加任何间隔地连续点击，
+// 界面统计实际收到的点击：发出和收到的每秒点击数、从发出到界面收到的延迟，以及丢失的点击。
+// 用来在设置很快的点击间隔之前，先确认当前平台的模拟输入能达到多快。
+// 延迟包含等待界面下一帧的时间，只能作为参考。鼠标离开目标或目标不再显示（例如折叠了面板、
+// 滚动了窗口）时立即停止，避免连续的点击落到别的控件或程序上。
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use eframe::egui;
+use enigo::Button;
+
+use crate::app_state::{AppState, SharedState};
+use crate::display;
+use crate::engine::Engine;
+use crate::i18n::t;
+
+/// 默认的点击次数
+pub const DEFAULT_CLICKS: u32 = 200;
+/// 测试目标的边长（逻辑像素）
+pub const TARGET_SIZE: f32 = 120.0;
+// 发出的点击过了这么久界面还没收到，就算作丢失
+const LOST_AFTER: Duration = Duration::from_secs(1);
+// 界面超过这么久没有确认目标还在原处，执行线程就停止
+const TARGET_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// 窗口内的位置换算成鼠标坐标。Windows 和 Linux 的鼠标坐标以物理像素为单位，macOS 以点为单位
+pub fn to_mouse(ctx: &egui::Context, pos: egui::Pos2) -> (i32, i32) {
+    let origin = ctx.input(|input| input.viewport().inner_rect.map_or(egui::Pos2::ZERO, |rect| rect.min));
+    let scale = if cfg!(target_os = "macos") { 1.0 } else { ctx.native_pixels_per_point().unwrap_or(1.0) };
+    let (x, y) = ((origin.x + pos.x) * scale, (origin.y + pos.y) * scale);
+    display::to_local(x.round() as i32, y.round() as i32)
+}
+
+#[derive(Debug, Default)]
+struct Progress {
+    /// 已发出、界面还没收到的点击的发出时刻
+    pending: VecDeque<Instant>,
+    sent: u32,
+    errors: u32,
+    /// 界面最近一次画出目标的时刻和目标中心的鼠标坐标
+    target_seen: Option<(Instant, (i32, i32))>,
+    /// 发出最后一次点击的时刻
+    finished: Option<Instant>,
+    /// 提前停止的原因
+    aborted: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkResult {
+    pub sent: u32,
+    pub received: u32,
+    pub errors: u32,
+    /// 发出点击的速率（次/秒）
+    pub send_rate: f64,
+    /// 界面收到点击的速率（次/秒）
+    pub receive_rate: f64,
+    pub mean_latency_ms: f64,
+    pub max_latency_ms: f64,
+    pub aborted: Option<String>,
+}
+
+impl BenchmarkResult {
+    /// 发出后没有收到的点击
+    pub fn lost(&self) -> u32 {
+        self.sent.saturating_sub(self.received)
+    }
+}
+
+#[derive(Debug)]
+pub struct Benchmark {
+    pub clicks: u32,
+    progress: Arc<Mutex<Progress>>,
+    started: Option<Instant>,
+    received: u32,
+    last_received: Option<Instant>,
+    latencies_ms: Vec<f64>,
+    pub result: Option<BenchmarkResult>,
+}
+
+impl Default for Benchmark {
+    fn default() -> Self {
+        Self {
+            clicks: DEFAULT_CLICKS,
+            progress: Arc::default(),
+            started: None,
+            received: 0,
+            last_received: None,
+            latencies_ms: Vec::new(),
+            result: None,
+        }
+    }
+}
+
+impl Benchmark {
+    pub fn is_active(&self) -> bool {
+        self.started.is_some()
+    }
+
+    /// 已收到的点击数，测试中和结束后都可用
+    pub fn received(&self) -> u32 {
+        self.received
+    }
+
+    /// 在执行线程中点击 `target`（鼠标坐标）。`state` 应当已经是执行中，结束时恢复为空闲。
+    /// 返回执行线程的句柄，退出程序时等待它结束
+    pub fn start(&mut self, target: (i32, i32), engine: Engine, state: SharedState) -> thread::JoinHandle<()> {
+        let progress = Arc::new(Mutex::new(Progress { target_seen: Some((Instant::now(), target)), ..Default::default() }));
+        self.progress = progress.clone();
+        self.started = Some(Instant::now());
+        self.received = 0;
+        self.last_received = None;
+        self.latencies_ms.clear();
+        self.result = None;
+        let clicks = self.clicks;
+
+        thread::spawn(move || {
+            let abort = |reason: &str| progress.lock().unwrap().aborted = Some(reason.to_string());
+            if engine.move_mouse_to(target.0, target.1).is_err() {
+                abort(t!("无法移动鼠标"));
+            }
+            for _ in 0..clicks {
+                if !state.is_running() || progress.lock().unwrap().aborted.is_some() {
+                    break;
+                }
+                let still_shown = progress
+                    .lock()
+                    .unwrap()
+                    .target_seen
+                    .is_some_and(|(seen, position)| position == target && seen.elapsed() < TARGET_TIMEOUT);
+                if !still_shown {
+                    abort(t!("测试目标已移动或不再显示"));
+                    break;
+                }
+                if engine.get_mouse_position() != Ok(target) {
+                    abort(t!("鼠标离开了测试目标"));
+                    break;
+                }
+                progress.lock().unwrap().pending.push_back(Instant::now());
+                let ok = engine.click(Button::Left).is_ok();
+                let mut progress = progress.lock().unwrap();
+                progress.sent += 1;
+                if !ok {
+                    progress.errors += 1;
+                    progress.pending.pop_back();
+                }
+            }
+            progress.lock().unwrap().finished = Some(Instant::now());
+            state.set(AppState::Idle);
+        })
+    }
+
+    /// 每帧调用：`target` 是本帧目标中心的鼠标坐标，`clicks` 是本帧落在目标上的点击数
+    pub fn poll(&mut self, target: (i32, i32), clicks: usize) {
+        let Some(started) = self.started else {
+            return;
+        };
+        let now = Instant::now();
+        let mut progress = self.progress.lock().unwrap();
+        progress.target_seen = Some((now, target));
+        for _ in 0..clicks {
+            let Some(sent) = progress.pending.pop_front() else {
+                break;
+            };
+            self.received += 1;
+            self.last_received = Some(now);
+            self.latencies_ms.push(now.duration_since(sent).as_secs_f64() * 1000.0);
+        }
+
+        // 全部发完后等剩下的点击到达，太久没到的算作丢失
+        let Some(finished) = progress.finished else {
+            return;
+        };
+        if !progress.pending.is_empty() && finished.elapsed() < LOST_AFTER {
+            return;
+        }
+        let rate = |count: u32, until: Option<Instant>| match until {
+            Some(until) if count > 0 => count as f64 / until.duration_since(started).as_secs_f64().max(0.001),
+            _ => 0.0,
+        };
+        let mean_latency_ms = if self.latencies_ms.is_empty() {
+            0.0
+        } else {
+            self.latencies_ms.iter().sum::<f64>() / self.latencies_ms.len() as f64
+        };
+        self.result = Some(BenchmarkResult {
+            sent: progress.sent - progress.errors,
+            received: self.received,
+            errors: progress.errors,
+            send_rate: rate(progress.sent, Some(finished)),
+            receive_rate: rate(self.received, self.last_received),
+            mean_latency_ms,
+            max_latency_ms: self.latencies_ms.iter().copied().fold(0.0, f64::max),
+            aborted: progress.aborted.take(),
+        });
+        drop(progress);
+        self.started = None;
+    }
+}
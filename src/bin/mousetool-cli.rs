@@ -0,0 +1,246 @@
+// 命令行程序：不打开界面，通过 SSH 或脚本使用同一个自动化核心
+//
+// 用法: mousetool-cli <命令> [参数]
+//
+//   click [--x <X> --y <Y>] [--button left|right|middle] [--double | --triple] [--count <次数>] [--interval <秒>]
+//       在指定坐标点击，省略坐标时在光标当前位置点击
+//   run-macro <宏文件> [--count <次数>] [--speed <倍率>] [--smooth <毫秒>] [--display <显示器名称>]
+//       执行宏文件，参数与界面程序的 `--run` 相同
+//   record <宏文件> [--seconds <秒>]
+//       录制鼠标点击并保存为宏文件，在本机按 Esc 或到达指定秒数时结束
+//   position [--watch]
+//       输出光标位置 `x,y`；带 `--watch` 时每次位置变化都输出一行，按 Ctrl+C 结束
+//
+// 除了 run-macro 带 `--display` 时，坐标都是全局坐标。出错时退出码为 1，参数错误时为 2。
+
+use std::time::{Duration, Instant};
+
+use device_query::Keycode;
+use mousetool_core::button_map::ButtonMapping;
+use mousetool_core::macro_file::{MacroFile, MacroMetadata};
+use mousetool_core::recorder::{self, Recorder};
+use mousetool_core::sequence::Sequence;
+use mousetool_core::{ClickMultiplicity, ClickType, display, logging, runner, wayland};
+
+// 持续输出光标位置时的查询间隔
+const WATCH_INTERVAL: Duration = Duration::from_millis(50);
+
+const USAGE: &str = "用法: mousetool-cli <命令> [参数]
+
+命令:
+  click [--x <X> --y <Y>] [--button left|right|middle] [--double | --triple] [--count <次数>] [--interval <秒>]
+      在指定坐标点击，省略坐标时在光标当前位置点击
+  run-macro <宏文件> [--count <次数>] [--speed <倍率>] [--smooth <毫秒>] [--display <显示器名称>]
+      执行宏文件，带 --display 时坐标相对于该显示器
+  record <宏文件> [--seconds <秒>]
+      录制鼠标点击并保存为宏文件，按 Esc 或到达指定秒数时结束
+  position [--watch]
+      输出光标位置 x,y，带 --watch 时持续输出";
+
+enum Command {
+    Click(ClickOptions),
+    RunMacro(runner::HeadlessOptions),
+    Record { path: String, seconds: Option<f64> },
+    Position { watch: bool },
+    Help,
+}
+
+struct ClickOptions {
+    target: Option<(i32, i32)>,
+    click_type: ClickType,
+    multiplicity: ClickMultiplicity,
+    count: u32,
+    interval: f64,
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Command, String> {
+    let mut args = args.into_iter();
+    let Some(command) = args.next() else {
+        return Ok(Command::Help);
+    };
+    match command.as_str() {
+        "click" => parse_click(args).map(Command::Click),
+        "run-macro" => {
+            let path = args.next().ok_or("run-macro 缺少宏文件")?;
+            let options = runner::parse_args(["--run".to_string(), path].into_iter().chain(args))?;
+            Ok(Command::RunMacro(options.expect("带 --run 参数时总会返回选项")))
+        }
+        "record" => {
+            let path = args.next().ok_or("record 缺少宏文件")?;
+            let mut seconds = None;
+            while let Some(arg) = args.next() {
+                if arg != "--seconds" {
+                    return Err(format!("未知参数: {}", arg));
+                }
+                let value = args.next().ok_or("--seconds 缺少参数值")?;
+                seconds = Some(value.parse::<f64>().ok().filter(|s| *s > 0.0).ok_or("--seconds 必须是正数")?);
+            }
+            Ok(Command::Record { path, seconds })
+        }
+        "position" => {
+            let mut watch = false;
+            for arg in args {
+                if arg != "--watch" {
+                    return Err(format!("未知参数: {}", arg));
+                }
+                watch = true;
+            }
+            Ok(Command::Position { watch })
+        }
+        "help" | "--help" | "-h" => Ok(Command::Help),
+        other => Err(format!("未知命令: {}\n\n{}", other, USAGE)),
+    }
+}
+
+fn parse_click(args: impl IntoIterator<Item = String>) -> Result<ClickOptions, String> {
+    let (mut x, mut y) = (None, None);
+    let mut options = ClickOptions {
+        target: None,
+        click_type: ClickType::Left,
+        multiplicity: ClickMultiplicity::Single,
+        count: 1,
+        interval: 0.1,
+    };
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("{} 缺少参数值", name));
+        match arg.as_str() {
+            "--x" => x = Some(value("--x")?.parse::<i32>().map_err(|_| "--x 必须是整数")?),
+            "--y" => y = Some(value("--y")?.parse::<i32>().map_err(|_| "--y 必须是整数")?),
+            "--button" => {
+                options.click_type = match value("--button")?.as_str() {
+                    "left" => ClickType::Left,
+                    "right" => ClickType::Right,
+                    "middle" => ClickType::Middle,
+                    _ => return Err("--button 只能是 left、right 或 middle".to_string()),
+                }
+            }
+            "--double" => options.multiplicity = ClickMultiplicity::Double,
+            "--triple" => options.multiplicity = ClickMultiplicity::Triple,
+            "--count" => options.count = value("--count")?.parse().map_err(|_| "--count 必须是正整数")?,
+            "--interval" => options.interval = value("--interval")?.parse().map_err(|_| "--interval 必须是秒数")?,
+            other => return Err(format!("未知参数: {}", other)),
+        }
+    }
+
+    options.target = match (x, y) {
+        (Some(x), Some(y)) => Some((x, y)),
+        (None, None) => None,
+        _ => return Err("--x 和 --y 必须同时指定".to_string()),
+    };
+    if options.count == 0 {
+        return Err("--count 必须是正整数".to_string());
+    }
+    if !(0.001..=3600.0).contains(&options.interval) {
+        return Err("--interval 的范围是 0.001 到 3600 秒".to_string());
+    }
+    Ok(options)
+}
+
+fn click(options: &ClickOptions) -> Result<(), String> {
+    let (x, y) = match options.target {
+        Some(target) => target,
+        None => runner::engine()?.get_mouse_position()?,
+    };
+    let mut sequence = Sequence::single_point(x, y, options.click_type, options.interval);
+    sequence.steps[0].multi_click = options.multiplicity;
+
+    let report = runner::execute(&sequence, options.count, 1.0, None, None)?;
+    println!("在 ({}, {}) 点击 {} 次，失败 {} 次", x, y, report.clicks_performed, report.errors);
+    match report.aborted {
+        Some(reason) => Err(format!("执行中止: {}", reason)),
+        None => Ok(()),
+    }
+}
+
+fn run_macro(options: &runner::HeadlessOptions) -> Result<(), String> {
+    println!("执行宏: {}（共 {} 次点击）", options.macro_path, options.count);
+    let report = runner::run(options)?;
+    println!("完成: 成功 {} 次，失败 {} 次", report.clicks_performed, report.errors);
+    match report.aborted {
+        Some(reason) => Err(format!("执行中止: {}", reason)),
+        None => Ok(()),
+    }
+}
+
+fn record(path: &str, seconds: Option<f64>) -> Result<(), String> {
+    let engine = runner::engine()?;
+    let deadline = seconds.map(|seconds| Instant::now() + Duration::from_secs_f64(seconds));
+    let mut recorder = Recorder::new(ButtonMapping::default(), &engine.get_mouse_button_states()?);
+
+    println!("正在录制鼠标点击，按 Esc 结束");
+    loop {
+        if engine.pressed_keys()?.contains(&Keycode::Escape) || deadline.is_some_and(|d| Instant::now() >= d) {
+            break;
+        }
+        let position = engine.get_mouse_position()?;
+        if let Some(step) = recorder.poll(&engine.get_mouse_button_states()?, position) {
+            println!("{} ({}, {})", step.click_type.label(), step.x, step.y);
+        }
+        std::thread::sleep(recorder::POLL_INTERVAL);
+    }
+
+    if recorder.is_empty() {
+        return Err("没有录制到任何点击".to_string());
+    }
+    let count = recorder.len();
+    let name = std::path::Path::new(path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let metadata = MacroMetadata { name, description: "由 mousetool-cli 录制".to_string(), ..Default::default() };
+    MacroFile::new(metadata, recorder.finish()).save(path)?;
+    println!("已保存 {} 个点击步骤到 {}", count, path);
+    Ok(())
+}
+
+fn position(watch: bool) -> Result<(), String> {
+    let engine = runner::engine()?;
+    let mut last = None;
+    loop {
+        let (x, y) = engine.get_mouse_position()?;
+        if last != Some((x, y)) {
+            println!("{},{}", x, y);
+            last = Some((x, y));
+        }
+        if !watch {
+            return Ok(());
+        }
+        std::thread::sleep(WATCH_INTERVAL);
+    }
+}
+
+fn main() {
+    display::enable_dpi_awareness();
+    logging::init(logging::LogLevel::default());
+
+    let command = match parse_args(std::env::args().skip(1)) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+    // 通过 XWayland 注入时只对 X11 程序有效，开始前提示一次
+    if !matches!(command, Command::Help)
+        && wayland::has_xwayland()
+        && let Some(message) = wayland::diagnostic()
+    {
+        eprintln!("{}", message);
+    }
+    let result = match command {
+        Command::Click(options) => click(&options),
+        Command::RunMacro(options) => run_macro(&options),
+        Command::Record { path, seconds } => record(&path, seconds),
+        Command::Position { watch } => position(watch),
+        Command::Help => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+    };
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,45 @@
+// 坐标书签：给常用的目标位置起名保存，之后一键填入坐标，不必每次重新捕捉
+//
+// 另外自动记录最近捕捉或使用过的坐标，方便跳回之前的目标。
+// 两者都与屏幕布局相关，保存在本机的持久化存储中，不随配置一起导出。
+
+use serde::{Deserialize, Serialize};
+
+use crate::thumbnail::Thumbnail;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+    /// 保存时目标周围的画面
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<Thumbnail>,
+}
+
+/// 添加书签。名称为空时用坐标命名，同名书签会被新坐标覆盖。
+pub fn add(bookmarks: &mut Vec<Bookmark>, name: &str, x: i32, y: i32, thumbnail: Option<Thumbnail>) -> String {
+    let name = match name.trim() {
+        "" => format!("({}, {})", x, y),
+        name => name.to_string(),
+    };
+    match bookmarks.iter_mut().find(|b| b.name == name) {
+        Some(existing) => {
+            existing.x = x;
+            existing.y = y;
+            existing.thumbnail = thumbnail;
+        }
+        None => bookmarks.push(Bookmark { name: name.clone(), x, y, thumbnail }),
+    }
+    name
+}
+
+// 最多记住的最近坐标数
+const RECENT_LIMIT: usize = 10;
+
+/// 把坐标放到最近列表的最前面，已存在时移动到最前
+pub fn push_recent(recent: &mut Vec<(i32, i32)>, x: i32, y: i32) {
+    recent.retain(|&point| point != (x, y));
+    recent.insert(0, (x, y));
+    recent.truncate(RECENT_LIMIT);
+}
@@ -0,0 +1,139 @@
+// 导出独立运行包
+//
+// 运行包是一个文件夹，包含当前程序、宏文件、找图步骤用到的模板图片和启动脚本，
+// 同事拿到后直接运行启动脚本即可执行同样的自动化，无需打开界面。
+// 模板图片复制到 images 文件夹，宏文件中改为相对路径，启动脚本先切换到运行包所在的文件夹。
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::i18n::t;
+use crate::macro_file::MacroFile;
+
+pub const MACRO_FILE: &str = "macro.json";
+const IMAGES_DIR: &str = "images";
+
+pub struct BundleOptions {
+    pub count: u32,
+    pub speed: f64,
+    pub smooth_move_ms: Option<u64>,
+    /// 坐标相对的显示器名称，与界面中的选择相同
+    pub display: Option<String>,
+}
+
+impl BundleOptions {
+    // 启动脚本中传给程序的参数，`quote` 按脚本的语法给显示器名称加引号
+    fn runner_args(&self, quote: fn(&str) -> String) -> String {
+        let mut args = format!("--run {} --count {} --speed {}", MACRO_FILE, self.count, self.speed);
+        if let Some(ms) = self.smooth_move_ms {
+            let _ = write!(args, " --smooth {}", ms);
+        }
+        if let Some(display) = &self.display {
+            let _ = write!(args, " --display {}", quote(display));
+        }
+        args
+    }
+}
+
+// Windows 的显示器名称形如 `\\.\DISPLAY1`，sh 中用单引号才不会转义反斜杠
+fn sh_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
+
+// 批处理中双引号内的 % 仍会展开变量
+fn bat_quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "").replace('%', "%%"))
+}
+
+fn executable_name() -> String {
+    format!("mousetool{}", std::env::consts::EXE_SUFFIX)
+}
+
+// 文件夹名中不能出现的字符替换为下划线
+fn sanitize(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    if name.is_empty() { "automation".to_string() } else { name }
+}
+
+/// 在 `parent` 下创建运行包，返回运行包所在的文件夹
+pub fn export(parent: &Path, file: &MacroFile, options: &BundleOptions) -> Result<PathBuf, String> {
+    let dir = parent.join(format!("{}_runner", sanitize(&file.metadata.name)));
+    std::fs::create_dir_all(&dir).map_err(|e| t!("无法创建文件夹: {}", e))?;
+
+    let exe = std::env::current_exe().map_err(|e| t!("找不到当前程序: {}", e))?;
+    std::fs::copy(&exe, dir.join(executable_name())).map_err(|e| t!("复制程序失败: {}", e))?;
+
+    let mut file = file.clone();
+    copy_images(&dir, &mut file)?;
+    file.save(dir.join(MACRO_FILE))?;
+
+    let unix_script = format!(
+        "#!/usr/bin/env sh\n# 由 mouseTool 导出的运行包\ncd \"$(dirname \"$0\")\"\n./{} {}\n",
+        executable_name(),
+        options.runner_args(sh_quote)
+    );
+    let windows_script = format!(
+        "@echo off\r\nrem 由 mouseTool 导出的运行包\r\ncd /d \"%~dp0\"\r\n{} {}\r\n",
+        executable_name(),
+        options.runner_args(bat_quote)
+    );
+    write_file(&dir.join("run.sh"), &unix_script)?;
+    write_file(&dir.join("run.bat"), &windows_script)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        for name in ["run.sh".to_string(), executable_name()] {
+            let _ = std::fs::set_permissions(dir.join(name), std::fs::Permissions::from_mode(0o755));
+        }
+    }
+
+    let readme = format!(
+        "{}\n\n{}\n\n运行方式:\n  Windows: 双击 run.bat\n  macOS / Linux: 执行 ./run.sh\n\n\
+         程序会在启动后立即执行 {}，共点击 {} 次。\n\
+         请先确认目标窗口已经打开并处于可见位置。\n\
+         运行包中的程序只适用于导出时的操作系统（{}）。\n",
+        file.metadata.name,
+        file.metadata.description,
+        MACRO_FILE,
+        options.count,
+        std::env::consts::OS
+    );
+    write_file(&dir.join("README.txt"), &readme)?;
+
+    Ok(dir)
+}
+
+// 把找图步骤的模板图片复制到运行包中，并把步骤中的路径改为相对运行包的路径。
+// 多个步骤使用同一张图片时只复制一次，文件名前加序号避免不同文件夹中的同名图片互相覆盖
+fn copy_images(dir: &Path, file: &mut MacroFile) -> Result<(), String> {
+    let mut copied: HashMap<String, String> = HashMap::new();
+    for (i, step) in file.sequence.steps.iter_mut().enumerate() {
+        if !step.kind.uses_image() {
+            continue;
+        }
+        if let Some(relative) = copied.get(&step.image) {
+            step.image = relative.clone();
+            continue;
+        }
+        let source = Path::new(&step.image);
+        if !source.is_file() {
+            return Err(t!("步骤 {}: 找不到模板图片 {}", i + 1, step.image));
+        }
+        let name = source.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+        let relative = format!("{}/{}_{}", IMAGES_DIR, copied.len() + 1, name);
+        std::fs::create_dir_all(dir.join(IMAGES_DIR)).map_err(|e| t!("无法创建文件夹: {}", e))?;
+        std::fs::copy(source, dir.join(&relative))
+            .map_err(|e| t!("无法复制模板图片 {}: {}", step.image, e))?;
+        copied.insert(std::mem::replace(&mut step.image, relative.clone()), relative);
+    }
+    Ok(())
+}
+
+fn write_file(path: &Path, content: &str) -> Result<(), String> {
+    std::fs::write(path, content).map_err(|e| t!("无法写入 {}: {}", path.display(), e))
+}
@@ -0,0 +1,121 @@
+// 内置的更新日志，用于升级后的"新功能"面板
+
+// 新功能对应的界面位置，点击日志条目可以直接跳转过去
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FeatureLink {
+    SmoothMove,
+    TwoPoint,
+    Sequence,
+    HoldKey,
+    ScriptExport,
+    ReleaseInputs,
+}
+
+pub struct ChangelogItem {
+    pub text: &'static str,
+    pub link: Option<FeatureLink>,
+}
+
+pub struct ChangelogEntry {
+    pub version: &'static str,
+    pub items: &'static [ChangelogItem],
+}
+
+// 按版本从新到旧排列
+pub const CHANGELOG: &[ChangelogEntry] = &[ChangelogEntry {
+    version: "0.2.0",
+    items: &[
+        ChangelogItem {
+            text: "平滑移动：逐步以绝对坐标移动到目标，不受系统指针速度和加速度影响",
+            link: Some(FeatureLink::SmoothMove),
+        },
+        ChangelogItem {
+            text: "A/B 两点交替点击，每个点可单独设置停留时间",
+            link: Some(FeatureLink::TwoPoint),
+        },
+        ChangelogItem {
+            text: "多步骤序列编辑器，支持打开/保存宏文件（JSON/TOML）和导入 AutoHotkey 脚本",
+            link: Some(FeatureLink::Sequence),
+        },
+        ChangelogItem {
+            text: "自动点击期间持续按住指定按键",
+            link: Some(FeatureLink::HoldKey),
+        },
+        ChangelogItem {
+            text: "把序列导出为 bash + xdotool 脚本",
+            link: Some(FeatureLink::ScriptExport),
+        },
+        ChangelogItem {
+            text: "停止、退出或异常时自动释放本工具按下的所有按键和按钮",
+            link: Some(FeatureLink::ReleaseInputs),
+        },
+        ChangelogItem {
+            text: "回放速度倍率、变量和计数器、嵌套循环，以及等待、移动、滚动、按键和注释步骤",
+            link: None,
+        },
+        ChangelogItem {
+            text: "等待颜色、点击图片、点击所有匹配和等待图片步骤，可设置相似度、缩放和查找区域",
+            link: None,
+        },
+        ChangelogItem {
+            text: "命名配置，可以导入导出；设置在两次启动之间保存，也可以用 --config 配置文件和命令行参数启动",
+            link: None,
+        },
+        ChangelogItem {
+            text: "坐标书签、最近使用的坐标、从截图选取坐标、放大镜和快捷键捕捉坐标",
+            link: None,
+        },
+        ChangelogItem {
+            text: "执行结束后锁定电脑、关闭显示器、关闭程序或运行命令，并可发送通知和播放提示音",
+            link: None,
+        },
+        ChangelogItem {
+            text: "自适应点击间隔、电量和 CPU 保护，执行期间阻止系统睡眠，意外退出后可以从中断处继续",
+            link: None,
+        },
+        ChangelogItem {
+            text: "事件日志、点击间隔统计、点击热力图、回放轨迹和执行进度",
+            link: None,
+        },
+        ChangelogItem {
+            text: "系统托盘、迷你工具条、深色/浅色主题和英文界面",
+            link: None,
+        },
+        ChangelogItem {
+            text: "多显示器选择、鼠标按钮校准、输入事件查看器，以及 SendInput、XTest、uinput 和 Interception 输入后端",
+            link: None,
+        },
+        ChangelogItem {
+            text: "执行前切换到目标窗口、防离开（鼠标微动）和模拟执行",
+            link: None,
+        },
+        ChangelogItem {
+            text: "插件提供的自定义步骤",
+            link: None,
+        },
+        ChangelogItem {
+            text: "HTTP/WebSocket、命名管道、UDP 和 MQTT 控制接口，手柄和 MIDI 触发，以及 Webhook 通知",
+            link: None,
+        },
+        ChangelogItem {
+            text: "命令行程序 mousetool-cli，以及把序列导出为可以单独运行的运行包",
+            link: None,
+        },
+    ],
+}];
+
+fn parse_version(version: &str) -> Vec<u32> {
+    version.split('.').map(|part| part.parse().unwrap_or(0)).collect()
+}
+
+/// 比 `last_seen` 更新的所有日志条目。从未记录过版本时返回全部条目。
+pub fn entries_since(last_seen: Option<&str>) -> Vec<&'static ChangelogEntry> {
+    let last_seen = last_seen.map(parse_version);
+    CHANGELOG
+        .iter()
+        .filter(|entry| match &last_seen {
+            Some(last_seen) => parse_version(entry.version) > *last_seen,
+            None => true,
+        })
+        .collect()
+}
@@ -0,0 +1,158 @@
+// 启动界面时的命令行参数，供其他脚本直接启动一次自动点击
+//
+// 用法见 `USAGE`。
+//
+// 指定了坐标或间隔时切换为单点自动点击；参数的优先级高于配置文件和上次保存的设置，
+// 但只在本次运行中生效，不会保存到配置中。不认识的参数视为错误，打印用法后退出。
+// 已有实例在运行时不会打开第二个窗口，参数转交给正在运行的实例（见 `single_instance`）。
+
+use crate::AutoClickMode;
+use crate::settings::{self, Settings};
+
+pub const USAGE: &str = "用法: mouseTOOL [--x <X>] [--y <Y>] [--interval <秒>] [--count <次数>] [--start] [--minimized] [--config <文件>] [--portable]";
+
+#[derive(Debug, Clone, Default)]
+pub struct LaunchOptions {
+    pub x: Option<i32>,
+    pub y: Option<i32>,
+    pub interval: Option<f64>,
+    pub count: Option<u32>,
+    /// 启动后立即开始自动点击
+    pub start: bool,
+    /// 启动时最小化窗口
+    pub minimized: bool,
+}
+
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<LaunchOptions, String> {
+    let mut options = LaunchOptions::default();
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("{} 缺少参数值", name));
+        match arg.as_str() {
+            "--x" => options.x = Some(value("--x")?.parse().map_err(|_| "--x 必须是整数")?),
+            "--y" => options.y = Some(value("--y")?.parse().map_err(|_| "--y 必须是整数")?),
+            "--interval" => {
+                options.interval = Some(value("--interval")?.parse().map_err(|_| "--interval 必须是秒数")?)
+            }
+            "--count" => options.count = Some(value("--count")?.parse().map_err(|_| "--count 必须是正整数")?),
+            "--start" => options.start = true,
+            "--minimized" => options.minimized = true,
+            // 由 `config` 读取
+            "--config" => {
+                value("--config")?;
+            }
+            // 由 `paths` 和 `single_instance` 处理
+            "--portable" | "--replace" => {}
+            // 旧版 macOS 从访达启动时附带的进程序列号
+            arg if arg.starts_with("-psn_") => {}
+            _ => return Err(format!("未知参数: {}", arg)),
+        }
+    }
+
+    if options.interval.is_some_and(|interval| !settings::INTERVAL_RANGE.contains(&interval)) {
+        return Err("--interval 的范围是 0.001 到 10 秒".to_string());
+    }
+    if options.count == Some(0) {
+        return Err("--count 必须是正整数".to_string());
+    }
+    Ok(options)
+}
+
+impl LaunchOptions {
+    /// 用命令行参数覆盖设置
+    pub fn apply(&self, settings: &mut Settings) {
+        if self.x.is_some() || self.y.is_some() || self.interval.is_some() {
+            settings.auto_click_mode = AutoClickMode::SinglePoint;
+        }
+        if let Some(x) = self.x {
+            settings.x = x;
+        }
+        if let Some(y) = self.y {
+            settings.y = y;
+        }
+        if let Some(interval) = self.interval {
+            settings.click_interval = interval;
+        }
+        if let Some(count) = self.count {
+            settings.click_count = count;
+        }
+    }
+
+    /// 之前转交过来、这次没有再指定的参数仍然生效
+    pub fn inherit(&mut self, previous: &LaunchOptions) {
+        self.x = self.x.or(previous.x);
+        self.y = self.y.or(previous.y);
+        self.interval = self.interval.or(previous.interval);
+        self.count = self.count.or(previous.count);
+    }
+
+    /// 把仍是命令行参数值的字段恢复为 `original` 中的值，用于保存设置；启动后在界面中改过的字段保留改过的值
+    pub fn revert(&self, settings: &mut Settings, original: &Settings) {
+        if (self.x.is_some() || self.y.is_some() || self.interval.is_some())
+            && settings.auto_click_mode == AutoClickMode::SinglePoint
+        {
+            settings.auto_click_mode = original.auto_click_mode;
+        }
+        if self.x == Some(settings.x) {
+            settings.x = original.x;
+        }
+        if self.y == Some(settings.y) {
+            settings.y = original.y;
+        }
+        if self.interval == Some(settings.click_interval) {
+            settings.click_interval = original.click_interval;
+        }
+        if self.count == Some(settings.click_count) {
+            settings.click_count = original.click_count;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<LaunchOptions, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parses_launch_options() {
+        let options = parse(&["--x", "10", "--y", "-20", "--interval", "0.5", "--count", "3", "--start", "--minimized"]).unwrap();
+        assert_eq!((options.x, options.y, options.interval, options.count), (Some(10), Some(-20), Some(0.5), Some(3)));
+        assert!(options.start && options.minimized);
+    }
+
+    #[test]
+    fn skips_arguments_handled_elsewhere() {
+        let options = parse(&["--config", "a.toml", "--portable", "--replace", "-psn_0_12345"]).unwrap();
+        assert_eq!((options.x, options.count, options.start), (None, None, false));
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        for args in [
+            &["--bogus"][..],
+            &["--x"],
+            &["--x", "ten"],
+            &["--count", "0"],
+            &["--interval", "0"],
+            &["--interval", "11"],
+            &["--config"],
+        ] {
+            assert!(parse(args).is_err(), "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn apply_and_revert_leave_saved_settings_unchanged() {
+        let original = Settings { auto_click_mode: AutoClickMode::Sequence, ..Settings::default() };
+        let options = parse(&["--x", "10", "--count", "3"]).unwrap();
+        let mut settings = original.clone();
+        options.apply(&mut settings);
+        assert_eq!((settings.x, settings.click_count, settings.auto_click_mode), (10, 3, AutoClickMode::SinglePoint));
+        options.revert(&mut settings, &original);
+        assert_eq!(settings, original);
+    }
+}
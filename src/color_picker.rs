@@ -0,0 +1,61 @@
+// 光标处的像素颜色
+//
+// 实时显示鼠标所在像素的 RGB 和十六进制颜色，配置像素颜色相关的功能时不必另找取色工具。
+// 把鼠标移到复制按钮上颜色就变了，所以用快捷键取色：按下时记下颜色并复制到剪贴板。
+// 实时读取像素只支持 Windows；其他平台取色时截取光标处的一个像素，无法截图时无法取色。
+
+use eframe::egui;
+
+use crate::{capture, display, feedback};
+
+// 最多保留的取色记录
+const HISTORY_LIMIT: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PickedColor {
+    pub x: i32,
+    pub y: i32,
+    pub color: egui::Color32,
+}
+
+impl PickedColor {
+    pub fn hex(&self) -> String {
+        format!("#{:02X}{:02X}{:02X}", self.color.r(), self.color.g(), self.color.b())
+    }
+
+    pub fn rgb(&self) -> String {
+        format!("{}, {}, {}", self.color.r(), self.color.g(), self.color.b())
+    }
+}
+
+/// 直接读取屏幕像素，开销很小，可以每帧调用；不支持的平台返回 None
+pub fn read(x: i32, y: i32) -> Option<PickedColor> {
+    let pixel = *feedback::read_pixels(x, y, 1, 1)?.first()?;
+    // COLORREF 为 0x00BBGGRR
+    let color = egui::Color32::from_rgb(pixel as u8, (pixel >> 8) as u8, (pixel >> 16) as u8);
+    Some(PickedColor { x, y, color })
+}
+
+/// 读取像素，不支持时截取光标处的一个像素
+pub fn pick(x: i32, y: i32) -> Option<PickedColor> {
+    if let Some(picked) = read(x, y) {
+        return Some(picked);
+    }
+    let (gx, gy) = display::to_global(x, y);
+    let [r, g, b] = capture::capture_region(capture::Region::new(gx, gy, 1, 1)).ok()?.pixel(gx, gy)?;
+    Some(PickedColor { x, y, color: egui::Color32::from_rgb(r, g, b) })
+}
+
+/// 把新取的颜色放到记录的最前面
+pub fn push_history(history: &mut Vec<PickedColor>, picked: PickedColor) {
+    history.insert(0, picked);
+    history.truncate(HISTORY_LIMIT);
+}
+
+/// 颜色色块
+pub fn swatch(ui: &mut egui::Ui, color: egui::Color32) -> egui::Response {
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(18.0, 18.0), egui::Sense::hover());
+    ui.painter().rect_filled(rect, 2.0, color);
+    ui.painter().rect_stroke(rect, 2.0, egui::Stroke::new(1.0, egui::Color32::GRAY), egui::StrokeKind::Inside);
+    response
+}
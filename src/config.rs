@@ -0,0 +1,137 @@
+// 启动配置文件
+//
+// 启动时读取一个 TOML 文件，用其中的设置覆盖当前配置，方便在多台电脑（例如展示机）上
+// 部署完全相同的设置。路径由 `--config <文件>` 指定，没有指定时读取数据目录下的 config.toml，
+// 文件不存在就跳过。文件中只需要写想覆盖的字段，字段名与保存的设置相同，例如：
+//
+// ```toml
+// x = 500
+// y = 300
+// click_interval = 0.5
+// click_count = 100
+// click_type = "left"
+// auto_click_mode = "SinglePoint"
+//
+// [end_action]
+// kind = "lock"
+// ```
+//
+// 数值超出界面允许的范围时整个文件视为有误（见 `Settings::validate`）；拼错或不存在的字段
+// 不影响加载，只在日志中警告。
+
+use std::path::{Path, PathBuf};
+
+use crate::paths;
+use crate::settings::Settings;
+
+const DEFAULT_CONFIG_FILE: &str = "config.toml";
+
+pub fn default_path() -> Option<PathBuf> {
+    paths::data_dir().map(|dir| dir.join(DEFAULT_CONFIG_FILE))
+}
+
+/// 命令行中 `--config` 指定的路径
+pub fn path_from_args(args: impl IntoIterator<Item = String>) -> Result<Option<PathBuf>, String> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--config" {
+            return args.next().map(|path| Some(PathBuf::from(path))).ok_or_else(|| "--config 缺少参数值".to_string());
+        }
+    }
+    Ok(None)
+}
+
+/// 读取配置文件。`required` 为 false 时文件不存在视为没有配置
+pub fn load(path: &Path, required: bool) -> Result<Option<toml::Table>, String> {
+    if !required && !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(path).map_err(|e| format!("无法读取配置文件 {}: {}", path.display(), e))?;
+    let table: toml::Table =
+        toml::from_str(&content).map_err(|e| format!("配置文件 {} 格式错误: {}", path.display(), e))?;
+    // 提前检查字段类型和范围，出错时在启动前就报告
+    apply(&Settings::default(), &table).map_err(|e| format!("配置文件 {} 有误: {}", path.display(), e))?;
+    for key in unknown_keys(&table) {
+        tracing::warn!(path = %path.display(), key = %key, "配置文件中有不认识的字段，已忽略");
+    }
+    Ok(Some(table))
+}
+
+// 设置中没有的顶层字段，多半是拼错了。嵌套的表中可以省略的字段不会出现在序列化结果中，无法可靠地判断，只检查顶层
+fn unknown_keys(config: &toml::Table) -> Vec<String> {
+    // 序列化为 JSON 时值为 None 的字段也会保留，不会被误报
+    let Ok(serde_json::Value::Object(known)) = serde_json::to_value(Settings::default()) else {
+        return Vec::new();
+    };
+    config.keys().filter(|key| !known.contains_key(key.as_str())).cloned().collect()
+}
+
+/// 用配置文件中出现的字段覆盖 `settings`，未出现的字段保持不变。合并后的数值超出范围时返回错误
+pub fn apply(settings: &Settings, config: &toml::Table) -> Result<Settings, String> {
+    let mut merged = toml::Table::try_from(settings).map_err(|e| e.to_string())?;
+    merge(&mut merged, config);
+    let merged: Settings = toml::Value::Table(merged).try_into().map_err(|e: toml::de::Error| e.message().to_string())?;
+    merged.validate()?;
+    Ok(merged)
+}
+
+// 嵌套的表逐个字段合并，其余值直接替换
+fn merge(base: &mut toml::Table, overlay: &toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overlay)) => merge(base, overlay),
+            _ => {
+                base.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::end_actions::EndActionKind;
+
+    fn table(content: &str) -> toml::Table {
+        content.parse().unwrap()
+    }
+
+    #[test]
+    fn overrides_only_the_fields_in_the_file() {
+        let base = Settings { y: 42, ..Settings::default() };
+        let merged = apply(&base, &table("x = 500\nclick_interval = 0.5")).unwrap();
+        assert_eq!((merged.x, merged.y, merged.click_interval), (500, 42, 0.5));
+        assert_eq!(merged.click_count, base.click_count);
+    }
+
+    #[test]
+    fn merges_nested_tables_field_by_field() {
+        let mut base = Settings::default();
+        base.end_action.command = "echo done".to_string();
+        let merged = apply(&base, &table("[end_action]\nkind = \"lock\"")).unwrap();
+        assert_eq!(merged.end_action.kind, EndActionKind::Lock);
+        assert_eq!(merged.end_action.command, "echo done");
+    }
+
+    #[test]
+    fn rejects_out_of_range_values_and_wrong_types() {
+        assert!(apply(&Settings::default(), &table("click_interval = 0.0")).is_err());
+        assert!(apply(&Settings::default(), &table("playback_speed = 10.0")).is_err());
+        assert!(apply(&Settings::default(), &table("click_count = 0")).is_err());
+        assert!(apply(&Settings::default(), &table("x = \"left\"")).is_err());
+    }
+
+    #[test]
+    fn reports_unknown_top_level_keys() {
+        let config = table("clik_count = 5\ndisplay = \"DP-1\"\nx = 1");
+        assert_eq!(unknown_keys(&config), ["clik_count"]);
+    }
+
+    #[test]
+    fn reads_the_config_path_from_arguments() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert_eq!(path_from_args(args(&["--start", "--config", "a.toml"])), Ok(Some(PathBuf::from("a.toml"))));
+        assert_eq!(path_from_args(args(&["--start"])), Ok(None));
+        assert!(path_from_args(args(&["--config"])).is_err());
+    }
+}
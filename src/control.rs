@@ -0,0 +1,272 @@
+// 控制接口的访问令牌
+//
+// 外部程序访问控制接口时必须带上令牌，每个令牌有各自的权限范围，
+// 例如仪表盘只发放只读令牌，只有受信任的客户端才能真正触发点击。
+// 令牌保存在数据目录下的 tokens.json 中。界面和控制接口的服务器（见 `http_api`）共用同一份令牌，
+// 发放和吊销后立即生效。
+//
+// 开始、停止这类要用到界面中设置的操作，由各个控制接口包装成 `Pending` 交给界面线程处理。
+
+use std::path::PathBuf;
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use tokio::sync::oneshot;
+
+use crate::i18n::t;
+use crate::paths;
+
+const TOKENS_FILE: &str = "tokens.json";
+// 令牌的随机字节数，以十六进制保存
+const TOKEN_BYTES: usize = 24;
+/// 界面线程处理请求的最长等待时间，窗口最小化时界面可能很久才刷新
+pub const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+// 权限范围，后面的范围包含前面的全部权限
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Scope {
+    /// 只能读取运行状态
+    Status,
+    /// 还可以开始和停止已配置好的自动点击
+    StartStop,
+    /// 完全控制，包括在任意坐标注入点击
+    Full,
+}
+
+impl Scope {
+    pub const ALL: [Scope; 3] = [Scope::Status, Scope::StartStop, Scope::Full];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Scope::Status => t!("只读状态"),
+            Scope::StartStop => t!("开始/停止"),
+            Scope::Full => t!("完全控制"),
+        }
+    }
+
+    pub fn allows(self, command: Command) -> bool {
+        let required = match command {
+            Command::Status => Scope::Status,
+            Command::Start | Command::Stop => Scope::StartStop,
+            Command::Click => Scope::Full,
+        };
+        self >= required
+    }
+}
+
+// 控制接口支持的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Status,
+    Start,
+    Stop,
+    /// 在指定坐标点击
+    Click,
+}
+
+impl Command {
+    pub const ALL: [Command; 4] = [Command::Status, Command::Start, Command::Stop, Command::Click];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Command::Status => t!("查看状态"),
+            Command::Start => t!("开始"),
+            Command::Stop => t!("停止"),
+            Command::Click => t!("坐标点击"),
+        }
+    }
+}
+
+// 需要界面线程处理的请求
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Request {
+    Start,
+    Stop,
+    /// 切换到指定名称的配置后开始
+    RunProfile(String),
+    /// 执行中时停止，否则开始，指定配置时先切换到该配置
+    Toggle(Option<String>),
+}
+
+/// 文本控制接口（命名管道、UDP）中的一行命令，格式与命令行相同，例如 `run-profile 签到`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextCommand {
+    Status,
+    Request(Request),
+}
+
+impl TextCommand {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let line = line.trim();
+        let (command, argument) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let argument = argument.trim();
+        match command {
+            "status" => Ok(TextCommand::Status),
+            "start" => Ok(TextCommand::Request(Request::Start)),
+            "stop" => Ok(TextCommand::Request(Request::Stop)),
+            "toggle" if argument.is_empty() => Ok(TextCommand::Request(Request::Toggle(None))),
+            "toggle" => Ok(TextCommand::Request(Request::Toggle(Some(argument.to_string())))),
+            "run-profile" if argument.is_empty() => Err(t!("run-profile 缺少配置名称").to_string()),
+            "run-profile" => Ok(TextCommand::Request(Request::RunProfile(argument.to_string()))),
+            _ => Err(t!("未知命令: {}", command)),
+        }
+    }
+
+    /// 执行这条命令需要的权限
+    pub fn command(&self) -> Command {
+        match self {
+            TextCommand::Status => Command::Status,
+            TextCommand::Request(Request::Stop) => Command::Stop,
+            TextCommand::Request(_) => Command::Start,
+        }
+    }
+}
+
+/// 等待界面线程处理的请求，处理后用 `reply` 返回结果
+pub struct Pending {
+    pub request: Request,
+    reply: oneshot::Sender<Result<(), String>>,
+}
+
+impl Pending {
+    /// 返回请求和等待结果的接收端
+    pub fn new(request: Request) -> (Self, oneshot::Receiver<Result<(), String>>) {
+        let (reply, result) = oneshot::channel();
+        (Self { request, reply }, result)
+    }
+
+    pub fn reply(self, result: Result<(), String>) {
+        // 客户端已经超时断开时没人接收结果
+        let _ = self.reply.send(result);
+    }
+}
+
+/// 请求没有被执行的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ForwardError {
+    /// 界面拒绝了请求，例如与当前状态冲突
+    Rejected(String),
+    /// 界面已关闭或没有及时响应
+    Unavailable(String),
+}
+
+impl std::fmt::Display for ForwardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ForwardError::Rejected(message) | ForwardError::Unavailable(message) => f.write_str(message),
+        }
+    }
+}
+
+impl From<ForwardError> for String {
+    fn from(error: ForwardError) -> Self {
+        error.to_string()
+    }
+}
+
+/// 交给界面线程处理并等待结果
+pub async fn forward(requests: &Sender<Pending>, ctx: &egui::Context, request: Request) -> Result<(), ForwardError> {
+    let (pending, result) = Pending::new(request);
+    if requests.send(pending).is_err() {
+        return Err(ForwardError::Unavailable(t!("界面已关闭").to_string()));
+    }
+    ctx.request_repaint();
+    match tokio::time::timeout(REPLY_TIMEOUT, result).await {
+        Ok(Ok(result)) => result.map_err(ForwardError::Rejected),
+        Ok(Err(_)) | Err(_) => Err(ForwardError::Unavailable(t!("界面没有及时响应").to_string())),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub name: String,
+    pub token: String,
+    pub scope: Scope,
+    /// 发放时间（Unix 时间戳，秒）
+    #[serde(default)]
+    pub created_at: u64,
+}
+
+// 校验请求失败的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Denied {
+    /// 没有携带令牌或令牌无效
+    Unauthorized,
+    /// 令牌的权限范围不包含该操作
+    Forbidden,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TokenStore {
+    #[serde(default)]
+    pub tokens: Vec<ApiToken>,
+}
+
+pub type SharedTokens = Arc<Mutex<TokenStore>>;
+
+fn tokens_path() -> Option<PathBuf> {
+    paths::data_dir().map(|dir| dir.join(TOKENS_FILE))
+}
+
+fn generate_token() -> Result<String, String> {
+    let mut bytes = [0u8; TOKEN_BYTES];
+    getrandom::fill(&mut bytes).map_err(|e| t!("无法生成随机令牌: {}", e))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+impl TokenStore {
+    // 文件不存在或损坏时视为没有令牌，所有请求都会被拒绝
+    pub fn load() -> Self {
+        tokens_path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = tokens_path().ok_or(t!("找不到数据目录"))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let content = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, content).map_err(|e| t!("无法保存令牌: {}", e))
+    }
+
+    /// 发放一个新令牌并保存
+    pub fn issue(&mut self, name: &str, scope: Scope) -> Result<&ApiToken, String> {
+        let token = ApiToken {
+            name: name.trim().to_string(),
+            token: generate_token()?,
+            scope,
+            created_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+        };
+        self.tokens.push(token);
+        self.save()?;
+        Ok(&self.tokens[self.tokens.len() - 1])
+    }
+
+    /// 吊销令牌，之后使用该令牌的请求都会被拒绝
+    pub fn revoke(&mut self, token: &str) -> Result<(), String> {
+        self.tokens.retain(|t| t.token != token);
+        self.save()
+    }
+
+    /// 检查 `token` 能否执行 `command`
+    pub fn authorize(&self, token: &str, command: Command) -> Result<&ApiToken, Denied> {
+        let found = self
+            .tokens
+            .iter()
+            .find(|t| constant_time_eq(t.token.as_bytes(), token.as_bytes()))
+            .ok_or(Denied::Unauthorized)?;
+        if found.scope.allows(command) { Ok(found) } else { Err(Denied::Forbidden) }
+    }
+}
+
+// 比较全部字节，耗时与第一个不同字节的位置无关，无法通过响应时间逐位猜出令牌
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
@@ -0,0 +1,35 @@
+// 坐标的文字形式
+//
+// 复制坐标时写成 "x,y"，粘贴时接受 "x,y"、"(x, y)"、"x y" 以及全角的逗号和括号，
+// 方便和其他工具、问题报告交换坐标。带小数的坐标（例如部分工具按缩放后的点显示）四舍五入。
+
+use crate::i18n::t;
+
+pub fn format(x: i32, y: i32) -> String {
+    format!("{},{}", x, y)
+}
+
+pub fn parse(text: &str) -> Result<(i32, i32), String> {
+    let text = text.trim();
+    let inner = text
+        .strip_prefix(['(', '（', '['])
+        .and_then(|rest| rest.strip_suffix([')', '）', ']']))
+        .unwrap_or(text);
+    let parts: Vec<&str> = inner
+        .split(|c: char| c == ',' || c == '，' || c == ';' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .collect();
+    let [x, y] = parts[..] else {
+        // 剪贴板里可能是很长的文字，只显示开头
+        let shown: String = text.chars().take(40).collect();
+        return Err(t!("「{}」不是 x,y 格式的坐标", shown));
+    };
+    let number = |part: &str| {
+        part.parse::<f64>()
+            .ok()
+            .filter(|value| value.is_finite() && value.abs() <= i32::MAX as f64)
+            .map(|value| value.round() as i32)
+            .ok_or_else(|| t!("「{}」不是有效的坐标值", part))
+    };
+    Ok((number(x)?, number(y)?))
+}
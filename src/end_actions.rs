@@ -0,0 +1,177 @@
+// 自动点击结束后的操作
+//
+// 通宵挂机时，任务完成后可以自动锁定电脑、关闭显示器、关闭目标程序或运行一条命令，
+// 不需要有人守在电脑前收尾。只有正常完成的运行才会执行；手动停止或中止时不执行。
+
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::t;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EndActionKind {
+    #[default]
+    None,
+    /// 锁定电脑
+    Lock,
+    /// 关闭显示器
+    DisplaySleep,
+    /// 按进程名关闭目标程序
+    CloseApp,
+    /// 运行一条命令
+    RunCommand,
+}
+
+impl EndActionKind {
+    pub const ALL: [EndActionKind; 5] = [
+        EndActionKind::None,
+        EndActionKind::Lock,
+        EndActionKind::DisplaySleep,
+        EndActionKind::CloseApp,
+        EndActionKind::RunCommand,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            EndActionKind::None => t!("无"),
+            EndActionKind::Lock => t!("锁定电脑"),
+            EndActionKind::DisplaySleep => t!("关闭显示器"),
+            EndActionKind::CloseApp => t!("关闭程序"),
+            EndActionKind::RunCommand => t!("运行命令"),
+        }
+    }
+}
+
+// 进程名和命令分开保存，切换操作类型时不会丢失已经填写的内容
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EndAction {
+    pub kind: EndActionKind,
+    /// 要关闭的进程名，例如 `game.exe`
+    pub process: String,
+    /// 要运行的命令，Windows 上由 cmd 执行，其他系统由 sh 执行
+    pub command: String,
+}
+
+impl EndAction {
+    pub fn is_none(&self) -> bool {
+        self.kind == EndActionKind::None
+    }
+
+    /// 状态栏中显示的说明
+    pub fn describe(&self) -> String {
+        match self.kind {
+            EndActionKind::CloseApp => t!("关闭程序 {}", self.process.trim()),
+            EndActionKind::RunCommand => t!("运行命令 {}", self.command.trim()),
+            kind => kind.label().to_string(),
+        }
+    }
+}
+
+pub fn perform(action: &EndAction) -> Result<(), String> {
+    match action.kind {
+        EndActionKind::None => Ok(()),
+        EndActionKind::Lock => platform::lock(),
+        EndActionKind::DisplaySleep => platform::display_sleep(),
+        EndActionKind::CloseApp => {
+            let process = action.process.trim();
+            if process.is_empty() {
+                return Err(t!("没有填写要关闭的程序").to_string());
+            }
+            platform::close_app(process)
+        }
+        EndActionKind::RunCommand => {
+            let command = action.command.trim();
+            if command.is_empty() {
+                return Err(t!("没有填写要运行的命令").to_string());
+            }
+            run_command(command)
+        }
+    }
+}
+
+fn run_command(command: &str) -> Result<(), String> {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    };
+    // 不等待命令结束，长时间运行的命令不会拖住工作线程
+    shell.spawn().map(|_| ()).map_err(|e| t!("无法运行命令: {}", e))
+}
+
+// 运行系统命令并检查退出状态
+fn run_tool(program: &str, args: &[&str]) -> Result<(), String> {
+    let status = Command::new(program)
+        .args(args)
+        .status()
+        .map_err(|e| t!("无法运行 {}: {}", program, e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(t!("{} 执行失败（{}）", program, status))
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use crate::i18n::t;
+    use windows_sys::Win32::System::Shutdown::LockWorkStation;
+    use windows_sys::Win32::UI::WindowsAndMessaging::{HWND_BROADCAST, PostMessageW, SC_MONITORPOWER, WM_SYSCOMMAND};
+
+    pub fn lock() -> Result<(), String> {
+        if unsafe { LockWorkStation() } == 0 {
+            return Err(t!("无法锁定电脑").to_string());
+        }
+        Ok(())
+    }
+
+    pub fn display_sleep() -> Result<(), String> {
+        // 参数 2 表示关闭显示器，移动鼠标或按键后自动唤醒
+        if unsafe { PostMessageW(HWND_BROADCAST, WM_SYSCOMMAND, SC_MONITORPOWER as usize, 2) } == 0 {
+            return Err(t!("无法关闭显示器").to_string());
+        }
+        Ok(())
+    }
+
+    pub fn close_app(process: &str) -> Result<(), String> {
+        super::run_tool("taskkill", &["/IM", process])
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    // macOS 没有单独的锁屏命令，关闭显示器后按系统设置立即要求密码
+    pub fn lock() -> Result<(), String> {
+        super::run_tool("pmset", &["displaysleepnow"])
+    }
+
+    pub fn display_sleep() -> Result<(), String> {
+        super::run_tool("pmset", &["displaysleepnow"])
+    }
+
+    pub fn close_app(process: &str) -> Result<(), String> {
+        super::run_tool("pkill", &["-x", process])
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    pub fn lock() -> Result<(), String> {
+        super::run_tool("loginctl", &["lock-session"])
+    }
+
+    pub fn display_sleep() -> Result<(), String> {
+        super::run_tool("xset", &["dpms", "force", "off"])
+    }
+
+    pub fn close_app(process: &str) -> Result<(), String> {
+        super::run_tool("pkill", &["-x", process])
+    }
+}
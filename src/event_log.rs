@@ -0,0 +1,73 @@
+// 事件日志
+//
+// 状态栏只能显示最后一条消息，执行中的开始、停止、中止和各种提示很快就会互相覆盖。
+// 这里按时间顺序记下每一条事件，界面中可以滚动查看并复制到剪贴板，状态栏显示最新的一条。
+// 执行线程也会记录每次点击的位置。只保留最近的若干条；每一条同时交给 `tracing`，
+// 状态消息按 info、单次点击按 debug 写入日志文件，见 mousetool-core 的 `logging`。
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use crate::timing::local_time;
+
+// 最多保留的条数，超出后丢弃最早的
+const MAX_ENTRIES: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    /// 操作结果和提示，同时显示在状态栏
+    Status,
+    /// 执行中的单次点击，只在日志中显示
+    Click,
+}
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    /// 本地时间，`HH:MM:SS`
+    pub time: String,
+    pub kind: EntryKind,
+    pub text: String,
+}
+
+#[derive(Debug, Default)]
+pub struct EventLog {
+    entries: VecDeque<Entry>,
+}
+
+pub type SharedLog = Arc<Mutex<EventLog>>;
+
+impl EventLog {
+    pub fn push(&mut self, kind: EntryKind, text: impl Into<String>) {
+        let text = text.into();
+        match kind {
+            EntryKind::Status => tracing::info!(target: "mousetool::status", "{}", text),
+            EntryKind::Click => tracing::debug!(target: "mousetool::click", "{}", text),
+        }
+        if self.entries.len() >= MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(Entry { time: local_time(), kind, text });
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.entries.iter()
+    }
+
+    /// 最新的一条状态消息
+    pub fn status(&self) -> Option<&str> {
+        self.entries.iter().rev().find(|entry| entry.kind == EntryKind::Status).map(|entry| entry.text.as_str())
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// 每条一行的纯文本，`clicks` 为 false 时省略单次点击
+    pub fn to_text(&self, clicks: bool) -> String {
+        self.entries
+            .iter()
+            .filter(|entry| clicks || entry.kind != EntryKind::Click)
+            .map(|entry| format!("{} {}\n", entry.time, entry.text))
+            .collect()
+    }
+}
@@ -0,0 +1,110 @@
+// 手柄按钮触发
+//
+// 通过 gilrs 读取手柄，按下绑定的按钮时开始/停止自动点击或在设定的坐标点击一次，
+// 适合坐在沙发上、键盘不在手边的场合。界面每次刷新时取出积压的手柄事件，
+// 和全局快捷键一样，窗口不在前台时也能触发。Windows 上使用 XInput，只支持 Xbox 兼容手柄。
+
+use gilrs::{Button, EventType, Gilrs};
+use serde::{Deserialize, Serialize};
+
+use mousetool_core::i18n::t;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GamepadAction {
+    /// 开始 / 停止自动点击
+    ToggleClicking,
+    /// 在设定的坐标点击一次
+    SingleClick,
+}
+
+impl GamepadAction {
+    pub const ALL: [GamepadAction; 2] = [GamepadAction::ToggleClicking, GamepadAction::SingleClick];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            GamepadAction::ToggleClicking => t!("开始/停止自动点击"),
+            GamepadAction::SingleClick => t!("单次点击"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GamepadBinding {
+    pub action: GamepadAction,
+    pub button: Button,
+}
+
+/// 按钮名称，按 Xbox 手柄的标识显示
+pub fn button_label(button: Button) -> String {
+    let label = match button {
+        Button::South => "A",
+        Button::East => "B",
+        Button::West => "X",
+        Button::North => "Y",
+        Button::LeftTrigger => "LB",
+        Button::RightTrigger => "RB",
+        Button::LeftTrigger2 => "LT",
+        Button::RightTrigger2 => "RT",
+        Button::Select => "View",
+        Button::Start => "Menu",
+        Button::Mode => "Guide",
+        Button::LeftThumb => "LS",
+        Button::RightThumb => "RS",
+        Button::DPadUp => t!("方向键上"),
+        Button::DPadDown => t!("方向键下"),
+        Button::DPadLeft => t!("方向键左"),
+        Button::DPadRight => t!("方向键右"),
+        other => return format!("{:?}", other),
+    };
+    label.to_string()
+}
+
+/// 已连接的手柄，无法初始化时不产生任何事件
+pub struct Gamepads {
+    gilrs: Option<Gilrs>,
+    error: Option<String>,
+}
+
+impl Default for Gamepads {
+    fn default() -> Self {
+        match Gilrs::new() {
+            Ok(gilrs) => Self { gilrs: Some(gilrs), error: None },
+            Err(e) => {
+                tracing::warn!(error = %e, "无法读取手柄");
+                Self { gilrs: None, error: Some(e.to_string()) }
+            }
+        }
+    }
+}
+
+impl Gamepads {
+    /// 无法读取手柄的原因
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// 已连接的手柄名称
+    pub fn connected(&self) -> Vec<String> {
+        let Some(gilrs) = &self.gilrs else {
+            return Vec::new();
+        };
+        gilrs.gamepads().map(|(_, gamepad)| gamepad.name().to_string()).collect()
+    }
+
+    /// 取出积压的事件，返回这段时间内新按下的按钮
+    pub fn poll(&mut self) -> Vec<Button> {
+        let Some(gilrs) = &mut self.gilrs else {
+            return Vec::new();
+        };
+        let mut pressed = Vec::new();
+        while let Some(event) = gilrs.next_event() {
+            if let EventType::ButtonPressed(button, _) = event.event
+                && button != Button::Unknown
+            {
+                pressed.push(button);
+            }
+        }
+        pressed
+    }
+}
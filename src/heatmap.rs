@@ -0,0 +1,59 @@
+// 点击热力图
+//
+// 记录本次启动以来每一次模拟点击的屏幕坐标，界面按屏幕比例画出散点或热力图（见 painting.rs），
+// 用来检查点击是否落在预期的区域内、分布是否均匀。只保存在内存中，退出后清空。
+
+use std::sync::{Arc, Mutex};
+
+// 最多保留的点数，超出后丢弃最早的一半
+const MAX_POINTS: usize = 100_000;
+
+#[derive(Debug, Default)]
+pub struct Heatmap {
+    points: Vec<(i32, i32)>,
+}
+
+pub type SharedHeatmap = Arc<Mutex<Heatmap>>;
+
+impl Heatmap {
+    pub fn push(&mut self, x: i32, y: i32) {
+        if self.points.len() >= MAX_POINTS {
+            self.points.drain(..MAX_POINTS / 2);
+        }
+        self.points.push((x, y));
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.points.clear();
+    }
+
+    pub fn points(&self) -> &[(i32, i32)] {
+        &self.points
+    }
+
+    /// 把屏幕（`screen` 为像素宽高）横向分成 `columns` 格、纵向按屏幕比例分格，
+    /// 返回行数和每一格中的点击次数（按行排列）
+    pub fn grid(&self, screen: (i32, i32), columns: usize) -> (usize, Vec<u32>) {
+        let (width, height) = (screen.0.max(1) as f32, screen.1.max(1) as f32);
+        let rows = ((columns as f32 * height / width).round() as usize).max(1);
+        let mut cells = vec![0u32; columns * rows];
+        for &(x, y) in &self.points {
+            // 落在屏幕外的点（例如副显示器上的）不计入
+            if x < 0 || y < 0 || x as f32 >= width || y as f32 >= height {
+                continue;
+            }
+            let column = (x as f32 / width * columns as f32) as usize;
+            let row = (y as f32 / height * rows as f32) as usize;
+            cells[row.min(rows - 1) * columns + column.min(columns - 1)] += 1;
+        }
+        (rows, cells)
+    }
+}
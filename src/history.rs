@@ -0,0 +1,243 @@
+// 本地执行历史
+//
+// 每次点击/执行结束后向数据目录下的 history.jsonl 追加一行记录，
+// 使用统计面板从这里汇总。所有数据只保存在本机，不会发送到任何地方。
+
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::t;
+use crate::paths;
+use crate::sequence::{RunReport, TargetStats};
+
+const HISTORY_FILE: &str = "history.jsonl";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunKind {
+    SingleClick,
+    SinglePoint,
+    TwoPoint,
+    Sequence,
+}
+
+impl RunKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            RunKind::SingleClick => t!("单次点击"),
+            RunKind::SinglePoint => t!("单点自动点击"),
+            RunKind::TwoPoint => t!("A/B 两点交替"),
+            RunKind::Sequence => t!("多步骤序列"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// 开始时间（Unix 时间戳，秒）
+    pub started_at: u64,
+    pub duration_secs: f64,
+    pub kind: RunKind,
+    pub planned_clicks: u32,
+    pub clicks: u32,
+    pub errors: u32,
+    pub stopped: bool,
+    #[serde(default)]
+    pub smooth_move: bool,
+    #[serde(default)]
+    pub hold_key: bool,
+    #[serde(default = "default_speed")]
+    pub speed: f64,
+    #[serde(default)]
+    pub targets: Vec<TargetStats>,
+}
+
+fn default_speed() -> f64 {
+    1.0
+}
+
+// 记录一次执行时除结果以外的信息
+pub struct RunInfo {
+    pub kind: RunKind,
+    pub planned_clicks: u32,
+    pub smooth_move: bool,
+    pub hold_key: bool,
+    pub speed: f64,
+}
+
+impl RunRecord {
+    pub fn new(info: &RunInfo, started: SystemTime, report: RunReport) -> Self {
+        Self {
+            started_at: started.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+            duration_secs: started.elapsed().map(|d| d.as_secs_f64()).unwrap_or(0.0),
+            kind: info.kind,
+            planned_clicks: info.planned_clicks,
+            clicks: report.clicks_performed,
+            errors: report.errors,
+            stopped: report.stopped,
+            smooth_move: info.smooth_move,
+            hold_key: info.hold_key,
+            speed: info.speed,
+            targets: report.targets,
+        }
+    }
+}
+
+fn history_path() -> Option<PathBuf> {
+    paths::data_dir().map(|dir| dir.join(HISTORY_FILE))
+}
+
+pub fn append(record: &RunRecord) -> Result<(), String> {
+    let path = history_path().ok_or(t!("找不到数据目录"))?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let line = serde_json::to_string(record).map_err(|e| e.to_string())?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| e.to_string())?;
+    writeln!(file, "{}", line).map_err(|e| e.to_string())
+}
+
+// 读取全部历史记录，损坏的行会被跳过
+pub fn load() -> Vec<RunRecord> {
+    let Some(file) = history_path().and_then(|path| std::fs::File::open(path).ok()) else {
+        return Vec::new();
+    };
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect()
+}
+
+pub fn clear() -> Result<(), String> {
+    match history_path() {
+        Some(path) if path.exists() => std::fs::remove_file(path).map_err(|e| e.to_string()),
+        _ => Ok(()),
+    }
+}
+
+// 从历史记录汇总出的使用统计
+#[derive(Debug, Clone, Default)]
+pub struct Insights {
+    pub total_runs: usize,
+    pub total_clicks: u64,
+    pub total_errors: u64,
+    /// 自动点击执行的平均点击数（不含单次点击）
+    pub average_run_clicks: f64,
+    pub average_run_secs: f64,
+    /// 手动停止的自动点击执行所占比例
+    pub stopped_ratio: f64,
+    /// 各功能的使用次数，按次数从多到少
+    pub feature_usage: Vec<(&'static str, usize)>,
+    /// 点击次数最多的目标
+    pub top_targets: Vec<TargetStats>,
+    /// 出错次数最多的目标
+    pub error_hot_spots: Vec<TargetStats>,
+    /// 最近的几次执行，最新的在前
+    pub recent_runs: Vec<RunRecord>,
+}
+
+const TOP_TARGETS: usize = 5;
+const RECENT_RUNS: usize = 10;
+
+pub fn insights(records: &[RunRecord]) -> Insights {
+    let mut kinds: HashMap<RunKind, usize> = HashMap::new();
+    let mut targets: HashMap<(i32, i32), TargetStats> = HashMap::new();
+    let mut smooth_move = 0;
+    let mut hold_key = 0;
+    let mut speed_changed = 0;
+
+    let mut auto_runs = 0;
+    let mut auto_clicks = 0u64;
+    let mut auto_secs = 0.0;
+    let mut auto_stopped = 0;
+
+    for record in records {
+        *kinds.entry(record.kind).or_default() += 1;
+        smooth_move += record.smooth_move as usize;
+        hold_key += record.hold_key as usize;
+        speed_changed += (record.speed != 1.0) as usize;
+
+        if record.kind != RunKind::SingleClick {
+            auto_runs += 1;
+            auto_clicks += record.clicks as u64;
+            auto_secs += record.duration_secs;
+            auto_stopped += record.stopped as usize;
+        }
+
+        for target in &record.targets {
+            let entry = targets.entry((target.x, target.y)).or_insert(TargetStats {
+                x: target.x,
+                y: target.y,
+                clicks: 0,
+                errors: 0,
+            });
+            entry.clicks += target.clicks;
+            entry.errors += target.errors;
+        }
+    }
+
+    let mut feature_usage: Vec<(&'static str, usize)> = kinds
+        .into_iter()
+        .map(|(kind, count)| (kind.label(), count))
+        .chain([(t!("平滑移动"), smooth_move), (t!("按住按键"), hold_key), (t!("调整回放速度"), speed_changed)])
+        .filter(|(_, count)| *count > 0)
+        .collect();
+    feature_usage.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+    let mut top_targets: Vec<TargetStats> = targets.values().cloned().collect();
+    top_targets.sort_by_key(|t| std::cmp::Reverse(t.clicks));
+    top_targets.truncate(TOP_TARGETS);
+
+    let mut error_hot_spots: Vec<TargetStats> = targets.into_values().filter(|t| t.errors > 0).collect();
+    error_hot_spots.sort_by_key(|t| std::cmp::Reverse(t.errors));
+    error_hot_spots.truncate(TOP_TARGETS);
+
+    let per_auto_run = |value: f64| if auto_runs > 0 { value / auto_runs as f64 } else { 0.0 };
+
+    Insights {
+        total_runs: records.len(),
+        total_clicks: records.iter().map(|r| r.clicks as u64).sum(),
+        total_errors: records.iter().map(|r| r.errors as u64).sum(),
+        average_run_clicks: per_auto_run(auto_clicks as f64),
+        average_run_secs: per_auto_run(auto_secs),
+        stopped_ratio: per_auto_run(auto_stopped as f64),
+        feature_usage,
+        top_targets,
+        error_hot_spots,
+        recent_runs: records.iter().rev().take(RECENT_RUNS).cloned().collect(),
+    }
+}
+
+/// 把开始时间显示为「多久以前」，避免为了显示本地时间引入时区处理
+pub fn format_ago(started_at: u64) -> String {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let secs = now.saturating_sub(started_at);
+    match secs {
+        0..60 => t!("刚刚").to_string(),
+        60..3600 => t!("{} 分钟前", secs / 60),
+        3600..86400 => t!("{} 小时前", secs / 3600),
+        _ => t!("{} 天前", secs / 86400),
+    }
+}
+
+/// 把秒数显示为「1 小时 5 分」「3 分 20 秒」「4.5 秒」
+pub fn format_duration(secs: f64) -> String {
+    let whole = secs as u64;
+    if whole >= 3600 {
+        t!("{} 小时 {} 分", whole / 3600, whole % 3600 / 60)
+    } else if whole >= 60 {
+        t!("{} 分 {} 秒", whole / 60, whole % 60)
+    } else {
+        t!("{} 秒", format!("{:.1}", secs))
+    }
+}
@@ -0,0 +1,369 @@
+// 全局快捷键
+//
+// 绑定按物理按键的位置保存（PC 键盘 set-1 扫描码，扩展键带 0xE0 前缀），
+// 而不是按键上的字符。这样在 AZERTY、Dvorak 或俄文等布局下，
+// 快捷键始终对应录制时按下的那个物理按键，不会悄悄变成另一个键。
+//
+// 显示时按当前布局取按键名称：Windows 直接向系统查询，
+// 其他平台使用录制时界面报告的按键名称。
+
+use device_query::Keycode;
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::t;
+use crate::input_hook::{ButtonEvent, HookButton};
+use crate::profile::Profile;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HotkeyAction {
+    /// 开始 / 停止自动点击
+    ToggleClicking,
+    /// 记录鼠标当前所在的位置，代替用中键或右键捕捉
+    CapturePosition,
+    /// 读取鼠标所在像素的颜色并复制到剪贴板
+    PickColor,
+}
+
+impl HotkeyAction {
+    pub const ALL: [HotkeyAction; 3] =
+        [HotkeyAction::ToggleClicking, HotkeyAction::CapturePosition, HotkeyAction::PickColor];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HotkeyAction::ToggleClicking => t!("开始/停止自动点击"),
+            HotkeyAction::CapturePosition => t!("记录鼠标位置"),
+            HotkeyAction::PickColor => t!("取色并复制"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Hotkey {
+    pub scancode: u16,
+    #[serde(default)]
+    pub ctrl: bool,
+    #[serde(default)]
+    pub shift: bool,
+    #[serde(default)]
+    pub alt: bool,
+    /// 录制时按当时的键盘布局得到的按键名称，仅用于显示
+    #[serde(default)]
+    pub label: String,
+}
+
+// 正在录制的快捷键属于哪个操作或配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordTarget {
+    Action(HotkeyAction),
+    /// 配置的下标
+    Profile(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HotkeyBinding {
+    pub action: HotkeyAction,
+    pub hotkey: Hotkey,
+}
+
+// 用作紧急停止的鼠标按钮。有些全屏游戏会独占键盘，但额外的鼠标按钮仍能被全局查询到
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopButton {
+    Middle,
+    /// 侧键「后退」（按钮 4）
+    Side1,
+    /// 侧键「前进」（按钮 5）
+    Side2,
+}
+
+impl StopButton {
+    pub const ALL: [StopButton; 3] = [StopButton::Middle, StopButton::Side1, StopButton::Side2];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            StopButton::Middle => t!("中键"),
+            StopButton::Side1 => t!("侧键 4（后退）"),
+            StopButton::Side2 => t!("侧键 5（前进）"),
+        }
+    }
+
+    // 在设备查询库返回的按钮状态中的下标
+    fn index(self) -> usize {
+        match self {
+            StopButton::Middle => 3,
+            StopButton::Side1 => 4,
+            StopButton::Side2 => 5,
+        }
+    }
+
+    /// 设备查询库只在 Windows 上报告侧键；Linux 上按钮 4、5 是滚轮，macOS 上没有
+    pub fn is_supported(self) -> bool {
+        self == StopButton::Middle || cfg!(target_os = "windows")
+    }
+
+    pub fn is_pressed(self, buttons: &[bool]) -> bool {
+        self.is_supported() && buttons.get(self.index()).copied().unwrap_or(false)
+    }
+
+    /// 系统钩子送来的事件中是否按下了这个按钮。有钩子时所有平台都能检测侧键
+    pub fn is_pressed_in(self, events: &[ButtonEvent]) -> bool {
+        let button = match self {
+            StopButton::Middle => HookButton::Middle,
+            StopButton::Side1 => HookButton::Back,
+            StopButton::Side2 => HookButton::Forward,
+        };
+        events.iter().any(|event| event.pressed && event.button == button)
+    }
+}
+
+pub fn default_bindings() -> Vec<HotkeyBinding> {
+    vec![
+        HotkeyBinding {
+            action: HotkeyAction::ToggleClicking,
+            hotkey: Hotkey { scancode: 0x42, ctrl: false, shift: false, alt: false, label: "F8".to_string() },
+        },
+        HotkeyBinding {
+            action: HotkeyAction::CapturePosition,
+            hotkey: Hotkey { scancode: 0x43, ctrl: false, shift: false, alt: false, label: "F9".to_string() },
+        },
+        HotkeyBinding {
+            action: HotkeyAction::PickColor,
+            hotkey: Hotkey { scancode: 0x44, ctrl: false, shift: false, alt: false, label: "F10".to_string() },
+        },
+    ]
+}
+
+// 物理按键对照表：扫描码、界面库中的物理键、设备查询库中的键、美式布局下的名称。
+// 界面库和设备查询库（Linux / macOS）给出的都是按美式布局命名的物理位置。
+const KEYS: &[(u16, egui::Key, Keycode, &str)] = &[
+    (0x01, egui::Key::Escape, Keycode::Escape, "Esc"),
+    (0x02, egui::Key::Num1, Keycode::Key1, "1"),
+    (0x03, egui::Key::Num2, Keycode::Key2, "2"),
+    (0x04, egui::Key::Num3, Keycode::Key3, "3"),
+    (0x05, egui::Key::Num4, Keycode::Key4, "4"),
+    (0x06, egui::Key::Num5, Keycode::Key5, "5"),
+    (0x07, egui::Key::Num6, Keycode::Key6, "6"),
+    (0x08, egui::Key::Num7, Keycode::Key7, "7"),
+    (0x09, egui::Key::Num8, Keycode::Key8, "8"),
+    (0x0A, egui::Key::Num9, Keycode::Key9, "9"),
+    (0x0B, egui::Key::Num0, Keycode::Key0, "0"),
+    (0x0C, egui::Key::Minus, Keycode::Minus, "-"),
+    (0x0D, egui::Key::Equals, Keycode::Equal, "="),
+    (0x0E, egui::Key::Backspace, Keycode::Backspace, "Backspace"),
+    (0x0F, egui::Key::Tab, Keycode::Tab, "Tab"),
+    (0x10, egui::Key::Q, Keycode::Q, "Q"),
+    (0x11, egui::Key::W, Keycode::W, "W"),
+    (0x12, egui::Key::E, Keycode::E, "E"),
+    (0x13, egui::Key::R, Keycode::R, "R"),
+    (0x14, egui::Key::T, Keycode::T, "T"),
+    (0x15, egui::Key::Y, Keycode::Y, "Y"),
+    (0x16, egui::Key::U, Keycode::U, "U"),
+    (0x17, egui::Key::I, Keycode::I, "I"),
+    (0x18, egui::Key::O, Keycode::O, "O"),
+    (0x19, egui::Key::P, Keycode::P, "P"),
+    (0x1A, egui::Key::OpenBracket, Keycode::LeftBracket, "["),
+    (0x1B, egui::Key::CloseBracket, Keycode::RightBracket, "]"),
+    (0x1C, egui::Key::Enter, Keycode::Enter, "Enter"),
+    (0x1E, egui::Key::A, Keycode::A, "A"),
+    (0x1F, egui::Key::S, Keycode::S, "S"),
+    (0x20, egui::Key::D, Keycode::D, "D"),
+    (0x21, egui::Key::F, Keycode::F, "F"),
+    (0x22, egui::Key::G, Keycode::G, "G"),
+    (0x23, egui::Key::H, Keycode::H, "H"),
+    (0x24, egui::Key::J, Keycode::J, "J"),
+    (0x25, egui::Key::K, Keycode::K, "K"),
+    (0x26, egui::Key::L, Keycode::L, "L"),
+    (0x27, egui::Key::Semicolon, Keycode::Semicolon, ";"),
+    (0x28, egui::Key::Quote, Keycode::Apostrophe, "'"),
+    (0x29, egui::Key::Backtick, Keycode::Grave, "`"),
+    (0x2B, egui::Key::Backslash, Keycode::BackSlash, "\\"),
+    (0x2C, egui::Key::Z, Keycode::Z, "Z"),
+    (0x2D, egui::Key::X, Keycode::X, "X"),
+    (0x2E, egui::Key::C, Keycode::C, "C"),
+    (0x2F, egui::Key::V, Keycode::V, "V"),
+    (0x30, egui::Key::B, Keycode::B, "B"),
+    (0x31, egui::Key::N, Keycode::N, "N"),
+    (0x32, egui::Key::M, Keycode::M, "M"),
+    (0x33, egui::Key::Comma, Keycode::Comma, ","),
+    (0x34, egui::Key::Period, Keycode::Dot, "."),
+    (0x35, egui::Key::Slash, Keycode::Slash, "/"),
+    (0x39, egui::Key::Space, Keycode::Space, "Space"),
+    (0x3B, egui::Key::F1, Keycode::F1, "F1"),
+    (0x3C, egui::Key::F2, Keycode::F2, "F2"),
+    (0x3D, egui::Key::F3, Keycode::F3, "F3"),
+    (0x3E, egui::Key::F4, Keycode::F4, "F4"),
+    (0x3F, egui::Key::F5, Keycode::F5, "F5"),
+    (0x40, egui::Key::F6, Keycode::F6, "F6"),
+    (0x41, egui::Key::F7, Keycode::F7, "F7"),
+    (0x42, egui::Key::F8, Keycode::F8, "F8"),
+    (0x43, egui::Key::F9, Keycode::F9, "F9"),
+    (0x44, egui::Key::F10, Keycode::F10, "F10"),
+    (0x57, egui::Key::F11, Keycode::F11, "F11"),
+    (0x58, egui::Key::F12, Keycode::F12, "F12"),
+    (0xE047, egui::Key::Home, Keycode::Home, "Home"),
+    (0xE048, egui::Key::ArrowUp, Keycode::Up, "↑"),
+    (0xE049, egui::Key::PageUp, Keycode::PageUp, "PageUp"),
+    (0xE04B, egui::Key::ArrowLeft, Keycode::Left, "←"),
+    (0xE04D, egui::Key::ArrowRight, Keycode::Right, "→"),
+    (0xE04F, egui::Key::End, Keycode::End, "End"),
+    (0xE050, egui::Key::ArrowDown, Keycode::Down, "↓"),
+    (0xE051, egui::Key::PageDown, Keycode::PageDown, "PageDown"),
+    (0xE052, egui::Key::Insert, Keycode::Insert, "Insert"),
+    (0xE053, egui::Key::Delete, Keycode::Delete, "Delete"),
+];
+
+impl Hotkey {
+    /// 由界面中的按键事件生成绑定。
+    ///
+    /// `physical_key` 决定绑定哪个物理按键，`key` 是当前布局下的按键，用作显示名称。
+    /// 对照表中没有的按键返回 None。
+    pub fn from_key_event(key: egui::Key, physical_key: Option<egui::Key>, modifiers: egui::Modifiers) -> Option<Self> {
+        let physical = physical_key.unwrap_or(key);
+        let &(scancode, ..) = KEYS.iter().find(|(_, k, ..)| *k == physical)?;
+        Some(Self {
+            scancode,
+            ctrl: modifiers.ctrl || modifiers.mac_cmd,
+            shift: modifiers.shift,
+            alt: modifiers.alt,
+            label: key.name().to_string(),
+        })
+    }
+
+    /// 当前布局下的显示名称，例如 `Ctrl+Shift+A`
+    pub fn display(&self) -> String {
+        let mut text = String::new();
+        for (enabled, name) in [(self.ctrl, "Ctrl+"), (self.shift, "Shift+"), (self.alt, "Alt+")] {
+            if enabled {
+                text.push_str(name);
+            }
+        }
+        text.push_str(&self.key_label());
+        text
+    }
+
+    fn key_label(&self) -> String {
+        if let Some(name) = platform::key_name(self.scancode) {
+            return name;
+        }
+        if !self.label.is_empty() {
+            return self.label.clone();
+        }
+        KEYS.iter()
+            .find(|(scancode, ..)| *scancode == self.scancode)
+            .map(|(.., name)| name.to_string())
+            .unwrap_or_else(|| format!("0x{:X}", self.scancode))
+    }
+
+    /// 按键和修饰键是否都处于按下状态。`pressed` 是设备查询得到的当前按键列表。
+    pub fn is_pressed(&self, pressed: &[Keycode]) -> bool {
+        let modifiers = platform::modifiers(pressed);
+        modifiers == (self.ctrl, self.shift, self.alt) && platform::is_scancode_down(self.scancode, pressed)
+    }
+}
+
+// 检测快捷键从松开到按下的变化，按住不放只触发一次
+#[derive(Debug, Default)]
+pub struct HotkeyListener {
+    down: Vec<HotkeyAction>,
+    /// 快捷键处于按下状态的配置名称
+    profiles_down: Vec<String>,
+    stop_button_down: bool,
+}
+
+impl HotkeyListener {
+    pub fn poll(&mut self, bindings: &[HotkeyBinding], pressed: &[Keycode]) -> Vec<HotkeyAction> {
+        let mut triggered = Vec::new();
+        for binding in bindings {
+            let is_down = binding.hotkey.is_pressed(pressed);
+            let was_down = self.down.contains(&binding.action);
+            if is_down && !was_down {
+                self.down.push(binding.action);
+                triggered.push(binding.action);
+            } else if !is_down && was_down {
+                self.down.retain(|action| *action != binding.action);
+            }
+        }
+        triggered
+    }
+
+    /// 返回快捷键刚被按下的配置的下标
+    pub fn poll_profiles(&mut self, profiles: &[Profile], pressed: &[Keycode]) -> Vec<usize> {
+        let mut triggered = Vec::new();
+        for (index, profile) in profiles.iter().enumerate() {
+            let is_down = profile.hotkey.as_ref().is_some_and(|hotkey| hotkey.is_pressed(pressed));
+            let was_down = self.profiles_down.contains(&profile.name);
+            if is_down && !was_down {
+                self.profiles_down.push(profile.name.clone());
+                triggered.push(index);
+            } else if !is_down && was_down {
+                self.profiles_down.retain(|name| *name != profile.name);
+            }
+        }
+        triggered
+    }
+
+    /// 紧急停止按钮是否刚被按下。`buttons` 是设备查询得到的鼠标按钮状态
+    pub fn poll_stop_button(&mut self, button: Option<StopButton>, buttons: &[bool]) -> bool {
+        let is_down = button.is_some_and(|button| button.is_pressed(buttons));
+        let triggered = is_down && !self.stop_button_down;
+        self.stop_button_down = is_down;
+        triggered
+    }
+}
+
+// Windows 的设备查询库返回的是虚拟键码，字母键随布局变化，
+// 因此改为把扫描码交给系统换算成当前布局下的虚拟键再查询状态。
+#[cfg(target_os = "windows")]
+mod platform {
+    use device_query::Keycode;
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{
+        GetAsyncKeyState, GetKeyNameTextW, MAPVK_VSC_TO_VK_EX, MapVirtualKeyW, VK_CONTROL, VK_MENU, VK_SHIFT,
+    };
+
+    fn is_vk_down(vk: u32) -> bool {
+        unsafe { GetAsyncKeyState(vk as i32) as u16 & 0x8000 != 0 }
+    }
+
+    pub fn is_scancode_down(scancode: u16, _pressed: &[Keycode]) -> bool {
+        let vk = unsafe { MapVirtualKeyW(scancode as u32, MAPVK_VSC_TO_VK_EX) };
+        vk != 0 && is_vk_down(vk)
+    }
+
+    pub fn modifiers(_pressed: &[Keycode]) -> (bool, bool, bool) {
+        (is_vk_down(VK_CONTROL as u32), is_vk_down(VK_SHIFT as u32), is_vk_down(VK_MENU as u32))
+    }
+
+    pub fn key_name(scancode: u16) -> Option<String> {
+        let extended = (scancode & 0xFF00 == 0xE000) as i32;
+        let lparam = ((scancode as i32 & 0xFF) << 16) | (extended << 24);
+        let mut buffer = [0u16; 64];
+        let len = unsafe { GetKeyNameTextW(lparam, buffer.as_mut_ptr(), buffer.len() as i32) };
+        (len > 0).then(|| String::from_utf16_lossy(&buffer[..len as usize]))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    use super::KEYS;
+    use device_query::Keycode;
+
+    pub fn is_scancode_down(scancode: u16, pressed: &[Keycode]) -> bool {
+        KEYS.iter()
+            .any(|(code, _, keycode, _)| *code == scancode && pressed.contains(keycode))
+    }
+
+    pub fn modifiers(pressed: &[Keycode]) -> (bool, bool, bool) {
+        let any = |keys: &[Keycode]| keys.iter().any(|key| pressed.contains(key));
+        (
+            any(&[Keycode::LControl, Keycode::RControl, Keycode::Command]),
+            any(&[Keycode::LShift, Keycode::RShift]),
+            any(&[Keycode::LAlt, Keycode::RAlt, Keycode::LOption, Keycode::ROption]),
+        )
+    }
+
+    // 没有系统接口可用时由调用方回退到录制时的名称
+    pub fn key_name(_scancode: u16) -> Option<String> {
+        None
+    }
+}
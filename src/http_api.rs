@@ -0,0 +1,364 @@
+// HTTP 控制接口
+//
+// 可选的内置 HTTP 服务器，本机或局域网内的其他工具和脚本可以通过它控制自动点击：
+//
+//   GET  /status  运行状态和累计点击数
+//   POST /start   按界面中的当前设置开始自动点击，不再弹出确认
+//   POST /stop    停止执行
+//   POST /click   在指定坐标点击，请求体如 {"x": 100, "y": 200, "button": "left"}，button 可省略
+//   GET  /ws      WebSocket，实时推送 `events` 中的事件并接收命令
+//
+// 每个请求都要带上 `Authorization: Bearer <令牌>`，令牌在「控制接口」中发放，
+// 能执行哪些操作由令牌的权限范围决定（见 `control`）。成功时返回 JSON，失败时返回
+// {"error": "原因"} 和对应的状态码：401 令牌无效，403 权限不足，409 与当前状态冲突。
+//
+// 浏览器中的 WebSocket 无法设置请求头，/ws 也接受 `?token=<令牌>`，连接需要「只读状态」权限，
+// 每次推送前重新检查，令牌被撤销后服务器关闭连接。
+// 连接后服务器把事件逐条发送为 JSON，例如 {"type": "click", "x": 100, "y": 200, "ok": true}；
+// 客户端发送 {"command": "start"}、{"command": "click", "x": 100, "y": 200} 这样的命令，
+// 可以附带任意 "id"，服务器按令牌的权限执行后回复 {"type": "reply", "id": ..., "ok": true}
+// 或 {"type": "reply", "id": ..., "error": "原因"}，status 命令的回复中带有状态。
+//
+// 服务器作为后台任务运行在 `tasks` 的运行时上。开始和停止要用到界面中的设置，
+// 通过通道交给界面线程处理并等待结果；坐标点击直接交给输入引擎。
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use mousetool_core::app_state::SharedState;
+use mousetool_core::engine::Engine;
+use mousetool_core::events;
+use mousetool_core::i18n::t;
+use mousetool_core::{ClickType, MoveMode, click_with, move_to_target, tasks};
+
+use crate::control::{self, Command, Denied, ForwardError, Pending, Request, SharedTokens};
+use crate::event_log::{self, SharedLog};
+
+pub const DEFAULT_PORT: u16 = 8765;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// 监听所有网卡，局域网内的其他电脑也能访问；否则只监听本机回环地址
+    pub allow_lan: bool,
+}
+
+impl Default for ApiSettings {
+    fn default() -> Self {
+        Self { enabled: false, port: DEFAULT_PORT, allow_lan: false }
+    }
+}
+
+/// 服务器直接读取或操作的共享状态
+pub struct Shared {
+    pub tokens: SharedTokens,
+    pub state: SharedState,
+    pub total_clicks: Arc<Mutex<u32>>,
+    pub engine: Engine,
+    pub log: SharedLog,
+}
+
+#[derive(Clone)]
+struct Api {
+    shared: Arc<Shared>,
+    requests: Sender<Pending>,
+    ctx: egui::Context,
+    /// 服务器停止时取消，WebSocket 连接随之关闭
+    shutdown: tasks::CancellationToken,
+}
+
+/// 正在运行的服务器，析构时停止
+pub struct Server {
+    address: SocketAddr,
+    requests: Receiver<Pending>,
+    _task: tasks::Task,
+}
+
+impl Server {
+    pub fn start(settings: &ApiSettings, shared: Shared, ctx: &egui::Context) -> Result<Self, String> {
+        let host = if settings.allow_lan { Ipv4Addr::UNSPECIFIED } else { Ipv4Addr::LOCALHOST };
+        // 在这里同步绑定端口，端口被占用时能立即告诉界面
+        let listener = std::net::TcpListener::bind((host, settings.port))
+            .and_then(|listener| listener.set_nonblocking(true).map(|_| listener))
+            .map_err(|e| t!("无法监听端口 {}: {}", settings.port, e))?;
+        let address = listener.local_addr().map_err(|e| e.to_string())?;
+
+        let (sender, requests) = mpsc::channel();
+        let shared = Arc::new(shared);
+        let ctx = ctx.clone();
+        let task = tasks::spawn(move |token| {
+            let api = Api { shared, requests: sender, ctx, shutdown: token.clone() };
+            let router = Router::new()
+                .route("/status", get(status))
+                .route("/start", post(start))
+                .route("/stop", post(stop))
+                .route("/click", post(click))
+                .route("/ws", get(websocket))
+                .with_state(api);
+            serve(listener, router, token)
+        });
+        Ok(Self { address, requests, _task: task })
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// 取出一个等待界面线程处理的请求
+    pub fn try_recv(&self) -> Option<Pending> {
+        self.requests.try_recv().ok()
+    }
+}
+
+async fn serve(listener: std::net::TcpListener, router: Router, token: tasks::CancellationToken) {
+    let address = listener.local_addr().ok();
+    let listener = match tokio::net::TcpListener::from_std(listener) {
+        Ok(listener) => listener,
+        Err(e) => {
+            tracing::error!(error = %e, "HTTP 控制接口无法接收连接");
+            return;
+        }
+    };
+    tracing::info!(address = ?address, "HTTP 控制接口已启动");
+    let server = axum::serve(listener, router).with_graceful_shutdown(token.cancelled_owned());
+    if let Err(e) = server.await {
+        tracing::error!(error = %e, "HTTP 控制接口异常退出");
+    }
+    tracing::info!("HTTP 控制接口已停止");
+}
+
+#[derive(Serialize)]
+struct StatusResponse {
+    state: mousetool_core::app_state::AppState,
+    total_clicks: u32,
+}
+
+#[derive(Deserialize)]
+struct ClickRequest {
+    x: i32,
+    y: i32,
+    #[serde(default)]
+    button: ClickType,
+}
+
+// HTTP 端点和 WebSocket 命令共用的操作
+#[derive(Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+enum Action {
+    Status,
+    Start,
+    Stop,
+    Click(ClickRequest),
+}
+
+impl Action {
+    fn command(&self) -> Command {
+        match self {
+            Action::Status => Command::Status,
+            Action::Start => Command::Start,
+            Action::Stop => Command::Stop,
+            Action::Click(_) => Command::Click,
+        }
+    }
+}
+
+// WebSocket 上收到的一条命令，`id` 原样放回回复中
+#[derive(Deserialize)]
+struct SocketCommand {
+    #[serde(default)]
+    id: Option<serde_json::Value>,
+    #[serde(flatten)]
+    action: Action,
+}
+
+#[derive(Deserialize)]
+struct SocketQuery {
+    token: Option<String>,
+}
+
+type Failure = (StatusCode, String);
+
+fn error(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, Json(serde_json::json!({ "error": message.into() }))).into_response()
+}
+
+// 请求头中的 Bearer 令牌，没有时为空
+fn bearer(headers: &HeaderMap) -> &str {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .unwrap_or_default()
+        .trim()
+}
+
+impl Api {
+    // 校验令牌，通过时返回令牌的名称，用于记录日志；失败时返回状态码和原因
+    fn authorize(&self, token: &str, command: Command) -> Result<String, Failure> {
+        let tokens = self.shared.tokens.lock().unwrap();
+        match tokens.authorize(token, command) {
+            Ok(token) => Ok(token.name.clone()),
+            Err(Denied::Unauthorized) => Err((StatusCode::UNAUTHORIZED, t!("令牌无效").to_string())),
+            Err(Denied::Forbidden) => Err((StatusCode::FORBIDDEN, t!("令牌的权限范围不包含「{}」", command.label()))),
+        }
+    }
+
+    fn log(&self, client: &str, text: String) {
+        let client = if client.is_empty() { t!("（未命名）") } else { client };
+        self.shared.log.lock().unwrap().push(event_log::EntryKind::Status, t!("HTTP 控制接口（{}）: {}", client, text));
+    }
+
+    // 校验令牌后执行操作，成功时返回回复的 JSON
+    async fn execute(&self, token: &str, action: Action) -> Result<serde_json::Value, Failure> {
+        let client = self.authorize(token, action.command())?;
+        match action {
+            Action::Status => Ok(serde_json::json!(StatusResponse {
+                state: self.shared.state.get(),
+                total_clicks: *self.shared.total_clicks.lock().unwrap(),
+            })),
+            Action::Start => {
+                self.log(&client, Command::Start.label().to_string());
+                self.forward(Request::Start).await
+            }
+            Action::Stop => {
+                self.log(&client, Command::Stop.label().to_string());
+                self.forward(Request::Stop).await
+            }
+            Action::Click(request) => self.click(&client, request).await,
+        }
+    }
+
+    // 交给界面线程处理并等待结果：界面拒绝时为 409，界面不可用时为 503
+    async fn forward(&self, request: Request) -> Result<serde_json::Value, Failure> {
+        match control::forward(&self.requests, &self.ctx, request).await {
+            Ok(()) => Ok(serde_json::json!({ "ok": true })),
+            Err(ForwardError::Rejected(message)) => Err((StatusCode::CONFLICT, message)),
+            Err(ForwardError::Unavailable(message)) => Err((StatusCode::SERVICE_UNAVAILABLE, message)),
+        }
+    }
+
+    async fn click(&self, client: &str, request: ClickRequest) -> Result<serde_json::Value, Failure> {
+        // 执行中插入的点击会打乱正在执行的序列
+        if self.shared.state.is_running() {
+            return Err((StatusCode::CONFLICT, t!("正在执行，不能同时坐标点击").to_string()));
+        }
+        let ClickRequest { x, y, button } = request;
+        let engine = self.shared.engine.clone();
+        let result = tasks::blocking(move || {
+            move_to_target(&engine, x, y, MoveMode::Teleport, 0)?;
+            click_with(&engine, button)
+        })
+        .await;
+        match result {
+            Some(Ok(())) => {
+                self.log(client, t!("{}点击 ({}, {})", button.label(), x, y));
+                events::emit(events::Event::Click { x, y, ok: true });
+                Ok(serde_json::json!({ "ok": true }))
+            }
+            Some(Err(e)) => {
+                self.log(client, t!("点击 ({}, {}) 失败: {}", x, y, e));
+                events::emit(events::Event::Click { x, y, ok: false });
+                Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+            }
+            None => Err((StatusCode::INTERNAL_SERVER_ERROR, t!("输入引擎已停止").to_string())),
+        }
+    }
+
+    async fn respond(&self, headers: &HeaderMap, action: Action) -> Response {
+        match self.execute(bearer(headers), action).await {
+            Ok(value) => Json(value).into_response(),
+            Err((status, message)) => error(status, message),
+        }
+    }
+
+    // 一个 WebSocket 连接：同时推送事件和处理命令，直到任一方断开或服务器停止
+    async fn session(self, mut socket: WebSocket, token: String) {
+        let mut events = events::subscribe();
+        loop {
+            let outgoing = tokio::select! {
+                _ = self.shutdown.cancelled() => break,
+                event = events.recv() => match event {
+                    // 令牌已被撤销或不再有读取状态的权限，关闭连接
+                    Ok(_) if self.authorize(&token, Command::Status).is_err() => break,
+                    Ok(event) => serde_json::to_string(&event).unwrap_or_default(),
+                    // 积压太多时跳过丢失的事件
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                message = socket.recv() => match message {
+                    Some(Ok(Message::Text(text))) => self.command(&token, &text).await.to_string(),
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    // Ping 由 axum 自动回复
+                    Some(Ok(_)) => continue,
+                },
+            };
+            if socket.send(Message::Text(outgoing.into())).await.is_err() {
+                break;
+            }
+        }
+        let _ = socket.send(Message::Close(None)).await;
+    }
+
+    // 执行 WebSocket 上收到的命令，返回回复
+    async fn command(&self, token: &str, text: &str) -> serde_json::Value {
+        let command: SocketCommand = match serde_json::from_str(text) {
+            Ok(command) => command,
+            Err(e) => return serde_json::json!({ "type": "reply", "error": t!("无法解析命令: {}", e) }),
+        };
+        let mut reply = match self.execute(token, command.action).await {
+            Ok(value) => value,
+            Err((_, message)) => serde_json::json!({ "error": message }),
+        };
+        reply["type"] = "reply".into();
+        if let Some(id) = command.id {
+            reply["id"] = id;
+        }
+        reply
+    }
+}
+
+async fn status(State(api): State<Api>, headers: HeaderMap) -> Response {
+    api.respond(&headers, Action::Status).await
+}
+
+async fn start(State(api): State<Api>, headers: HeaderMap) -> Response {
+    api.respond(&headers, Action::Start).await
+}
+
+async fn stop(State(api): State<Api>, headers: HeaderMap) -> Response {
+    api.respond(&headers, Action::Stop).await
+}
+
+async fn click(State(api): State<Api>, headers: HeaderMap, Json(request): Json<ClickRequest>) -> Response {
+    api.respond(&headers, Action::Click(request)).await
+}
+
+async fn websocket(
+    State(api): State<Api>,
+    headers: HeaderMap,
+    Query(query): Query<SocketQuery>,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    let token = match query.token {
+        Some(token) => token,
+        None => bearer(&headers).to_string(),
+    };
+    // 连接本身只需要读取状态的权限，每条命令另外检查
+    if let Err((status, message)) = api.authorize(&token, Command::Status) {
+        return error(status, message);
+    }
+    upgrade.on_upgrade(move |socket| api.session(socket, token))
+}
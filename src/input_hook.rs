@@ -0,0 +1,393 @@
+// 系统级鼠标按钮钩子
+//
+// 逐帧查询鼠标按钮状态会漏掉两帧之间完成的快速点击，捕捉坐标时为了不漏掉点击又要以 60 FPS 刷新界面。
+// 这里在后台线程安装系统钩子，按钮按下和松开作为事件通过通道送给界面，并唤醒界面处理，
+// 界面只需在有事件时刷新：
+// - Windows：WH_MOUSE_LL 低级鼠标钩子
+// - macOS：只监听的 CGEventTap，需要「输入监控」权限
+// - Linux：X11 的 XInput2 原始按钮事件
+// 本程序自己模拟的点击会被忽略，以免执行中模拟的中键触发紧急停止。
+// 键盘快捷键仍然按帧查询，它们本来就是按住的组合键，不存在漏掉的问题。
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, SyncSender, TrySendError};
+
+use eframe::egui;
+
+use crate::i18n::t;
+
+// 界面长时间不刷新（例如最小化）时最多积压的事件数，超过的事件丢弃
+const QUEUE_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookButton {
+    Left,
+    Right,
+    Middle,
+    /// 侧键「后退」
+    Back,
+    /// 侧键「前进」
+    Forward,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ButtonEvent {
+    pub button: HookButton,
+    pub pressed: bool,
+    /// 事件发生时的鼠标位置，全局坐标
+    pub position: (i32, i32),
+}
+
+// 钩子线程持有的发送端
+struct Sink {
+    sender: SyncSender<ButtonEvent>,
+    ctx: egui::Context,
+    wake_on_motion: Arc<AtomicBool>,
+}
+
+impl Sink {
+    fn send(&self, event: ButtonEvent) {
+        match self.sender.try_send(event) {
+            Ok(()) | Err(TrySendError::Full(_)) => self.ctx.request_repaint(),
+            Err(TrySendError::Disconnected(_)) => {}
+        }
+    }
+
+    fn motion(&self) {
+        if self.wake_on_motion.load(Ordering::Relaxed) {
+            self.ctx.request_repaint();
+        }
+    }
+}
+
+pub struct InputHook {
+    receiver: Receiver<ButtonEvent>,
+    wake_on_motion: Arc<AtomicBool>,
+}
+
+impl InputHook {
+    /// 在后台线程安装钩子，每个事件都会唤醒 `ctx` 刷新
+    pub fn start(ctx: egui::Context) -> Result<Self, String> {
+        let (sender, receiver) = std::sync::mpsc::sync_channel(QUEUE_CAPACITY);
+        let wake_on_motion = Arc::new(AtomicBool::new(false));
+        platform::start(Sink { sender, ctx, wake_on_motion: wake_on_motion.clone() })?;
+        Ok(Self { receiver, wake_on_motion })
+    }
+
+    /// 取出上次调用以来的按钮事件
+    pub fn drain(&self) -> Vec<ButtonEvent> {
+        self.receiver.try_iter().collect()
+    }
+
+    /// 鼠标移动时是否也唤醒界面，捕捉坐标时放大镜需要跟随鼠标
+    pub fn set_wake_on_motion(&self, wake: bool) {
+        self.wake_on_motion.store(wake, Ordering::Relaxed);
+    }
+}
+
+// 等待钩子线程报告安装结果
+fn spawn(name: &str, run: impl FnOnce(SyncSender<Result<(), String>>) + Send + 'static) -> Result<(), String> {
+    let (ready, started) = std::sync::mpsc::sync_channel(1);
+    std::thread::Builder::new()
+        .name(name.to_string())
+        .spawn(move || run(ready))
+        .map_err(|e| e.to_string())?;
+    started.recv().unwrap_or_else(|_| Err(t!("钩子线程意外退出").to_string()))
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{ButtonEvent, HookButton, Sink};
+    use crate::i18n::t;
+    use std::sync::OnceLock;
+    use windows_sys::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
+    use windows_sys::Win32::System::LibraryLoader::GetModuleHandleW;
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        CallNextHookEx, DispatchMessageW, GetMessageW, LLMHF_INJECTED, MSG, MSLLHOOKSTRUCT, SetWindowsHookExW,
+        TranslateMessage, WH_MOUSE_LL, WM_LBUTTONDOWN, WM_LBUTTONUP, WM_MBUTTONDOWN, WM_MBUTTONUP, WM_MOUSEMOVE,
+        WM_RBUTTONDOWN, WM_RBUTTONUP, WM_XBUTTONDOWN, WM_XBUTTONUP, XBUTTON1,
+    };
+
+    // 钩子回调没有用户参数，只能通过全局变量找到发送端
+    static SINK: OnceLock<Sink> = OnceLock::new();
+
+    pub fn start(sink: Sink) -> Result<(), String> {
+        super::spawn("mouse-hook", move |ready| unsafe {
+            if SINK.set(sink).is_err() {
+                let _ = ready.send(Err(t!("鼠标钩子已经安装").to_string()));
+                return;
+            }
+            // 低级钩子在安装它的线程上回调，这个线程必须一直处理消息
+            let hook = SetWindowsHookExW(WH_MOUSE_LL, Some(mouse_proc), GetModuleHandleW(std::ptr::null()), 0);
+            if hook.is_null() {
+                let _ = ready.send(Err(t!("安装鼠标钩子失败: {}", std::io::Error::last_os_error())));
+                return;
+            }
+            let _ = ready.send(Ok(()));
+            let mut msg: MSG = std::mem::zeroed();
+            while GetMessageW(&mut msg, std::ptr::null_mut(), 0, 0) > 0 {
+                TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+        })
+    }
+
+    unsafe extern "system" fn mouse_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        if code >= 0
+            && let Some(sink) = SINK.get()
+        {
+            let info = unsafe { &*(lparam as *const MSLLHOOKSTRUCT) };
+            // 通过 Interception 驱动注入的事件没有注入标志，只能按附加信息识别
+            if info.flags & LLMHF_INJECTED == 0 && info.dwExtraInfo != crate::interception::EXTRA_INFO as usize {
+                let side = if (info.mouseData >> 16) as u16 == XBUTTON1 { HookButton::Back } else { HookButton::Forward };
+                let event = match wparam as u32 {
+                    WM_LBUTTONDOWN => Some((HookButton::Left, true)),
+                    WM_LBUTTONUP => Some((HookButton::Left, false)),
+                    WM_RBUTTONDOWN => Some((HookButton::Right, true)),
+                    WM_RBUTTONUP => Some((HookButton::Right, false)),
+                    WM_MBUTTONDOWN => Some((HookButton::Middle, true)),
+                    WM_MBUTTONUP => Some((HookButton::Middle, false)),
+                    WM_XBUTTONDOWN => Some((side, true)),
+                    WM_XBUTTONUP => Some((side, false)),
+                    WM_MOUSEMOVE => {
+                        sink.motion();
+                        None
+                    }
+                    _ => None,
+                };
+                if let Some((button, pressed)) = event {
+                    sink.send(ButtonEvent { button, pressed, position: (info.pt.x, info.pt.y) });
+                }
+            }
+        }
+        unsafe { CallNextHookEx(std::ptr::null_mut(), code, wparam, lparam) }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{ButtonEvent, HookButton, Sink};
+    use crate::i18n::t;
+    use std::ffi::c_void;
+    use std::sync::OnceLock;
+    use std::sync::atomic::{AtomicPtr, Ordering};
+
+    #[repr(C)]
+    struct CGPoint {
+        x: f64,
+        y: f64,
+    }
+
+    type TapCallback = unsafe extern "C" fn(*mut c_void, u32, *mut c_void, *mut c_void) -> *mut c_void;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    unsafe extern "C" {
+        fn CGEventTapCreate(
+            tap: u32,
+            place: u32,
+            options: u32,
+            events_of_interest: u64,
+            callback: TapCallback,
+            user_info: *mut c_void,
+        ) -> *mut c_void;
+        fn CGEventTapEnable(tap: *mut c_void, enable: bool);
+        fn CGEventGetLocation(event: *mut c_void) -> CGPoint;
+        fn CGEventGetIntegerValueField(event: *mut c_void, field: u32) -> i64;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    unsafe extern "C" {
+        static kCFRunLoopCommonModes: *const c_void;
+        fn CFMachPortCreateRunLoopSource(allocator: *const c_void, port: *mut c_void, order: isize) -> *mut c_void;
+        fn CFRunLoopGetCurrent() -> *mut c_void;
+        fn CFRunLoopAddSource(run_loop: *mut c_void, source: *mut c_void, mode: *const c_void);
+        fn CFRunLoopRun();
+    }
+
+    const SESSION_EVENT_TAP: u32 = 1;
+    const HEAD_INSERT_EVENT_TAP: u32 = 0;
+    const TAP_OPTION_LISTEN_ONLY: u32 = 1;
+    // 回调太慢时系统会停用事件监听，需要重新启用
+    const TAP_DISABLED_BY_TIMEOUT: u32 = 0xFFFF_FFFE;
+    const TAP_DISABLED_BY_USER_INPUT: u32 = 0xFFFF_FFFF;
+
+    const LEFT_MOUSE_DOWN: u32 = 1;
+    const LEFT_MOUSE_UP: u32 = 2;
+    const RIGHT_MOUSE_DOWN: u32 = 3;
+    const RIGHT_MOUSE_UP: u32 = 4;
+    const MOUSE_MOVED: u32 = 5;
+    const LEFT_MOUSE_DRAGGED: u32 = 6;
+    const RIGHT_MOUSE_DRAGGED: u32 = 7;
+    const OTHER_MOUSE_DOWN: u32 = 25;
+    const OTHER_MOUSE_UP: u32 = 26;
+    const OTHER_MOUSE_DRAGGED: u32 = 27;
+
+    const MOUSE_EVENT_BUTTON_NUMBER: u32 = 3;
+    const EVENT_SOURCE_UNIX_PROCESS_ID: u32 = 41;
+
+    static SINK: OnceLock<Sink> = OnceLock::new();
+    static TAP: AtomicPtr<c_void> = AtomicPtr::new(std::ptr::null_mut());
+
+    pub fn start(sink: Sink) -> Result<(), String> {
+        super::spawn("mouse-hook", move |ready| unsafe {
+            let mask = [
+                LEFT_MOUSE_DOWN,
+                LEFT_MOUSE_UP,
+                RIGHT_MOUSE_DOWN,
+                RIGHT_MOUSE_UP,
+                MOUSE_MOVED,
+                LEFT_MOUSE_DRAGGED,
+                RIGHT_MOUSE_DRAGGED,
+                OTHER_MOUSE_DOWN,
+                OTHER_MOUSE_UP,
+                OTHER_MOUSE_DRAGGED,
+            ]
+            .iter()
+            .fold(0u64, |mask, kind| mask | 1 << kind);
+            let tap = CGEventTapCreate(
+                SESSION_EVENT_TAP,
+                HEAD_INSERT_EVENT_TAP,
+                TAP_OPTION_LISTEN_ONLY,
+                mask,
+                tap_callback,
+                std::ptr::null_mut(),
+            );
+            if tap.is_null() {
+                let _ = ready.send(Err(t!("无法监听鼠标事件，请在系统设置中允许「输入监控」权限").to_string()));
+                return;
+            }
+            // 回调只在下面的运行循环中执行，安装失败时留给下次重试
+            if SINK.set(sink).is_err() {
+                let _ = ready.send(Err(t!("鼠标钩子已经安装").to_string()));
+                return;
+            }
+            TAP.store(tap, Ordering::Relaxed);
+            let source = CFMachPortCreateRunLoopSource(std::ptr::null(), tap, 0);
+            CFRunLoopAddSource(CFRunLoopGetCurrent(), source, kCFRunLoopCommonModes);
+            CGEventTapEnable(tap, true);
+            let _ = ready.send(Ok(()));
+            CFRunLoopRun();
+        })
+    }
+
+    unsafe extern "C" fn tap_callback(_proxy: *mut c_void, kind: u32, event: *mut c_void, _user_info: *mut c_void) -> *mut c_void {
+        if kind == TAP_DISABLED_BY_TIMEOUT || kind == TAP_DISABLED_BY_USER_INPUT {
+            unsafe { CGEventTapEnable(TAP.load(Ordering::Relaxed), true) };
+            return event;
+        }
+        let Some(sink) = SINK.get() else {
+            return event;
+        };
+        // 本程序模拟的事件带有本进程的 ID
+        if unsafe { CGEventGetIntegerValueField(event, EVENT_SOURCE_UNIX_PROCESS_ID) } == std::process::id() as i64 {
+            return event;
+        }
+        let other = || match unsafe { CGEventGetIntegerValueField(event, MOUSE_EVENT_BUTTON_NUMBER) } {
+            2 => Some(HookButton::Middle),
+            3 => Some(HookButton::Back),
+            4 => Some(HookButton::Forward),
+            _ => None,
+        };
+        let action = match kind {
+            LEFT_MOUSE_DOWN => Some((HookButton::Left, true)),
+            LEFT_MOUSE_UP => Some((HookButton::Left, false)),
+            RIGHT_MOUSE_DOWN => Some((HookButton::Right, true)),
+            RIGHT_MOUSE_UP => Some((HookButton::Right, false)),
+            OTHER_MOUSE_DOWN => other().map(|button| (button, true)),
+            OTHER_MOUSE_UP => other().map(|button| (button, false)),
+            _ => {
+                sink.motion();
+                None
+            }
+        };
+        if let Some((button, pressed)) = action {
+            let location = unsafe { CGEventGetLocation(event) };
+            sink.send(ButtonEvent { button, pressed, position: (location.x.round() as i32, location.y.round() as i32) });
+        }
+        event
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    use super::{ButtonEvent, HookButton, Sink};
+    use crate::i18n::t;
+    use x11rb::connection::Connection;
+    use x11rb::protocol::Event;
+    use x11rb::protocol::xinput::{self, ConnectionExt as _};
+    use x11rb::protocol::xproto::ConnectionExt as _;
+
+    pub fn start(sink: Sink) -> Result<(), String> {
+        super::spawn("mouse-hook", move |ready| {
+            let (conn, screen) = match x11rb::connect(None) {
+                Ok(connection) => connection,
+                Err(e) => {
+                    let _ = ready.send(Err(t!("无法连接 X 服务器: {}", e)));
+                    return;
+                }
+            };
+            let root = conn.setup().roots[screen].root;
+            let xtest_devices = match select_raw_events(&conn, root) {
+                Ok(devices) => devices,
+                Err(e) => {
+                    let _ = ready.send(Err(e));
+                    return;
+                }
+            };
+            let _ = ready.send(Ok(()));
+            while let Ok(event) = conn.wait_for_event() {
+                let (detail, sourceid, pressed) = match event {
+                    Event::XinputRawButtonPress(event) => (event.detail, event.sourceid, true),
+                    Event::XinputRawButtonRelease(event) => (event.detail, event.sourceid, false),
+                    Event::XinputRawMotion(_) => {
+                        sink.motion();
+                        continue;
+                    }
+                    _ => continue,
+                };
+                // XTest 模拟的事件来自专门的虚拟设备；按钮 4～7 是滚轮
+                if xtest_devices.contains(&sourceid) {
+                    continue;
+                }
+                let button = match detail {
+                    1 => HookButton::Left,
+                    2 => HookButton::Middle,
+                    3 => HookButton::Right,
+                    8 => HookButton::Back,
+                    9 => HookButton::Forward,
+                    _ => continue,
+                };
+                // 原始事件不带位置
+                let Some(pointer) = conn.query_pointer(root).ok().and_then(|cookie| cookie.reply().ok()) else {
+                    continue;
+                };
+                sink.send(ButtonEvent { button, pressed, position: (pointer.root_x.into(), pointer.root_y.into()) });
+            }
+        })
+    }
+
+    // 在根窗口上订阅所有主设备的原始按钮和移动事件，返回 XTest 虚拟设备的编号
+    fn select_raw_events(conn: &impl Connection, root: u32) -> Result<Vec<u16>, String> {
+        let unavailable = |e: &dyn std::fmt::Display| t!("X 服务器不支持 XInput2: {}", e);
+        conn.xinput_xi_query_version(2, 0)
+            .map_err(|e| unavailable(&e))?
+            .reply()
+            .map_err(|e| unavailable(&e))?;
+        let mask = xinput::XIEventMask::RAW_BUTTON_PRESS | xinput::XIEventMask::RAW_BUTTON_RELEASE | xinput::XIEventMask::RAW_MOTION;
+        conn.xinput_xi_select_events(root, &[xinput::EventMask { deviceid: xinput::Device::ALL_MASTER.into(), mask: vec![mask] }])
+            .map_err(|e| unavailable(&e))?;
+        conn.flush().map_err(|e| unavailable(&e))?;
+        let devices = conn
+            .xinput_xi_query_device(xinput::Device::ALL)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .map(|reply| reply.infos)
+            .unwrap_or_default();
+        Ok(devices
+            .into_iter()
+            .filter(|device| String::from_utf8_lossy(&device.name).contains("XTEST"))
+            .map(|device| device.deviceid)
+            .collect())
+    }
+}
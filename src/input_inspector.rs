@@ -0,0 +1,129 @@
+// 输入事件检查器
+//
+// 按帧读取设备查询库报告的鼠标按钮数组、光标位置和键盘按键，把每一次变化记成一条事件：
+// 哪个下标按下或松开、按当前的按钮映射对应哪个按钮、光标移动到哪里、按下了哪个键。
+// 排查按钮映射在不同平台上的差异时，比只看当前状态更容易看出发生了什么。
+// 只在调试面板打开时记录，按帧读取，两帧之间的极短按下可能漏掉。
+
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use device_query::Keycode;
+
+use crate::button_map::{ButtonMapping, CalibrationButton};
+use crate::i18n::t;
+
+// 最多保留的事件数，超出后丢弃最早的
+const MAX_EVENTS: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Button,
+    Move,
+    Key,
+}
+
+impl EventKind {
+    pub const ALL: [EventKind; 3] = [EventKind::Button, EventKind::Move, EventKind::Key];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            EventKind::Button => t!("按钮"),
+            EventKind::Move => t!("移动"),
+            EventKind::Key => t!("按键"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InputEvent {
+    /// 距离开始记录的秒数
+    pub secs: f64,
+    pub kind: EventKind,
+    pub text: String,
+}
+
+#[derive(Debug)]
+pub struct Inspector {
+    started: Instant,
+    events: VecDeque<InputEvent>,
+    buttons: Vec<bool>,
+    keys: Vec<Keycode>,
+    position: Option<(i32, i32)>,
+    /// 各类事件是否显示
+    pub show: [bool; 3],
+    pub paused: bool,
+}
+
+impl Default for Inspector {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            events: VecDeque::new(),
+            buttons: Vec::new(),
+            keys: Vec::new(),
+            position: None,
+            // 光标移动的事件很多，默认不显示
+            show: [true, false, true],
+            paused: false,
+        }
+    }
+}
+
+impl Inspector {
+    fn push(&mut self, kind: EventKind, text: String) {
+        if self.events.len() >= MAX_EVENTS {
+            self.events.pop_front();
+        }
+        self.events.push_back(InputEvent { secs: self.started.elapsed().as_secs_f64(), kind, text });
+    }
+
+    /// 与上一帧比较，记录发生变化的部分
+    pub fn poll(&mut self, buttons: &[bool], keys: &[Keycode], position: (i32, i32), mapping: ButtonMapping) {
+        if self.paused {
+            return;
+        }
+        let (x, y) = position;
+        for (index, &pressed) in buttons.iter().enumerate() {
+            if pressed == self.buttons.get(index).copied().unwrap_or(false) {
+                continue;
+            }
+            let mapped = mapping.button_at(index).map_or(t!("未映射"), CalibrationButton::label);
+            let state = if pressed { t!("按下") } else { t!("松开") };
+            self.push(EventKind::Button, t!("索引{} {}（{}） @ ({}, {})", index, state, mapped, x, y));
+        }
+        self.buttons = buttons.to_vec();
+
+        let pressed: Vec<String> =
+            keys.iter().filter(|key| !self.keys.contains(key)).map(|key| t!("{} 按下", format!("{:?}", key))).collect();
+        let released: Vec<String> =
+            self.keys.iter().filter(|key| !keys.contains(key)).map(|key| t!("{} 松开", format!("{:?}", key))).collect();
+        for text in pressed.into_iter().chain(released) {
+            self.push(EventKind::Key, text);
+        }
+        self.keys = keys.to_vec();
+
+        if self.position.is_some_and(|previous| previous != position) {
+            self.push(EventKind::Move, format!("({}, {})", x, y));
+        }
+        self.position = Some(position);
+    }
+
+    pub fn is_shown(&self, kind: EventKind) -> bool {
+        self.show[kind as usize]
+    }
+
+    /// 按筛选条件显示的事件
+    pub fn visible(&self) -> impl Iterator<Item = &InputEvent> {
+        self.events.iter().filter(|event| self.is_shown(event.kind))
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    /// 按筛选条件导出为每条一行的纯文本
+    pub fn to_text(&self) -> String {
+        self.visible().map(|event| format!("{:>9.3} [{}] {}\n", event.secs, event.kind.label(), event.text)).collect()
+    }
+}
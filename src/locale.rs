@@ -0,0 +1,182 @@
+// 系统语言检测和字体选择
+//
+// 同一个汉字在简体中文、繁体中文、日文、韩文字体中的字形并不相同，
+// 因此按系统语言选择优先使用的 CJK 字体；西文系统则保留 egui 自带的西文字体在前，
+// CJK 字体只作为后备。可以在界面中手动指定，覆盖自动检测的结果。
+//
+// 程序自带一份简体中文字体子集（见 build.rs），默认排在最前面，系统字体路径经常找不到
+// （如精简安装的 Linux），找到的系统字体只作为子集中没有的字的后备；也可以选择优先使用
+// 系统字体，以便繁体中文、日文和韩文显示本地的字形。
+
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::t;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Script {
+    SimplifiedChinese,
+    TraditionalChinese,
+    Japanese,
+    Korean,
+    Latin,
+}
+
+impl Script {
+    pub const ALL: [Script; 5] = [
+        Script::SimplifiedChinese,
+        Script::TraditionalChinese,
+        Script::Japanese,
+        Script::Korean,
+        Script::Latin,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Script::SimplifiedChinese => t!("简体中文"),
+            Script::TraditionalChinese => t!("繁體中文"),
+            Script::Japanese => t!("日本語"),
+            Script::Korean => "한국어",
+            Script::Latin => t!("西文（English 等）"),
+        }
+    }
+
+    /// 由语言标签（如 `zh-CN`、`zh_TW.UTF-8`、`ja_JP`）判断文字
+    pub fn from_locale(locale: &str) -> Self {
+        let locale = locale.to_ascii_lowercase().replace('_', "-");
+        let language = locale.split(['-', '.', '@']).next().unwrap_or("");
+        match language {
+            "zh" => {
+                let traditional = ["-tw", "-hk", "-mo", "-hant"].iter().any(|tag| locale.contains(tag));
+                if traditional { Script::TraditionalChinese } else { Script::SimplifiedChinese }
+            }
+            "ja" => Script::Japanese,
+            "ko" => Script::Korean,
+            _ => Script::Latin,
+        }
+    }
+
+    pub fn is_cjk(self) -> bool {
+        self != Script::Latin
+    }
+}
+
+/// 系统的语言标签，读取失败时返回 None
+pub fn system_locale() -> Option<String> {
+    platform_locale().filter(|locale| !locale.is_empty() && locale != "C" && locale != "POSIX")
+}
+
+#[cfg(target_os = "windows")]
+fn platform_locale() -> Option<String> {
+    use windows_sys::Win32::Globalization::GetUserDefaultLocaleName;
+
+    // LOCALE_NAME_MAX_LENGTH
+    let mut buffer = [0u16; 85];
+    let len = unsafe { GetUserDefaultLocaleName(buffer.as_mut_ptr(), buffer.len() as i32) };
+    // 返回的长度包含结尾的 0
+    (len > 1).then(|| String::from_utf16_lossy(&buffer[..len as usize - 1]))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn platform_locale() -> Option<String> {
+    let from_env = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty());
+    if from_env.is_some() || !cfg!(target_os = "macos") {
+        return from_env;
+    }
+
+    // 从 Finder 启动的 macOS 程序没有 LANG，读取系统偏好
+    let output = std::process::Command::new("defaults")
+        .args(["read", "-g", "AppleLocale"])
+        .output()
+        .ok()?;
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+pub fn detect_script() -> Script {
+    system_locale().map(|locale| Script::from_locale(&locale)).unwrap_or(Script::SimplifiedChinese)
+}
+
+// 各文字可用的 CJK 字体：路径和字体集（.ttc）中的序号
+fn fonts_for(script: Script) -> &'static [(&'static str, u32)] {
+    if cfg!(windows) {
+        match script {
+            Script::SimplifiedChinese | Script::Latin => &[
+                ("C:/Windows/Fonts/msyh.ttc", 0),   // 微软雅黑
+                ("C:/Windows/Fonts/simsun.ttc", 0), // 宋体
+                ("C:/Windows/Fonts/simhei.ttf", 0), // 黑体
+            ],
+            Script::TraditionalChinese => &[
+                ("C:/Windows/Fonts/msjh.ttc", 0),    // 微軟正黑體
+                ("C:/Windows/Fonts/mingliu.ttc", 0), // 細明體
+            ],
+            Script::Japanese => &[
+                ("C:/Windows/Fonts/YuGothM.ttc", 0), // 游ゴシック
+                ("C:/Windows/Fonts/meiryo.ttc", 0),  // メイリオ
+                ("C:/Windows/Fonts/msgothic.ttc", 0),
+            ],
+            Script::Korean => &[
+                ("C:/Windows/Fonts/malgun.ttf", 0), // 맑은 고딕
+                ("C:/Windows/Fonts/gulim.ttc", 0),
+            ],
+        }
+    } else if cfg!(target_os = "macos") {
+        match script {
+            Script::SimplifiedChinese | Script::Latin => &[
+                ("/System/Library/Fonts/PingFang.ttc", 0),
+                ("/Library/Fonts/PingFang.ttc", 0),
+                ("/System/Library/Fonts/STHeiti Light.ttc", 0),
+            ],
+            Script::TraditionalChinese => &[
+                ("/System/Library/Fonts/PingFang.ttc", 1),
+                ("/System/Library/Fonts/STHeiti Light.ttc", 0),
+            ],
+            Script::Japanese => &[("/System/Library/Fonts/ヒラギノ角ゴシック W3.ttc", 0)],
+            Script::Korean => &[("/System/Library/Fonts/AppleSDGothicNeo.ttc", 0)],
+        }
+    } else {
+        // Noto Sans CJK 字体集的序号：0=日文, 1=韩文, 2=简体中文, 3=繁体中文
+        match script {
+            Script::SimplifiedChinese | Script::Latin => &[
+                ("/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc", 2),
+                ("/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc", 2),
+                ("/usr/share/fonts/truetype/wqy/wqy-microhei.ttc", 0),
+                ("/usr/share/fonts/wenquanyi/wqy-microhei/wqy-microhei.ttc", 0),
+            ],
+            Script::TraditionalChinese => &[
+                ("/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc", 3),
+                ("/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc", 3),
+            ],
+            Script::Japanese => &[
+                ("/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc", 0),
+                ("/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc", 0),
+            ],
+            Script::Korean => &[
+                ("/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc", 1),
+                ("/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc", 1),
+            ],
+        }
+    }
+}
+
+/// 编译进程序的 CJK 字体，构建时缺少字体文件则为 None
+#[cfg(embedded_font)]
+pub const EMBEDDED_FONT: Option<&[u8]> = Some(include_bytes!("../assets/fonts/NotoSansSC-Subset.otf"));
+#[cfg(not(embedded_font))]
+pub const EMBEDDED_FONT: Option<&[u8]> = None;
+
+/// 按优先顺序排列的候选 CJK 字体。
+/// 首选文字的字体在前，其余文字的字体作为后备，保证中文界面至少能显示出来。
+pub fn font_candidates(script: Script) -> Vec<(&'static str, u32)> {
+    let mut candidates = fonts_for(script).to_vec();
+    for other in Script::ALL {
+        for font in fonts_for(other) {
+            if !candidates.contains(font) {
+                candidates.push(*font);
+            }
+        }
+    }
+    candidates
+}
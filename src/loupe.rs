@@ -0,0 +1,99 @@
+// 捕捉坐标时的放大镜
+//
+// 在鼠标旁边显示光标周围像素的放大图，带像素网格、实时坐标和中心像素的颜色，
+// 可以精确对准单个像素。读取屏幕像素目前只支持 Windows，其他平台只显示坐标。
+
+use eframe::egui;
+
+use crate::feedback;
+use crate::i18n::t;
+
+// 光标两侧各显示的像素数
+const RADIUS: i32 = 7;
+// 每个像素放大后的边长（逻辑像素）
+const CELL_SIZE: f32 = 8.0;
+// 放大图下方坐标文字的高度
+const CAPTION_HEIGHT: f32 = 20.0;
+
+pub struct Loupe {
+    pub x: i32,
+    pub y: i32,
+    /// 以光标为中心的像素，无法读取屏幕时为 None
+    pixels: Option<Vec<u32>>,
+}
+
+fn span() -> i32 {
+    RADIUS * 2 + 1
+}
+
+impl Loupe {
+    pub fn capture(x: i32, y: i32) -> Self {
+        Self { x, y, pixels: feedback::read_pixels(x - RADIUS, y - RADIUS, span(), span()) }
+    }
+
+    /// 放大镜窗口的大小（逻辑像素）
+    pub fn size() -> egui::Vec2 {
+        let side = span() as f32 * CELL_SIZE;
+        egui::vec2(side, side + CAPTION_HEIGHT)
+    }
+
+    pub fn paint(&self, painter: &egui::Painter) {
+        let side = span() as f32 * CELL_SIZE;
+        let image = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(side, side));
+        painter.rect_filled(egui::Rect::from_min_size(egui::Pos2::ZERO, Self::size()), 0.0, egui::Color32::from_gray(30));
+
+        let color = |pixel: u32| {
+            // COLORREF 为 0x00BBGGRR
+            egui::Color32::from_rgb(pixel as u8, (pixel >> 8) as u8, (pixel >> 16) as u8)
+        };
+        let mut center_color = None;
+        match &self.pixels {
+            Some(pixels) => {
+                for (i, &pixel) in pixels.iter().enumerate() {
+                    let (column, row) = (i as i32 % span(), i as i32 / span());
+                    let cell = egui::Rect::from_min_size(
+                        egui::pos2(column as f32 * CELL_SIZE, row as f32 * CELL_SIZE),
+                        egui::vec2(CELL_SIZE, CELL_SIZE),
+                    );
+                    painter.rect_filled(cell, 0.0, color(pixel));
+                }
+                center_color = pixels.get((RADIUS * span() + RADIUS) as usize).map(|&pixel| color(pixel));
+
+                let grid = egui::Stroke::new(1.0, egui::Color32::from_black_alpha(60));
+                for i in 1..span() {
+                    let offset = i as f32 * CELL_SIZE;
+                    painter.line_segment([egui::pos2(offset, 0.0), egui::pos2(offset, side)], grid);
+                    painter.line_segment([egui::pos2(0.0, offset), egui::pos2(side, offset)], grid);
+                }
+            }
+            None => {
+                painter.text(
+                    image.center(),
+                    egui::Align2::CENTER_CENTER,
+                    t!("放大镜仅支持 Windows"),
+                    egui::FontId::proportional(12.0),
+                    egui::Color32::GRAY,
+                );
+            }
+        }
+
+        // 标出光标所在的像素
+        let center = egui::Rect::from_min_size(
+            egui::pos2(RADIUS as f32 * CELL_SIZE, RADIUS as f32 * CELL_SIZE),
+            egui::vec2(CELL_SIZE, CELL_SIZE),
+        );
+        painter.rect_stroke(center, 0.0, egui::Stroke::new(2.0, egui::Color32::RED), egui::StrokeKind::Outside);
+
+        let mut caption = format!("({}, {})", self.x, self.y);
+        if let Some(color) = center_color {
+            caption.push_str(&format!("  #{:02X}{:02X}{:02X}", color.r(), color.g(), color.b()));
+        }
+        painter.text(
+            egui::pos2(side / 2.0, side + CAPTION_HEIGHT / 2.0),
+            egui::Align2::CENTER_CENTER,
+            caption,
+            egui::FontId::monospace(12.0),
+            egui::Color32::WHITE,
+        );
+    }
+}
@@ -2,205 +2,1048 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use eframe::egui;
+use mousetool_core::app_state::{self, AppState};
+use mousetool_core::i18n::{self, t};
+use mousetool_core::{
+    backend, button_map, capture, color, cross_platform_mouse, display, engine, error, events, export, feedback, import,
+    interception, jiggler, logging, macro_file, paths, permissions, plugins, power, runner, secure_desktop, sequence,
+    simulation, target_window, tasks, template, timing, uinput, wayland,
+};
+use mousetool_core::{
+    ClickMultiplicity, ClickType, FALLBACK_DOUBLE_CLICK_TIME, MoveMode, click_times, move_to_target, multi_click_gap,
+};
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime};
 
-// 跨平台鼠标控制模块
-mod cross_platform_mouse {
-    use device_query::{DeviceQuery, DeviceState, Keycode};
-    use enigo::{Enigo, Mouse, Button, Coordinate, Direction, Settings};
+mod autostart;
+mod benchmark;
+mod bookmarks;
+mod bundle;
+mod changelog;
+mod cli;
+mod color_picker;
+mod config;
+mod control;
+mod coordinate_text;
+mod end_actions;
+mod event_log;
+mod gamepad;
+mod heatmap;
+mod history;
+mod hotkey;
+mod http_api;
+mod input_hook;
+mod input_inspector;
+mod locale;
+mod loupe;
+mod marker;
+mod midi;
+mod mqtt_control;
+mod notification;
+mod painting;
+mod pipe_control;
+mod profile;
+mod recovery;
+mod settings;
+mod single_instance;
+mod sound;
+mod thumbnail;
+mod trail;
+mod translations;
+mod tray;
+mod udp_control;
+mod webhook;
 
-    pub struct MouseController {
-        enigo: Enigo,
-        device_state: DeviceState,
-    }
+struct MouseClickerApp {
+    x_pos: i32,
+    y_pos: i32,
+    click_interval: f64,
+    click_count: u32,
+    /// 开始执行前倒计时的秒数，留出时间切换到目标窗口
+    start_delay_secs: u32,
+    /// 空闲、捕捉坐标或执行中的哪个阶段，与执行线程共享
+    state: app_state::SharedState,
+    total_clicks: Arc<Mutex<u32>>,
+    click_type: ClickType,
+    click_multiplicity: ClickMultiplicity,
+    /// 多击间隔（毫秒），None 表示跟随系统双击时间
+    multi_click_gap_ms: Option<u64>,
+    auto_mode: bool,
+    /// 事件日志，最新的一条状态消息显示在状态栏
+    event_log: event_log::SharedLog,
+    /// 日志面板中是否列出每次点击
+    log_show_clicks: bool,
+    /// 最近一次执行中实际点击间隔的统计
+    timing: timing::SharedTiming,
+    /// 本次启动以来所有模拟点击的位置
+    heatmap: heatmap::SharedHeatmap,
+    heatmap_view: painting::HeatmapView,
+    benchmark: benchmark::Benchmark,
+    last_capture_button_state: bool,
+    /// 输入引擎线程的句柄，所有注入和输入状态读取都经过它
+    engine: engine::Engine,
+    /// 执行、单击、模拟和测速的工作线程，退出前等待它们结束
+    workers: Vec<thread::JoinHandle<()>>,
+    show_debug_info: bool,
+    /// 校准过的鼠标按钮下标，同时设置在鼠标控制器中
+    button_mapping: button_map::ButtonMapping,
+    input_inspector: input_inspector::Inspector,
+    /// 选择的输入后端，以及无法使用时的原因
+    input_backend: backend::Backend,
+    input_backend_error: Option<String>,
+    /// 系统鼠标钩子，无法安装时退回逐帧查询按钮状态
+    input_hook: Option<input_hook::InputHook>,
+    input_hook_error: Option<String>,
+    /// 本帧从钩子取出的按钮事件
+    hook_events: Vec<input_hook::ButtonEvent>,
+    /// 正在进行的鼠标按钮校准
+    button_calibration: Option<button_map::Calibration>,
+    capture_button_type: CaptureButtonType,
+    move_mode: MoveMode,
+    smooth_move_ms: u64,
+    auto_click_mode: AutoClickMode,
+    point_a: sequence::SequenceStep,
+    point_b: sequence::SequenceStep,
+    custom_sequence: sequence::Sequence,
+    macro_metadata: macro_file::MacroMetadata,
+    whats_new: Vec<&'static changelog::ChangelogEntry>,
+    show_whats_new: bool,
+    /// 尚未获得的 macOS 权限和上次检测的时间
+    missing_permissions: Vec<permissions::Permission>,
+    permissions_checked: Instant,
+    show_permissions: bool,
+    /// 是否打开注入错误面板
+    show_errors: bool,
+    /// 目标坐标处的窗口以管理员权限运行，以及上次检测的时间
+    elevated_target: bool,
+    elevation_checked: Instant,
+    feature_highlight: Option<(changelog::FeatureLink, Instant)>,
+    feature_scrolled: bool,
+    hold_key_enabled: bool,
+    hold_key: HoldKey,
+    playback_speed: f64,
+    end_action: end_actions::EndAction,
+    adaptive_interval: feedback::AdaptiveInterval,
+    show_trail: bool,
+    trail: trail::SharedTrail,
+    sound: sound::SoundSettings,
+    /// 执行结束时发送桌面通知
+    notify_on_finish: bool,
+    /// 执行前切换到前台的窗口和切换时机，以及上次刷新得到的窗口列表
+    focus_window: Option<target_window::WindowTarget>,
+    focus_mode: target_window::FocusMode,
+    keep_awake: bool,
+    jiggler_settings: jiggler::JigglerSettings,
+    // 开启防离开时正在运行的线程
+    jiggler: Option<jiggler::Jiggler>,
+    /// 开始时只模拟执行，不移动鼠标也不点击
+    simulate: bool,
+    // 上次模拟执行的结果，为 Some 时显示结果窗口
+    simulation: Arc<Mutex<Option<simulation::Simulation>>>,
+    open_windows: Vec<target_window::WindowTarget>,
+    /// 坐标相对的显示器名称，None 表示使用全局坐标
+    display_name: Option<String>,
+    displays: Vec<display::Display>,
+    /// 在屏幕上标记目标位置
+    show_target_marker: bool,
+    screenshot_pick: Option<ScreenshotPick>,
+    power_guard: power::PowerGuard,
+    /// 电量或 CPU 保护暂停执行时的原因
+    power_pause: Arc<Mutex<Option<String>>>,
+    /// 叠加层覆盖的屏幕尺寸（像素），第一次显示时读取
+    trail_screen_size: Option<(i32, i32)>,
+    insights: Option<history::Insights>,
+    show_sequence_expressions: bool,
+    hotkeys: Vec<hotkey::HotkeyBinding>,
+    hotkey_listener: hotkey::HotkeyListener,
+    gamepads: gamepad::Gamepads,
+    gamepad_bindings: Vec<gamepad::GamepadBinding>,
+    /// 正在等待按下手柄按钮来绑定的操作
+    recording_gamepad: Option<gamepad::GamepadAction>,
+    midi_settings: midi::MidiSettings,
+    /// 启用 MIDI 触发时连接的输入设备
+    midi: Option<midi::MidiInputs>,
+    /// 添加 MIDI 绑定时选择的操作
+    new_midi_action: midi::MidiAction,
+    /// 正在等待下一条 MIDI 消息来绑定
+    learning_midi: bool,
+    /// 紧急停止全部操作的鼠标按钮
+    stop_button: Option<hotkey::StopButton>,
+    recording_hotkey: Option<hotkey::RecordTarget>,
+    api_tokens: control::SharedTokens,
+    /// HTTP 控制接口的设置，启用时在 `http_api` 中运行
+    http_api_settings: http_api::ApiSettings,
+    http_api: Option<http_api::Server>,
+    /// Windows 上的命名管道控制接口，启用时在 `pipe_control` 中运行
+    pipe_control_enabled: bool,
+    pipe_control: Option<pipe_control::Server>,
+    /// Stream Deck 等外部按键使用的 UDP 控制接口的设置，启用时在 `udp_control` 中运行
+    udp_control_settings: udp_control::UdpSettings,
+    udp_control: Option<udp_control::Server>,
+    /// MQTT 控制接口的设置，启用时在 `mqtt_control` 中运行
+    mqtt_settings: mqtt_control::MqttSettings,
+    mqtt: Option<mqtt_control::Server>,
+    /// 执行事件的 Webhook 通知设置，启用时在 `webhook` 中运行
+    webhook_settings: webhook::WebhookSettings,
+    webhook: Option<tasks::Task>,
+    new_token_name: String,
+    new_token_scope: control::Scope,
+    profiles: profile::Profiles,
+    /// 正在重命名配置时输入框中的名称
+    profile_rename: Option<String>,
+    /// 添加显示名称时输入的语言标签
+    new_profile_language: String,
+    /// 系统语言，用于选择配置的显示名称
+    system_locale: Option<String>,
+    /// 登录时自动启动的注册状态
+    autostart: Option<autostart::AutostartStatus>,
+    /// 托盘图标，未启用时为 None
+    tray: Option<tray::Tray>,
+    /// 主窗口已隐藏到托盘
+    hidden_to_tray: bool,
+    /// 之后启动的实例转交过来的命令行参数
+    forwarded_args: single_instance::Forwarded,
+    /// 仍在生效的命令行参数和应用参数前的设置，切换配置后为 None
+    launch_overrides: Option<(cli::LaunchOptions, settings::Settings)>,
+    /// 开始执行时自动隐藏到托盘，执行结束后恢复
+    hide_to_tray_while_running: bool,
+    /// 刚开始执行，等下一帧隐藏窗口
+    pending_hide_to_tray: bool,
+    /// 窗口是因为执行而隐藏的，执行结束后需要恢复
+    restore_after_run: bool,
+    /// 迷你模式：只显示一条置顶的工具条
+    compact_mode: bool,
+    /// 进入迷你模式前的窗口大小，退出时恢复
+    full_window_size: Option<egui::Vec2>,
+    /// 手动指定的字体文字，None 表示按系统语言自动选择
+    font_script: Option<locale::Script>,
+    /// 优先使用系统安装的 CJK 字体，没有内置字体时总是使用系统字体
+    system_font: bool,
+    /// 界面主题：跟随系统、深色或浅色
+    theme: egui::ThemePreference,
+    /// 界面语言
+    language: i18n::Language,
+    /// 写入日志文件的最低级别
+    log_level: logging::LogLevel,
+    bookmarks: Vec<bookmarks::Bookmark>,
+    /// 捕捉坐标时截取的目标画面，坐标改动后不再显示
+    target_thumbnail: Option<thumbnail::Thumbnail>,
+    /// 用快捷键取过的颜色，最新的在前
+    picked_colors: Vec<color_picker::PickedColor>,
+    thumbnail_textures: thumbnail::TextureCache,
+    new_bookmark_name: String,
+    /// 最近捕捉或使用过的坐标，最新的在前
+    recent_coordinates: Vec<(i32, i32)>,
+    /// 点了粘贴坐标按钮时的界面帧号，剪贴板内容在之后的帧送到
+    coordinate_paste_requested: Option<u64>,
+    /// 工作线程中止执行时留给界面显示的说明
+    /// 最近一次执行前检查发现的无法执行的步骤
+    preflight_issues: Vec<(usize, String)>,
+    /// 正在进行或最近一次的自动点击
+    current_run: Option<RunProgress>,
+    /// 第一帧时最小化窗口（命令行 `--minimized`）
+    minimize_on_start: bool,
+    /// 上次意外中断、可以继续的执行
+    recovery_offer: Option<recovery::RunSnapshot>,
+    /// 速率过高或次数很多时先弹出确认框
+    confirm_extreme_runs: bool,
+    pending_confirmation: Option<RunConfirmation>,
+    /// 执行中关闭窗口时先确认
+    confirm_close: bool,
+    /// 已确认退出，不再拦截关闭请求
+    close_confirmed: bool,
+}
 
-    impl MouseController {
-        pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
-            let enigo = Enigo::new(&Settings::default())?;
-            let device_state = DeviceState::new();
+// 速率过高或次数很多时，开始前等待确认的执行
+struct RunConfirmation {
+    kind: history::RunKind,
+    clicks: u32,
+    /// 估算的每秒点击数
+    rate: Option<f64>,
+}
 
-            Ok(Self {
-                enigo,
-                device_state,
-            })
-        }
+// 从截图选取坐标的进度
+enum ScreenshotPick {
+    /// 主窗口已最小化，等它完全消失后再截图
+    Waiting(Instant),
+    Ready {
+        texture: egui::TextureHandle,
+        /// 截图的像素尺寸
+        size: egui::Vec2,
+        /// 截图像素到屏幕坐标的比例；macOS 视网膜屏上截图的像素是坐标的两倍
+        scale: egui::Vec2,
+    },
+}
 
-        pub fn get_mouse_position(&self) -> (i32, i32) {
-            let mouse = self.device_state.get_mouse();
-            (mouse.coords.0, mouse.coords.1)
-        }
+// 界面上显示的本次执行进度
+struct RunProgress {
+    started: Instant,
+    /// 开始时的累计点击次数，用来算出本次的点击数
+    clicks_before: u32,
+    /// 开始时还剩的点击次数，从中断处继续时扣除已完成的部分
+    remaining: u32,
+    /// 结束时的点击数和耗时
+    finished: Option<(u32, Duration)>,
+}
 
-        pub fn is_left_button_pressed(&self) -> bool {
-            let mouse = self.device_state.get_mouse();
-            mouse.button_pressed[1]
-        }
+// 执行线程上的观察者：把每次点击写入事件日志、回放轨迹和热力图，播放点击提示音，
+// 定期保存执行位置供意外退出后恢复
+struct RunFeedback {
+    log: event_log::SharedLog,
+    trail: Option<trail::SharedTrail>,
+    heatmap: heatmap::SharedHeatmap,
+    click_sound: bool,
+    checkpoint: recovery::Checkpointer,
+}
 
-        pub fn get_mouse_button_states(&self) -> Vec<bool> {
-            let mouse = self.device_state.get_mouse();
-            mouse.button_pressed.clone()
+impl sequence::RunObserver for RunFeedback {
+    fn moved(&self, x: i32, y: i32) {
+        if let Some(trail) = &self.trail {
+            trail.lock().unwrap().push(x, y, false);
         }
+    }
 
-        pub fn is_middle_button_pressed(&self) -> bool {
-            let mouse = self.device_state.get_mouse();
-            // 根据反馈，实际的按钮映射可能是：
-            // 0=左键, 1=中键, 2=右键 (在某些系统上)
-            if mouse.button_pressed.len() > 1 {
-                mouse.button_pressed[3] // 尝试索引1作为中键
-            } else {
-                false
-            }
+    fn clicked(&self, x: i32, y: i32, result: Result<(), &error::InputError>) {
+        if let Some(trail) = &self.trail {
+            trail.lock().unwrap().push(x, y, result.is_ok());
         }
-
-        pub fn is_right_button_pressed(&self) -> bool {
-            let mouse = self.device_state.get_mouse();
-            // 根据反馈，右键可能是索引2
-            if mouse.button_pressed.len() > 2 {
-                mouse.button_pressed[2] // 尝试索引2作为右键
-            } else {
-                false
+        if result.is_ok() {
+            self.heatmap.lock().unwrap().push(x, y);
+            if self.click_sound {
+                sound::play(sound::Sound::Click);
             }
         }
+        let text = match result {
+            Ok(()) => t!("点击 ({}, {})", x, y),
+            Err(error) => t!("点击 ({}, {}) 失败: {}", x, y, error),
+        };
+        self.log.lock().unwrap().push(event_log::EntryKind::Click, text);
+    }
 
-        pub fn move_mouse_to(&mut self, x: i32, y: i32) -> Result<(), Box<dyn std::error::Error>> {
-            self.enigo.move_mouse(x, y, Coordinate::Abs)?;
-            Ok(())
-        }
-
-        pub fn click_left(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-            self.enigo.button(Button::Left, Direction::Click)?;
-            Ok(())
-        }
-
-        pub fn click_right(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-            self.enigo.button(Button::Right, Direction::Click)?;
-            Ok(())
-        }
+    fn status(&self, text: String) {
+        self.log.lock().unwrap().push(event_log::EntryKind::Status, text);
+    }
 
-        pub fn click_middle(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-            self.enigo.button(Button::Middle, Direction::Click)?;
-            Ok(())
-        }
+    fn checkpoint_due(&self) -> bool {
+        self.checkpoint.is_due()
+    }
 
-        pub fn get_screen_size(&self) -> Result<(i32, i32), Box<dyn std::error::Error>> {
-            let (width, height) = self.enigo.main_display()?;
-            Ok((width, height))
-        }
+    fn checkpoint(&self, position: sequence::RunPosition) {
+        self.checkpoint.save(position);
     }
 }
 
-struct MouseClickerApp {
-    x_pos: i32,
-    y_pos: i32,
-    click_interval: f64,
-    click_count: u32,
-    is_clicking: Arc<Mutex<bool>>,
-    total_clicks: Arc<Mutex<u32>>,
-    click_type: ClickType,
-    auto_mode: bool,
-    status_message: String,
-    is_picking_position: bool,
-    last_capture_button_state: bool,
-    mouse_controller: Arc<Mutex<cross_platform_mouse::MouseController>>,
-    show_debug_info: bool,
-    capture_button_type: CaptureButtonType,
-}
+// 主窗口的默认大小和最小大小
+const WINDOW_SIZE: [f32; 2] = [480.0, 650.0];
+const MIN_WINDOW_SIZE: [f32; 2] = [450.0, 600.0];
+// 迷你模式工具条的大小
+const COMPACT_WINDOW_SIZE: [f32; 2] = [400.0, 36.0];
+
+// 持久化存储中记录上次看过更新日志的版本
+const LAST_SEEN_VERSION_KEY: &str = "last_seen_version";
+// 持久化存储中的全局快捷键绑定
+const HOTKEYS_KEY: &str = "hotkeys";
+// 持久化存储中的手柄按钮绑定
+const GAMEPAD_KEY: &str = "gamepad_bindings";
+// 持久化存储中 MIDI 触发的设置和绑定
+const MIDI_KEY: &str = "midi";
+// 持久化存储中紧急停止的鼠标按钮
+const STOP_BUTTON_KEY: &str = "stop_button";
+// 持久化存储中是否显示托盘图标
+const TRAY_KEY: &str = "tray";
+// 持久化存储中是否在执行期间隐藏到托盘
+const HIDE_TO_TRAY_KEY: &str = "hide_to_tray_while_running";
+// 旧版本持久化存储中的界面设置，只在升级为命名配置时读取
+const SETTINGS_KEY: &str = "settings";
+// 持久化存储中的命名配置
+const PROFILES_KEY: &str = "profiles";
+// 持久化存储中的坐标书签
+const BOOKMARKS_KEY: &str = "bookmarks";
+// 持久化存储中最近使用的坐标
+const RECENT_COORDINATES_KEY: &str = "recent_coordinates";
+const TARGET_THUMBNAIL_KEY: &str = "target_thumbnail";
+// 持久化存储中的累计点击次数，跨会话累加
+const TOTAL_CLICKS_KEY: &str = "total_clicks";
+// 持久化存储中手动指定的字体文字，没有时按系统语言自动选择
+const FONT_SCRIPT_KEY: &str = "font_script";
+// 持久化存储中是否优先使用系统字体
+const SYSTEM_FONT_KEY: &str = "system_font";
+// 持久化存储中的界面主题
+const THEME_KEY: &str = "theme";
+// 持久化存储中的界面语言，没有时按系统语言选择
+const LANGUAGE_KEY: &str = "language";
+// 持久化存储中的日志级别，与诊断有关，不随配置保存
+const LOG_LEVEL_KEY: &str = "log_level";
+// 持久化存储中校准过的鼠标按钮下标，与设备相关，不随配置保存
+const BUTTON_MAPPING_KEY: &str = "button_mapping";
+// 持久化存储中选择的输入后端，与系统设置相关，不随配置保存
+const INPUT_BACKEND_KEY: &str = "input_backend";
+// 持久化存储中 HTTP 控制接口设置的键
+const HTTP_API_KEY: &str = "http_api";
+// 持久化存储中是否启用命名管道控制接口
+const PIPE_CONTROL_KEY: &str = "pipe_control";
+// 持久化存储中 UDP 控制接口的设置
+const UDP_CONTROL_KEY: &str = "udp_control";
+// 持久化存储中 MQTT 控制接口的设置
+const MQTT_KEY: &str = "mqtt";
+// 持久化存储中 Webhook 通知的设置
+const WEBHOOK_KEY: &str = "webhook";
+// 缺少 macOS 权限时重新检测的间隔
+const PERMISSION_RECHECK: Duration = Duration::from_secs(1);
+// 检测目标窗口是否以管理员权限运行的间隔，目标窗口随时可能打开或关闭
+const ELEVATION_RECHECK: Duration = Duration::from_secs(2);
+// 持久化存储中是否在速率过高或次数很多时先确认
+const CONFIRM_EXTREME_KEY: &str = "confirm_extreme_runs";
+// 超过这个每秒点击数或点击次数时开始前需要确认
+const CONFIRM_RATE_LIMIT: f64 = 50.0;
+const CONFIRM_COUNT_LIMIT: u32 = 500;
+// 从更新日志跳转后高亮对应设置的时长
+const FEATURE_HIGHLIGHT_DURATION: Duration = Duration::from_secs(2);
+// 退出时等待后台任务和工作线程结束的最长时间，以及检查线程是否结束的间隔
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(3);
+const SHUTDOWN_POLL: Duration = Duration::from_millis(10);
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
 enum CaptureButtonType {
     MiddleButton,
     RightButton,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum ClickType {
-    Left,
-    Right,
-    Middle,
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+enum AutoClickMode {
+    SinglePoint,
+    TwoPoint,
+    Sequence,
 }
 
-impl Default for ClickType {
-    fn default() -> Self {
-        ClickType::Left
+// 自动点击期间持续按住的按键
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+enum HoldKey {
+    W,
+    A,
+    S,
+    D,
+    Space,
+    Shift,
+    Control,
+    Alt,
+}
+
+impl HoldKey {
+    const ALL: [HoldKey; 8] = [
+        HoldKey::W,
+        HoldKey::A,
+        HoldKey::S,
+        HoldKey::D,
+        HoldKey::Space,
+        HoldKey::Shift,
+        HoldKey::Control,
+        HoldKey::Alt,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            HoldKey::W => "W",
+            HoldKey::A => "A",
+            HoldKey::S => "S",
+            HoldKey::D => "D",
+            HoldKey::Space => t!("空格"),
+            HoldKey::Shift => "Shift",
+            HoldKey::Control => "Ctrl",
+            HoldKey::Alt => "Alt",
+        }
+    }
+
+    fn to_key(self) -> enigo::Key {
+        match self {
+            HoldKey::W => enigo::Key::Unicode('w'),
+            HoldKey::A => enigo::Key::Unicode('a'),
+            HoldKey::S => enigo::Key::Unicode('s'),
+            HoldKey::D => enigo::Key::Unicode('d'),
+            HoldKey::Space => enigo::Key::Space,
+            HoldKey::Shift => enigo::Key::Shift,
+            HoldKey::Control => enigo::Key::Control,
+            HoldKey::Alt => enigo::Key::Alt,
+        }
     }
 }
 
 impl MouseClickerApp {
-    fn new(cc: &eframe::CreationContext<'_>) -> Self {
-        // 设置中文字体支持
-        Self::setup_fonts(&cc.egui_ctx);
+    fn new(
+        cc: &eframe::CreationContext<'_>,
+        config: Option<(PathBuf, toml::Table)>,
+        launch: cli::LaunchOptions,
+        instance: single_instance::Instance,
+    ) -> Self {
+        // 按系统语言选择字体，可以在界面中手动覆盖
+        let font_script: Option<locale::Script> = cc.storage.and_then(|storage| eframe::get_value(storage, FONT_SCRIPT_KEY));
+        let system_font = cc.storage.and_then(|storage| eframe::get_value(storage, SYSTEM_FONT_KEY)).unwrap_or(false);
+        Self::setup_fonts(&cc.egui_ctx, font_script.unwrap_or_else(locale::detect_script), system_font);
+        let theme = cc.storage
+            .and_then(|storage| eframe::get_value(storage, THEME_KEY))
+            .unwrap_or(egui::ThemePreference::System);
+        cc.egui_ctx.set_theme(theme);
+        // 界面语言要在生成任何提示文字之前确定
+        let language = cc.storage
+            .and_then(|storage| eframe::get_value(storage, LANGUAGE_KEY))
+            .unwrap_or_else(|| i18n::Language::from_locale(locale::system_locale().as_deref()));
+        i18n::set_language(language);
+        let log_level = cc.storage
+            .and_then(|storage| eframe::get_value(storage, LOG_LEVEL_KEY))
+            .unwrap_or_default();
+        logging::set_level(log_level);
 
-        // 初始化鼠标控制器
-        let mouse_controller = match cross_platform_mouse::MouseController::new() {
-            Ok(controller) => Arc::new(Mutex::new(controller)),
+        // 启动输入引擎线程，鼠标控制器在其中创建
+        let button_mapping = cc.storage
+            .and_then(|storage| eframe::get_value(storage, BUTTON_MAPPING_KEY))
+            .unwrap_or_default();
+        let engine = match engine::Engine::start(button_mapping) {
+            Ok(engine) => engine,
             Err(e) => {
-                eprintln!("Failed to initialize mouse controller: {}", e);
+                tracing::error!(error = %e, "无法启动输入引擎");
+                if let Some(message) = wayland::diagnostic() {
+                    tracing::error!("{}", i18n::tr(message));
+                }
                 // 创建一个dummy控制器，虽然可能无法工作，但不会崩溃
                 panic!("Cannot initialize mouse controller: {}", e);
             }
         };
 
-        Self {
-            x_pos: 100,
-            y_pos: 100,
-            click_interval: 1.0,
-            click_count: 10,
-            is_clicking: Arc::new(Mutex::new(false)),
-            total_clicks: Arc::new(Mutex::new(0)),
-            click_type: ClickType::Left,
+        // 升级后首次启动时展示新功能
+        let last_seen_version = cc.storage.and_then(|storage| storage.get_string(LAST_SEEN_VERSION_KEY));
+        let whats_new = changelog::entries_since(last_seen_version.as_deref());
+        let show_whats_new = !whats_new.is_empty();
+        let missing_permissions = permissions::missing();
+        // 平台诊断信息，报告问题时从日志中可以看出输入为什么没有生效
+        if let Some(message) = wayland::diagnostic() {
+            tracing::warn!("{}", i18n::tr(message));
+        }
+        for permission in &missing_permissions {
+            tracing::warn!(permission = permission.label(), "缺少系统权限");
+        }
+
+        let mut profiles: profile::Profiles = cc.storage
+            .and_then(|storage| eframe::get_value(storage, PROFILES_KEY))
+            .unwrap_or_else(|| {
+                // 旧版本只保存了一套设置，升级为默认配置
+                let settings = cc.storage
+                    .and_then(|storage| eframe::get_value(storage, SETTINGS_KEY))
+                    .unwrap_or_default();
+                profile::Profiles::with_settings(settings)
+            });
+        let mut status_message = t!("准备就绪").to_string();
+        // 启动配置文件覆盖正在使用的配置
+        if let Some((path, config)) = &config {
+            let active = profiles.active_mut();
+            status_message = match config::apply(&active.settings, config) {
+                Ok(settings) => {
+                    active.settings = settings;
+                    t!("已加载配置文件: {}", path.display())
+                }
+                Err(e) => t!("⚠️ 配置文件 {} 有误: {}", path.display(), e),
+            };
+        }
+        // 命令行参数只在本次运行中生效，保存时恢复（见 `stored_settings`）
+        let profile_settings = profiles.active().settings.clone();
+        let mut settings = profile_settings.clone();
+        launch.apply(&mut settings);
+        let mut log = event_log::EventLog::default();
+        log.push(event_log::EntryKind::Status, status_message);
+        let registry = plugins::registry();
+        for plugin in &registry.plugins {
+            log.push(
+                event_log::EntryKind::Status,
+                t!("已加载插件 {}（{} 种步骤）", plugin.manifest.name, plugin.manifest.steps.len()),
+            );
+        }
+        for (path, reason) in &registry.errors {
+            log.push(event_log::EntryKind::Status, t!("⚠️ 无法加载插件 {}: {}", path.display(), reason));
+        }
+
+        let mut app = Self {
+            x_pos: settings.x,
+            y_pos: settings.y,
+            click_interval: settings.click_interval,
+            click_count: settings.click_count,
+            start_delay_secs: settings.start_delay_secs,
+            state: app_state::SharedState::default(),
+            total_clicks: Arc::new(Mutex::new(
+                cc.storage
+                    .and_then(|storage| eframe::get_value(storage, TOTAL_CLICKS_KEY))
+                    .unwrap_or(0),
+            )),
+            click_type: settings.click_type,
+            click_multiplicity: settings.click_multiplicity,
+            multi_click_gap_ms: settings.multi_click_gap_ms,
             auto_mode: false,
-            status_message: "准备就绪".to_string(),
-            is_picking_position: false,
+            event_log: Arc::new(Mutex::new(log)),
+            log_show_clicks: true,
+            timing: Arc::new(Mutex::new(timing::TimingStats::default())),
+            heatmap: Arc::new(Mutex::new(heatmap::Heatmap::default())),
+            heatmap_view: painting::HeatmapView::default(),
+            benchmark: benchmark::Benchmark::default(),
             last_capture_button_state: false,
-            mouse_controller,
+            engine,
+            workers: Vec::new(),
             show_debug_info: false,
-            capture_button_type: CaptureButtonType::MiddleButton,
+            button_mapping,
+            input_inspector: input_inspector::Inspector::default(),
+            input_backend: cc.storage
+                .and_then(|storage| eframe::get_value(storage, INPUT_BACKEND_KEY))
+                .unwrap_or_default(),
+            input_backend_error: None,
+            input_hook: None,
+            input_hook_error: None,
+            hook_events: Vec::new(),
+            button_calibration: None,
+            capture_button_type: settings.capture_button,
+            move_mode: settings.move_mode,
+            smooth_move_ms: settings.smooth_move_ms,
+            auto_click_mode: settings.auto_click_mode,
+            point_a: settings.point_a,
+            point_b: settings.point_b,
+            custom_sequence: profiles.active().sequence.clone(),
+            macro_metadata: macro_file::MacroMetadata::default(),
+            whats_new,
+            show_whats_new,
+            missing_permissions: missing_permissions.clone(),
+            permissions_checked: Instant::now(),
+            elevated_target: false,
+            elevation_checked: Instant::now() - ELEVATION_RECHECK,
+            show_permissions: !missing_permissions.is_empty(),
+            show_errors: false,
+            feature_highlight: None,
+            feature_scrolled: false,
+            hold_key_enabled: settings.hold_key_enabled,
+            hold_key: settings.hold_key,
+            playback_speed: settings.playback_speed,
+            end_action: settings.end_action,
+            adaptive_interval: settings.adaptive_interval,
+            show_trail: settings.show_trail,
+            sound: settings.sound.clone(),
+            notify_on_finish: settings.notify_on_finish,
+            display_name: settings.display,
+            focus_window: settings.focus_window,
+            focus_mode: settings.focus_mode,
+            keep_awake: settings.keep_awake,
+            jiggler_settings: settings.jiggler,
+            jiggler: None,
+            simulate: false,
+            simulation: Arc::new(Mutex::new(None)),
+            open_windows: Vec::new(),
+            displays: display::enumerate(),
+            power_guard: settings.power_guard,
+            power_pause: Arc::new(Mutex::new(None)),
+            trail: trail::SharedTrail::default(),
+            show_target_marker: false,
+            screenshot_pick: None,
+            trail_screen_size: None,
+            insights: None,
+            show_sequence_expressions: false,
+            hotkeys: cc.storage
+                .and_then(|storage| eframe::get_value(storage, HOTKEYS_KEY))
+                .unwrap_or_else(hotkey::default_bindings),
+            hotkey_listener: hotkey::HotkeyListener::default(),
+            gamepads: gamepad::Gamepads::default(),
+            gamepad_bindings: cc.storage
+                .and_then(|storage| eframe::get_value(storage, GAMEPAD_KEY))
+                .unwrap_or_default(),
+            recording_gamepad: None,
+            midi_settings: cc.storage
+                .and_then(|storage| eframe::get_value(storage, MIDI_KEY))
+                .unwrap_or_default(),
+            midi: None,
+            new_midi_action: midi::MidiAction::ToggleClicking,
+            learning_midi: false,
+            stop_button: cc.storage
+                .and_then(|storage| eframe::get_value(storage, STOP_BUTTON_KEY))
+                .unwrap_or_default(),
+            recording_hotkey: None,
+            api_tokens: Arc::new(Mutex::new(control::TokenStore::load())),
+            http_api_settings: cc.storage
+                .and_then(|storage| eframe::get_value(storage, HTTP_API_KEY))
+                .unwrap_or_default(),
+            http_api: None,
+            pipe_control_enabled: cc.storage
+                .and_then(|storage| eframe::get_value(storage, PIPE_CONTROL_KEY))
+                .unwrap_or(false),
+            pipe_control: None,
+            udp_control_settings: cc.storage
+                .and_then(|storage| eframe::get_value(storage, UDP_CONTROL_KEY))
+                .unwrap_or_default(),
+            udp_control: None,
+            mqtt_settings: cc.storage
+                .and_then(|storage| eframe::get_value(storage, MQTT_KEY))
+                .unwrap_or_default(),
+            mqtt: None,
+            webhook_settings: cc.storage
+                .and_then(|storage| eframe::get_value(storage, WEBHOOK_KEY))
+                .unwrap_or_default(),
+            webhook: None,
+            new_token_name: String::new(),
+            new_token_scope: control::Scope::Status,
+            profiles,
+            profile_rename: None,
+            new_profile_language: String::new(),
+            system_locale: locale::system_locale(),
+            autostart: autostart::status(),
+            tray: cc.storage
+                .and_then(|storage| eframe::get_value(storage, TRAY_KEY))
+                .unwrap_or(false)
+                .then(|| tray::Tray::new(&cc.egui_ctx).ok())
+                .flatten(),
+            hidden_to_tray: false,
+            forwarded_args: instance.listen(&cc.egui_ctx),
+            launch_overrides: Some((launch.clone(), profile_settings)),
+            hide_to_tray_while_running: cc.storage
+                .and_then(|storage| eframe::get_value(storage, HIDE_TO_TRAY_KEY))
+                .unwrap_or(false),
+            pending_hide_to_tray: false,
+            restore_after_run: false,
+            compact_mode: false,
+            full_window_size: None,
+            font_script,
+            system_font,
+            theme,
+            language,
+            log_level,
+            bookmarks: cc.storage
+                .and_then(|storage| eframe::get_value(storage, BOOKMARKS_KEY))
+                .unwrap_or_default(),
+            target_thumbnail: cc.storage.and_then(|storage| eframe::get_value(storage, TARGET_THUMBNAIL_KEY)),
+            thumbnail_textures: thumbnail::TextureCache::default(),
+            picked_colors: Vec::new(),
+            new_bookmark_name: String::new(),
+            recent_coordinates: cc.storage
+                .and_then(|storage| eframe::get_value(storage, RECENT_COORDINATES_KEY))
+                .unwrap_or_default(),
+            coordinate_paste_requested: None,
+            preflight_issues: Vec::new(),
+            current_run: None,
+            minimize_on_start: launch.minimized,
+            recovery_offer: recovery::load(),
+            confirm_extreme_runs: cc.storage
+                .and_then(|storage| eframe::get_value(storage, CONFIRM_EXTREME_KEY))
+                .unwrap_or(true),
+            pending_confirmation: None,
+            confirm_close: false,
+            close_confirmed: false,
+        };
+
+        app.select_display();
+        app.apply_input_backend();
+        app.start_input_hook(&cc.egui_ctx);
+        app.restart_http_api(&cc.egui_ctx);
+        app.restart_pipe_control(&cc.egui_ctx);
+        app.restart_udp_control(&cc.egui_ctx);
+        app.restart_mqtt(&cc.egui_ctx);
+        app.restart_webhook();
+        app.reconnect_midi(&cc.egui_ctx);
+        // 命令行要求启动后立即开始时不再询问，可能无人值守
+        if launch.start {
+            app.auto_mode = true;
+            app.run_auto_clicking();
         }
+        app
     }
 
-    fn setup_fonts(ctx: &egui::Context) {
-        let mut fonts = egui::FontDefinitions::default();
+    // 当前需要跨会话保存的设置
+    fn settings(&self) -> settings::Settings {
+        settings::Settings {
+            x: self.x_pos,
+            y: self.y_pos,
+            click_interval: self.click_interval,
+            click_count: self.click_count,
+            start_delay_secs: self.start_delay_secs,
+            click_type: self.click_type,
+            click_multiplicity: self.click_multiplicity,
+            multi_click_gap_ms: self.multi_click_gap_ms,
+            capture_button: self.capture_button_type,
+            move_mode: self.move_mode,
+            smooth_move_ms: self.smooth_move_ms,
+            auto_click_mode: self.auto_click_mode,
+            point_a: self.point_a.clone(),
+            point_b: self.point_b.clone(),
+            hold_key_enabled: self.hold_key_enabled,
+            hold_key: self.hold_key,
+            playback_speed: self.playback_speed,
+            end_action: self.end_action.clone(),
+            adaptive_interval: self.adaptive_interval.clone(),
+            show_trail: self.show_trail,
+            power_guard: self.power_guard.clone(),
+            sound: self.sound.clone(),
+            notify_on_finish: self.notify_on_finish,
+            display: self.display_name.clone(),
+            focus_window: self.focus_window.clone(),
+            focus_mode: self.focus_mode,
+            keep_awake: self.keep_awake,
+            jiggler: self.jiggler_settings.clone(),
+        }
+    }
 
-        // 尝试加载中文字体
-        let font_paths = if cfg!(windows) {
-            vec![
-                "C:/Windows/Fonts/msyh.ttc",      // 微软雅黑
-                "C:/Windows/Fonts/simsun.ttc",   // 宋体
-                "C:/Windows/Fonts/simhei.ttf",   // 黑体
-            ]
-        } else if cfg!(target_os = "macos") {
-            vec![
-                "/Library/Fonts/PingFang.ttc",           // 苹方
-                "/System/Library/Fonts/STHeiti Light.ttc", // 黑体
-                "/System/Library/Fonts/Helvetica.ttc",    // 备选
-            ]
-        } else {
-            vec![
-                "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
-                "/usr/share/fonts/TTF/DejaVuSans.ttf",
-                "/usr/share/fonts/truetype/liberation/LiberationSans-Regular.ttf",
-                "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
-                "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
-            ]
-        };
-
-        for (i, path) in font_paths.iter().enumerate() {
-            if let Ok(font_data) = std::fs::read(path) {
-                let font_name = format!("custom_font_{}", i);
-                fonts.font_data.insert(
-                    font_name.clone(),
-                    egui::FontData::from_owned(font_data).into(),
-                );
+    fn apply_settings(&mut self, settings: settings::Settings) {
+        self.x_pos = settings.x;
+        self.y_pos = settings.y;
+        self.click_interval = settings.click_interval;
+        self.click_count = settings.click_count;
+        self.start_delay_secs = settings.start_delay_secs;
+        self.click_type = settings.click_type;
+        self.click_multiplicity = settings.click_multiplicity;
+        self.multi_click_gap_ms = settings.multi_click_gap_ms;
+        self.capture_button_type = settings.capture_button;
+        self.move_mode = settings.move_mode;
+        self.smooth_move_ms = settings.smooth_move_ms;
+        self.auto_click_mode = settings.auto_click_mode;
+        self.point_a = settings.point_a;
+        self.point_b = settings.point_b;
+        self.hold_key_enabled = settings.hold_key_enabled;
+        self.hold_key = settings.hold_key;
+        self.playback_speed = settings.playback_speed;
+        self.end_action = settings.end_action;
+        self.adaptive_interval = settings.adaptive_interval;
+        self.show_trail = settings.show_trail;
+        self.power_guard = settings.power_guard;
+        self.sound = settings.sound;
+        self.notify_on_finish = settings.notify_on_finish;
+        self.display_name = settings.display;
+        self.focus_window = settings.focus_window;
+        self.focus_mode = settings.focus_mode;
+        self.keep_awake = settings.keep_awake;
+        self.jiggler_settings = settings.jiggler;
+        self.select_display();
+    }
+
+    // 把当前的修改写回正在使用的配置
+    // 要保存的设置：命令行参数只在本次运行中生效，还没有在界面中改过的字段恢复为参数之前的值
+    fn stored_settings(&self) -> settings::Settings {
+        let mut settings = self.settings();
+        if let Some((launch, original)) = &self.launch_overrides {
+            launch.revert(&mut settings, original);
+        }
+        settings
+    }
+
+    fn store_active_profile(&mut self) {
+        let settings = self.stored_settings();
+        let sequence = self.custom_sequence.clone();
+        let profile = self.profiles.active_mut();
+        profile.settings = settings;
+        profile.sequence = sequence;
+    }
+
+    fn switch_profile(&mut self, index: usize) {
+        if index == self.profiles.active || index >= self.profiles.profiles.len() {
+            return;
+        }
+        if self.state.is_running() {
+            self.set_status(t!("自动点击进行中，请先停止再切换配置").to_string());
+            return;
+        }
+        self.store_active_profile();
+        self.profiles.active = index;
+        self.launch_overrides = None;
+        let profile = self.profiles.active().clone();
+        self.apply_settings(profile.settings);
+        self.custom_sequence = profile.sequence;
+        self.set_status(t!("已切换到配置「{}」", profile.name));
+    }
+
+    fn profile_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(t!("配置:"));
+            if let Some(name) = &mut self.profile_rename {
+                let response = ui.add(egui::TextEdit::singleline(name).desired_width(140.0));
+                let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                if ui.button(t!("确定")).clicked() || submitted {
+                    let name = name.clone();
+                    match self.profiles.rename(self.profiles.active, &name) {
+                        Ok(()) => {
+                            self.profile_rename = None;
+                            self.set_status(t!("配置已重命名为「{}」", name.trim()));
+                        }
+                        Err(e) => self.set_status(e),
+                    }
+                }
+                if ui.button(t!("取消")).clicked() {
+                    self.profile_rename = None;
+                }
+                return;
+            }
+
+            let locale = self.system_locale.as_deref();
+            let mut selected = self.profiles.active;
+            let response = egui::ComboBox::from_id_salt("active_profile")
+                .selected_text(self.profiles.active().display_name(locale))
+                .show_ui(ui, |ui| {
+                    for (i, profile) in self.profiles.profiles.iter().enumerate() {
+                        let response = ui.selectable_value(&mut selected, i, profile.display_name(locale));
+                        if !profile.info.description.is_empty() {
+                            response.on_hover_text(&profile.info.description);
+                        }
+                    }
+                })
+                .response;
+            let info = &self.profiles.active().info;
+            if !info.author.is_empty() {
+                response.on_hover_text(t!("作者: {}", info.author));
+            }
+            self.switch_profile(selected);
+
+            if ui.button(t!("新建")).on_hover_text(t!("以当前设置新建一个配置")).clicked() {
+                self.store_active_profile();
+                let mut profile = self.profiles.active().clone();
+                profile.name = self.profiles.unique_name(t!("新配置"));
+                let index = self.profiles.add(profile);
+                self.profiles.active = index;
+                self.profile_rename = Some(self.profiles.active().name.clone());
+            }
+            if ui.button(t!("重命名")).clicked() {
+                self.profile_rename = Some(self.profiles.active().name.clone());
+            }
+            if ui.button(t!("删除")).clicked() {
+                let index = self.profiles.active;
+                match self.profiles.delete(index) {
+                    Ok(removed) => {
+                        let profile = self.profiles.active().clone();
+                        self.apply_settings(profile.settings);
+                        self.custom_sequence = profile.sequence;
+                        self.set_status(t!("已删除配置「{}」，切换到「{}」", removed.name, profile.name));
+                    }
+                    Err(e) => self.set_status(e),
+                }
+            }
+            if ui.button(t!("导出…")).on_hover_text(t!("把当前配置保存为可分享的文件")).clicked() {
+                self.export_profile();
+            }
+            if ui.button(t!("导入…")).clicked() {
+                self.import_profile();
+            }
+        });
+
+        let description = &self.profiles.active().info.description;
+        if !description.is_empty() {
+            ui.weak(description);
+        }
+        egui::CollapsingHeader::new(t!("配置说明")).id_salt("profile_info").show(ui, |ui| {
+            self.profile_info_ui(ui);
+        });
+    }
+
+    // 编辑配置的标题、说明、作者和各语言的显示名称
+    fn profile_info_ui(&mut self, ui: &mut egui::Ui) {
+        let info = &mut self.profiles.active_mut().info;
+        egui::Grid::new("profile_info_grid").num_columns(2).show(ui, |ui| {
+            ui.label(t!("标题:"));
+            ui.text_edit_singleline(&mut info.title);
+            ui.end_row();
+            ui.label(t!("作者:"));
+            ui.text_edit_singleline(&mut info.author);
+            ui.end_row();
+            ui.label(t!("说明:"));
+            ui.add(egui::TextEdit::multiline(&mut info.description).desired_rows(2));
+            ui.end_row();
+        });
+
+        ui.label(t!("各语言的显示名称（按系统语言显示，没有对应语言时显示标题）:"));
+        let mut removed = None;
+        for (language, name) in info.names.iter_mut() {
+            ui.horizontal(|ui| {
+                ui.label(language.as_str());
+                ui.text_edit_singleline(name);
+                if ui.small_button("🗑").clicked() {
+                    removed = Some(language.clone());
+                }
+            });
+        }
+        if let Some(language) = removed {
+            info.names.remove(&language);
+        }
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.new_profile_language)
+                .hint_text(t!("语言标签，如 en、zh-TW、ja"))
+                .desired_width(160.0));
+            let language = self.new_profile_language.trim().to_string();
+            if ui.add_enabled(!language.is_empty(), egui::Button::new(t!("添加语言"))).clicked() {
+                info.names.entry(language).or_default();
+                self.new_profile_language.clear();
+            }
+        });
+    }
+
+    fn export_profile(&mut self) {
+        self.store_active_profile();
+        let profile = self.profiles.active();
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter(t!("配置文件"), &["json"])
+            .set_file_name(format!("{}.json", profile.name))
+            .save_file()
+        else {
+            return;
+        };
+
+        self.set_status(match profile::export(profile, &path) {
+            Ok(()) => t!("配置「{}」已导出到: {}", profile.name, path.display()),
+            Err(e) => t!("导出配置失败: {}", e),
+        });
+    }
+
+    // 导入的配置作为新配置添加并立即切换过去，重名时自动加序号
+    fn import_profile(&mut self) {
+        let Some(path) = rfd::FileDialog::new().add_filter(t!("配置文件"), &["json"]).pick_file() else {
+            return;
+        };
+
+        match profile::import(&path) {
+            Ok(imported) => {
+                let index = self.profiles.add(imported.profile);
+                let name = self.profiles.profiles[index].name.clone();
+                self.switch_profile(index);
+                if self.profiles.active == index {
+                    self.set_status(match imported.dropped_end_action {
+                        Some(action) => t!(
+                            "已导入配置「{}」，文件中的结束后操作（{}）没有导入，需要时请重新设置",
+                            name,
+                            action.describe()
+                        ),
+                        None => t!("已导入配置「{}」", name),
+                    });
+                }
+            }
+            Err(e) => self.set_status(t!("导入配置失败: {}", e)),
+        }
+    }
+
+    fn setup_fonts(ctx: &egui::Context, script: locale::Script, system_font: bool) {
+        let mut fonts = egui::FontDefinitions::default();
+        let mut cjk_fonts = Vec::new();
 
-                fonts.families.entry(egui::FontFamily::Proportional)
-                    .or_default()
-                    .insert(0, font_name.clone());
+        if let Some(data) = locale::EMBEDDED_FONT {
+            fonts.font_data.insert("embedded_cjk".to_string(), egui::FontData::from_static(data).into());
+            cjk_fonts.push("embedded_cjk".to_string());
+        }
+        // 按系统语言的优先顺序加载第一个存在的系统 CJK 字体，作为内置子集中没有的字的后备；
+        // 优先使用系统字体时排在内置字体前面
+        let system = locale::font_candidates(script)
+            .into_iter()
+            .find_map(|(path, index)| std::fs::read(path).ok().map(|data| (data, index)));
+        if let Some((data, index)) = system {
+            fonts.font_data.insert(
+                "system_cjk".to_string(),
+                egui::FontData { index, ..egui::FontData::from_owned(data) }.into(),
+            );
+            let position = if system_font { 0 } else { cjk_fonts.len() };
+            cjk_fonts.insert(position, "system_cjk".to_string());
+        }
 
-                fonts.families.entry(egui::FontFamily::Monospace)
-                    .or_default()
-                    .insert(0, font_name);
-                break;
+        // CJK 系统优先使用这些字体；西文系统保留自带字体在前，只把它们作为后备
+        for family in [egui::FontFamily::Proportional, egui::FontFamily::Monospace] {
+            let list = fonts.families.entry(family).or_default();
+            if script.is_cjk() {
+                list.splice(0..0, cjk_fonts.iter().cloned());
+            } else {
+                list.extend(cjk_fonts.iter().cloned());
             }
         }
 
@@ -208,385 +1051,4199 @@ impl MouseClickerApp {
     }
 
     fn start_position_picking(&mut self) {
-        self.is_picking_position = true;
+        if !self.state.transition(AppState::Idle, AppState::Picking) {
+            self.set_status(t!("请先停止自动点击再捕捉坐标").to_string());
+            return;
+        }
         let button_name = match self.capture_button_type {
-            CaptureButtonType::MiddleButton => "鼠标中键（滚轮键）",
-            CaptureButtonType::RightButton => "鼠标右键",
+            CaptureButtonType::MiddleButton => t!("鼠标中键（滚轮键）"),
+            CaptureButtonType::RightButton => t!("鼠标右键"),
         };
-        self.status_message = format!("坐标捕捉模式已激活！请在屏幕任意位置点击{}...", button_name);
+        self.set_status(t!("坐标捕捉模式已激活！请在屏幕任意位置点击{}...", button_name));
         self.last_capture_button_state = false;
     }
 
+    fn is_picking(&self) -> bool {
+        self.state.get() == AppState::Picking
+    }
+
     fn check_position_picking(&mut self) {
-        if !self.is_picking_position {
+        if !self.is_picking() {
+            return;
+        }
+
+        // 安全桌面上读不到真实的鼠标位置，继续捕捉只会得到错误的坐标
+        if secure_desktop::input_desktop_is_secure() {
+            self.state.transition(AppState::Picking, AppState::Idle);
+            self.set_status(t!("⚠️ 已取消坐标捕捉: {}", i18n::tr(secure_desktop::SECURE_DESKTOP_MESSAGE)));
             return;
         }
 
-        if let Ok(controller) = self.mouse_controller.lock() {
+        let mut captured = None;
+        if self.input_hook.is_some() {
+            let button = match self.capture_button_type {
+                CaptureButtonType::MiddleButton => input_hook::HookButton::Middle,
+                CaptureButtonType::RightButton => input_hook::HookButton::Right,
+            };
+            // 钩子送来的是每一次按下和松开，两帧之间完成的点击也不会漏掉
+            for event in self.hook_events.iter().filter(|event| event.button == button) {
+                if self.last_capture_button_state && !event.pressed {
+                    captured = Some(display::to_local(event.position.0, event.position.1));
+                }
+                self.last_capture_button_state = event.pressed;
+            }
+        } else if let Ok((middle, right, position)) = self.engine.call(|controller| {
+            (controller.is_middle_button_pressed(), controller.is_right_button_pressed(), controller.get_mouse_position())
+        }) {
             let current_button_state = match self.capture_button_type {
-                CaptureButtonType::MiddleButton => controller.is_middle_button_pressed(),
-                CaptureButtonType::RightButton => controller.is_right_button_pressed(),
+                CaptureButtonType::MiddleButton => middle,
+                CaptureButtonType::RightButton => right,
             };
 
             // 检测鼠标按键从按下到释放的完整点击动作
             if self.last_capture_button_state && !current_button_state {
                 // 完整的点击动作完成，获取点击位置的坐标
-                let (x, y) = controller.get_mouse_position();
-
-                // 将捕捉到的坐标填入输入框
-                self.x_pos = x;
-                self.y_pos = y;
-
-                let button_name = match self.capture_button_type {
-                    CaptureButtonType::MiddleButton => "中键",
-                    CaptureButtonType::RightButton => "右键",
-                };
-
-                // 更新状态消息
-                self.status_message = format!("✅ 坐标捕捉成功！已设置为: ({}, {}) [使用{}捕捉]", x, y, button_name);
-
-                // 退出捕捉模式
-                self.is_picking_position = false;
+                captured = Some(position);
             }
 
             self.last_capture_button_state = current_button_state;
         } else {
             // 如果无法访问鼠标控制器，退出捕捉模式
-            self.is_picking_position = false;
-            self.status_message = "⚠️ 鼠标控制器访问失败，请重试".to_string();
+            self.state.transition(AppState::Picking, AppState::Idle);
+            self.set_status(t!("⚠️ 鼠标控制器访问失败，请重试").to_string());
         }
-    }
 
-    fn get_current_mouse_pos(&mut self) {
-        if let Ok(controller) = self.mouse_controller.lock() {
-            let (x, y) = controller.get_mouse_position();
-            self.x_pos = x;
-            self.y_pos = y;
-            self.status_message = format!("已获取当前鼠标位置: ({}, {})", x, y);
+        if let Some((x, y)) = captured {
+            let button_name = match self.capture_button_type {
+                CaptureButtonType::MiddleButton => t!("中键"),
+                CaptureButtonType::RightButton => t!("右键"),
+            };
+            self.set_captured_position(x, y, button_name);
         }
     }
 
-    fn get_screen_info(&mut self) {
-        if let Ok(controller) = self.mouse_controller.lock() {
-            match controller.get_screen_size() {
-                Ok((width, height)) => {
-                    self.status_message = format!("屏幕尺寸: {}x{}", width, height);
-                }
-                Err(e) => {
-                    self.status_message = format!("获取屏幕信息失败: {}", e);
-                }
-            }
+    // 按快捷键记录鼠标当前位置。有些程序会拦截中键和右键，右键还会弹出菜单，快捷键不受这些影响
+    fn capture_position_by_hotkey(&mut self) {
+        // 安全桌面上读不到真实的鼠标位置
+        if secure_desktop::input_desktop_is_secure() {
+            return;
         }
+        let Ok((x, y)) = self.engine.get_mouse_position() else {
+            return;
+        };
+        let method = self
+            .hotkeys
+            .iter()
+            .find(|binding| binding.action == hotkey::HotkeyAction::CapturePosition)
+            .map(|binding| binding.hotkey.display())
+            .unwrap_or_default();
+        self.set_captured_position(x, y, &method);
     }
 
-    fn perform_single_click(&self) {
-        let x = self.x_pos;
-        let y = self.y_pos;
-        let click_type = self.click_type;
-        let total_clicks = self.total_clicks.clone();
-        let mouse_controller = self.mouse_controller.clone();
-
-        thread::spawn(move || {
-            if let Ok(mut controller) = mouse_controller.lock() {
-                let _ = controller.move_mouse_to(x, y);
-                thread::sleep(Duration::from_millis(50));
+    // 将捕捉到的坐标填入输入框并退出捕捉模式，`method` 是捕捉所用的按钮或按键
+    fn set_captured_position(&mut self, x: i32, y: i32, method: &str) {
+        self.x_pos = x;
+        self.y_pos = y;
+        bookmarks::push_recent(&mut self.recent_coordinates, x, y);
+        self.target_thumbnail = thumbnail::Thumbnail::capture(x, y);
+        self.set_status(t!("✅ 坐标捕捉成功！已设置为: ({}, {}) [使用{}捕捉]", x, y, method));
+        if let Some(reason) = secure_desktop::target_blocked(x, y) {
+            self.set_status(t!("⚠️ 坐标已设置为 ({}, {})，但{}", x, y, reason));
+        }
+        self.state.transition(AppState::Picking, AppState::Idle);
+    }
 
-                let result = match click_type {
-                    ClickType::Left => controller.click_left(),
-                    ClickType::Right => controller.click_right(),
-                    ClickType::Middle => controller.click_middle(),
-                };
+    fn copy_coordinates(&self, ctx: &egui::Context) {
+        let text = coordinate_text::format(self.x_pos, self.y_pos);
+        ctx.copy_text(text.clone());
+        self.set_status(t!("已复制坐标 {}", text));
+    }
 
-                if result.is_ok() {
-                    if let Ok(mut count) = total_clicks.lock() {
-                        *count += 1;
-                    }
-                }
-            }
+    // 坐标行的复制和粘贴：鼠标在这一行上且没有输入框获得焦点时响应 Ctrl+C 和 Ctrl+V，
+    // 点过粘贴按钮后接收随后送到的剪贴板内容
+    fn coordinate_clipboard(&mut self, ctx: &egui::Context, hovered: bool) {
+        let hovered = hovered && !ctx.wants_keyboard_input();
+        let (copy, pasted) = ctx.input(|input| {
+            let copy = input.events.iter().any(|event| matches!(event, egui::Event::Copy));
+            let pasted = input.events.iter().find_map(|event| match event {
+                egui::Event::Paste(text) => Some(text.clone()),
+                _ => None,
+            });
+            (copy, pasted)
         });
+        if copy && hovered {
+            self.copy_coordinates(ctx);
+        }
+        // 剪贴板为空时不会送来内容，过几帧后不再等待，免得之后在别处的粘贴被当成坐标
+        let requested = self.coordinate_paste_requested.is_some_and(|pass| ctx.cumulative_pass_nr() <= pass + 3);
+        if !requested {
+            self.coordinate_paste_requested = None;
+        }
+        let Some(text) = pasted.filter(|_| requested || hovered) else {
+            return;
+        };
+        self.coordinate_paste_requested = None;
+        match coordinate_text::parse(&text) {
+            Ok((x, y)) => {
+                self.x_pos = x;
+                self.y_pos = y;
+                bookmarks::push_recent(&mut self.recent_coordinates, x, y);
+                self.set_status(t!("已粘贴坐标 ({}, {})", x, y));
+            }
+            Err(e) => self.set_status(t!("无法粘贴坐标: {}", e)),
+        }
     }
 
-    fn start_auto_clicking(&mut self) {
-        if *self.is_clicking.lock().unwrap() {
+    fn pick_color_by_hotkey(&mut self, ctx: &egui::Context) {
+        let Some((x, y)) = self.engine.get_mouse_position().ok() else {
             return;
+        };
+        match color_picker::pick(x, y) {
+            Some(picked) => {
+                ctx.copy_text(picked.hex());
+                self.set_status(t!("已复制 ({}, {}) 处的颜色 {}", x, y, picked.hex()));
+                color_picker::push_history(&mut self.picked_colors, picked);
+            }
+            None => {
+                self.set_status(t!("无法读取 ({}, {}) 处的颜色", x, y));
+            }
         }
+    }
 
-        *self.is_clicking.lock().unwrap() = true;
-        self.status_message = "自动点击中...".to_string();
-
-        let is_clicking = self.is_clicking.clone();
-        let total_clicks = self.total_clicks.clone();
-        let mouse_controller = self.mouse_controller.clone();
-        let x = self.x_pos;
-        let y = self.y_pos;
-        let interval = self.click_interval;
-        let max_clicks = self.click_count;
-        let click_type = self.click_type;
+    fn get_current_mouse_pos(&mut self) {
+        if let Ok((x, y)) = self.engine.get_mouse_position() {
+            self.x_pos = x;
+            self.y_pos = y;
+            self.set_status(t!("已获取当前鼠标位置: ({}, {})", x, y));
+            bookmarks::push_recent(&mut self.recent_coordinates, x, y);
+        }
+    }
 
-        thread::spawn(move || {
-            let mut clicks_performed = 0;
+    // 按名称找到所选的显示器，作为坐标原点；显示器已断开时退回全局坐标
+    fn select_display(&mut self) {
+        let selected = self.display_name.as_ref().map(|name| self.displays.iter().find(|d| &d.name == name).cloned());
+        if let Some(None) = selected {
+            let name = self.display_name.clone().unwrap_or_default();
+            self.set_status(t!("找不到显示器 {}，暂时使用全局坐标", name));
+        }
+        display::select(selected.flatten());
+        // 叠加层按所选显示器的尺寸重新读取
+        self.trail_screen_size = None;
+    }
 
-            while *is_clicking.lock().unwrap() && clicks_performed < max_clicks {
-                if let Ok(mut controller) = mouse_controller.lock() {
-                    let _ = controller.move_mouse_to(x, y);
-                    thread::sleep(Duration::from_millis(10));
+    fn display_ui(&mut self, ui: &mut egui::Ui) {
+        let selected_text = match &self.display_name {
+            Some(name) => self.displays.iter().find(|d| &d.name == name).map_or(name.clone(), |d| d.label()),
+            None => t!("全局坐标（原点在主显示器左上角）").to_string(),
+        };
+        let before = self.display_name.clone();
+        ui.label(t!("坐标相对于:"));
+        egui::ComboBox::from_id_salt("display")
+            .selected_text(selected_text)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.display_name, None, t!("全局坐标（原点在主显示器左上角）"));
+                for display in &self.displays {
+                    ui.selectable_value(&mut self.display_name, Some(display.name.clone()), display.label());
+                }
+            });
+        let hover = match display::virtual_desktop(&self.displays) {
+            Some((x, y, width, height)) => format!(
+                "{}\n{}",
+                t!("重新检测显示器"),
+                t!("虚拟桌面 {}x{}，左上角 ({}, {})，主显示器左边或上方的坐标为负数", width, height, x, y)
+            ),
+            None => t!("重新检测显示器").to_string(),
+        };
+        if ui.button("🔄").on_hover_text(hover).clicked() {
+            self.displays = display::enumerate();
+            self.select_display();
+        }
+        if self.display_name != before {
+            self.select_display();
+        }
+    }
 
-                    let result = match click_type {
-                        ClickType::Left => controller.click_left(),
-                        ClickType::Right => controller.click_right(),
-                        ClickType::Middle => controller.click_middle(),
-                    };
+    // 实时的按钮数组和光标位置，以及按帧记录的输入事件
+    fn input_inspector_ui(&mut self, ui: &mut egui::Ui) {
+        let Ok((buttons, keys, (x, y))) = self
+            .engine
+            .call(|c| (c.get_mouse_button_states(), c.pressed_keys(), c.get_mouse_position()))
+        else {
+            return;
+        };
+        self.input_inspector.poll(&buttons, &keys, (x, y), self.button_mapping);
+        ui.ctx().request_repaint();
 
-                    if result.is_ok() {
-                        clicks_performed += 1;
-                        if let Ok(mut count) = total_clicks.lock() {
-                            *count += 1;
-                        }
-                    }
+        ui.monospace(t!("按钮数组: {}  光标: ({}, {})", format!("{:?}", buttons), x, y));
+        ui.horizontal(|ui| {
+            for button in button_map::CalibrationButton::ORDER {
+                let text = t!("{}（索引{}）", button.label(), self.button_mapping.index(button));
+                if self.button_mapping.is_pressed(&buttons, button) {
+                    ui.colored_label(egui::Color32::GREEN, text);
+                } else {
+                    ui.weak(text);
                 }
-
-                thread::sleep(Duration::from_secs_f64(interval));
             }
-
-            *is_clicking.lock().unwrap() = false;
         });
-    }
-
-    fn stop_clicking(&mut self) {
-        *self.is_clicking.lock().unwrap() = false;
-        self.status_message = "已停止".to_string();
-    }
-}
-
-impl eframe::App for MouseClickerApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        // 检查是否在拾取坐标模式
-        self.check_position_picking();
 
-        egui::CentralPanel::default().show(ctx, |ui| {
-            ui.heading("🖱️ 跨平台鼠标点击工具");
+        ui.horizontal(|ui| {
+            ui.label(t!("显示:"));
+            for kind in input_inspector::EventKind::ALL {
+                ui.checkbox(&mut self.input_inspector.show[kind as usize], kind.label());
+            }
             ui.separator();
-
-            // 如果在捕捉模式，添加醒目的提示框
-            if self.is_picking_position {
-                ui.allocate_ui_with_layout(
-                    [ui.available_width(), 60.0].into(),
-                    egui::Layout::top_down(egui::Align::Center),
-                    |ui| {
-                        ui.add_space(10.0);
-                        ui.colored_label(egui::Color32::from_rgb(255, 165, 0), "🎯 坐标捕捉模式激活中");
-                        ui.colored_label(egui::Color32::LIGHT_RED, "请在屏幕任意位置点击鼠标中键（滚轮键）来捕捉坐标");
-                        ui.add_space(10.0);
-                    }
-                );
-                ui.separator();
+            ui.toggle_value(&mut self.input_inspector.paused, t!("⏸ 暂停"));
+            if ui.button(t!("清空")).clicked() {
+                self.input_inspector.clear();
+            }
+            if ui.button(t!("复制")).clicked() {
+                ui.ctx().copy_text(self.input_inspector.to_text());
             }
+        });
+        egui::ScrollArea::vertical()
+            .id_salt("input_inspector")
+            .max_height(200.0)
+            .stick_to_bottom(true)
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                for event in self.input_inspector.visible() {
+                    ui.monospace(format!("{:>9.3} [{}] {}", event.secs, event.kind.label(), event.text));
+                }
+            });
+    }
 
-            // 坐标设置
+    // 依次按下左、右、中键，记录每个按钮在状态数组中的下标
+    fn button_calibration_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(calibration) = &mut self.button_calibration else {
+            let mapping = self.button_mapping;
+            ui.label(t!(
+                "按钮映射: 左键 = 索引{}，右键 = 索引{}，中键 = 索引{}",
+                mapping.left,
+                mapping.right,
+                mapping.middle
+            ));
             ui.horizontal(|ui| {
-                ui.label("点击坐标:");
-
-                // 在捕捉模式下高亮显示坐标输入框
-                if self.is_picking_position {
-                    ui.style_mut().visuals.extreme_bg_color = egui::Color32::from_rgb(255, 255, 200);
+                if ui.button(t!("校准按钮映射")).on_hover_text(t!("捕捉坐标用的按钮不起作用时，重新识别各个按钮")).clicked() {
+                    self.button_calibration = Some(button_map::Calibration::new());
                 }
-
-                ui.add(egui::DragValue::new(&mut self.x_pos).prefix("X: "));
-                ui.add(egui::DragValue::new(&mut self.y_pos).prefix("Y: "));
-
-                if self.is_picking_position {
-                    ui.label("👈 坐标将自动填入这里");
+                if mapping != button_map::ButtonMapping::default() && ui.button(t!("恢复默认")).clicked() {
+                    self.set_button_mapping(button_map::ButtonMapping::default());
                 }
             });
+            return;
+        };
 
-            ui.horizontal(|ui| {
-                if !self.is_picking_position {
-                    if ui.button("捕捉坐标").clicked() {
-                        self.start_position_picking();
-                    }
-                    if ui.button("获取当前位置").clicked() {
-                        self.get_current_mouse_pos();
-                    }
-                    if ui.button("获取屏幕信息").clicked() {
-                        self.get_screen_info();
-                    }
-                } else {
-                    let button_name = match self.capture_button_type {
-                        CaptureButtonType::MiddleButton => "中键",
-                        CaptureButtonType::RightButton => "右键",
+        let Ok(buttons) = self.engine.get_mouse_button_states() else {
+            return;
+        };
+        let finished = calibration.poll(&buttons);
+        ui.ctx().request_repaint();
+        for (button, index) in calibration.found() {
+            ui.label(t!("{}: 索引{}", button.label(), index));
+        }
+        if let Some(button) = calibration.current() {
+            ui.colored_label(egui::Color32::YELLOW, t!("请在空白处按一下鼠标{}", button.label()));
+        }
+        let cancel = ui.button(t!("取消校准")).clicked();
+        if let Some(mapping) = finished {
+            self.button_calibration = None;
+            self.set_button_mapping(mapping);
+            self.set_status(t!(
+                "按钮映射已校准: 左键 = 索引{}，右键 = 索引{}，中键 = 索引{}",
+                mapping.left,
+                mapping.right,
+                mapping.middle
+            ));
+        } else if cancel {
+            self.button_calibration = None;
+        }
+    }
+
+    fn set_button_mapping(&mut self, mapping: button_map::ButtonMapping) {
+        self.engine.set_button_mapping(mapping);
+        self.button_mapping = mapping;
+    }
+
+    // 按选择的后端重新创建注入输入的设备，失败时记下原因并继续使用默认后端
+    fn apply_input_backend(&mut self) {
+        self.input_backend_error = match self.engine.set_backend(self.input_backend) {
+            Ok(()) => None,
+            Err(error::InputError::Backend { reason, .. }) => Some(reason),
+            Err(error) => Some(error.to_string()),
+        };
+        if let Some(error) = &self.input_backend_error {
+            self.set_status(t!("⚠️ 无法使用 {} 后端，继续使用 enigo: {}", self.input_backend.label(), error));
+        }
+    }
+
+    // 安装系统鼠标钩子；macOS 上要先获得「输入监控」权限，因此获得权限后会再次尝试
+    fn start_input_hook(&mut self, ctx: &egui::Context) {
+        match input_hook::InputHook::start(ctx.clone()) {
+            Ok(hook) => {
+                self.input_hook = Some(hook);
+                self.input_hook_error = None;
+            }
+            Err(error) => {
+                tracing::warn!(%error, "无法安装系统鼠标钩子，改为逐帧查询按钮状态");
+                self.input_hook_error = Some(error);
+            }
+        }
+    }
+
+    fn input_hook_ui(&mut self, ui: &mut egui::Ui) {
+        match &self.input_hook_error {
+            None => {
+                ui.label(t!("鼠标按钮检测: 系统钩子"));
+            }
+            Some(error) => {
+                ui.label(t!("鼠标按钮检测: 逐帧查询（可能漏掉很快的点击）"));
+                ui.colored_label(egui::Color32::from_rgb(255, 165, 0), format!("⚠️ {}", error));
+                if ui.button(t!("🔄 重试")).clicked() {
+                    let ctx = ui.ctx().clone();
+                    self.start_input_hook(&ctx);
+                }
+            }
+        }
+    }
+
+    fn input_backend_ui(&mut self, ui: &mut egui::Ui) {
+        // 只有一种后端的平台不显示
+        if backend::Backend::available().len() < 2 {
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.label(t!("输入后端:"));
+            // 执行中切换会丢掉按住的输入
+            ui.add_enabled_ui(!self.state.is_running(), |ui| {
+                egui::ComboBox::from_id_salt("input_backend")
+                    .selected_text(self.input_backend.label())
+                    .show_ui(ui, |ui| {
+                        for &backend in backend::Backend::available() {
+                            if ui.selectable_value(&mut self.input_backend, backend, backend.label())
+                                .on_hover_text(backend.description())
+                                .changed()
+                            {
+                                self.apply_input_backend();
+                            }
+                        }
+                    });
+            });
+        });
+        ui.label(egui::RichText::new(self.input_backend.description()).small().weak());
+        if self.input_backend == backend::Backend::Interception {
+            ui.colored_label(egui::Color32::RED, format!("⚠️ {}", i18n::tr(interception::WARNING)));
+        }
+        let Some(error) = &self.input_backend_error else {
+            return;
+        };
+        ui.colored_label(egui::Color32::from_rgb(255, 165, 0), format!("⚠️ {}", error));
+        if self.input_backend == backend::Backend::Interception {
+            ui.label(i18n::tr(interception::INSTALL_HINT));
+        }
+        if self.input_backend == backend::Backend::Uinput {
+            ui.label(t!("在终端中执行以下命令允许当前用户使用 uinput，然后注销并重新登录:"));
+            ui.code(uinput::SETUP_COMMANDS);
+        }
+        ui.horizontal(|ui| {
+            if self.input_backend == backend::Backend::Uinput && ui.button(t!("📋 复制命令")).clicked() {
+                ui.ctx().copy_text(uinput::SETUP_COMMANDS.to_string());
+            }
+            if ui.button(t!("🔄 重试")).clicked() {
+                self.apply_input_backend();
+            }
+        });
+    }
+
+    fn get_screen_info(&mut self) {
+        match self.engine.get_screen_size() {
+            Ok((width, height)) => match display::virtual_desktop(&self.displays).filter(|_| self.displays.len() > 1) {
+                Some((x, y, desktop_width, desktop_height)) => self.set_status(t!(
+                    "屏幕尺寸: {}x{}，虚拟桌面: {}x{}，左上角 ({}, {})",
+                    width, height, desktop_width, desktop_height, x, y
+                )),
+                None => self.set_status(t!("屏幕尺寸: {}x{}", width, height)),
+            },
+            Err(e) => {
+                self.set_status(t!("获取屏幕信息失败: {}", e));
+            }
+        }
+    }
+
+    fn perform_single_click(&mut self) {
+        let x = self.x_pos;
+        let y = self.y_pos;
+        bookmarks::push_recent(&mut self.recent_coordinates, x, y);
+        let click_type = self.click_type;
+        let multiplicity = self.click_multiplicity;
+        let gap = multi_click_gap(self.multi_click_gap_ms);
+        let move_mode = self.move_mode;
+        let smooth_move_ms = self.smooth_move_ms;
+        let total_clicks = self.total_clicks.clone();
+        let engine = self.engine.clone();
+        let click_sound = self.sound.on_click;
+        let log = self.event_log.clone();
+        let heatmap = self.heatmap.clone();
+
+        let info = history::RunInfo {
+            kind: history::RunKind::SingleClick,
+            planned_clicks: 1,
+            smooth_move: move_mode == MoveMode::Smooth,
+            hold_key: false,
+            speed: 1.0,
+        };
+
+        self.spawn_worker(move || {
+            let started = SystemTime::now();
+            let mut report = sequence::RunReport::default();
+
+            let moved = move_to_target(&engine, x, y, move_mode, smooth_move_ms);
+            thread::sleep(Duration::from_millis(50));
+
+            let result = click_times(&engine, click_type, multiplicity, gap);
+
+            let clicked = moved.is_ok() && result.is_ok();
+            report.record(x, y, clicked);
+            events::emit(events::Event::Click { x, y, ok: clicked });
+            let text = match moved.and(result.clone()) {
+                Ok(()) => t!("点击 ({}, {})", x, y),
+                Err(error) => t!("点击 ({}, {}) 失败: {}", x, y, error),
+            };
+            log.lock().unwrap().push(event_log::EntryKind::Click, text);
+            if clicked {
+                heatmap.lock().unwrap().push(x, y);
+            }
+            if result.is_ok() {
+                if let Ok(mut count) = total_clicks.lock() {
+                    *count += 1;
+                }
+                if click_sound {
+                    sound::play(sound::Sound::Click);
+                }
+            }
+
+            let _ = history::append(&history::RunRecord::new(&info, started, report));
+        });
+    }
+
+    // 只有空闲时才能开始执行，无法开始时在状态栏说明原因
+    fn ready_to_start(&self) -> bool {
+        match self.state.get() {
+            AppState::Idle => true,
+            AppState::Picking => {
+                self.set_status(t!("正在捕捉坐标，请先完成或取消捕捉").to_string());
+                false
+            }
+            AppState::Stopping => {
+                self.set_status(t!("正在等待上一次执行停止，请稍后再试").to_string());
+                false
+            }
+            AppState::Countdown | AppState::Running | AppState::Paused => false,
+        }
+    }
+
+    fn start_auto_clicking(&mut self) {
+        if !self.ready_to_start() {
+            return;
+        }
+        if self.confirm_extreme_runs && !self.simulate {
+            let rate = self.build_sequence().estimated_rate(self.playback_speed);
+            if rate.is_some_and(|rate| rate > CONFIRM_RATE_LIMIT) || self.click_count > CONFIRM_COUNT_LIMIT {
+                self.pending_confirmation = Some(RunConfirmation { kind: self.run_kind(), clicks: self.click_count, rate });
+                return;
+            }
+        }
+        self.run_auto_clicking();
+    }
+
+    fn run_kind(&self) -> history::RunKind {
+        match self.auto_click_mode {
+            AutoClickMode::SinglePoint => history::RunKind::SinglePoint,
+            AutoClickMode::TwoPoint => history::RunKind::TwoPoint,
+            AutoClickMode::Sequence => history::RunKind::Sequence,
+        }
+    }
+
+    // 不经确认直接开始
+    fn run_auto_clicking(&mut self) {
+        let sequence = self.build_sequence();
+        if self.start_run(sequence, self.click_count, self.run_kind(), None) && self.auto_click_mode == AutoClickMode::SinglePoint {
+            bookmarks::push_recent(&mut self.recent_coordinates, self.x_pos, self.y_pos);
+        }
+    }
+
+    // 在工作线程中执行序列，`resume` 为 Some 时从上次中断的位置继续。无法开始时返回 false
+    fn start_run(
+        &mut self,
+        sequence: sequence::Sequence,
+        max_clicks: u32,
+        kind: history::RunKind,
+        resume: Option<sequence::RunPosition>,
+    ) -> bool {
+        if !self.ready_to_start() {
+            return false;
+        }
+        if let Err(e) = sequence.compile() {
+            self.set_status(t!("无法开始: {}", e));
+            return false;
+        }
+        if self.adaptive_interval.enabled && !feedback::AdaptiveInterval::is_supported() {
+            self.set_status(t!("无法开始: {}", i18n::tr(feedback::UNSUPPORTED_MESSAGE)));
+            return false;
+        }
+        self.preflight_issues = secure_desktop::preflight(&sequence);
+        self.preflight_issues.extend(display::preflight(&sequence));
+        if let Some((i, reason)) = self.preflight_issues.first() {
+            self.set_status(t!("无法开始: 步骤 {}: {}", i + 1, reason));
+            return false;
+        }
+        if self.simulate {
+            self.start_simulation(sequence, max_clicks, resume);
+            return true;
+        }
+
+        let countdown = Duration::from_secs(self.start_delay_secs as u64);
+        if countdown.is_zero() {
+            self.state.set(AppState::Running);
+            self.set_status(t!("自动点击中...").to_string());
+        } else {
+            self.state.set(AppState::Countdown);
+            self.set_status(t!("{} 秒后开始自动点击...", self.start_delay_secs));
+        }
+        self.pending_hide_to_tray = self.hide_to_tray_while_running;
+        self.timing.lock().unwrap().reset();
+        self.current_run = Some(RunProgress {
+            // 倒计时结束后才开始计时
+            started: Instant::now() + countdown,
+            clicks_before: *self.total_clicks.lock().unwrap(),
+            remaining: max_clicks.saturating_sub(resume.as_ref().map_or(0, |position| position.clicks)),
+            finished: None,
+        });
+
+        let state = self.state.clone();
+        let total_clicks = self.total_clicks.clone();
+        let engine = self.engine.clone();
+        let checkpoint = recovery::Checkpointer::new(&self.profiles.active().name, kind, max_clicks, sequence.clone());
+        let options = sequence::RunOptions {
+            max_clicks,
+            move_mode: self.move_mode,
+            smooth_move_ms: self.smooth_move_ms,
+            hold_key: self.hold_key_enabled.then(|| self.hold_key.to_key()),
+            speed: self.playback_speed,
+            multi_click_gap: multi_click_gap(self.multi_click_gap_ms),
+            adaptive: self.adaptive_interval.enabled.then(|| self.adaptive_interval.clone()),
+            power_guard: self.power_guard.is_enabled().then(|| (self.power_guard.clone(), self.power_pause.clone())),
+            resume,
+            timing: Some(self.timing.clone()),
+            observer: Some(Arc::new(RunFeedback {
+                log: self.event_log.clone(),
+                trail: self.show_trail.then(|| self.trail.clone()),
+                heatmap: self.heatmap.clone(),
+                click_sound: self.sound.on_click,
+                checkpoint,
+            })),
+            focus_window: self.focus_window.clone().map(|window| (window, self.focus_mode)),
+            keep_awake: self.keep_awake,
+            plugins: true,
+            events: true,
+            screen: true,
+        };
+        let info = history::RunInfo {
+            kind,
+            planned_clicks: options.max_clicks,
+            smooth_move: options.move_mode == MoveMode::Smooth,
+            hold_key: options.hold_key.is_some(),
+            speed: options.speed,
+        };
+
+        let log = self.event_log.clone();
+        let end_action = self.end_action.clone();
+        let finish_sound = self.sound.on_finish;
+        let notify_on_finish = self.notify_on_finish;
+
+        self.spawn_worker(move || {
+            if !state.wait_countdown(countdown) {
+                // 倒计时中被停止，还没有点击过
+                state.set(AppState::Idle);
+                return;
+            }
+            let started = SystemTime::now();
+            let report = sequence::run(&sequence, &options, &engine, &state, &total_clicks);
+            state.set(AppState::Idle);
+            // 执行已经正常结束，不需要恢复
+            recovery::clear();
+            if finish_sound && !report.stopped {
+                sound::play(sound::Sound::Finish);
+            }
+            if notify_on_finish && !report.stopped {
+                let body = match &report.aborted {
+                    Some(reason) => t!("自动点击已中止（{}），共点击 {} 次", reason, report.clicks_performed),
+                    None => t!("自动点击完成，共点击 {} 次", report.clicks_performed),
+                };
+                notification::send(i18n::tr(paths::APP_NAME), &body);
+            }
+            let status = |text: String| log.lock().unwrap().push(event_log::EntryKind::Status, text);
+            if let Some(reason) = &report.aborted {
+                status(t!("⚠️ 自动点击已中止: {}", reason));
+            } else if report.stopped {
+                // 手动停止时界面已经记录过
+            } else if end_action.is_none() {
+                status(t!("自动点击完成，共点击 {} 次", report.clicks_performed));
+            } else {
+                status(match end_actions::perform(&end_action) {
+                    Ok(()) => t!("自动点击完成，已{}", end_action.describe()),
+                    Err(e) => t!("⚠️ 自动点击完成，但{}失败: {}", end_action.describe(), e),
+                });
+            }
+            if report.adaptive_timeouts > 0 {
+                status(t!(
+                    "⚠️ 反馈区域有 {} 次在最长等待时间内没有变化，请检查区域位置是否正确",
+                    report.adaptive_timeouts
+                ));
+            }
+            let _ = history::append(&history::RunRecord::new(&info, started, report));
+        });
+        true
+    }
+
+    // 在工作线程中用模拟后端执行序列，不倒计时，也不记录历史、不执行结束动作
+    fn start_simulation(&mut self, sequence: sequence::Sequence, max_clicks: u32, resume: Option<sequence::RunPosition>) {
+        self.state.set(AppState::Running);
+        self.set_status(t!("正在模拟执行...").to_string());
+
+        let state = self.state.clone();
+        let start = self.engine.get_mouse_position().unwrap_or_default();
+        let result = self.simulation.clone();
+        let log = self.event_log.clone();
+        let options = sequence::RunOptions {
+            max_clicks: max_clicks.saturating_sub(resume.map_or(0, |position| position.clicks)),
+            move_mode: self.move_mode,
+            smooth_move_ms: self.smooth_move_ms,
+            hold_key: self.hold_key_enabled.then(|| self.hold_key.to_key()),
+            speed: self.playback_speed,
+            multi_click_gap: multi_click_gap(self.multi_click_gap_ms),
+            adaptive: None,
+            power_guard: None,
+            resume: None,
+            timing: None,
+            observer: None,
+            focus_window: None,
+            keep_awake: false,
+            plugins: false,
+            events: false,
+            screen: false,
+        };
+
+        self.spawn_worker(move || {
+            let simulation = simulation::simulate(&sequence, options, start, &state);
+            state.set(AppState::Idle);
+            log.lock().unwrap().push(
+                event_log::EntryKind::Status,
+                t!(
+                    "模拟执行完成: {} 次点击，{} 个动作，真实执行约需 {}",
+                    simulation.report.clicks_performed,
+                    simulation.actions.len(),
+                    history::format_duration(simulation.duration.as_secs_f64())
+                ),
+            );
+            *result.lock().unwrap() = Some(simulation);
+        });
+    }
+
+    fn simulation_window(&mut self, ctx: &egui::Context) {
+        let mut result = self.simulation.lock().unwrap();
+        let Some(simulation) = result.as_ref() else {
+            return;
+        };
+        let mut open = true;
+
+        egui::Window::new(t!("🧪 模拟执行结果"))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                let report = &simulation.report;
+                ui.label(t!("点击 {} 次，失败 {} 次", report.clicks_performed, report.errors));
+                ui.label(t!(
+                    "共 {} 个动作，真实执行约需 {}",
+                    simulation.actions.len(),
+                    history::format_duration(simulation.duration.as_secs_f64())
+                ));
+                if let Some(reason) = &report.aborted {
+                    ui.colored_label(egui::Color32::LIGHT_RED, t!("⚠️ 执行会中止: {}", reason));
+                }
+                if simulation.truncated {
+                    ui.colored_label(
+                        egui::Color32::LIGHT_RED,
+                        t!("⚠️ 动作超过 {} 个，只模拟了前面的部分", simulation::MAX_ACTIONS),
+                    );
+                } else if report.stopped {
+                    ui.colored_label(egui::Color32::YELLOW, t!("模拟被手动停止，结果不完整"));
+                }
+                if self.focus_window.is_some() {
+                    ui.weak(t!("模拟时没有切换目标窗口"));
+                }
+                if self.adaptive_interval.enabled {
+                    ui.weak(t!("模拟时自适应间隔按固定的停留时间等待"));
+                }
+                if !self.end_action.is_none() {
+                    ui.weak(t!("真实执行完成后还会{}", self.end_action.describe()));
+                }
+                ui.separator();
+
+                let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+                egui::ScrollArea::vertical().max_height(320.0).auto_shrink([false, true]).show_rows(
+                    ui,
+                    row_height,
+                    simulation.actions.len(),
+                    |ui, rows| {
+                        for recorded in &simulation.actions[rows] {
+                            ui.monospace(format!(
+                                "{:>10.3}s  {}",
+                                recorded.at.as_secs_f64(),
+                                recorded.action.describe()
+                            ));
+                        }
+                    },
+                );
+            });
+
+        if !open {
+            *result = None;
+        }
+    }
+
+    fn errors_window(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        let mut errors = self.engine.errors().lock().unwrap();
+        // 面板打开期间发生的失败都算已看到
+        errors.mark_seen();
+
+        egui::Window::new(t!("⛔ 输入错误"))
+            .open(&mut open)
+            .default_width(460.0)
+            .show(ctx, |ui| {
+                ui.label(t!("当前输入后端: {}", self.input_backend.label()));
+                ui.horizontal(|ui| {
+                    if ui.button(t!("复制到剪贴板")).clicked() {
+                        ui.ctx().copy_text(errors.to_text());
+                    }
+                    if ui.button(t!("清空")).clicked() {
+                        errors.clear();
+                    }
+                });
+                ui.separator();
+                egui::ScrollArea::vertical().max_height(300.0).auto_shrink([false, true]).show(ui, |ui| {
+                    if errors.last().is_none() {
+                        ui.weak(t!("没有注入失败"));
+                    }
+                    // 最新的在最上面
+                    for record in errors.records().rev() {
+                        ui.horizontal_wrapped(|ui| {
+                            ui.weak(&record.time);
+                            if record.count > 1 {
+                                ui.weak(format!("×{}", record.count));
+                            }
+                            ui.colored_label(egui::Color32::LIGHT_RED, record.error.to_string());
+                        });
+                    }
+                });
+                ui.separator();
+                ui.weak(t!("目标程序收不到输入时，可以在「平台信息」中换一个输入后端，或以管理员身份运行"));
+            });
+
+        if !open {
+            self.show_errors = false;
+        }
+    }
+
+    fn run_confirmation_window(&mut self, ctx: &egui::Context) {
+        if self.pending_confirmation.is_none() {
+            return;
+        }
+        // 从托盘或迷你模式开始时先把窗口恢复出来，否则看不到确认框
+        if self.hidden_to_tray {
+            self.show_from_tray(ctx);
+        }
+        if self.compact_mode {
+            self.set_compact_mode(ctx, false);
+        }
+        let Some(confirmation) = &self.pending_confirmation else {
+            return;
+        };
+        let mut confirm = false;
+        let mut cancel = false;
+        let stop_hotkey = self.hotkeys.iter().find(|binding| binding.action == hotkey::HotkeyAction::ToggleClicking);
+
+        egui::Window::new(t!("⚠️ 确认开始自动点击"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(t!("模式: {}", confirmation.kind.label()));
+                ui.label(t!("点击次数: {}", confirmation.clicks));
+                if let Some(rate) = confirmation.rate {
+                    ui.label(t!("估算速率: 约每秒 {} 次", format!("{:.1}", rate)));
+                    if rate > CONFIRM_RATE_LIMIT {
+                        ui.colored_label(
+                            egui::Color32::LIGHT_RED,
+                            t!("速率超过每秒 {} 次，目标程序或系统可能来不及响应", CONFIRM_RATE_LIMIT),
+                        );
+                    }
+                }
+                if confirmation.clicks > CONFIRM_COUNT_LIMIT {
+                    ui.colored_label(egui::Color32::LIGHT_RED, t!("点击次数超过 {} 次", CONFIRM_COUNT_LIMIT));
+                }
+                if let Some(binding) = stop_hotkey {
+                    ui.weak(t!("执行中可以随时按 {} 停止", binding.hotkey.display()));
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    confirm = ui.button(t!("确认开始")).clicked();
+                    cancel = ui.button(t!("取消")).clicked();
+                });
+            });
+
+        if confirm {
+            self.pending_confirmation = None;
+            self.run_auto_clicking();
+        } else if cancel {
+            self.pending_confirmation = None;
+            self.set_status(t!("已取消自动点击").to_string());
+        }
+    }
+
+    // 执行中关闭窗口（包括托盘菜单的退出）时先取消关闭，询问是否停止执行并退出
+    fn check_close_request(&mut self, ctx: &egui::Context) {
+        if ctx.input(|i| i.viewport().close_requested()) && self.state.is_running() && !self.close_confirmed {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            self.confirm_close = true;
+        }
+    }
+
+    fn close_confirmation_window(&mut self, ctx: &egui::Context) {
+        if !self.confirm_close {
+            return;
+        }
+        // 询问期间执行已经结束时不需要再确认
+        if !self.state.is_running() {
+            self.confirm_close = false;
+            return;
+        }
+        if self.hidden_to_tray {
+            self.show_from_tray(ctx);
+        }
+        if self.compact_mode {
+            self.set_compact_mode(ctx, false);
+        }
+        let mut quit = false;
+        let mut cancel = false;
+        egui::Window::new(t!("⚠️ 确认退出"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(t!("自动点击正在执行，退出前会先停止执行"));
+                ui.separator();
+                ui.horizontal(|ui| {
+                    quit = ui.button(t!("停止并退出")).clicked();
+                    cancel = ui.button(t!("取消")).clicked();
+                });
+            });
+
+        if quit {
+            self.confirm_close = false;
+            self.close_confirmed = true;
+            self.stop_clicking();
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+        } else if cancel {
+            self.confirm_close = false;
+        }
+    }
+
+    // 缺少权限时每秒重新检测一次，用户在系统设置中勾选后不需要重启就能继续
+    fn check_permissions(&mut self, ctx: &egui::Context) {
+        if self.missing_permissions.is_empty() {
+            return;
+        }
+        ctx.request_repaint_after(PERMISSION_RECHECK);
+        if self.permissions_checked.elapsed() < PERMISSION_RECHECK {
+            return;
+        }
+        self.permissions_checked = Instant::now();
+        self.missing_permissions.retain(|permission| !permission.is_granted());
+        if self.missing_permissions.is_empty() {
+            self.show_permissions = false;
+            self.set_status(t!("✅ 已获得所需的 macOS 权限"));
+            if self.input_hook.is_none() {
+                self.start_input_hook(ctx);
+            }
+        }
+    }
+
+    // 目标窗口以管理员权限运行时，普通权限的本工具发出的点击会被系统丢弃（UIPI），只有 Windows 上可能发生
+    fn check_elevation(&mut self) {
+        if self.elevation_checked.elapsed() < ELEVATION_RECHECK {
+            return;
+        }
+        self.elevation_checked = Instant::now();
+        self.elevated_target = secure_desktop::target_blocked(self.x_pos, self.y_pos).is_some();
+    }
+
+    // 以管理员身份启动新的实例并关闭当前实例，关闭时照常保存设置
+    fn restart_elevated(&mut self, ctx: &egui::Context) {
+        if self.state.is_running() {
+            self.stop_clicking();
+        }
+        match secure_desktop::relaunch_elevated() {
+            Ok(()) => ctx.send_viewport_cmd(egui::ViewportCommand::Close),
+            Err(error) => self.set_status(format!("⚠️ {}", error)),
+        }
+    }
+
+    fn permissions_window(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        let mut dismissed = false;
+        egui::Window::new(t!("🔐 需要 macOS 权限"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(t!("macOS 会静默丢弃没有权限的程序模拟的输入。请在系统设置中允许本工具:"));
+                ui.add_space(4.0);
+                for permission in permissions::Permission::ALL {
+                    let granted = !self.missing_permissions.contains(&permission);
+                    ui.horizontal(|ui| {
+                        if granted {
+                            ui.colored_label(egui::Color32::GREEN, format!("✅ {}", permission.label()));
+                        } else {
+                            ui.colored_label(egui::Color32::LIGHT_RED, format!("❌ {}", permission.label()));
+                        }
+                        ui.label(egui::RichText::new(permission.purpose()).weak());
+                        if !granted && ui.button(t!("打开系统设置")).clicked() {
+                            // 先请求一次，让本程序出现在设置的列表里
+                            permission.request();
+                            if let Err(e) = permission.open_settings() {
+                                self.set_status(format!("⚠️ {}", e));
+                            }
+                        }
+                    });
+                }
+                ui.add_space(4.0);
+                ui.label(egui::RichText::new(t!("勾选后这里会自动更新；如果一直没有变化，请退出并重新打开本工具")).small().weak());
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(t!("正在等待授权…"));
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button(t!("稍后")).clicked() {
+                            dismissed = true;
+                        }
+                    });
+                });
+            });
+        if !open || dismissed {
+            self.show_permissions = false;
+        }
+    }
+
+    fn recovery_window(&mut self, ctx: &egui::Context) {
+        let Some(snapshot) = &self.recovery_offer else {
+            return;
+        };
+        let mut resume = false;
+        let mut discard = false;
+
+        egui::Window::new(t!("♻️ 恢复上次的执行"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(t!("上次的自动点击没有正常结束，可能是程序崩溃或被强制关闭。"));
+                ui.label(t!("配置: {}（{}）", snapshot.profile, snapshot.kind.label()));
+                ui.label(t!(
+                    "进度: 已完成 {}/{} 次点击，停在第 {} 个步骤（{}保存）",
+                    snapshot.position.clicks,
+                    snapshot.max_clicks,
+                    snapshot.position.step + 1,
+                    history::format_ago(snapshot.saved_at)
+                ));
+                ui.weak(t!("继续时从该步骤重新开始，最后几秒内的点击可能会重复一次"));
+                ui.separator();
+                ui.horizontal(|ui| {
+                    resume = ui.button(t!("从中断处继续")).clicked();
+                    discard = ui.button(t!("放弃")).clicked();
+                });
+            });
+
+        if resume {
+            let snapshot = snapshot.clone();
+            // 无法开始时保留提示，可以处理问题后再试
+            if self.start_run(snapshot.sequence, snapshot.max_clicks, snapshot.kind, Some(snapshot.position)) {
+                self.recovery_offer = None;
+            }
+        } else if discard {
+            self.recovery_offer = None;
+            recovery::clear();
+        }
+    }
+
+    // 根据当前的自动点击模式生成要执行的序列
+    fn build_sequence(&self) -> sequence::Sequence {
+        match self.auto_click_mode {
+            AutoClickMode::SinglePoint => {
+                let mut sequence =
+                    sequence::Sequence::single_point(self.x_pos, self.y_pos, self.click_type, self.click_interval);
+                sequence.steps[0].multi_click = self.click_multiplicity;
+                sequence
+            }
+            AutoClickMode::TwoPoint => {
+                let mut a = self.point_a.clone();
+                let mut b = self.point_b.clone();
+                a.click_type = self.click_type;
+                b.click_type = self.click_type;
+                a.multi_click = self.click_multiplicity;
+                b.multi_click = self.click_multiplicity;
+                sequence::Sequence::two_point(a, b)
+            }
+            AutoClickMode::Sequence => self.custom_sequence.clone(),
+        }
+    }
+
+    fn open_macro(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter(t!("宏文件"), &["json", "toml"])
+            .add_filter(t!("AutoHotkey 脚本"), &["ahk"])
+            .pick_file()
+        else {
+            return;
+        };
+
+        match import::load_file(&path) {
+            Ok(imported) => {
+                let skipped = if imported.skipped_lines > 0 {
+                    t!("，跳过 {} 行无法识别的内容", imported.skipped_lines)
+                } else {
+                    String::new()
+                };
+                self.set_status(t!("已导入 {} 个步骤{}", imported.sequence.steps.len(), skipped));
+                self.custom_sequence = imported.sequence;
+                self.macro_metadata = imported.metadata;
+                self.auto_click_mode = AutoClickMode::Sequence;
+            }
+            Err(e) => {
+                self.set_status(t!("导入失败: {}", e));
+            }
+        }
+    }
+
+    fn save_macro(&mut self) {
+        let file_name = if self.macro_metadata.name.is_empty() {
+            "macro.json".to_string()
+        } else {
+            format!("{}.json", self.macro_metadata.name)
+        };
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter(t!("JSON 宏文件"), &["json"])
+            .add_filter(t!("TOML 宏文件"), &["toml"])
+            .set_file_name(file_name)
+            .save_file()
+        else {
+            return;
+        };
+
+        let file = macro_file::MacroFile::new(self.macro_metadata.clone(), self.build_sequence());
+        match file.save(&path) {
+            Ok(()) => {
+                self.set_status(t!("宏已保存到: {}", path.display()));
+            }
+            Err(e) => {
+                self.set_status(t!("保存宏失败: {}", e));
+            }
+        }
+    }
+
+    // 按点击图片步骤的设置立即找一次图
+    fn test_image_step(&mut self, index: usize) {
+        let step = &self.custom_sequence.steps[index];
+        let started = Instant::now();
+        if step.kind == sequence::StepKind::ClickAllImages {
+            let result =
+                template::load(std::path::Path::new(&step.image)).and_then(|image| template::find_all(&image, &step.matching));
+            let elapsed = started.elapsed().as_millis();
+            self.set_status(match result {
+                Ok(found) if found.is_empty() => t!("没有找到相似度达到阈值的位置，用时 {} 毫秒", elapsed),
+                Ok(found) => t!("找到 {} 处，用时 {} 毫秒", found.len(), elapsed),
+                Err(e) => t!("找图失败: {}", e),
+            });
+            return;
+        }
+        let result = template::load(std::path::Path::new(&step.image)).and_then(|image| template::find(&image, &step.matching));
+        let elapsed = started.elapsed().as_millis();
+        self.set_status(match result {
+            Ok(Some(found)) => {
+                let (x, y) = found.center();
+                t!("找到图片，中心 ({}, {})，相似度 {}，用时 {} 毫秒", x, y, format!("{:.2}", found.confidence), elapsed)
+            }
+            Ok(None) => t!("没有找到相似度达到阈值的位置，用时 {} 毫秒", elapsed),
+            Err(e) => t!("找图失败: {}", e),
+        });
+    }
+
+    fn sequence_editor_ui(&mut self, ui: &mut egui::Ui) {
+        let depths = self.custom_sequence.depths();
+        let preflight_issues = &self.preflight_issues;
+        let step_count = self.custom_sequence.steps.len();
+        let mut remove = None;
+        let mut move_up = None;
+        let mut duplicate = None;
+        let mut test_image = None;
+        // 拖放：(被拖动的步骤, 放到哪个步骤之前)
+        let mut dropped = None;
+        for (i, step) in self.custom_sequence.steps.iter_mut().enumerate() {
+            let row = ui.horizontal(|ui| {
+                ui.add_space(depths[i] as f32 * 16.0);
+                ui.dnd_drag_source(egui::Id::new(("sequence_step", i)), i, |ui| {
+                    ui.label("☰");
+                })
+                .response
+                .on_hover_cursor(egui::CursorIcon::Grab)
+                .on_hover_text(t!("拖动调整顺序"));
+                ui.label(format!("{}.", i + 1));
+                match step.kind {
+                    sequence::StepKind::Click => {
+                        ui.label("🖱");
+                        ui.add(egui::DragValue::new(&mut step.x).prefix("X: "));
+                        ui.add(egui::DragValue::new(&mut step.y).prefix("Y: "));
+                        egui::ComboBox::from_id_salt(("step_click_type", i))
+                            .selected_text(step.click_type.label())
+                            .show_ui(ui, |ui| {
+                                for click_type in ClickType::ALL {
+                                    ui.selectable_value(&mut step.click_type, click_type, click_type.label());
+                                }
+                            });
+                        egui::ComboBox::from_id_salt(("step_multi_click", i))
+                            .selected_text(step.multi_click.label())
+                            .width(50.0)
+                            .show_ui(ui, |ui| {
+                                for multiplicity in ClickMultiplicity::ALL {
+                                    ui.selectable_value(&mut step.multi_click, multiplicity, multiplicity.label());
+                                }
+                            });
+                        ui.label(t!("停留(秒):"));
+                        ui.add(egui::DragValue::new(&mut step.dwell)
+                            .range(0.0..=60.0)
+                            .speed(0.1));
+                    }
+                    sequence::StepKind::Wait => {
+                        ui.label(t!("⏳ 等待"));
+                        ui.add(egui::DragValue::new(&mut step.dwell)
+                            .range(0.0..=3600.0)
+                            .speed(0.1)
+                            .suffix(t!(" 秒")));
+                    }
+                    sequence::StepKind::Move => {
+                        ui.label(t!("➡ 移动到"));
+                        ui.add(egui::DragValue::new(&mut step.x).prefix("X: "));
+                        ui.add(egui::DragValue::new(&mut step.y).prefix("Y: "));
+                        ui.label(t!("停留(秒):"));
+                        ui.add(egui::DragValue::new(&mut step.dwell)
+                            .range(0.0..=60.0)
+                            .speed(0.1));
+                    }
+                    sequence::StepKind::Scroll => {
+                        ui.label(t!("🔃 滚动"));
+                        ui.add(egui::DragValue::new(&mut step.scroll).range(-100..=100));
+                        ui.label(t!("格（正数向下）停留(秒):"));
+                        ui.add(egui::DragValue::new(&mut step.dwell)
+                            .range(0.0..=60.0)
+                            .speed(0.1));
+                    }
+                    sequence::StepKind::Key => {
+                        ui.label(t!("⌨ 按键"));
+                        let response = ui.add(egui::TextEdit::singleline(&mut step.key)
+                            .hint_text(t!("如 Enter、F5、a"))
+                            .desired_width(70.0));
+                        if sequence::parse_key(&step.key).is_err() {
+                            response.on_hover_text(t!("无法识别的按键"));
+                        }
+                        ui.label(t!("停留(秒):"));
+                        ui.add(egui::DragValue::new(&mut step.dwell)
+                            .range(0.0..=60.0)
+                            .speed(0.1));
+                    }
+                    sequence::StepKind::WaitColor => {
+                        ui.label(t!("🎨 等待颜色"));
+                        ui.add(egui::DragValue::new(&mut step.x).prefix("X: "));
+                        ui.add(egui::DragValue::new(&mut step.y).prefix("Y: "));
+                        egui::color_picker::color_edit_button_srgb(ui, &mut step.color.color)
+                            .on_hover_text(step.color.hex());
+                        if ui.button("💧").on_hover_text(t!("读取该坐标当前的颜色")).clicked()
+                            && let Some(picked) = color_picker::pick(step.x, step.y)
+                        {
+                            step.color.color = [picked.color.r(), picked.color.g(), picked.color.b()];
+                        }
+                        egui::ComboBox::from_id_salt(("step_color_space", i))
+                            .selected_text(step.color.space.label())
+                            .show_ui(ui, |ui| {
+                                for space in color::ColorSpace::ALL {
+                                    ui.selectable_value(&mut step.color.space, space, space.label());
+                                }
+                            });
+                        ui.label(t!("容差:"));
+                        ui.add(egui::DragValue::new(&mut step.color.tolerance).range(0..=100).suffix("%"))
+                            .on_hover_text(t!("RGB 下是每个通道允许的差，HSV 下是色相允许的差（100% 为 180°）"));
+                        ui.label(t!("停留(秒):"));
+                        ui.add(egui::DragValue::new(&mut step.dwell)
+                            .range(0.0..=60.0)
+                            .speed(0.1));
+                    }
+                    sequence::StepKind::ClickImage => {
+                        ui.label(t!("🖼 点击图片"));
+                        template_file_ui(ui, &mut step.image);
+                        egui::ComboBox::from_id_salt(("step_click_type", i))
+                            .selected_text(step.click_type.label())
+                            .show_ui(ui, |ui| {
+                                for click_type in ClickType::ALL {
+                                    ui.selectable_value(&mut step.click_type, click_type, click_type.label());
+                                }
+                            });
+                        ui.label(t!("停留(秒):"));
+                        ui.add(egui::DragValue::new(&mut step.dwell)
+                            .range(0.0..=60.0)
+                            .speed(0.1));
+                    }
+                    sequence::StepKind::ClickAllImages => {
+                        ui.label(t!("🖼 点击所有匹配"));
+                        template_file_ui(ui, &mut step.image);
+                        egui::ComboBox::from_id_salt(("step_click_type", i))
+                            .selected_text(step.click_type.label())
+                            .show_ui(ui, |ui| {
+                                for click_type in ClickType::ALL {
+                                    ui.selectable_value(&mut step.click_type, click_type, click_type.label());
+                                }
+                            });
+                        ui.label(t!("点击间隔(秒):"));
+                        ui.add(egui::DragValue::new(&mut step.click_delay)
+                            .range(0.0..=10.0)
+                            .speed(0.05))
+                            .on_hover_text(t!("相邻两次点击之间等待的时间"));
+                        ui.label(t!("停留(秒):"));
+                        ui.add(egui::DragValue::new(&mut step.dwell)
+                            .range(0.0..=60.0)
+                            .speed(0.1));
+                    }
+                    sequence::StepKind::WaitImage => {
+                        ui.label(t!("⌛ 等待图片"));
+                        template_file_ui(ui, &mut step.image);
+                        ui.label(t!("超时(秒):"));
+                        ui.add(egui::DragValue::new(&mut step.timeout)
+                            .range(0.0..=3600.0)
+                            .speed(0.5))
+                            .on_hover_text(t!("0 表示一直等到图片出现"));
+                        egui::ComboBox::from_id_salt(("step_on_timeout", i))
+                            .selected_text(step.on_timeout.label())
+                            .show_ui(ui, |ui| {
+                                for action in sequence::TimeoutAction::ALL {
+                                    ui.selectable_value(&mut step.on_timeout, action, action.label());
+                                }
+                            });
+                        ui.label(t!("停留(秒):"));
+                        ui.add(egui::DragValue::new(&mut step.dwell)
+                            .range(0.0..=60.0)
+                            .speed(0.1));
+                    }
+                    sequence::StepKind::Comment => {
+                        ui.colored_label(egui::Color32::GRAY, "#");
+                        ui.add(egui::TextEdit::singleline(&mut step.comment)
+                            .hint_text(t!("注释，执行时跳过"))
+                            .text_color(egui::Color32::GRAY)
+                            .desired_width(220.0));
+                    }
+                    sequence::StepKind::Plugin => match plugins::registry().step(&step.plugin, &step.plugin_step) {
+                        Ok((_, spec)) => {
+                            ui.label(format!("🧩 {}", spec.label()));
+                            // 按插件声明的参数生成输入框，值都以文字保存
+                            for param in &spec.params {
+                                let value = step.params.entry(param.name.clone()).or_insert_with(|| param.default.clone());
+                                ui.label(if param.label.is_empty() { &param.name } else { &param.label });
+                                match param.kind {
+                                    plugins::ParamKind::Text => {
+                                        ui.add(egui::TextEdit::singleline(value).desired_width(80.0));
+                                    }
+                                    plugins::ParamKind::Number => {
+                                        let mut number = value.parse::<f64>().unwrap_or_default();
+                                        if ui.add(egui::DragValue::new(&mut number).speed(0.1)).changed() {
+                                            *value = number.to_string();
+                                        }
+                                    }
+                                    plugins::ParamKind::Bool => {
+                                        let mut checked = value == "true";
+                                        if ui.checkbox(&mut checked, "").changed() {
+                                            *value = checked.to_string();
+                                        }
+                                    }
+                                }
+                            }
+                            ui.label(t!("停留(秒):"));
+                            ui.add(egui::DragValue::new(&mut step.dwell)
+                                .range(0.0..=60.0)
+                                .speed(0.1));
+                        }
+                        Err(reason) => {
+                            ui.colored_label(egui::Color32::LIGHT_RED, format!("🧩 {}/{}", step.plugin, step.plugin_step))
+                                .on_hover_text(reason);
+                        }
+                    },
+                    sequence::StepKind::LoopStart => {
+                        ui.label(t!("🔁 重复"));
+                        ui.add(egui::DragValue::new(&mut step.repeat).range(0..=100000));
+                        ui.label(t!("次，轮次变量:"));
+                        ui.add(egui::TextEdit::singleline(&mut step.loop_var)
+                            .hint_text(t!("可选"))
+                            .desired_width(50.0));
+                    }
+                    sequence::StepKind::LoopEnd => {
+                        ui.label(t!("⏹ 循环结束"));
+                    }
+                }
+                if let Some((_, reason)) = preflight_issues.iter().find(|(index, _)| *index == i) {
+                    ui.colored_label(egui::Color32::LIGHT_RED, t!("⛔ 不支持")).on_hover_text(reason);
+                }
+                if ui.add_enabled(i > 0, egui::Button::new("⬆")).clicked() {
+                    move_up = Some(i);
+                }
+                if ui.add_enabled(i + 1 < step_count, egui::Button::new("⬇")).clicked() {
+                    move_up = Some(i + 1);
+                }
+                if ui.button("⧉").on_hover_text(t!("复制此步骤，循环开始会连同整个循环一起复制")).clicked() {
+                    duplicate = Some(i);
+                }
+                if ui.button(t!("删除")).clicked() {
+                    remove = Some(i);
+                }
+            })
+            .response;
+            // 拖到某一行的上半部分时放在它前面，下半部分时放在它后面
+            if let Some(pointer) = ui.ctx().pointer_interact_pos()
+                && row.dnd_hover_payload::<usize>().is_some()
+            {
+                let before = pointer.y < row.rect.center().y;
+                let y = if before { row.rect.top() } else { row.rect.bottom() };
+                ui.painter().hline(row.rect.x_range(), y, ui.visuals().selection.stroke);
+                if let Some(from) = row.dnd_release_payload::<usize>() {
+                    dropped = Some((*from, if before { i } else { i + 1 }));
+                }
+            }
+            if step.kind.uses_image() {
+                ui.indent(("step_matching", i), |ui| {
+                    ui.horizontal(|ui| {
+                        ui.add_space(depths[i] as f32 * 16.0);
+                        ui.label(t!("相似度阈值:"));
+                        ui.add(egui::Slider::new(&mut step.matching.threshold, 0.5..=1.0).fixed_decimals(2));
+                        ui.checkbox(&mut step.matching.multi_scale, t!("多尺度"))
+                            .on_hover_text(t!("模板在缩放比例不同的显示器上截取时开启，查找耗时成倍增加"));
+                        if ui.button(t!("测试")).on_hover_text(t!("立即在屏幕上查找一次，显示位置、相似度和耗时")).clicked() {
+                            test_image = Some(i);
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.add_space(depths[i] as f32 * 16.0);
+                        let mut restricted = step.matching.region.is_some();
+                        if ui.checkbox(&mut restricted, t!("限定搜索区域"))
+                            .on_hover_text(t!("只在这块区域中查找，屏幕越大、区域越小，查找越快"))
+                            .changed()
+                        {
+                            step.matching.region = restricted.then(|| capture::Region::new(self.x_pos - 100, self.y_pos - 100, 200, 200));
+                        }
+                        if let Some(region) = &mut step.matching.region {
+                            ui.add(egui::DragValue::new(&mut region.x).prefix("X: "));
+                            ui.add(egui::DragValue::new(&mut region.y).prefix("Y: "));
+                            ui.add(egui::DragValue::new(&mut region.width).range(1..=16384).prefix(t!("宽: ")));
+                            ui.add(egui::DragValue::new(&mut region.height).range(1..=16384).prefix(t!("高: ")));
+                        }
+                    });
+                });
+            }
+            if self.show_sequence_expressions && step.kind.has_target() {
+                ui.indent(("step_expressions", i), |ui| {
+                    ui.horizontal(|ui| {
+                        ui.add_space(depths[i] as f32 * 16.0);
+                        ui.label("X =");
+                        ui.add(egui::TextEdit::singleline(&mut step.x_expr)
+                            .hint_text(t!("如 x0 + 30 * i"))
+                            .desired_width(110.0));
+                        ui.label("Y =");
+                        ui.add(egui::TextEdit::singleline(&mut step.y_expr)
+                            .desired_width(110.0));
+                    });
+                    ui.horizontal(|ui| {
+                        ui.add_space(depths[i] as f32 * 16.0);
+                        ui.label(t!("点击后:"));
+                        ui.add(egui::TextEdit::singleline(&mut step.update)
+                            .hint_text(t!("如 k = k + 1"))
+                            .desired_width(200.0));
+                    });
+                });
+            }
+        }
+        if let Some(i) = move_up {
+            self.custom_sequence.steps.swap(i - 1, i);
+        }
+        if let Some((from, to)) = dropped {
+            self.custom_sequence.move_step(from, to);
+        }
+        if let Some(i) = duplicate {
+            self.custom_sequence.duplicate_step(i);
+        }
+        if let Some(i) = test_image {
+            self.test_image_step(i);
+        }
+        if let Some(i) = remove {
+            self.custom_sequence.steps.remove(i);
+        }
+
+        if self.custom_sequence.steps.is_empty() {
+            ui.label(t!("序列为空，请添加步骤或导入宏文件"));
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button(t!("添加步骤（使用当前坐标）")).clicked() {
+                self.custom_sequence.steps.push(sequence::SequenceStep {
+                    x: self.x_pos,
+                    y: self.y_pos,
+                    click_type: self.click_type,
+                    dwell: self.click_interval,
+                    ..Default::default()
+                });
+            }
+            ui.menu_button(t!("添加其他步骤"), |ui| {
+                for kind in sequence::StepKind::ACTIONS {
+                    if kind != sequence::StepKind::Click && ui.button(kind.label()).clicked() {
+                        self.custom_sequence.steps.push(sequence::SequenceStep::new(kind, self.x_pos, self.y_pos));
+                        ui.close_menu();
+                    }
+                }
+                for plugin in &plugins::registry().plugins {
+                    ui.separator();
+                    ui.label(&plugin.manifest.name).on_hover_text(&plugin.manifest.description);
+                    for spec in &plugin.manifest.steps {
+                        let label = match spec.trigger {
+                            true => t!("🧩 {}（触发）", spec.label()),
+                            false => format!("🧩 {}", spec.label()),
+                        };
+                        if ui.button(label).clicked() {
+                            self.custom_sequence.steps.push(sequence::SequenceStep::plugin(&plugin.manifest.name, spec));
+                            ui.close_menu();
+                        }
+                    }
+                }
+            });
+            if ui.button(t!("添加循环")).clicked() {
+                self.custom_sequence.steps.push(sequence::SequenceStep::loop_start(3));
+                self.custom_sequence.steps.push(sequence::SequenceStep::loop_end());
+            }
+            if ui.button(t!("清空")).clicked() {
+                self.custom_sequence.steps.clear();
+                self.custom_sequence.variables.clear();
+            }
+            if ui.button(t!("执行前检查")).on_hover_text(t!("检查目标位置是否允许模拟点击")).clicked() {
+                self.preflight_issues = secure_desktop::preflight(&self.custom_sequence);
+                self.preflight_issues.extend(display::preflight(&self.custom_sequence));
+                self.set_status(match self.preflight_issues.len() {
+                    0 => t!("✅ 检查通过").to_string(),
+                    n => t!("⚠️ 有 {} 个步骤无法执行", n),
+                });
+            }
+        });
+        if !self.custom_sequence.steps.is_empty()
+            && let Err(e) = self.custom_sequence.compile()
+        {
+            ui.colored_label(egui::Color32::LIGHT_RED, t!("序列无法执行: {}", e));
+        }
+
+        ui.checkbox(&mut self.show_sequence_expressions, t!("变量与坐标表达式"));
+        if self.show_sequence_expressions {
+            self.sequence_variables_ui(ui);
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(t!("名称:"));
+            ui.text_edit_singleline(&mut self.macro_metadata.name);
+        });
+        ui.horizontal(|ui| {
+            ui.label(t!("描述:"));
+            ui.text_edit_singleline(&mut self.macro_metadata.description);
+        });
+
+        ui.horizontal(|ui| {
+            if ui.button(t!("打开宏…")).clicked() {
+                self.open_macro();
+            }
+            if ui.button(t!("保存宏…")).clicked() {
+                self.save_macro();
+            }
+        });
+        ui.label(t!("支持宏文件（.json / .toml）和 AutoHotkey 的 Click/MouseMove/Sleep 语句（.ahk）"));
+    }
+
+    fn sequence_variables_ui(&mut self, ui: &mut egui::Ui) {
+        ui.label(t!(
+            "内置变量: {}（第几轮，从 0 开始）、{}（已点击次数）。坐标表达式为空时使用上面的固定坐标",
+            sequence::ITERATION_VAR,
+            sequence::CLICKS_VAR
+        ));
+
+        let mut remove = None;
+        for (i, variable) in self.custom_sequence.variables.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut variable.name).desired_width(80.0));
+                ui.label(t!("初始值:"));
+                ui.add(egui::DragValue::new(&mut variable.value));
+                if ui.button(t!("删除")).clicked() {
+                    remove = Some(i);
+                }
+            });
+        }
+        if let Some(i) = remove {
+            self.custom_sequence.variables.remove(i);
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button(t!("添加计数器")).clicked() {
+                let name = format!("k{}", self.custom_sequence.variables.len());
+                self.custom_sequence.variables.push(sequence::Variable { name, value: 0.0 });
+            }
+            // 把捕捉到的坐标保存为一对变量，供表达式引用
+            if ui.button(t!("当前坐标存为变量")).clicked() {
+                let index = self.custom_sequence.variables.len() / 2;
+                self.custom_sequence.variables.push(sequence::Variable {
+                    name: format!("x{}", index),
+                    value: self.x_pos as f64,
+                });
+                self.custom_sequence.variables.push(sequence::Variable {
+                    name: format!("y{}", index),
+                    value: self.y_pos as f64,
+                });
+            }
+        });
+    }
+
+    fn power_guard_ui(&mut self, ui: &mut egui::Ui) {
+        let guard = &mut self.power_guard;
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut guard.battery_enabled, t!("使用电池且电量低于"));
+            ui.add(egui::DragValue::new(&mut guard.battery_min_percent).range(1..=100).suffix("%"));
+            ui.label(t!("时暂停"));
+        });
+        match power::battery() {
+            Some(status) => ui.weak(t!(
+                "当前电量 {}%，{}",
+                status.percent,
+                if status.on_battery { t!("使用电池") } else { t!("已接通电源") }
+            )),
+            None => ui.weak(t!("未检测到电池")),
+        };
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut guard.cpu_enabled, t!("CPU 占用连续"));
+            ui.add(egui::DragValue::new(&mut guard.cpu_secs).range(1..=600).suffix(t!(" 秒")));
+            ui.label(t!("高于"));
+            ui.add(egui::DragValue::new(&mut guard.cpu_max_percent).range(1..=100).suffix("%"));
+            ui.label(t!("时暂停"));
+        });
+        if guard.cpu_enabled && !power::cpu_supported() {
+            ui.colored_label(egui::Color32::YELLOW, t!("当前平台无法读取 CPU 占用，该条件不会生效"));
+        }
+        ui.label(t!("条件恢复后自动继续；每个配置分别设置"));
+    }
+
+    // 执行前切换到前台的窗口；打开下拉框时重新读取窗口列表
+    fn target_window_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(t!("切换到窗口:"));
+            let selected = self.focus_window.as_ref().map_or(t!("不切换").to_string(), |window| window.label());
+            let response = egui::ComboBox::from_id_salt("focus_window")
+                .selected_text(selected)
+                .width(280.0)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.focus_window, None, t!("不切换"));
+                    for window in &self.open_windows {
+                        ui.selectable_value(&mut self.focus_window, Some(window.clone()), window.label());
+                    }
+                })
+                .response;
+            if response.clicked() {
+                self.open_windows = target_window::list();
+            }
+        });
+        let Some(window) = self.focus_window.clone() else {
+            ui.weak(t!("切换到其他窗口后，点击会落在挡在前面的窗口上；选择目标窗口后执行时会先把它切到前台"));
+            return;
+        };
+        ui.horizontal(|ui| {
+            ui.label(t!("切换时机:"));
+            for mode in target_window::FocusMode::ALL {
+                ui.radio_value(&mut self.focus_mode, mode, mode.label());
+            }
+        });
+        if self.focus_mode == target_window::FocusMode::BeforeEachClick {
+            ui.weak(t!("窗口已经在前台时不重复切换；找不到窗口时中止执行"));
+        }
+        if ui.button(t!("🎯 立即切换")).clicked() {
+            match target_window::bring_to_front(&window) {
+                Ok(()) => self.set_status(t!("已切换到「{}」", window.label())),
+                Err(error) => self.set_status(format!("⚠️ {}", error)),
+            }
+        }
+    }
+
+    // 防离开的开关、发送方式、间隔和生效时间段
+    fn jiggler_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if self.jiggler.is_some() {
+                if ui.button(t!("⏹ 停止防离开")).clicked() {
+                    self.jiggler = None;
+                    self.set_status(t!("已停止防离开").to_string());
+                }
+            } else if ui.button(t!("▶ 开始防离开")).clicked() {
+                let settings = self.jiggler_settings.clone();
+                self.jiggler = Some(jiggler::Jiggler::start(settings, self.engine.clone(), self.state.clone()));
+                self.set_status(t!("已开始防离开").to_string());
+            }
+            match self.jiggler.as_ref().map(|jiggler| (jiggler.last(), jiggler.error())) {
+                Some((_, Some(error))) => ui.colored_label(egui::Color32::RED, t!("发送失败: {}", error)),
+                Some((Some(last), None)) => ui.weak(t!("上次发送: {}", last)),
+                Some((None, None)) => ui.weak(t!("运行中")),
+                None => ui.weak(t!("未开启")),
+            };
+        });
+        let settings = &mut self.jiggler_settings;
+        ui.horizontal(|ui| {
+            ui.label(t!("方式:"));
+            for action in jiggler::JiggleAction::ALL {
+                ui.radio_value(&mut settings.action, action, action.label());
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label(t!("每隔"));
+            ui.add(egui::DragValue::new(&mut settings.interval_secs).range(5..=3600).suffix(t!(" 秒")));
+            ui.label(t!("发送一次"));
+        });
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut settings.schedule_enabled, t!("只在每天的这段时间内生效:"));
+            let enabled = settings.schedule_enabled;
+            let time = |minute| {
+                egui::DragValue::new(minute)
+                    .range(0..=24 * 60 - 1)
+                    .custom_formatter(|value, _| jiggler::format_minute(value as u32))
+                    .custom_parser(|text| {
+                        let (hour, minute) = text.split_once(':')?;
+                        Some(hour.trim().parse::<f64>().ok()? * 60.0 + minute.trim().parse::<f64>().ok()?)
+                    })
+            };
+            ui.add_enabled(enabled, time(&mut settings.start_minute));
+            ui.label("–");
+            ui.add_enabled(enabled, time(&mut settings.end_minute));
+        });
+        ui.weak(t!("鼠标被动过或正在执行自动点击时跳过这一次；结束时间早于开始时间时跨过午夜"));
+    }
+
+    fn adaptive_interval_ui(&mut self, ui: &mut egui::Ui) {
+        let adaptive = &mut self.adaptive_interval;
+        ui.checkbox(&mut adaptive.enabled, t!("自适应间隔：等待反馈区域变化后再点击下一下"))
+            .on_hover_text(t!("点击后观察反馈区域，界面有反应就立即继续，代替固定的点击间隔"));
+        if !adaptive.enabled {
+            return;
+        }
+        if !feedback::AdaptiveInterval::is_supported() {
+            ui.colored_label(egui::Color32::YELLOW, i18n::tr(feedback::UNSUPPORTED_MESSAGE));
+        }
+        ui.horizontal(|ui| {
+            ui.label(t!("反馈区域中心:"));
+            ui.add(egui::DragValue::new(&mut adaptive.x).prefix("X: "));
+            ui.add(egui::DragValue::new(&mut adaptive.y).prefix("Y: "));
+            ui.label(t!("边长:"));
+            ui.add(egui::DragValue::new(&mut adaptive.size).range(1..=feedback::MAX_REGION_SIZE).suffix(" px"));
+            if ui.button(t!("使用当前坐标")).clicked() {
+                adaptive.x = self.x_pos;
+                adaptive.y = self.y_pos;
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label(t!("等待时间:"));
+            ui.add(egui::DragValue::new(&mut adaptive.min_ms).range(0..=10_000).prefix(t!("最短 ")).suffix(t!(" 毫秒")));
+            ui.add(egui::DragValue::new(&mut adaptive.max_ms)
+                .range(adaptive.min_ms..=60_000)
+                .prefix(t!("最长 "))
+                .suffix(t!(" 毫秒")));
+        });
+    }
+
+    fn export_runner_bundle(&mut self) {
+        let Some(parent) = rfd::FileDialog::new().pick_folder() else {
+            return;
+        };
+
+        let sequence = self.build_sequence();
+        if let Err(e) = sequence.compile() {
+            self.set_status(t!("导出运行包失败: {}", e));
+            return;
+        }
+        let file = macro_file::MacroFile::new(self.macro_metadata.clone(), sequence);
+        let options = bundle::BundleOptions {
+            count: self.click_count,
+            speed: self.playback_speed,
+            smooth_move_ms: (self.move_mode == MoveMode::Smooth).then_some(self.smooth_move_ms),
+            display: self.display_name.clone(),
+        };
+
+        match bundle::export(&parent, &file, &options) {
+            Ok(dir) => {
+                self.set_status(t!("运行包已导出到: {}", dir.display()));
+            }
+            Err(e) => {
+                self.set_status(t!("导出运行包失败: {}", e));
+            }
+        }
+    }
+
+    fn export_xdotool_script(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter(t!("Shell 脚本"), &["sh"])
+            .set_file_name("mousetool_macro.sh")
+            .save_file()
+        else {
+            return;
+        };
+
+        let script = match export::xdotool_script(&self.build_sequence(), self.click_count, multi_click_gap(self.multi_click_gap_ms)) {
+            Ok(script) => script,
+            Err(e) => {
+                self.set_status(t!("导出脚本失败: {}", e));
+                return;
+            }
+        };
+        match std::fs::write(&path, script) {
+            Ok(()) => {
+                // 导出的脚本需要可执行权限
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::PermissionsExt;
+                    let _ = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755));
+                }
+                self.set_status(t!("已导出 xdotool 脚本: {}", path.display()));
+            }
+            Err(e) => {
+                self.set_status(t!("导出脚本失败: {}", e));
+            }
+        }
+    }
+
+    // 从更新日志跳转到对应的设置：先让设置可见，再滚动过去并短暂高亮
+    fn open_feature(&mut self, link: changelog::FeatureLink) {
+        use changelog::FeatureLink;
+        match link {
+            FeatureLink::SmoothMove => {
+                self.move_mode = MoveMode::Smooth;
+            }
+            FeatureLink::TwoPoint => {
+                self.auto_mode = true;
+                self.auto_click_mode = AutoClickMode::TwoPoint;
+            }
+            FeatureLink::Sequence => {
+                self.auto_mode = true;
+                self.auto_click_mode = AutoClickMode::Sequence;
+            }
+            FeatureLink::HoldKey | FeatureLink::ScriptExport => {
+                self.auto_mode = true;
+            }
+            FeatureLink::ReleaseInputs => {}
+        }
+        self.feature_highlight = Some((link, Instant::now()));
+        self.feature_scrolled = false;
+    }
+
+    fn is_feature_highlighted(&self, link: changelog::FeatureLink) -> bool {
+        self.feature_highlight
+            .is_some_and(|(target, since)| target == link && since.elapsed() < FEATURE_HIGHLIGHT_DURATION)
+    }
+
+    fn reveal_feature(&mut self, ui: &egui::Ui, link: changelog::FeatureLink, response: &egui::Response) {
+        if !self.is_feature_highlighted(link) {
+            return;
+        }
+        if !self.feature_scrolled {
+            response.scroll_to_me(Some(egui::Align::Center));
+            self.feature_scrolled = true;
+        }
+        ui.painter().rect_stroke(
+            response.rect.expand(2.0),
+            4.0,
+            egui::Stroke::new(2.0, egui::Color32::from_rgb(255, 165, 0)),
+            egui::StrokeKind::Outside,
+        );
+    }
+
+    fn whats_new_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_whats_new;
+        let mut clicked_link = None;
+
+        egui::Window::new(t!("🆕 新功能"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                // 一次升级跨过多个版本时条目很多，列表单独滚动，按钮始终可见
+                egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                    for entry in &self.whats_new {
+                        ui.strong(t!("版本 {}", entry.version));
+                        for item in entry.items {
+                            ui.horizontal_wrapped(|ui| {
+                                ui.label("•");
+                                ui.label(i18n::tr(item.text));
+                                if let Some(link) = item.link
+                                    && ui.link(t!("前往设置")).clicked()
+                                {
+                                    clicked_link = Some(link);
+                                }
+                            });
+                        }
+                        ui.add_space(6.0);
+                    }
+                });
+                ui.separator();
+                if ui.button(t!("知道了")).clicked() {
+                    self.show_whats_new = false;
+                }
+            });
+
+        if let Some(link) = clicked_link {
+            self.open_feature(link);
+            open = false;
+        }
+        self.show_whats_new &= open;
+    }
+
+    fn insights_ui(&mut self, ui: &mut egui::Ui) {
+        ui.colored_label(egui::Color32::GREEN, t!("🔒 统计数据只从本机的历史记录计算，不会上传或发送到任何地方"));
+
+        ui.horizontal(|ui| {
+            if ui.button(t!("刷新统计")).clicked() || self.insights.is_none() {
+                self.insights = Some(history::insights(&history::load()));
+            }
+            if ui.button(t!("清除历史记录")).clicked() {
+                match history::clear() {
+                    Ok(()) => {
+                        self.insights = Some(history::Insights::default());
+                        self.set_status(t!("历史记录已清除").to_string());
+                    }
+                    Err(e) => {
+                        self.set_status(t!("清除历史记录失败: {}", e));
+                    }
+                }
+            }
+        });
+
+        let Some(insights) = &self.insights else {
+            return;
+        };
+        if insights.total_runs == 0 {
+            ui.label(t!("暂无历史记录"));
+            return;
+        }
+
+        ui.label(t!("执行次数: {}", insights.total_runs));
+        ui.label(t!("累计点击: {}，失败: {}", insights.total_clicks, insights.total_errors));
+        ui.label(t!(
+            "自动点击平均每次 {} 下，耗时 {} 秒，{}% 被手动停止",
+            format!("{:.1}", insights.average_run_clicks),
+            format!("{:.1}", insights.average_run_secs),
+            format!("{:.0}", insights.stopped_ratio * 100.0)
+        ));
+
+        ui.separator();
+        ui.label(t!("最近的执行:"));
+        for record in &insights.recent_runs {
+            ui.label(t!(
+                "  {} {}: {}/{} 下，{}{}",
+                history::format_ago(record.started_at),
+                record.kind.label(),
+                record.clicks,
+                record.planned_clicks,
+                history::format_duration(record.duration_secs),
+                if record.stopped { t!("，手动停止") } else { "" }
+            ));
+        }
+
+        ui.separator();
+        ui.label(t!("功能使用次数:"));
+        for (feature, count) in &insights.feature_usage {
+            ui.label(format!("  {}: {}", feature, count));
+        }
+
+        ui.separator();
+        ui.label(t!("最常点击的位置:"));
+        for target in &insights.top_targets {
+            ui.label(t!("  ({}, {}): {} 次", target.x, target.y, target.clicks));
+        }
+
+        if !insights.error_hot_spots.is_empty() {
+            ui.separator();
+            ui.colored_label(egui::Color32::LIGHT_RED, t!("出错最多的位置:"));
+            for target in &insights.error_hot_spots {
+                ui.label(t!(
+                    "  ({}, {}): 失败 {} 次 / 成功 {} 次",
+                    target.x, target.y, target.errors, target.clicks
+                ));
+            }
+        }
+    }
+
+    // 按屏幕比例显示本次启动以来的点击位置
+    fn heatmap_ui(&mut self, ui: &mut egui::Ui) {
+        if self.trail_screen_size.is_none() {
+            self.trail_screen_size = self.engine.get_screen_size().ok();
+        }
+        let Some(screen) = self.trail_screen_size else {
+            ui.label(t!("无法获取屏幕尺寸"));
+            return;
+        };
+        let count = self.heatmap.lock().unwrap().len();
+
+        ui.horizontal(|ui| {
+            for view in painting::HeatmapView::ALL {
+                ui.radio_value(&mut self.heatmap_view, view, view.label());
+            }
+            let empty = self.heatmap.lock().unwrap().is_empty();
+            if ui.add_enabled(!empty, egui::Button::new(t!("清除"))).clicked() {
+                self.heatmap.lock().unwrap().clear();
+            }
+        });
+        ui.label(t!("共 {} 次点击（仅主显示器，退出后清空）", count));
+
+        let width = ui.available_width().min(480.0);
+        let size = egui::vec2(width, width * screen.1 as f32 / screen.0.max(1) as f32);
+        let (rect, response) = ui.allocate_exact_size(size, egui::Sense::hover());
+        painting::heatmap(ui.painter(), rect, &self.heatmap.lock().unwrap(), screen, self.heatmap_view);
+        if let Some(pos) = response.hover_pos() {
+            let x = ((pos.x - rect.min.x) / rect.width() * screen.0 as f32) as i32;
+            let y = ((pos.y - rect.min.y) / rect.height() * screen.1 as f32) as i32;
+            response.on_hover_text(format!("({}, {})", x, y));
+        }
+    }
+
+    // 在窗口内的测试目标上不加间隔地连续点击，统计实际的点击速率和延迟
+    fn benchmark_ui(&mut self, ui: &mut egui::Ui) {
+        ui.weak(t!("把鼠标移到下面的目标上尽快连续点击左键，测出当前平台的模拟点击能达到多快；测试中请不要移动鼠标"));
+        let idle = self.state.get() == AppState::Idle;
+        let mut start = false;
+        ui.horizontal(|ui| {
+            ui.label(t!("点击次数:"));
+            ui.add_enabled(idle, egui::DragValue::new(&mut self.benchmark.clicks).range(10..=5000));
+            if self.benchmark.is_active() {
+                if ui.button(t!("停止")).clicked() {
+                    self.stop_clicking();
+                }
+            } else {
+                start = ui.add_enabled(idle, egui::Button::new(t!("开始测试"))).clicked();
+            }
+        });
+
+        let (rect, _) = ui.allocate_exact_size(egui::Vec2::splat(benchmark::TARGET_SIZE), egui::Sense::click());
+        let color = if self.benchmark.is_active() { egui::Color32::GREEN } else { egui::Color32::GRAY };
+        ui.painter().rect_filled(rect, 4.0, color.gamma_multiply(0.3));
+        ui.painter().rect_stroke(rect, 4.0, egui::Stroke::new(1.0, color), egui::StrokeKind::Inside);
+        ui.painter().text(
+            rect.center(),
+            egui::Align2::CENTER_CENTER,
+            self.benchmark.received().to_string(),
+            egui::FontId::proportional(24.0),
+            ui.visuals().text_color(),
+        );
+
+        let target = benchmark::to_mouse(ui.ctx(), rect.center());
+        let clicks = ui.input(|input| {
+            input
+                .events
+                .iter()
+                .filter(|event| {
+                    matches!(event, egui::Event::PointerButton { pos, button: egui::PointerButton::Primary, pressed: true, .. } if rect.contains(*pos))
+                })
+                .count()
+        });
+        self.benchmark.poll(target, clicks);
+        if start && self.ready_to_start() {
+            self.state.set(AppState::Running);
+            let worker = self.benchmark.start(target, self.engine.clone(), self.state.clone());
+            self.workers.push(worker);
+            self.set_status(t!("点击速度测试中...").to_string());
+        }
+        if self.benchmark.is_active() {
+            // 尽快处理收到的点击，否则延迟里会包含很长的刷新间隔
+            ui.ctx().request_repaint();
+        }
+
+        let Some(result) = &self.benchmark.result else {
+            return;
+        };
+        if let Some(reason) = &result.aborted {
+            ui.colored_label(egui::Color32::from_rgb(255, 165, 0), t!("⚠️ 测试提前停止: {}", reason));
+        }
+        ui.label(t!("发出 {} 次，每秒 {} 次", result.sent, format!("{:.1}", result.send_rate)));
+        ui.label(t!("界面收到 {} 次，每秒 {} 次", result.received, format!("{:.1}", result.receive_rate)));
+        ui.label(t!(
+            "延迟: 平均 {} ms，最长 {} ms（包含等待界面下一帧的时间）",
+            format!("{:.1}", result.mean_latency_ms),
+            format!("{:.1}", result.max_latency_ms)
+        ));
+        if result.lost() > 0 {
+            ui.colored_label(egui::Color32::from_rgb(255, 165, 0), t!("⚠️ 有 {} 次点击没有收到", result.lost()));
+        }
+        if result.errors > 0 {
+            ui.colored_label(egui::Color32::LIGHT_RED, t!("⚠️ 有 {} 次点击发送失败", result.errors));
+        }
+    }
+
+    // 透明、置顶、鼠标可穿透的全屏窗口，覆盖主显示器
+    fn screen_overlay(&mut self, ctx: &egui::Context, id: &str, title: &str, paint: impl Fn(&egui::Painter, f32)) {
+        if self.trail_screen_size.is_none() {
+            self.trail_screen_size = self.engine.get_screen_size().ok();
+        }
+        let Some((width, height)) = self.trail_screen_size else {
+            return;
+        };
+        // 叠加层放在主显示器上，按主显示器的缩放换算
+        let scale = display::scale_at(&self.displays, 0, 0, ctx.native_pixels_per_point().unwrap_or(1.0));
+
+        ctx.show_viewport_immediate(
+            egui::ViewportId::from_hash_of(id),
+            egui::ViewportBuilder::default()
+                .with_title(title)
+                .with_position([0.0, 0.0])
+                .with_inner_size([width as f32 / scale, height as f32 / scale])
+                .with_decorations(false)
+                .with_transparent(true)
+                .with_always_on_top()
+                .with_mouse_passthrough(true)
+                .with_taskbar(false)
+                .with_active(false),
+            |ctx, _class| {
+                egui::CentralPanel::default().frame(egui::Frame::NONE).show(ctx, |ui| {
+                    paint(ui.painter(), scale);
+                });
+            },
+        );
+    }
+
+    // 绘制回放轨迹
+    fn trail_overlay(&mut self, ctx: &egui::Context) {
+        if !self.trail.lock().unwrap().prune() {
+            return;
+        }
+        let trail = self.trail.clone();
+        self.screen_overlay(ctx, "trail_overlay", t!("回放轨迹"), |painter, pixels_per_point| {
+            painting::trail(painter, &trail.lock().unwrap(), pixels_per_point);
+        });
+        // 淡出动画需要连续重绘
+        ctx.request_repaint();
+    }
+
+    fn start_screenshot_pick(&mut self, ctx: &egui::Context) {
+        // 先把自己藏起来，免得挡住要点选的位置
+        ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(true));
+        self.screenshot_pick = Some(ScreenshotPick::Waiting(Instant::now()));
+        self.set_status(t!("正在截取屏幕...").to_string());
+    }
+
+    fn screenshot_pick_window(&mut self, ctx: &egui::Context) {
+        match &self.screenshot_pick {
+            None => {}
+            Some(ScreenshotPick::Waiting(since)) => {
+                // 等待最小化动画结束
+                if since.elapsed() < Duration::from_millis(500) {
+                    return;
+                }
+                let result = capture::capture_primary();
+                ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+                ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+                self.screenshot_pick = match result {
+                    Ok(shot) => {
+                        let size = egui::vec2(shot.width as f32, shot.height as f32);
+                        let screen = self.engine.get_screen_size().ok();
+                        let scale = screen.map_or(egui::Vec2::splat(1.0), |(w, h)| egui::vec2(w as f32, h as f32) / size);
+                        let image = egui::ColorImage::from_rgba_unmultiplied([shot.width, shot.height], &shot.rgba);
+                        let texture = ctx.load_texture("screenshot_pick", image, egui::TextureOptions::NEAREST);
+                        self.set_status(t!("请在截图上点击目标位置，按 Esc 取消").to_string());
+                        Some(ScreenshotPick::Ready { texture, size, scale })
+                    }
+                    Err(e) => {
+                        self.set_status(t!("截图失败: {}", e));
+                        None
+                    }
+                };
+            }
+            Some(ScreenshotPick::Ready { texture, size, scale }) => {
+                let (size, scale) = (*size, *scale);
+                let picked = ctx.show_viewport_immediate(
+                    egui::ViewportId::from_hash_of("screenshot_pick"),
+                    egui::ViewportBuilder::default()
+                        .with_title(t!("从截图选取坐标 - 点击目标位置，Esc 取消"))
+                        .with_maximized(true),
+                    |ctx, _class| {
+                        if ctx.input(|i| i.viewport().close_requested() || i.key_pressed(egui::Key::Escape)) {
+                            return Some(None);
+                        }
+                        egui::CentralPanel::default().frame(egui::Frame::NONE).show(ctx, |ui| {
+                            // 按比例缩放到窗口大小，不放大
+                            let fit = (ui.available_width() / size.x).min(ui.available_height() / size.y).min(1.0);
+                            let response = ui.add(
+                                egui::Image::new(texture).fit_to_exact_size(size * fit).sense(egui::Sense::click()),
+                            );
+                            let pointer = response.hover_pos()?;
+                            let pixel = (pointer - response.rect.min) / fit;
+                            let (x, y) = ((pixel.x * scale.x) as i32, (pixel.y * scale.y) as i32);
+
+                            let painter = ui.painter();
+                            let stroke = egui::Stroke::new(1.0, egui::Color32::RED);
+                            painter.hline(response.rect.x_range(), pointer.y, stroke);
+                            painter.vline(pointer.x, response.rect.y_range(), stroke);
+                            painter.text(
+                                pointer + egui::vec2(12.0, 12.0),
+                                egui::Align2::LEFT_TOP,
+                                format!("({}, {})", x, y),
+                                egui::FontId::monospace(14.0),
+                                egui::Color32::RED,
+                            );
+                            response.clicked().then_some(Some((x, y)))
+                        })
+                        .inner
+                    },
+                );
+
+                match picked {
+                    Some(Some((x, y))) => {
+                        self.x_pos = x;
+                        self.y_pos = y;
+                        bookmarks::push_recent(&mut self.recent_coordinates, x, y);
+                        self.set_status(t!("✅ 已从截图选取坐标: ({}, {})", x, y));
+                        if let Some(reason) = secure_desktop::target_blocked(x, y) {
+                            self.set_status(t!("⚠️ 坐标已设置为 ({}, {})，但{}", x, y, reason));
+                        }
+                        self.screenshot_pick = None;
+                    }
+                    Some(None) => {
+                        self.set_status(t!("已取消从截图选取坐标").to_string());
+                        self.screenshot_pick = None;
+                    }
+                    None => {}
+                }
+            }
+        }
+    }
+
+    // 捕捉坐标时跟随鼠标的放大镜
+    fn loupe_overlay(&mut self, ctx: &egui::Context) {
+        let Some((x, y)) = self.engine.get_mouse_position().ok() else {
+            return;
+        };
+        if self.trail_screen_size.is_none() {
+            self.trail_screen_size = self.engine.get_screen_size().ok();
+        }
+        // 按光标所在显示器的缩放换算
+        let (global_x, global_y) = display::to_global(x, y);
+        let scale = display::scale_at(&self.displays, global_x, global_y, ctx.native_pixels_per_point().unwrap_or(1.0));
+        let size = loupe::Loupe::size();
+        // 放在光标右下方，靠近屏幕边缘时换到另一侧
+        let (screen_width, screen_height) = self.trail_screen_size.unwrap_or((i32::MAX, i32::MAX));
+        let cursor = egui::pos2(x as f32, y as f32) / scale;
+        let screen = egui::vec2(screen_width as f32, screen_height as f32) / scale;
+        const OFFSET: f32 = 24.0;
+        let left = if cursor.x + OFFSET + size.x > screen.x { cursor.x - OFFSET - size.x } else { cursor.x + OFFSET };
+        let top = if cursor.y + OFFSET + size.y > screen.y { cursor.y - OFFSET - size.y } else { cursor.y + OFFSET };
+
+        let loupe = loupe::Loupe::capture(x, y);
+        ctx.show_viewport_immediate(
+            egui::ViewportId::from_hash_of("loupe"),
+            egui::ViewportBuilder::default()
+                .with_title(t!("放大镜"))
+                .with_position([left, top])
+                .with_inner_size(size)
+                .with_decorations(false)
+                .with_always_on_top()
+                .with_mouse_passthrough(true)
+                .with_taskbar(false)
+                .with_active(false),
+            |ctx, _class| {
+                egui::CentralPanel::default().frame(egui::Frame::NONE).show(ctx, |ui| {
+                    loupe.paint(ui.painter());
+                });
+            },
+        );
+    }
+
+    // 在将要点击的位置绘制标记
+    fn target_marker_overlay(&mut self, ctx: &egui::Context) {
+        let targets = match self.auto_click_mode {
+            AutoClickMode::SinglePoint => vec![marker::Target::new(self.x_pos, self.y_pos, "")],
+            AutoClickMode::TwoPoint => vec![
+                marker::Target::new(self.point_a.x, self.point_a.y, "A"),
+                marker::Target::new(self.point_b.x, self.point_b.y, "B"),
+            ],
+            AutoClickMode::Sequence => marker::sequence_targets(&self.custom_sequence),
+        };
+        self.screen_overlay(ctx, "target_marker_overlay", t!("目标位置"), |painter, pixels_per_point| {
+            marker::paint(painter, &targets, pixels_per_point);
+        });
+    }
+
+    fn run_progress_ui(&mut self, ui: &mut egui::Ui, total: u32) {
+        let running = self.state.is_running();
+        let Some(run) = &mut self.current_run else {
+            return;
+        };
+        let clicks = total.saturating_sub(run.clicks_before);
+        if !running && run.finished.is_none() {
+            run.finished = Some((clicks, run.started.elapsed()));
+        }
+        let (label, clicks, elapsed) = match run.finished {
+            Some((clicks, elapsed)) => (t!("上次"), clicks, elapsed),
+            None => (t!("本次"), clicks, run.started.elapsed()),
+        };
+        ui.label(t!(
+            "｜{}: {} 下，{}",
+            label,
+            clicks,
+            history::format_duration(elapsed.as_secs_f64())
+        ));
+    }
+
+    // 执行中的进度条、已用时间和预计剩余时间
+    fn run_progress_bar(&self, ui: &mut egui::Ui) {
+        if !self.state.is_running() {
+            return;
+        }
+        // 点击速度测试也处于执行中，此时的 run 是上一次已经结束的执行
+        let Some(run) = self.current_run.as_ref().filter(|run| run.finished.is_none()) else {
+            return;
+        };
+        let done = self.total_clicks.lock().unwrap().saturating_sub(run.clicks_before).min(run.remaining);
+        let fraction = if run.remaining == 0 { 1.0 } else { done as f32 / run.remaining as f32 };
+        let elapsed = run.started.elapsed().as_secs_f64();
+        // 按目前的平均速度估算，还没有点击时无法估算
+        let eta = if done > 0 {
+            history::format_duration(elapsed / done as f64 * (run.remaining - done) as f64)
+        } else {
+            t!("计算中").to_string()
+        };
+        ui.add(egui::ProgressBar::new(fraction).text(t!("{}/{} 下", done, run.remaining)));
+        ui.label(t!("已用时 {}，预计还需 {}", history::format_duration(elapsed), eta));
+    }
+
+    // 实际点击间隔的平均值、最短、最长和抖动，单点模式下和设定的间隔对照
+    fn timing_ui(&self, ui: &mut egui::Ui) {
+        match self.timing.lock().unwrap().summary() {
+            Some(summary) => {
+                ui.label(t!(
+                    "平均 {} ms，最短 {} ms，最长 {} ms",
+                    format!("{:.1}", summary.mean_ms),
+                    format!("{:.1}", summary.min_ms),
+                    format!("{:.1}", summary.max_ms)
+                ));
+                ui.label(t!("抖动（标准差）: ±{} ms", format!("{:.1}", summary.jitter_ms)));
+                ui.weak(t!("共统计 {} 个间隔", summary.count));
+            }
+            None => {
+                ui.weak(t!("开始自动点击后统计相邻两次点击的实际间隔"));
+            }
+        }
+        if self.auto_click_mode == AutoClickMode::SinglePoint {
+            let expected = self.click_interval * 1000.0 / self.playback_speed;
+            ui.weak(t!("设定间隔: {} ms（不含移动和点击本身的耗时）", format!("{:.1}", expected)));
+        }
+    }
+
+    // 轮询全局快捷键；窗口不在前台时也能触发
+    fn check_hotkeys(&mut self, ctx: &egui::Context) {
+        if let Some(target) = self.recording_hotkey {
+            self.record_hotkey(ctx, target);
+            return;
+        }
+
+        let Ok((pressed, buttons)) = self.engine.call(|c| (c.pressed_keys(), c.get_mouse_button_states())) else {
+            return;
+        };
+        let stop_pressed = match self.input_hook {
+            Some(_) => self.stop_button.is_some_and(|button| button.is_pressed_in(&self.hook_events)),
+            None => self.hotkey_listener.poll_stop_button(self.stop_button, &buttons),
+        };
+        if stop_pressed && self.state.is_running() {
+            self.stop_clicking();
+            self.set_status(t!("已通过鼠标按钮紧急停止，所有按键和按钮已释放").to_string());
+        }
+        for action in self.hotkey_listener.poll(&self.hotkeys, &pressed) {
+            match action {
+                hotkey::HotkeyAction::ToggleClicking => self.toggle_clicking(),
+                hotkey::HotkeyAction::CapturePosition => self.capture_position_by_hotkey(),
+                hotkey::HotkeyAction::PickColor => self.pick_color_by_hotkey(ctx),
+            }
+        }
+        for index in self.hotkey_listener.poll_profiles(&self.profiles.profiles, &pressed) {
+            self.toggle_profile(index);
+        }
+    }
+
+    // 执行中时只停止，等旧的执行线程退出后再触发一次才切换到该配置并开始
+    fn toggle_profile(&mut self, index: usize) {
+        if self.state.is_running() {
+            self.stop_clicking();
+            return;
+        }
+        self.switch_profile(index);
+        if self.profiles.active == index {
+            self.start_auto_clicking();
+        }
+    }
+
+    fn toggle_clicking(&mut self) {
+        if self.state.is_running() {
+            self.stop_clicking();
+        } else {
+            self.start_auto_clicking();
+        }
+    }
+
+    // 处理手柄按钮；正在绑定时第一个按下的按钮用于绑定，不触发操作
+    fn check_gamepad(&mut self) {
+        for button in self.gamepads.poll() {
+            if let Some(action) = self.recording_gamepad.take() {
+                self.gamepad_bindings.retain(|binding| binding.action != action);
+                self.gamepad_bindings.push(gamepad::GamepadBinding { action, button });
+                self.set_status(t!("「{}」已绑定到手柄按钮 {}", action.label(), gamepad::button_label(button)));
+                continue;
+            }
+            let actions: Vec<_> = self
+                .gamepad_bindings
+                .iter()
+                .filter(|binding| binding.button == button)
+                .map(|binding| binding.action)
+                .collect();
+            for action in actions {
+                match action {
+                    gamepad::GamepadAction::ToggleClicking => self.toggle_clicking(),
+                    // 执行中插入的点击会打乱正在执行的序列
+                    gamepad::GamepadAction::SingleClick if self.state.is_running() => {}
+                    gamepad::GamepadAction::SingleClick => self.perform_single_click(),
+                }
+            }
+        }
+    }
+
+    fn gamepad_ui(&mut self, ui: &mut egui::Ui) {
+        if let Some(error) = self.gamepads.error() {
+            ui.colored_label(egui::Color32::YELLOW, t!("⚠️ 无法读取手柄: {}", error));
+            return;
+        }
+        let connected = self.gamepads.connected();
+        if connected.is_empty() {
+            ui.weak(t!("没有检测到手柄"));
+        } else {
+            ui.label(t!("已连接: {}", connected.join(t!("、"))));
+        }
+        for action in gamepad::GamepadAction::ALL {
+            ui.horizontal(|ui| {
+                ui.label(format!("{}:", action.label()));
+                if self.recording_gamepad == Some(action) {
+                    ui.colored_label(egui::Color32::from_rgb(255, 165, 0), t!("请按下手柄上的按钮..."));
+                    if ui.button(t!("取消")).clicked() {
+                        self.recording_gamepad = None;
+                    }
+                    return;
+                }
+                let binding = self.gamepad_bindings.iter().find(|binding| binding.action == action);
+                match binding {
+                    Some(binding) => ui.monospace(gamepad::button_label(binding.button)),
+                    None => ui.label(t!("未设置")),
+                };
+                if ui.button(t!("录制")).clicked() {
+                    self.recording_gamepad = Some(action);
+                }
+                if binding.is_some() && ui.button(t!("清除")).clicked() {
+                    self.gamepad_bindings.retain(|binding| binding.action != action);
+                }
+            });
+        }
+        ui.label(t!("窗口不在前台时也能触发，适合键盘不在手边的场合；Windows 上只支持 Xbox 兼容手柄"));
+    }
+
+    fn reconnect_midi(&mut self, ctx: &egui::Context) {
+        // 先断开旧的连接，有些平台上同一个设备只能连接一次
+        self.midi = None;
+        if !self.midi_settings.enabled {
+            return;
+        }
+        match midi::MidiInputs::connect(ctx) {
+            Ok(inputs) => self.midi = Some(inputs),
+            Err(e) => self.set_status(format!("⚠️ {}", e)),
+        }
+    }
+
+    // 处理 MIDI 消息；正在学习时第一条消息用于绑定，不触发操作
+    fn check_midi(&mut self) {
+        while let Some(trigger) = self.midi.as_ref().and_then(|inputs| inputs.try_recv()) {
+            if self.learning_midi {
+                self.learning_midi = false;
+                let action = self.new_midi_action.clone();
+                self.set_status(t!("「{}」已绑定到 MIDI {}", action.label(), trigger.display()));
+                self.midi_settings.bindings.push(midi::MidiBinding { trigger, action });
+                continue;
+            }
+            let actions: Vec<_> = self
+                .midi_settings
+                .bindings
+                .iter()
+                .filter(|binding| binding.trigger == trigger)
+                .map(|binding| binding.action.clone())
+                .collect();
+            for action in actions {
+                match action {
+                    midi::MidiAction::ToggleClicking => self.toggle_clicking(),
+                    // 执行中插入的点击会打乱正在执行的序列
+                    midi::MidiAction::SingleClick if self.state.is_running() => {}
+                    midi::MidiAction::SingleClick => self.perform_single_click(),
+                    midi::MidiAction::ToggleProfile(name) => {
+                        match self.profiles.profiles.iter().position(|profile| profile.name == name) {
+                            Some(index) => self.toggle_profile(index),
+                            None => self.set_status(t!("⚠️ MIDI 绑定的配置「{}」已不存在", name)),
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn midi_ui(&mut self, ui: &mut egui::Ui) {
+        ui.label(t!("打击垫、MIDI 控制器上的音符或 CC 按钮可以开始和停止自动点击、配置或点击一次"));
+        ui.horizontal(|ui| {
+            if ui.checkbox(&mut self.midi_settings.enabled, t!("启用 MIDI 触发")).changed() {
+                self.reconnect_midi(ui.ctx());
+            }
+            if self.midi_settings.enabled && ui.button(t!("重新连接设备")).clicked() {
+                self.reconnect_midi(ui.ctx());
+            }
+        });
+        let Some(inputs) = &self.midi else {
+            return;
+        };
+        if inputs.ports().is_empty() {
+            ui.weak(t!("没有检测到 MIDI 输入设备"));
+        } else {
+            ui.label(t!("已连接: {}", inputs.ports().join(t!("、"))));
+        }
+
+        let mut remove = None;
+        for (index, binding) in self.midi_settings.bindings.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.monospace(binding.trigger.display());
+                ui.label("→");
+                ui.label(binding.action.label());
+                if ui.small_button("🗑").clicked() {
+                    remove = Some(index);
+                }
+            });
+        }
+        if let Some(index) = remove {
+            self.midi_settings.bindings.remove(index);
+        }
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("new_midi_action")
+                .selected_text(self.new_midi_action.label())
+                .show_ui(ui, |ui| {
+                    for action in [midi::MidiAction::ToggleClicking, midi::MidiAction::SingleClick] {
+                        let label = action.label();
+                        ui.selectable_value(&mut self.new_midi_action, action, label);
+                    }
+                    for profile in &self.profiles.profiles {
+                        let action = midi::MidiAction::ToggleProfile(profile.name.clone());
+                        let label = action.label();
+                        ui.selectable_value(&mut self.new_midi_action, action, label);
+                    }
+                });
+            if self.learning_midi {
+                ui.colored_label(egui::Color32::from_rgb(255, 165, 0), t!("请按下控制器上的按键..."));
+                if ui.button(t!("取消")).clicked() {
+                    self.learning_midi = false;
+                }
+            } else if ui.button(t!("学习")).on_hover_text(t!("点击后按下控制器上要绑定的按键")).clicked() {
+                self.learning_midi = true;
+            }
+        });
+    }
+
+    // 录制快捷键：记下按键的物理位置，Esc 取消
+    fn record_hotkey(&mut self, ctx: &egui::Context, target: hotkey::RecordTarget) {
+        let recorded = ctx.input(|input| {
+            input.events.iter().find_map(|event| match event {
+                egui::Event::Key { key, physical_key, pressed: true, modifiers, .. } => Some((*key, *physical_key, *modifiers)),
+                _ => None,
+            })
+        });
+        let Some((key, physical_key, modifiers)) = recorded else {
+            return;
+        };
+
+        self.recording_hotkey = None;
+        if key == egui::Key::Escape {
+            self.set_status(t!("已取消录制快捷键").to_string());
+            return;
+        }
+        let Some(hotkey) = hotkey::Hotkey::from_key_event(key, physical_key, modifiers) else {
+            self.set_status(t!("不支持把该按键设为快捷键").to_string());
+            return;
+        };
+        match target {
+            hotkey::RecordTarget::Action(action) => {
+                self.set_status(t!("「{}」的快捷键已设置为 {}", action.label(), hotkey.display()));
+                self.hotkeys.retain(|binding| binding.action != action);
+                self.hotkeys.push(hotkey::HotkeyBinding { action, hotkey });
+            }
+            hotkey::RecordTarget::Profile(index) => {
+                let Some(profile) = self.profiles.profiles.get_mut(index) else {
+                    return;
+                };
+                let message = t!("配置「{}」的快捷键已设置为 {}", profile.name, hotkey.display());
+                profile.hotkey = Some(hotkey);
+                self.set_status(message);
+            }
+        }
+    }
+
+    fn hotkeys_ui(&mut self, ui: &mut egui::Ui) {
+        for action in hotkey::HotkeyAction::ALL {
+            ui.horizontal(|ui| {
+                ui.label(format!("{}:", action.label()));
+                let binding = self.hotkeys.iter().find(|binding| binding.action == action);
+                if self.recording_hotkey == Some(hotkey::RecordTarget::Action(action)) {
+                    ui.colored_label(egui::Color32::from_rgb(255, 165, 0), t!("请按下新的快捷键（Esc 取消）..."));
+                } else {
+                    match binding {
+                        Some(binding) => ui.monospace(binding.hotkey.display()),
+                        None => ui.label(t!("未设置")),
                     };
-                    ui.colored_label(egui::Color32::RED, format!("等待{}点击中，请在屏幕任意位置点击鼠标{}...", button_name, button_name));
-                    if ui.button("取消捕捉").clicked() {
-                        self.is_picking_position = false;
-                        self.status_message = "已取消坐标捕捉".to_string();
+                    if ui.button(t!("录制")).clicked() {
+                        self.recording_hotkey = Some(hotkey::RecordTarget::Action(action));
+                    }
+                    if binding.is_some() && ui.button(t!("清除")).clicked() {
+                        self.hotkeys.retain(|binding| binding.action != action);
+                    }
+                }
+            });
+        }
+        ui.label(t!("快捷键按键盘上的物理位置记录，切换键盘布局（如 AZERTY、Dvorak）后仍对应同一个按键"));
+
+        ui.separator();
+        ui.label(t!("各配置的快捷键（按下后切换到该配置并开始执行，执行中再按一次停止）:"));
+        let locale = self.system_locale.as_deref();
+        for (index, profile) in self.profiles.profiles.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!("{}:", profile.display_name(locale)));
+                if self.recording_hotkey == Some(hotkey::RecordTarget::Profile(index)) {
+                    ui.colored_label(egui::Color32::from_rgb(255, 165, 0), t!("请按下新的快捷键（Esc 取消）..."));
+                    return;
+                }
+                match &profile.hotkey {
+                    Some(hotkey) => ui.monospace(hotkey.display()),
+                    None => ui.label(t!("未设置")),
+                };
+                if ui.button(t!("录制")).clicked() {
+                    self.recording_hotkey = Some(hotkey::RecordTarget::Profile(index));
+                }
+                if profile.hotkey.is_some() && ui.button(t!("清除")).clicked() {
+                    profile.hotkey = None;
+                }
+            });
+        }
+        // 名称只用于显示，按物理按键和修饰键判断是否重复
+        let key = |hotkey: &hotkey::Hotkey| (hotkey.scancode, hotkey.ctrl, hotkey.shift, hotkey.alt);
+        let mut used: Vec<_> = self.hotkeys.iter().map(|binding| key(&binding.hotkey)).collect();
+        for hotkey in self.profiles.profiles.iter().filter_map(|profile| profile.hotkey.as_ref()) {
+            if used.contains(&key(hotkey)) {
+                ui.colored_label(egui::Color32::YELLOW, t!("⚠️ 快捷键 {} 被设置了多次，按下时会同时触发", hotkey.display()));
+            }
+            used.push(key(hotkey));
+        }
+
+        ui.separator();
+        let hooked = self.input_hook.is_some();
+        ui.horizontal(|ui| {
+            ui.label(t!("紧急停止鼠标按钮:"));
+            egui::ComboBox::from_id_salt("stop_button")
+                .selected_text(self.stop_button.map_or(t!("无"), |button| button.label()))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.stop_button, None, t!("无"));
+                    for button in hotkey::StopButton::ALL {
+                        ui.add_enabled_ui(hooked || button.is_supported(), |ui| {
+                            ui.selectable_value(&mut self.stop_button, Some(button), button.label())
+                                .on_disabled_hover_text(t!("当前平台无法全局检测鼠标侧键"));
+                        });
+                    }
+                });
+        });
+        ui.label(t!("执行期间按下该按钮立即停止并释放所有按键，适合键盘被全屏游戏独占的场合"));
+        if self.stop_button == Some(hotkey::StopButton::Middle) && self.capture_button_type == CaptureButtonType::MiddleButton {
+            ui.colored_label(egui::Color32::YELLOW, t!("⚠️ 中键同时用于捕捉坐标，只在执行期间作为紧急停止"));
+        }
+    }
+
+    fn http_api_ui(&mut self, ui: &mut egui::Ui) {
+        ui.label(t!("启用后其他程序和脚本可以通过 HTTP 请求或 WebSocket 查看状态、开始、停止和坐标点击，请求需要携带下面发放的令牌"));
+        let mut changed = ui.checkbox(&mut self.http_api_settings.enabled, t!("启用 HTTP 控制接口")).changed();
+        ui.add_enabled_ui(self.http_api_settings.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(t!("端口:"));
+                changed |= ui.add(egui::DragValue::new(&mut self.http_api_settings.port).range(1024..=65535)).changed();
+            });
+            changed |= ui.checkbox(&mut self.http_api_settings.allow_lan, t!("允许局域网内的其他电脑访问")).changed();
+        });
+        if self.http_api_settings.enabled && self.http_api_settings.allow_lan {
+            ui.colored_label(egui::Color32::YELLOW, t!("⚠️ 请求不加密，只在可信的网络中开启，并只把令牌交给可信的客户端"));
+        }
+        if let Some(server) = &self.http_api {
+            ui.horizontal(|ui| {
+                ui.label(t!("正在监听:"));
+                ui.monospace(format!("http://{}", server.address()));
+            });
+        }
+        if changed {
+            self.restart_http_api(ui.ctx());
+        }
+    }
+
+    // 按当前设置启动或停止 HTTP 控制接口，设置改变后重新监听
+    fn restart_http_api(&mut self, ctx: &egui::Context) {
+        // 先停止旧的服务器，释放端口
+        self.http_api = None;
+        if !self.http_api_settings.enabled {
+            return;
+        }
+        let shared = http_api::Shared {
+            tokens: self.api_tokens.clone(),
+            state: self.state.clone(),
+            total_clicks: self.total_clicks.clone(),
+            engine: self.engine.clone(),
+            log: self.event_log.clone(),
+        };
+        match http_api::Server::start(&self.http_api_settings, shared, ctx) {
+            Ok(server) => {
+                self.set_status(t!("HTTP 控制接口已在 {} 上启动", server.address()));
+                self.http_api = Some(server);
+            }
+            Err(e) => self.set_status(t!("⚠️ 无法启动 HTTP 控制接口: {}", e)),
+        }
+    }
+
+    // 处理 HTTP 控制接口转来的开始和停止请求
+    fn check_http_api(&mut self) {
+        while let Some(pending) = self.http_api.as_ref().and_then(|server| server.try_recv()) {
+            let result = self.handle_control_request(&pending.request);
+            pending.reply(result);
+        }
+    }
+
+    fn pipe_control_ui(&mut self, ui: &mut egui::Ui) {
+        ui.label(t!("启用后 AutoHotkey、PowerShell 等本机脚本可以通过命名管道发送 status、start、stop、toggle、run-profile <名称> 命令，不需要令牌"));
+        if ui.checkbox(&mut self.pipe_control_enabled, t!("启用命名管道控制接口")).changed() {
+            self.restart_pipe_control(ui.ctx());
+        }
+        if self.pipe_control.is_some() {
+            ui.horizontal(|ui| {
+                ui.label(t!("管道名称:"));
+                ui.monospace(pipe_control::PIPE_NAME);
+            });
+        }
+    }
+
+    fn restart_pipe_control(&mut self, ctx: &egui::Context) {
+        self.pipe_control = None;
+        if !self.pipe_control_enabled {
+            return;
+        }
+        let shared = pipe_control::Shared {
+            state: self.state.clone(),
+            total_clicks: self.total_clicks.clone(),
+            log: self.event_log.clone(),
+        };
+        match pipe_control::Server::start(shared, ctx) {
+            Ok(server) => self.pipe_control = Some(server),
+            Err(e) => self.set_status(t!("⚠️ 无法启动命名管道控制接口: {}", e)),
+        }
+    }
+
+    fn check_pipe_control(&mut self) {
+        while let Some(pending) = self.pipe_control.as_ref().and_then(|server| server.try_recv()) {
+            let result = self.handle_control_request(&pending.request);
+            pending.reply(result);
+        }
+    }
+
+    fn udp_control_ui(&mut self, ui: &mut egui::Ui) {
+        ui.label(t!("启用后 Stream Deck 等外部按键可以向本机端口发送「<令牌> toggle <配置名称>」这样的 UDP 数据报开始和停止配置，发送「<令牌> subscribe」订阅运行状态"));
+        let mut changed = ui.checkbox(&mut self.udp_control_settings.enabled, t!("启用 UDP 控制接口")).changed();
+        ui.add_enabled_ui(self.udp_control_settings.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(t!("端口:"));
+                changed |= ui.add(egui::DragValue::new(&mut self.udp_control_settings.port).range(1024..=65535)).changed();
+            });
+        });
+        if let Some(server) = &self.udp_control {
+            ui.horizontal(|ui| {
+                ui.label(t!("正在监听:"));
+                ui.monospace(format!("udp://{}", server.address()));
+            });
+        }
+        if changed {
+            self.restart_udp_control(ui.ctx());
+        }
+    }
+
+    // 按当前设置启动或停止 UDP 控制接口，设置改变后重新监听
+    fn restart_udp_control(&mut self, ctx: &egui::Context) {
+        self.udp_control = None;
+        if !self.udp_control_settings.enabled {
+            return;
+        }
+        let shared = udp_control::Shared {
+            tokens: self.api_tokens.clone(),
+            state: self.state.clone(),
+            total_clicks: self.total_clicks.clone(),
+            log: self.event_log.clone(),
+        };
+        match udp_control::Server::start(&self.udp_control_settings, shared, ctx) {
+            Ok(server) => self.udp_control = Some(server),
+            Err(e) => self.set_status(t!("⚠️ 无法启动 UDP 控制接口: {}", e)),
+        }
+    }
+
+    fn check_udp_control(&mut self) {
+        while let Some(pending) = self.udp_control.as_ref().and_then(|server| server.try_recv()) {
+            let result = self.handle_control_request(&pending.request);
+            pending.reply(result);
+        }
+    }
+
+    fn mqtt_ui(&mut self, ui: &mut egui::Ui) {
+        ui.label(t!("启用后连接到 MQTT 代理，Home Assistant 等家庭自动化可以向 <主题>/command 发送 start、stop、toggle <配置名称> 等命令，运行状态发布到 <主题>/state"));
+        let mut changed = ui.checkbox(&mut self.mqtt_settings.enabled, t!("启用 MQTT 控制接口")).changed();
+        // 文本框每输入一个字符都会变化，编辑完成后再重新连接
+        let mut edited = false;
+        ui.add_enabled_ui(self.mqtt_settings.enabled, |ui| {
+            egui::Grid::new("mqtt_settings").num_columns(2).show(ui, |ui| {
+                ui.label(t!("代理地址:"));
+                edited |= ui.text_edit_singleline(&mut self.mqtt_settings.host).lost_focus();
+                ui.end_row();
+                ui.label(t!("端口:"));
+                changed |= ui.add(egui::DragValue::new(&mut self.mqtt_settings.port).range(1..=65535)).changed();
+                ui.end_row();
+                ui.label(t!("用户名:"));
+                edited |= ui.text_edit_singleline(&mut self.mqtt_settings.username).lost_focus();
+                ui.end_row();
+                ui.label(t!("密码:"));
+                edited |= ui.add(egui::TextEdit::singleline(&mut self.mqtt_settings.password).password(true)).lost_focus();
+                ui.end_row();
+                ui.label(t!("主题前缀:"));
+                edited |= ui.text_edit_singleline(&mut self.mqtt_settings.topic).lost_focus();
+                ui.end_row();
+            });
+        });
+        if self.mqtt_settings.enabled {
+            ui.colored_label(egui::Color32::YELLOW, t!("⚠️ 连接不加密，能向代理发布消息的客户端都能控制本程序，请在代理上设置用户名和访问控制"));
+        }
+        if let Some(server) = &self.mqtt {
+            ui.horizontal(|ui| {
+                ui.label(t!("连接状态:"));
+                ui.label(server.connection().label());
+            });
+        }
+        if changed || edited {
+            self.restart_mqtt(ui.ctx());
+        }
+    }
+
+    // 按当前设置连接或断开 MQTT 代理，设置改变后重新连接
+    fn restart_mqtt(&mut self, ctx: &egui::Context) {
+        self.mqtt = None;
+        if !self.mqtt_settings.enabled {
+            return;
+        }
+        let shared = mqtt_control::Shared {
+            state: self.state.clone(),
+            total_clicks: self.total_clicks.clone(),
+            log: self.event_log.clone(),
+        };
+        match mqtt_control::Server::start(&self.mqtt_settings, shared, ctx) {
+            Ok(server) => self.mqtt = Some(server),
+            Err(e) => self.set_status(t!("⚠️ 无法启动 MQTT 控制接口: {}", e)),
+        }
+    }
+
+    fn check_mqtt(&mut self) {
+        while let Some(pending) = self.mqtt.as_ref().and_then(|server| server.try_recv()) {
+            let result = self.handle_control_request(&pending.request);
+            pending.reply(result);
+        }
+    }
+
+    fn webhook_ui(&mut self, ui: &mut egui::Ui) {
+        ui.label(t!("执行开始、结束时向指定地址发送 JSON，可以直接填写 Slack 或 Discord 的 Webhook 地址"));
+        let mut changed = ui.checkbox(&mut self.webhook_settings.enabled, t!("启用 Webhook 通知")).changed();
+        ui.add_enabled_ui(self.webhook_settings.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(t!("地址:"));
+                changed |= ui
+                    .add(egui::TextEdit::singleline(&mut self.webhook_settings.url).hint_text("https://hooks.slack.com/…"))
+                    .lost_focus();
+                if ui.button(t!("发送测试")).clicked() {
+                    match webhook::send_test(&self.webhook_settings.url, self.event_log.clone()) {
+                        Ok(()) => self.set_status(t!("正在发送测试通知，结果见事件日志").to_string()),
+                        Err(e) => self.set_status(format!("⚠️ {}", e)),
+                    }
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label(t!("发送时机:"));
+                changed |= ui.checkbox(&mut self.webhook_settings.on_start, t!("开始")).changed();
+                changed |= ui.checkbox(&mut self.webhook_settings.on_complete, t!("完成")).changed();
+                changed |= ui.checkbox(&mut self.webhook_settings.on_abort, t!("出错或被条件中止")).changed();
+                changed |= ui.checkbox(&mut self.webhook_settings.on_stop, t!("手动停止")).changed();
+            });
+        });
+        if changed {
+            self.restart_webhook();
+        }
+    }
+
+    fn restart_webhook(&mut self) {
+        self.webhook = None;
+        if !self.webhook_settings.enabled {
+            return;
+        }
+        match webhook::start(self.webhook_settings.clone(), self.event_log.clone()) {
+            Ok(task) => self.webhook = Some(task),
+            Err(e) => self.set_status(t!("⚠️ 无法启用 Webhook 通知: {}", e)),
+        }
+    }
+
+    // 在界面线程中执行控制接口转来的请求
+    fn handle_control_request(&mut self, request: &control::Request) -> Result<(), String> {
+        match request {
+            control::Request::Start | control::Request::RunProfile(_) if self.state.is_running() => {
+                Err(t!("已经在执行").to_string())
+            }
+            // 远程开始时通常无人值守，与命令行的 --start 一样不再询问
+            control::Request::Start => {
+                self.auto_mode = true;
+                self.run_auto_clicking();
+                if self.state.is_running() { Ok(()) } else { Err(self.status_text()) }
+            }
+            control::Request::RunProfile(name) => {
+                let index = self
+                    .profiles
+                    .profiles
+                    .iter()
+                    .position(|profile| profile.name == *name)
+                    .ok_or_else(|| t!("找不到配置「{}」", name))?;
+                self.switch_profile(index);
+                self.handle_control_request(&control::Request::Start)
+            }
+            control::Request::Stop => {
+                if self.state.is_running() {
+                    self.stop_clicking();
+                }
+                Ok(())
+            }
+            control::Request::Toggle(_) if self.state.is_running() => {
+                self.stop_clicking();
+                Ok(())
+            }
+            control::Request::Toggle(Some(name)) => {
+                self.handle_control_request(&control::Request::RunProfile(name.clone()))
+            }
+            control::Request::Toggle(None) => self.handle_control_request(&control::Request::Start),
+        }
+    }
+
+    fn api_tokens_ui(&mut self, ui: &mut egui::Ui) {
+        ui.label(t!("外部程序访问控制接口时需要携带令牌，令牌的权限范围决定它能执行哪些操作"));
+
+        egui::Grid::new("scope_matrix").striped(true).show(ui, |ui| {
+            ui.label("");
+            for command in control::Command::ALL {
+                ui.label(command.label());
+            }
+            ui.end_row();
+            for scope in control::Scope::ALL {
+                ui.label(scope.label());
+                for command in control::Command::ALL {
+                    ui.label(if scope.allows(command) { "✅" } else { "—" });
+                }
+                ui.end_row();
+            }
+        });
+
+        ui.separator();
+        let mut revoke = None;
+        let tokens = self.api_tokens.lock().unwrap().tokens.clone();
+        for token in &tokens {
+            ui.horizontal(|ui| {
+                ui.label(if token.name.is_empty() { t!("（未命名）") } else { &token.name });
+                ui.label(format!("[{}]", token.scope.label()));
+                ui.monospace(format!("{}…", &token.token[..8.min(token.token.len())]));
+                if ui.button(t!("复制")).clicked() {
+                    ui.ctx().copy_text(token.token.clone());
+                    self.set_status(t!("令牌已复制到剪贴板").to_string());
+                }
+                if ui.button(t!("吊销")).clicked() {
+                    revoke = Some(token.token.clone());
+                }
+            });
+        }
+        if tokens.is_empty() {
+            ui.label(t!("还没有发放任何令牌"));
+        }
+        if let Some(token) = revoke {
+            let message = match self.api_tokens.lock().unwrap().revoke(&token) {
+                Ok(()) => t!("令牌已吊销").to_string(),
+                Err(e) => t!("吊销令牌失败: {}", e),
+            };
+            self.set_status(message);
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(t!("名称:"));
+            ui.add(egui::TextEdit::singleline(&mut self.new_token_name)
+                .hint_text(t!("如 仪表盘"))
+                .desired_width(100.0));
+            egui::ComboBox::from_id_salt("new_token_scope")
+                .selected_text(self.new_token_scope.label())
+                .show_ui(ui, |ui| {
+                    for scope in control::Scope::ALL {
+                        ui.selectable_value(&mut self.new_token_scope, scope, scope.label());
+                    }
+                });
+            if ui.button(t!("发放令牌")).clicked() {
+                let message = match self.api_tokens.lock().unwrap().issue(&self.new_token_name, self.new_token_scope) {
+                    Ok(token) => {
+                        ui.ctx().copy_text(token.token.clone());
+                        t!("已发放「{}」令牌并复制到剪贴板", token.scope.label())
+                    }
+                    Err(e) => t!("发放令牌失败: {}", e),
+                };
+                self.set_status(message);
+                self.new_token_name.clear();
+            }
+        });
+    }
+
+    // 多击间隔：默认跟随系统双击时间，也可以手动指定
+    fn multi_click_gap_ui(&mut self, ui: &mut egui::Ui) {
+        let threshold = cross_platform_mouse::double_click_time();
+        ui.horizontal(|ui| {
+            ui.label(t!("多击间隔:"));
+            let mut custom = self.multi_click_gap_ms.is_some();
+            if ui.checkbox(&mut custom, t!("自定义")).changed() {
+                self.multi_click_gap_ms = custom.then(|| multi_click_gap(None).as_millis() as u64);
+            }
+            match &mut self.multi_click_gap_ms {
+                Some(ms) => {
+                    ui.add(egui::DragValue::new(ms).range(settings::MULTI_CLICK_GAP_RANGE).suffix(" ms"));
+                }
+                None => {
+                    ui.label(t!("跟随系统（{} ms）", multi_click_gap(None).as_millis()));
+                }
+            }
+        });
+        match threshold {
+            Some(threshold) => {
+                if multi_click_gap(self.multi_click_gap_ms) >= threshold {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(255, 165, 0),
+                        t!("⚠️ 间隔不短于系统双击时间 {} ms，双击会被识别为两次单击", threshold.as_millis()),
+                    );
+                }
+            }
+            None => {
+                ui.label(t!("无法读取系统双击时间，按 {} ms 估算", FALLBACK_DOUBLE_CLICK_TIME.as_millis()));
+            }
+        }
+    }
+
+    fn bookmarks_ui(&mut self, ui: &mut egui::Ui) {
+        let mut remove = None;
+        for (i, bookmark) in self.bookmarks.iter().enumerate() {
+            ui.horizontal(|ui| {
+                if let Some(thumbnail) = &bookmark.thumbnail {
+                    self.thumbnail_textures.show(ui, thumbnail);
+                }
+                ui.label(&bookmark.name);
+                ui.monospace(format!("({}, {})", bookmark.x, bookmark.y));
+                if ui.button(t!("使用")).clicked() {
+                    self.x_pos = bookmark.x;
+                    self.y_pos = bookmark.y;
+                    self.target_thumbnail = bookmark.thumbnail.clone();
+                    bookmarks::push_recent(&mut self.recent_coordinates, bookmark.x, bookmark.y);
+                    let message = t!("已使用书签「{}」: ({}, {})", bookmark.name, bookmark.x, bookmark.y);
+                    self.event_log.lock().unwrap().push(event_log::EntryKind::Status, message);
+                }
+                if ui.button(t!("删除")).clicked() {
+                    remove = Some(i);
+                }
+            });
+        }
+        if let Some(i) = remove {
+            self.bookmarks.remove(i);
+        }
+        if self.bookmarks.is_empty() {
+            ui.label(t!("还没有书签，先捕捉坐标再保存"));
+        }
+
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.new_bookmark_name)
+                .hint_text(t!("书签名称"))
+                .desired_width(120.0));
+            if ui.button(t!("保存当前坐标 ({}, {})", self.x_pos, self.y_pos)).clicked() {
+                let thumbnail = self.current_thumbnail().cloned();
+                let name = bookmarks::add(&mut self.bookmarks, &self.new_bookmark_name, self.x_pos, self.y_pos, thumbnail);
+                self.set_status(t!("已保存书签「{}」", name));
+                self.new_bookmark_name.clear();
+            }
+        });
+    }
+
+    // 光标处颜色的实时读数和取色记录
+    fn color_picker_ui(&mut self, ui: &mut egui::Ui) {
+        let position = self.engine.get_mouse_position().ok();
+        match position.and_then(|(x, y)| color_picker::read(x, y)) {
+            Some(live) => {
+                ui.horizontal(|ui| {
+                    color_picker::swatch(ui, live.color);
+                    ui.monospace(format!("{}  RGB({})  ({}, {})", live.hex(), live.rgb(), live.x, live.y));
+                });
+                ui.ctx().request_repaint_after(Duration::from_millis(100));
+            }
+            None => {
+                ui.weak(t!("实时读取像素颜色仅支持 Windows，其他平台可以用快捷键取色"));
+            }
+        }
+        match self.hotkeys.iter().find(|binding| binding.action == hotkey::HotkeyAction::PickColor) {
+            Some(binding) => ui.weak(t!("按 {} 读取光标处的颜色并复制到剪贴板", binding.hotkey.display())),
+            None => ui.weak(t!("在「全局快捷键」中设置取色快捷键后，按下即可读取光标处的颜色")),
+        };
+
+        for picked in &self.picked_colors {
+            ui.horizontal(|ui| {
+                color_picker::swatch(ui, picked.color);
+                ui.monospace(format!("{}  RGB({})  ({}, {})", picked.hex(), picked.rgb(), picked.x, picked.y));
+                if ui.small_button(t!("复制")).on_hover_text(t!("复制十六进制颜色")).clicked() {
+                    ui.ctx().copy_text(picked.hex());
+                }
+                if ui.small_button("RGB").on_hover_text(t!("复制 RGB 数值")).clicked() {
+                    ui.ctx().copy_text(picked.rgb());
+                }
+            });
+        }
+        if !self.picked_colors.is_empty() && ui.button(t!("清除记录")).clicked() {
+            self.picked_colors.clear();
+        }
+    }
+
+    // 与当前坐标一致的目标缩略图
+    fn current_thumbnail(&self) -> Option<&thumbnail::Thumbnail> {
+        self.target_thumbnail.as_ref().filter(|thumbnail| (thumbnail.x, thumbnail.y) == (self.x_pos, self.y_pos))
+    }
+
+    fn autostart_ui(&mut self, ui: &mut egui::Ui) {
+        let mut enabled = self.autostart.is_some();
+        let mut minimized = self.autostart.is_some_and(|status| status.minimized);
+        let before = (enabled, minimized);
+        ui.checkbox(&mut enabled, t!("登录系统时自动启动"));
+        ui.add_enabled(enabled, egui::Checkbox::new(&mut minimized, t!("启动时最小化窗口")));
+        if (enabled, minimized) != before {
+            let result = if enabled { autostart::enable(minimized) } else { autostart::disable() };
+            self.set_status(match result {
+                Ok(()) if enabled => t!("已设置登录时自动启动").to_string(),
+                Ok(()) => t!("已取消登录时自动启动").to_string(),
+                Err(e) => t!("设置自动启动失败: {}", e),
+            });
+            self.autostart = autostart::status();
+        }
+        ui.label(t!("只对当前用户生效；移动程序位置后需要重新勾选"));
+    }
+
+    fn tray_ui(&mut self, ui: &mut egui::Ui) {
+        let mut enabled = self.tray.is_some();
+        let response = ui.add_enabled(tray::Tray::is_supported(), egui::Checkbox::new(&mut enabled, t!("显示托盘图标")));
+        response.on_disabled_hover_text(i18n::tr(tray::UNSUPPORTED_MESSAGE));
+        if enabled != self.tray.is_some() {
+            if enabled {
+                match tray::Tray::new(ui.ctx()) {
+                    Ok(tray) => self.tray = Some(tray),
+                    Err(e) => self.set_status(t!("无法显示托盘图标: {}", e)),
+                }
+            } else {
+                self.tray = None;
+            }
+        }
+        ui.add_enabled(
+            self.tray.is_some(),
+            egui::Checkbox::new(&mut self.hide_to_tray_while_running, t!("开始执行时隐藏到托盘，执行结束后恢复窗口")),
+        );
+        if ui.add_enabled(self.tray.is_some(), egui::Button::new(t!("隐藏到托盘"))).clicked() {
+            self.hide_to_tray(ui.ctx());
+        }
+        ui.label(t!("图标灰色表示空闲、绿色表示正在执行、橙色表示已暂停；右键菜单可以开始、停止、显示窗口和退出，双击显示窗口"));
+    }
+
+    fn set_compact_mode(&mut self, ctx: &egui::Context, compact: bool) {
+        if compact == self.compact_mode {
+            return;
+        }
+        self.compact_mode = compact;
+        if compact {
+            self.full_window_size = ctx.input(|input| input.viewport().inner_rect.map(|rect| rect.size()));
+            ctx.send_viewport_cmd(egui::ViewportCommand::MinInnerSize(COMPACT_WINDOW_SIZE.into()));
+            ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(COMPACT_WINDOW_SIZE.into()));
+            ctx.send_viewport_cmd(egui::ViewportCommand::Decorations(false));
+            ctx.send_viewport_cmd(egui::ViewportCommand::WindowLevel(egui::WindowLevel::AlwaysOnTop));
+        } else {
+            let size = self.full_window_size.take().unwrap_or(WINDOW_SIZE.into());
+            ctx.send_viewport_cmd(egui::ViewportCommand::Decorations(true));
+            ctx.send_viewport_cmd(egui::ViewportCommand::WindowLevel(egui::WindowLevel::Normal));
+            ctx.send_viewport_cmd(egui::ViewportCommand::MinInnerSize(MIN_WINDOW_SIZE.into()));
+            ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(size.max(MIN_WINDOW_SIZE.into())));
+        }
+    }
+
+    // 迷你模式的工具条：开始/停止、目标坐标和剩余次数
+    fn compact_toolbar(&mut self, ctx: &egui::Context) {
+        let running = self.state.is_running();
+        let target = match self.auto_click_mode {
+            AutoClickMode::SinglePoint => format!("🎯 ({}, {})", self.x_pos, self.y_pos),
+            AutoClickMode::TwoPoint => format!(
+                "🎯 A({}, {}) B({}, {})",
+                self.point_a.x, self.point_a.y, self.point_b.x, self.point_b.y
+            ),
+            AutoClickMode::Sequence => t!("🎯 序列 {} 步", self.custom_sequence.steps.len()),
+        };
+        let remaining = match &self.current_run {
+            _ if self.state.get() == AppState::Countdown => self.state_text(),
+            Some(run) if running && run.finished.is_none() => {
+                let done = self.total_clicks.lock().unwrap().saturating_sub(run.clicks_before);
+                t!("剩余 {} 次", run.remaining.saturating_sub(done))
+            }
+            _ => t!("共 {} 次", self.click_count),
+        };
+        let paused = self.power_pause.lock().unwrap().clone();
+
+        let mut expand = false;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            // 没有标题栏，按住空白处拖动窗口
+            let background = ui.interact(ui.max_rect(), ui.id().with("compact_drag"), egui::Sense::drag());
+            if background.drag_started() {
+                ctx.send_viewport_cmd(egui::ViewportCommand::StartDrag);
+            }
+            ui.horizontal_centered(|ui| {
+                if running {
+                    if ui.button(t!("⏹ 停止")).clicked() {
+                        self.stop_clicking();
+                    }
+                } else if ui.button(t!("▶ 开始")).clicked() {
+                    self.start_auto_clicking();
+                }
+                ui.label(target);
+                ui.label(remaining).on_hover_text(self.status_text());
+                if let Some(reason) = paused {
+                    ui.colored_label(egui::Color32::YELLOW, "⏸").on_hover_text(reason);
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    expand = ui.button("⛶").on_hover_text(t!("展开完整窗口")).clicked();
+                });
+            });
+        });
+        if expand {
+            self.set_compact_mode(ctx, false);
+        }
+    }
+
+    fn hide_to_tray(&mut self, ctx: &egui::Context) {
+        ctx.send_viewport_cmd(egui::ViewportCommand::Visible(false));
+        self.hidden_to_tray = true;
+    }
+
+    fn show_from_tray(&mut self, ctx: &egui::Context) {
+        ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+        ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+        ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+        self.hidden_to_tray = false;
+    }
+
+    // 应用之后启动的实例转交过来的命令行参数，并把窗口切换到前台
+    fn check_forwarded_args(&mut self, ctx: &egui::Context) {
+        while let Some(args) = self.forwarded_args.try_recv() {
+            let launch = match cli::parse_args(args.clone()) {
+                Ok(launch) => launch,
+                Err(e) => {
+                    self.set_status(t!("⚠️ 另一个实例的命令行参数有误: {}", e));
+                    continue;
+                }
+            };
+            if !launch.minimized {
+                self.show_from_tray(ctx);
+            }
+            if args.is_empty() {
+                continue;
+            }
+            let mut settings = self.settings();
+            // 保存时恢复的值：去掉之前的命令行参数，包含这次的配置文件
+            let mut original = self.stored_settings();
+            match config::path_from_args(args).and_then(|path| match path {
+                Some(path) => Ok(config::load(&path, true)?.map(|config| (path, config))),
+                None => Ok(None),
+            }) {
+                Ok(Some((path, config))) => {
+                    match config::apply(&settings, &config).and_then(|merged| Ok((merged, config::apply(&original, &config)?))) {
+                        Ok((merged, merged_original)) => {
+                            settings = merged;
+                            original = merged_original;
+                            self.set_status(t!("已加载配置文件: {}", path.display()));
+                        }
+                        Err(e) => self.set_status(t!("⚠️ 配置文件 {} 有误: {}", path.display(), e)),
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => self.set_status(t!("⚠️ 另一个实例的命令行参数有误: {}", e)),
+            }
+            launch.apply(&mut settings);
+            self.apply_settings(settings);
+            self.set_status(t!("已应用另一个实例转交的命令行参数"));
+            // 与启动时的 --start 相同，不再询问
+            let start = launch.start;
+            let mut overrides = launch;
+            if let Some((previous, _)) = &self.launch_overrides {
+                overrides.inherit(previous);
+            }
+            self.launch_overrides = Some((overrides, original));
+            if start && !self.state.is_running() {
+                self.auto_mode = true;
+                self.run_auto_clicking();
+            }
+        }
+    }
+
+    // 同步托盘图标的状态并处理托盘菜单的命令
+    fn check_tray(&mut self, ctx: &egui::Context) {
+        let running = self.state.is_running();
+        // 执行结束（包括通过快捷键停止）后恢复窗口；托盘被关掉时也要恢复，否则窗口再也找不回来
+        if self.restore_after_run && (!running || self.tray.is_none()) {
+            self.restore_after_run = false;
+            if self.hidden_to_tray {
+                self.show_from_tray(ctx);
+            }
+        }
+        if self.tray.is_none() {
+            self.pending_hide_to_tray = false;
+            return;
+        }
+        if std::mem::take(&mut self.pending_hide_to_tray) && running && !self.hidden_to_tray {
+            self.hide_to_tray(ctx);
+            self.restore_after_run = true;
+        }
+        let Some(tray) = &mut self.tray else {
+            return;
+        };
+        let state = match self.state.get() {
+            AppState::Idle | AppState::Picking => tray::TrayState::Idle,
+            AppState::Paused => tray::TrayState::Paused,
+            AppState::Countdown | AppState::Running | AppState::Stopping => tray::TrayState::Running,
+        };
+        tray.set_state(state);
+
+        while let Some(command) = self.tray.as_ref().and_then(|tray| tray.try_recv()) {
+            match command {
+                tray::TrayCommand::Start => self.start_auto_clicking(),
+                tray::TrayCommand::Stop => self.stop_clicking(),
+                tray::TrayCommand::Show => self.show_from_tray(ctx),
+                tray::TrayCommand::Quit => ctx.send_viewport_cmd(egui::ViewportCommand::Close),
+            }
+        }
+    }
+
+    fn font_script_ui(&mut self, ui: &mut egui::Ui) {
+        let detected = locale::detect_script();
+        let auto_label = t!("自动（{}）", detected.label());
+        let before = (self.font_script, self.system_font);
+        ui.horizontal(|ui| {
+            ui.label(t!("字体偏好:"));
+            egui::ComboBox::from_id_salt("font_script")
+                .selected_text(self.font_script.map_or(auto_label.as_str(), |script| script.label()))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.font_script, None, &auto_label);
+                    for script in locale::Script::ALL {
+                        ui.selectable_value(&mut self.font_script, Some(script), script.label());
+                    }
+                });
+        });
+        if locale::EMBEDDED_FONT.is_some() {
+            ui.checkbox(&mut self.system_font, t!("优先使用系统字体"))
+                .on_hover_text(t!("繁体中文、日文和韩文使用系统字体能显示本地的字形；找不到系统字体时仍使用内置字体"));
+        }
+        if (self.font_script, self.system_font) != before {
+            Self::setup_fonts(ui.ctx(), self.font_script.unwrap_or(detected), self.system_font);
+        }
+    }
+
+    fn theme_ui(&mut self, ui: &mut egui::Ui) {
+        let before = self.theme;
+        ui.horizontal(|ui| {
+            ui.label(t!("界面主题:"));
+            ui.selectable_value(&mut self.theme, egui::ThemePreference::System, t!("跟随系统"));
+            ui.selectable_value(&mut self.theme, egui::ThemePreference::Dark, t!("深色"));
+            ui.selectable_value(&mut self.theme, egui::ThemePreference::Light, t!("浅色"));
+        });
+        if self.theme != before {
+            ui.ctx().set_theme(self.theme);
+        }
+    }
+
+    fn language_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(t!("界面语言:"));
+            egui::ComboBox::from_id_salt("language")
+                .selected_text(self.language.label())
+                .show_ui(ui, |ui| {
+                    for language in i18n::Language::ALL {
+                        if ui.selectable_value(&mut self.language, language, language.label()).changed() {
+                            i18n::set_language(language);
+                        }
                     }
-                }
-            });
+                });
+        });
+    }
 
-            // 捕捉按钮类型选择
-            ui.horizontal(|ui| {
-                ui.label("捕捉按钮:");
-                ui.radio_value(&mut self.capture_button_type, CaptureButtonType::MiddleButton, "中键");
-                ui.radio_value(&mut self.capture_button_type, CaptureButtonType::RightButton, "右键");
-            });
+    fn log_level_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(t!("日志级别:"));
+            egui::ComboBox::from_id_salt("log_level")
+                .selected_text(self.log_level.label())
+                .show_ui(ui, |ui| {
+                    for level in logging::LogLevel::ALL {
+                        if ui.selectable_value(&mut self.log_level, level, level.label()).changed() {
+                            logging::set_level(level);
+                        }
+                    }
+                });
+        })
+        .response
+        .on_hover_text(t!("报告问题时请附上日志文件，需要更详细的信息时选择「调试」"));
+        ui.label(t!(
+            "日志目录: {}",
+            logging::logs_dir().map_or(t!("未知").to_string(), |dir| dir.display().to_string())
+        ));
+    }
 
-            ui.separator();
+    fn set_status(&self, text: impl Into<String>) {
+        self.event_log.lock().unwrap().push(event_log::EntryKind::Status, text);
+    }
 
-            // 点击类型选择
-            ui.horizontal(|ui| {
-                ui.label("点击类型:");
-                ui.radio_value(&mut self.click_type, ClickType::Left, "左键");
-                ui.radio_value(&mut self.click_type, ClickType::Right, "右键");
-                ui.radio_value(&mut self.click_type, ClickType::Middle, "中键");
-            });
+    fn status_text(&self) -> String {
+        self.event_log.lock().unwrap().status().unwrap_or_default().to_string()
+    }
 
-            ui.separator();
+    // 状态栏中的运行状态，倒计时中加上剩余秒数
+    fn state_text(&self) -> String {
+        let state = self.state.get();
+        match &self.current_run {
+            Some(run) if state == AppState::Countdown => {
+                let left = run.started.saturating_duration_since(Instant::now());
+                t!("{}（{} 秒）", state.label(), left.as_secs() + 1)
+            }
+            _ => state.label().to_string(),
+        }
+    }
 
-            // 单次点击
-            ui.horizontal(|ui| {
-                if ui.button("单次点击").clicked() {
-                    self.perform_single_click();
-                    self.status_message = "执行单次点击".to_string();
-                }
-            });
+    fn event_log_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui.button(t!("复制到剪贴板")).clicked() {
+                let text = self.event_log.lock().unwrap().to_text(self.log_show_clicks);
+                ui.ctx().copy_text(text);
+            }
+            if ui.button(t!("清空")).clicked() {
+                self.event_log.lock().unwrap().clear();
+            }
+            ui.checkbox(&mut self.log_show_clicks, t!("显示每次点击"));
+        });
+        egui::ScrollArea::vertical().max_height(200.0).stick_to_bottom(true).auto_shrink([false, true]).show(ui, |ui| {
+            let log = self.event_log.lock().unwrap();
+            for entry in log.entries().filter(|entry| self.log_show_clicks || entry.kind != event_log::EntryKind::Click) {
+                ui.horizontal(|ui| {
+                    ui.weak(&entry.time);
+                    ui.label(&entry.text);
+                });
+            }
+        });
+    }
 
-            ui.separator();
+    // 在新的工作线程中执行 `f`，同时清理已经结束的线程
+    fn spawn_worker(&mut self, f: impl FnOnce() + Send + 'static) {
+        self.workers.retain(|worker| !worker.is_finished());
+        self.workers.push(thread::spawn(f));
+    }
+
+    // 退出前停止执行、取消后台任务并等待工作线程结束，最后释放所有被按住的输入，
+    // 不会留下还在点击的线程
+    fn shutdown(&mut self) {
+        self.state.stop();
+        let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+        if !tasks::shutdown(SHUTDOWN_TIMEOUT) {
+            tracing::warn!("后台任务没有在规定时间内结束");
+        }
+        for worker in self.workers.drain(..) {
+            while !worker.is_finished() && Instant::now() < deadline {
+                thread::sleep(SHUTDOWN_POLL);
+            }
+            if !worker.is_finished() {
+                tracing::warn!("工作线程没有在规定时间内结束");
+            } else if worker.join().is_err() {
+                tracing::error!("工作线程 panic");
+            }
+        }
+        self.release_all_inputs();
+        tracing::info!("已退出");
+    }
+
+    fn stop_clicking(&mut self) {
+        self.state.stop();
+        // 不等工作线程结束，立即释放所有被按住的输入
+        self.release_all_inputs();
+        self.set_status(t!("已停止").to_string());
+    }
+
+    // 紧急释放所有被按住的输入；工作线程 panic 时引擎仍然可用，照样能释放
+    fn release_all_inputs(&self) {
+        self.engine.release_all();
+    }
+}
+
+impl Drop for MouseClickerApp {
+    // 界面线程 panic 展开时也会走到这里，保证不会留下被按住的输入
+    fn drop(&mut self) {
+        self.state.stop();
+        tasks::cancel_all();
+        self.release_all_inputs();
+    }
+}
+
+impl eframe::App for MouseClickerApp {
+    // 回放轨迹叠加层需要透明背景；主窗口由面板完全覆盖，不受影响
+    fn clear_color(&self, _visuals: &egui::Visuals) -> [f32; 4] {
+        egui::Rgba::TRANSPARENT.to_array()
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.shutdown();
+    }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        storage.set_string(LAST_SEEN_VERSION_KEY, env!("CARGO_PKG_VERSION").to_string());
+        eframe::set_value(storage, HOTKEYS_KEY, &self.hotkeys);
+        eframe::set_value(storage, GAMEPAD_KEY, &self.gamepad_bindings);
+        eframe::set_value(storage, MIDI_KEY, &self.midi_settings);
+        eframe::set_value(storage, STOP_BUTTON_KEY, &self.stop_button);
+        eframe::set_value(storage, TRAY_KEY, &self.tray.is_some());
+        eframe::set_value(storage, HTTP_API_KEY, &self.http_api_settings);
+        eframe::set_value(storage, PIPE_CONTROL_KEY, &self.pipe_control_enabled);
+        eframe::set_value(storage, UDP_CONTROL_KEY, &self.udp_control_settings);
+        eframe::set_value(storage, MQTT_KEY, &self.mqtt_settings);
+        eframe::set_value(storage, WEBHOOK_KEY, &self.webhook_settings);
+        eframe::set_value(storage, HIDE_TO_TRAY_KEY, &self.hide_to_tray_while_running);
+        self.store_active_profile();
+        eframe::set_value(storage, PROFILES_KEY, &self.profiles);
+        eframe::set_value(storage, FONT_SCRIPT_KEY, &self.font_script);
+        eframe::set_value(storage, SYSTEM_FONT_KEY, &self.system_font);
+        eframe::set_value(storage, THEME_KEY, &self.theme);
+        eframe::set_value(storage, LANGUAGE_KEY, &self.language);
+        eframe::set_value(storage, LOG_LEVEL_KEY, &self.log_level);
+        eframe::set_value(storage, BOOKMARKS_KEY, &self.bookmarks);
+        eframe::set_value(storage, RECENT_COORDINATES_KEY, &self.recent_coordinates);
+        eframe::set_value(storage, TARGET_THUMBNAIL_KEY, &self.target_thumbnail);
+        eframe::set_value(storage, CONFIRM_EXTREME_KEY, &self.confirm_extreme_runs);
+        eframe::set_value(storage, BUTTON_MAPPING_KEY, &self.button_mapping);
+        eframe::set_value(storage, INPUT_BACKEND_KEY, &self.input_backend);
+        eframe::set_value(storage, TOTAL_CLICKS_KEY, &*self.total_clicks.lock().unwrap());
+    }
+
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        if std::mem::take(&mut self.minimize_on_start) {
+            if self.tray.is_some() {
+                self.hide_to_tray(ctx);
+            } else {
+                ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(true));
+            }
+        }
+        self.check_tray(ctx);
+        self.check_forwarded_args(ctx);
+        self.check_http_api();
+        self.check_pipe_control();
+        self.check_udp_control();
+        self.check_mqtt();
+        self.check_close_request(ctx);
+        // 修改的设置和切换配置后的设置都交给正在运行的防离开线程
+        if let Some(jiggler) = &self.jiggler {
+            jiggler.update(&self.jiggler_settings);
+        }
+        if let Some(hook) = &self.input_hook {
+            self.hook_events = hook.drain();
+            hook.set_wake_on_motion(self.is_picking());
+        }
+        // 检查是否在拾取坐标模式
+        self.check_position_picking();
+        self.check_hotkeys(ctx);
+        self.check_gamepad();
+        self.check_midi();
+        if self.show_trail {
+            self.trail_overlay(ctx);
+        }
+        if self.show_target_marker {
+            self.target_marker_overlay(ctx);
+        }
+        if self.is_picking() {
+            self.loupe_overlay(ctx);
+        }
+        self.screenshot_pick_window(ctx);
+        if self.compact_mode {
+            self.compact_toolbar(ctx);
+            ctx.request_repaint_after(Duration::from_millis(100));
+            return;
+        }
 
-            // 自动点击设置
-            ui.checkbox(&mut self.auto_mode, "自动点击模式");
+        if self.show_whats_new {
+            self.whats_new_window(ctx);
+        }
+        self.recovery_window(ctx);
+        self.run_confirmation_window(ctx);
+        self.close_confirmation_window(ctx);
+        self.simulation_window(ctx);
+        if self.show_errors {
+            self.errors_window(ctx);
+        }
+        self.check_permissions(ctx);
+        self.check_elevation();
+        if self.show_permissions {
+            self.permissions_window(ctx);
+        }
 
-            if self.auto_mode {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
                 ui.horizontal(|ui| {
-                    ui.label("点击间隔(秒):");
-                    ui.add(egui::DragValue::new(&mut self.click_interval)
-                        .range(0.1..=10.0)
-                        .speed(0.1));
+                    ui.heading(t!("🖱️ 跨平台鼠标点击工具"));
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button(t!("🆕 新功能")).clicked() {
+                            self.whats_new = changelog::CHANGELOG.iter().collect();
+                            self.show_whats_new = true;
+                        }
+                        if ui.button(t!("🗕 迷你模式")).on_hover_text(t!("收起为置顶的小工具条")).clicked() {
+                            self.set_compact_mode(ctx, true);
+                        }
+                    });
                 });
+                self.profile_ui(ui);
+                ui.separator();
 
-                ui.horizontal(|ui| {
-                    ui.label("点击次数:");
-                    ui.add(egui::DragValue::new(&mut self.click_count)
-                        .range(1..=1000));
+                // uinput 注入的点击不受 Wayland 限制
+                let uinput_active = self.input_backend == backend::Backend::Uinput && self.input_backend_error.is_none();
+                if let Some(message) = wayland::diagnostic().filter(|_| !uinput_active) {
+                    ui.colored_label(egui::Color32::from_rgb(255, 165, 0), format!("⚠️ {}", i18n::tr(message)));
+                    ui.separator();
+                }
+
+                if !self.missing_permissions.is_empty() {
+                    ui.horizontal_wrapped(|ui| {
+                        let names: Vec<&str> = self.missing_permissions.iter().map(|p| p.label()).collect();
+                        ui.colored_label(
+                            egui::Color32::from_rgb(255, 165, 0),
+                            t!("⚠️ 缺少 macOS 权限: {}，点击和快捷键可能无效", names.join(t!("、"))),
+                        );
+                        if ui.button(t!("🔐 设置权限")).clicked() {
+                            self.show_permissions = true;
+                        }
+                    });
+                    ui.separator();
+                }
+
+                if self.elevated_target {
+                    ui.horizontal_wrapped(|ui| {
+                        ui.colored_label(
+                            egui::Color32::from_rgb(255, 165, 0),
+                            t!("⚠️ 目标坐标处的窗口以管理员权限运行，会忽略本工具发出的点击"),
+                        );
+                        if ui.button(t!("🛡 以管理员身份重新启动")).clicked() {
+                            self.restart_elevated(ctx);
+                        }
+                    });
+                    ui.separator();
+                }
+
+                // 注入失败时界面上看起来和成功一样，必须明确提示
+                let (unseen, last_error) = {
+                    let errors = self.engine.errors().lock().unwrap();
+                    (errors.unseen(), errors.last().map(|record| record.error.to_string()))
+                };
+                if unseen > 0 {
+                    ui.horizontal_wrapped(|ui| {
+                        ui.colored_label(
+                            egui::Color32::LIGHT_RED,
+                            t!("⛔ 有 {} 次输入注入失败，点击可能没有生效: {}", unseen, last_error.unwrap_or_default()),
+                        );
+                        if ui.button(t!("查看错误")).clicked() {
+                            self.show_errors = true;
+                        }
+                    });
+                    ui.separator();
+                }
+
+                // 如果在捕捉模式，添加醒目的提示框
+                if self.is_picking() {
+                    ui.allocate_ui_with_layout(
+                        [ui.available_width(), 60.0].into(),
+                        egui::Layout::top_down(egui::Align::Center),
+                        |ui| {
+                            ui.add_space(10.0);
+                            ui.colored_label(egui::Color32::from_rgb(255, 165, 0), t!("🎯 坐标捕捉模式激活中"));
+                            ui.colored_label(egui::Color32::LIGHT_RED, t!("请在屏幕任意位置点击鼠标中键（滚轮键）来捕捉坐标"));
+                            ui.add_space(10.0);
+                        }
+                    );
+                    ui.separator();
+                }
+
+                // 坐标设置
+                let coordinate_row = ui.horizontal(|ui| {
+                    ui.label(t!("点击坐标:"));
+
+                    // 在捕捉模式下高亮显示坐标输入框
+                    if self.is_picking() {
+                        ui.style_mut().visuals.extreme_bg_color = egui::Color32::from_rgb(255, 255, 200);
+                    }
+
+                    ui.add(egui::DragValue::new(&mut self.x_pos).prefix("X: "));
+                    ui.add(egui::DragValue::new(&mut self.y_pos).prefix("Y: "));
+                    if let Some(thumbnail) = self.current_thumbnail().cloned() {
+                        self.thumbnail_textures.show(ui, &thumbnail);
+                    }
+
+                    if !self.recent_coordinates.is_empty() {
+                        ui.menu_button("🕘", |ui| {
+                            for &(x, y) in &self.recent_coordinates {
+                                if ui.button(format!("({}, {})", x, y)).clicked() {
+                                    self.x_pos = x;
+                                    self.y_pos = y;
+                                    ui.close_menu();
+                                }
+                            }
+                            ui.separator();
+                            if ui.button(t!("清除记录")).clicked() {
+                                self.recent_coordinates.clear();
+                                ui.close_menu();
+                            }
+                        }).response.on_hover_text(t!("最近使用的坐标"));
+                    }
+                    ui.toggle_value(&mut self.show_target_marker, "⌖")
+                        .on_hover_text(t!("在屏幕上标记将要点击的位置（仅主显示器）"));
+                    if ui.button("📋").on_hover_text(t!("复制坐标（鼠标在这一行上时也可以按 Ctrl+C）")).clicked() {
+                        self.copy_coordinates(ui.ctx());
+                    }
+                    if ui.button("📥").on_hover_text(t!("粘贴 x,y 或 (x, y) 格式的坐标（鼠标在这一行上时也可以按 Ctrl+V）")).clicked() {
+                        self.coordinate_paste_requested = Some(ctx.cumulative_pass_nr());
+                        ctx.send_viewport_cmd(egui::ViewportCommand::RequestPaste);
+                    }
+
+                    if self.is_picking() {
+                        ui.label(t!("👈 坐标将自动填入这里"));
+                    }
                 });
+                self.coordinate_clipboard(ctx, coordinate_row.response.contains_pointer());
+                if !display::contains(&self.displays, self.x_pos, self.y_pos) {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(255, 165, 0),
+                        format!("⚠️ {}", t!("({}, {}) 不在任何显示器上", self.x_pos, self.y_pos)),
+                    );
+                }
 
-                ui.horizontal(|ui| {
-                    let is_clicking = *self.is_clicking.lock().unwrap();
+                // 只有一个显示器时不需要选择
+                if self.displays.len() > 1 || self.display_name.is_some() {
+                    ui.horizontal(|ui| self.display_ui(ui));
+                }
 
-                    if !is_clicking {
-                        if ui.button("开始自动点击").clicked() {
-                            self.start_auto_clicking();
+                ui.horizontal(|ui| {
+                    if !self.is_picking() {
+                        if ui.button(t!("捕捉坐标")).clicked() {
+                            self.start_position_picking();
+                        }
+                        if ui.button(t!("从截图选取")).on_hover_text(t!("截取整个屏幕，在截图上点击目标位置")).clicked() {
+                            self.start_screenshot_pick(ctx);
+                        }
+                        if ui.button(t!("获取当前位置")).clicked() {
+                            self.get_current_mouse_pos();
+                        }
+                        if ui.button(t!("获取屏幕信息")).clicked() {
+                            self.get_screen_info();
                         }
                     } else {
-                        if ui.button("停止点击").clicked() {
-                            self.stop_clicking();
+                        let button_name = match self.capture_button_type {
+                            CaptureButtonType::MiddleButton => t!("中键"),
+                            CaptureButtonType::RightButton => t!("右键"),
+                        };
+                        ui.colored_label(egui::Color32::RED, t!("等待{}点击中，请在屏幕任意位置点击鼠标{}...", button_name, button_name));
+                        if ui.button(t!("取消捕捉")).clicked() {
+                            self.state.transition(AppState::Picking, AppState::Idle);
+                            self.set_status(t!("已取消坐标捕捉").to_string());
                         }
                     }
                 });
-            }
 
-            ui.separator();
+                // 捕捉按钮类型选择
+                ui.horizontal(|ui| {
+                    ui.label(t!("捕捉按钮:"));
+                    ui.radio_value(&mut self.capture_button_type, CaptureButtonType::MiddleButton, t!("中键"));
+                    ui.radio_value(&mut self.capture_button_type, CaptureButtonType::RightButton, t!("右键"));
+                    let capture_hotkey = self.hotkeys.iter().find(|binding| binding.action == hotkey::HotkeyAction::CapturePosition);
+                    if let Some(binding) = capture_hotkey {
+                        ui.weak(t!("（也可以随时按 {} 记录鼠标所在位置）", binding.hotkey.display()));
+                    }
+                });
 
-            // 状态信息
-            ui.horizontal(|ui| {
-                ui.label("状态:");
-                ui.colored_label(egui::Color32::BLUE, &self.status_message);
-            });
+                egui::CollapsingHeader::new(t!("📌 坐标书签（{}）", self.bookmarks.len()))
+                    .id_salt("bookmarks")
+                    .show(ui, |ui| {
+                        self.bookmarks_ui(ui);
+                    });
+                egui::CollapsingHeader::new(t!("🎨 光标处颜色")).id_salt("color_picker").show(ui, |ui| {
+                    self.color_picker_ui(ui);
+                });
 
-            ui.horizontal(|ui| {
-                ui.label("总点击次数:");
-                let total = *self.total_clicks.lock().unwrap();
-                ui.colored_label(egui::Color32::GREEN, total.to_string());
-            });
+                ui.separator();
 
-            ui.separator();
+                // 点击类型选择
+                ui.horizontal(|ui| {
+                    ui.label(t!("点击类型:"));
+                    ui.radio_value(&mut self.click_type, ClickType::Left, t!("左键"));
+                    ui.radio_value(&mut self.click_type, ClickType::Right, t!("右键"));
+                    ui.radio_value(&mut self.click_type, ClickType::Middle, t!("中键"));
+                });
+                ui.horizontal(|ui| {
+                    ui.label(t!("点击次数:"));
+                    for multiplicity in ClickMultiplicity::ALL {
+                        ui.radio_value(&mut self.click_multiplicity, multiplicity, multiplicity.label());
+                    }
+                });
+                self.multi_click_gap_ui(ui);
 
-            // 额外功能
-            ui.horizontal(|ui| {
-                if ui.button("重置计数器").clicked() {
-                    *self.total_clicks.lock().unwrap() = 0;
-                    self.status_message = "计数器已重置".to_string();
+                // 移动方式选择
+                let response = ui.horizontal(|ui| {
+                    ui.label(t!("移动方式:"));
+                    ui.radio_value(&mut self.move_mode, MoveMode::Teleport, t!("瞬移"));
+                    ui.radio_value(&mut self.move_mode, MoveMode::Smooth, t!("平滑"));
+                    if self.move_mode == MoveMode::Smooth {
+                        ui.add(egui::DragValue::new(&mut self.smooth_move_ms)
+                            .range(settings::SMOOTH_MOVE_RANGE)
+                            .suffix(" ms"));
+                    }
+                }).response;
+                self.reveal_feature(ui, changelog::FeatureLink::SmoothMove, &response);
+                if self.move_mode == MoveMode::Smooth {
+                    ui.label(t!("平滑移动使用绝对坐标逐步注入，不受系统指针速度和加速度设置影响"));
                 }
-            });
 
-            ui.separator();
+                ui.separator();
 
-            // 平台信息
-            ui.collapsing("平台信息", |ui| {
-                ui.label(format!("操作系统: {}", std::env::consts::OS));
-                ui.label(format!("架构: {}", std::env::consts::ARCH));
-                ui.label("支持的平台: Windows, macOS, Linux");
-                ui.label("使用纯Rust实现，无需额外系统依赖");
+                // 单次点击
+                ui.horizontal(|ui| {
+                    if ui.button(t!("单次点击")).clicked() {
+                        self.perform_single_click();
+                        self.set_status(t!("执行单次点击").to_string());
+                    }
+                });
 
                 ui.separator();
-                ui.checkbox(&mut self.show_debug_info, "显示鼠标按钮调试信息");
 
-                if self.show_debug_info {
-                    if let Ok(controller) = self.mouse_controller.lock() {
-                        let button_states = controller.get_mouse_button_states();
-                        ui.label(format!("鼠标按钮状态数组: {:?}", button_states));
-                        ui.label("数组说明: [索引0, 索引1, 索引2, 索引3, 索引4, 索引5]");
+                // 自动点击设置
+                ui.checkbox(&mut self.auto_mode, t!("自动点击模式"));
 
-                        let (x, y) = controller.get_mouse_position();
-                        ui.label(format!("当前鼠标位置: ({}, {})", x, y));
+                if self.auto_mode {
+                    let response = ui.horizontal(|ui| {
+                        ui.label(t!("点击方式:"));
+                        ui.radio_value(&mut self.auto_click_mode, AutoClickMode::SinglePoint, t!("单点"));
+                        ui.radio_value(&mut self.auto_click_mode, AutoClickMode::TwoPoint, t!("A/B 两点交替"));
+                        ui.radio_value(&mut self.auto_click_mode, AutoClickMode::Sequence, t!("多步骤序列"));
+                    }).response;
+                    self.reveal_feature(ui, changelog::FeatureLink::TwoPoint, &response);
+                    self.reveal_feature(ui, changelog::FeatureLink::Sequence, &response);
 
-                        let left = controller.is_left_button_pressed();
-                        let right = controller.is_right_button_pressed();
-                        let middle = controller.is_middle_button_pressed();
-                        ui.label(format!("实时按钮状态:"));
-                        ui.horizontal(|ui| {
-                            if left {
-                                ui.colored_label(egui::Color32::GREEN, "左键:按下");
-                            } else {
-                                ui.label("左键:释放");
+                    match self.auto_click_mode {
+                        AutoClickMode::SinglePoint => {
+                            ui.horizontal(|ui| {
+                                ui.label(t!("点击间隔(秒):"));
+                                // 最短 1 毫秒，执行时使用高精度等待
+                                ui.add(egui::DragValue::new(&mut self.click_interval)
+                                    .range(settings::INTERVAL_RANGE)
+                                    .speed(0.01)
+                                    .max_decimals(3));
+                            });
+                        }
+                        AutoClickMode::TwoPoint => {
+                            let (x_pos, y_pos) = (self.x_pos, self.y_pos);
+                            for (name, point) in [("A", &mut self.point_a), ("B", &mut self.point_b)] {
+                                ui.horizontal(|ui| {
+                                    ui.label(t!("点{}:", name));
+                                    ui.add(egui::DragValue::new(&mut point.x).prefix("X: "));
+                                    ui.add(egui::DragValue::new(&mut point.y).prefix("Y: "));
+                                    ui.label(t!("停留(秒):"));
+                                    ui.add(egui::DragValue::new(&mut point.dwell)
+                                        .range(settings::INTERVAL_RANGE)
+                                        .speed(0.01)
+                                        .max_decimals(3));
+                                    if ui.button(t!("使用当前坐标")).clicked() {
+                                        point.x = x_pos;
+                                        point.y = y_pos;
+                                    }
+                                });
+                            }
+                        }
+                        AutoClickMode::Sequence => {
+                            self.sequence_editor_ui(ui);
+                        }
+                    }
+
+                    self.adaptive_interval_ui(ui);
+
+                    ui.horizontal(|ui| {
+                        ui.label(t!("回放速度:"));
+                        ui.add(egui::Slider::new(&mut self.playback_speed, settings::SPEED_RANGE)
+                            .logarithmic(true)
+                            .suffix("×"));
+                        if ui.button("1×").clicked() {
+                            self.playback_speed = 1.0;
+                        }
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label(t!("点击次数:"));
+                        ui.add(egui::DragValue::new(&mut self.click_count)
+                            .range(1..=1000));
+                    });
+                    ui.checkbox(&mut self.confirm_extreme_runs, t!("速率过高或次数很多时开始前先确认"))
+                        .on_hover_text(t!(
+                            "每秒超过 {} 次或点击次数超过 {} 次时，开始前显示将要执行的内容",
+                            CONFIRM_RATE_LIMIT,
+                            CONFIRM_COUNT_LIMIT
+                        ));
+
+                    let response = ui.horizontal(|ui| {
+                        ui.checkbox(&mut self.hold_key_enabled, t!("点击期间按住按键:"));
+                        egui::ComboBox::from_id_salt("hold_key")
+                            .selected_text(self.hold_key.label())
+                            .show_ui(ui, |ui| {
+                                for key in HoldKey::ALL {
+                                    ui.selectable_value(&mut self.hold_key, key, key.label());
+                                }
+                            });
+                    }).response;
+                    self.reveal_feature(ui, changelog::FeatureLink::HoldKey, &response);
+
+                    ui.horizontal(|ui| {
+                        ui.label(t!("完成后:"));
+                        egui::ComboBox::from_id_salt("end_action")
+                            .selected_text(self.end_action.kind.label())
+                            .show_ui(ui, |ui| {
+                                for kind in end_actions::EndActionKind::ALL {
+                                    ui.selectable_value(&mut self.end_action.kind, kind, kind.label());
+                                }
+                            });
+                        match self.end_action.kind {
+                            end_actions::EndActionKind::CloseApp => {
+                                ui.add(egui::TextEdit::singleline(&mut self.end_action.process)
+                                    .hint_text(t!("进程名，如 game.exe"))
+                                    .desired_width(160.0));
+                            }
+                            end_actions::EndActionKind::RunCommand => {
+                                ui.add(egui::TextEdit::singleline(&mut self.end_action.command)
+                                    .hint_text(t!("命令"))
+                                    .desired_width(220.0));
+                            }
+                            _ => {}
+                        }
+                    }).response.on_hover_text(t!("只在全部点击正常完成后执行，手动停止或中止时不执行"));
+
+                    egui::CollapsingHeader::new(t!("电量和 CPU 保护")).id_salt("power_guard").show(ui, |ui| {
+                        self.power_guard_ui(ui);
+                    });
+
+                    egui::CollapsingHeader::new(t!("目标窗口")).id_salt("target_window").show(ui, |ui| {
+                        self.target_window_ui(ui);
+                    });
+
+                    egui::CollapsingHeader::new(t!("防离开（鼠标微动）")).id_salt("jiggler").show(ui, |ui| {
+                        self.jiggler_ui(ui);
+                    });
+
+                    ui.checkbox(&mut self.show_trail, t!("执行时在屏幕上显示回放轨迹"))
+                        .on_hover_text(t!("绘制最近的移动路径和点击波纹，方便旁观者看清正在执行的操作（仅主显示器）"));
+
+                    ui.horizontal(|ui| {
+                        ui.label(t!("提示音:"));
+                        ui.checkbox(&mut self.sound.on_click, t!("每次点击"));
+                        ui.checkbox(&mut self.sound.on_finish, t!("执行结束"))
+                            .on_hover_text(t!("全部完成或被条件中止时播放，手动停止时不播放"));
+                    });
+                    ui.checkbox(&mut self.notify_on_finish, t!("执行结束时发送桌面通知"))
+                        .on_hover_text(t!("全部完成或被条件中止时通知点击次数，手动停止时不通知"));
+                    egui::CollapsingHeader::new(t!("Webhook 通知")).id_salt("webhook").show(ui, |ui| {
+                        self.webhook_ui(ui);
+                    });
+                    ui.checkbox(&mut self.keep_awake, t!("执行期间阻止系统睡眠和屏幕保护"))
+                        .on_hover_text(t!("执行结束或停止后恢复系统原来的睡眠设置"));
+
+                    ui.horizontal(|ui| {
+                        ui.label(t!("开始前倒计时(秒):"));
+                        ui.add(egui::DragValue::new(&mut self.start_delay_secs).range(0..=60));
+                    }).response.on_hover_text(t!("开始后先等待这么多秒再点击，留出时间切换到目标窗口；用快捷键开始时同样生效"));
+
+                    ui.horizontal(|ui| {
+                        if !self.state.is_running() {
+                            let label = if self.simulate { t!("开始模拟执行") } else { t!("开始自动点击") };
+                            if ui.button(label).clicked() {
+                                self.start_auto_clicking();
+                            }
+                        } else {
+                            if ui.button(t!("停止点击")).clicked() {
+                                self.stop_clicking();
                             }
-                            if right {
-                                ui.colored_label(egui::Color32::GREEN, "右键:按下");
-                            } else {
-                                ui.label("右键:释放");
+                        }
+                        ui.add_enabled(!self.state.is_running(), egui::Checkbox::new(&mut self.simulate, t!("🧪 模拟")))
+                            .on_hover_text(t!("不移动鼠标也不点击，立即算出序列会执行的每个动作和真实执行所需的时间"));
+                    });
+
+                    let export_open = self
+                        .is_feature_highlighted(changelog::FeatureLink::ScriptExport)
+                        .then_some(true);
+                    let response = egui::CollapsingHeader::new(t!("导出脚本")).open(export_open).show(ui, |ui| {
+                        ui.label(t!("导出独立运行包：包含本程序、宏文件和启动脚本，对方无需使用界面即可执行"));
+                        if ui.button(t!("导出运行包…")).clicked() {
+                            self.export_runner_bundle();
+                        }
+                        ui.separator();
+                        ui.label(t!("导出为 bash + xdotool 脚本，可在 Linux 无界面或脚本环境中执行"));
+                        ui.horizontal(|ui| {
+                            if ui.button(t!("导出 xdotool 脚本…")).clicked() {
+                                self.export_xdotool_script();
                             }
-                            if middle {
-                                ui.colored_label(egui::Color32::GREEN, "中键:按下");
-                            } else {
-                                ui.label("中键:释放");
+                            if ui.button(t!("复制到剪贴板")).clicked() {
+                                match export::xdotool_script(&self.build_sequence(), self.click_count, multi_click_gap(self.multi_click_gap_ms)) {
+                                    Ok(script) => {
+                                        ui.ctx().copy_text(script);
+                                        self.set_status(t!("xdotool 脚本已复制到剪贴板").to_string());
+                                    }
+                                    Err(e) => {
+                                        self.set_status(t!("导出脚本失败: {}", e));
+                                    }
+                                }
                             }
                         });
+                    });
+                    self.reveal_feature(ui, changelog::FeatureLink::ScriptExport, &response.header_response);
+                }
+
+                ui.separator();
+
+                // 状态信息
+                ui.horizontal(|ui| {
+                    ui.label(t!("状态:"));
+                    ui.colored_label(painting::state_color(self.state.get()), format!("● {}", self.state_text()));
+                    ui.colored_label(egui::Color32::BLUE, self.status_text());
+                    if let Some(reason) = &*self.power_pause.lock().unwrap() {
+                        ui.colored_label(egui::Color32::from_rgb(255, 165, 0), t!("⏸ 已暂停: {}，恢复后自动继续", reason));
+                    }
+                });
+                egui::CollapsingHeader::new(t!("事件日志")).id_salt("event_log").show(ui, |ui| {
+                    self.event_log_ui(ui);
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label(t!("累计点击次数:"));
+                    let total = *self.total_clicks.lock().unwrap();
+                    ui.colored_label(egui::Color32::GREEN, total.to_string())
+                        .on_hover_text(t!("重启后继续累加，点击「重置计数器」清零"));
+                    self.run_progress_ui(ui, total);
+                });
+                self.run_progress_bar(ui);
+                egui::CollapsingHeader::new(t!("点击间隔统计")).id_salt("timing").show(ui, |ui| {
+                    self.timing_ui(ui);
+                });
+
+                ui.separator();
+
+                // 额外功能
+                ui.horizontal(|ui| {
+                    if ui.button(t!("重置计数器")).clicked() {
+                        *self.total_clicks.lock().unwrap() = 0;
+                        self.set_status(t!("计数器已重置").to_string());
+                    }
+                    let response = ui.button(t!("释放所有按键"));
+                    if response.clicked() {
+                        self.release_all_inputs();
+                        self.set_status(t!("已释放所有由本工具按下的按键和按钮").to_string());
+                    }
+                    self.reveal_feature(ui, changelog::FeatureLink::ReleaseInputs, &response);
+                    let has_held_inputs = self.engine.has_held_inputs();
+                    if has_held_inputs {
+                        ui.colored_label(egui::Color32::from_rgb(255, 165, 0), t!("⚠️ 有按键处于按住状态"));
+                    }
+                });
+
+                ui.separator();
+
+                ui.collapsing(t!("全局快捷键"), |ui| {
+                    self.hotkeys_ui(ui);
+                });
+
+                ui.separator();
+
+                ui.collapsing(t!("手柄按钮"), |ui| {
+                    self.gamepad_ui(ui);
+                });
+
+                ui.separator();
+
+                ui.collapsing(t!("MIDI 触发"), |ui| {
+                    self.midi_ui(ui);
+                });
 
-                        ui.label(format!("技术细节:"));
-                        ui.label(format!("  左键: {} (使用索引2)", left));
-                        ui.label(format!("  右键: {} (使用索引3)", right));
-                        ui.label(format!("  中键: {} (使用索引4)", middle));
+                ui.separator();
 
+                ui.collapsing(t!("控制接口"), |ui| {
+                    self.http_api_ui(ui);
+                    ui.separator();
+                    if cfg!(windows) {
+                        self.pipe_control_ui(ui);
                         ui.separator();
-                        ui.colored_label(egui::Color32::GREEN, "✅ 按钮映射已修正:");
-                        ui.label("索引0-1: 未知功能");
-                        ui.label("索引2: 左键");
-                        ui.label("索引3: 右键");
-                        ui.label("索引4: 中键");
-                        ui.label("索引5: 可能是额外按钮");
                     }
-                }
-            });
+                    self.udp_control_ui(ui);
+                    ui.separator();
+                    self.mqtt_ui(ui);
+                    ui.separator();
+                    self.api_tokens_ui(ui);
+                });
 
-            ui.separator();
+                ui.separator();
+
+                ui.collapsing(t!("使用统计（仅本地）"), |ui| {
+                    self.insights_ui(ui);
+                });
+                ui.collapsing(t!("点击热力图"), |ui| {
+                    self.heatmap_ui(ui);
+                });
+                ui.collapsing(t!("⚡ 点击速度测试"), |ui| {
+                    self.benchmark_ui(ui);
+                });
+
+                ui.separator();
+
+                ui.collapsing(t!("开机启动"), |ui| {
+                    self.autostart_ui(ui);
+                });
+                ui.collapsing(t!("托盘图标"), |ui| {
+                    self.tray_ui(ui);
+                });
+
+                ui.separator();
+
+                // 平台信息
+                ui.collapsing(t!("平台信息"), |ui| {
+                    ui.label(t!("操作系统: {}", std::env::consts::OS));
+                    ui.label(t!("架构: {}", std::env::consts::ARCH));
+                    if cfg!(target_os = "windows") {
+                        ui.label(if secure_desktop::is_elevated() { t!("运行权限: 管理员") } else { t!("运行权限: 普通用户") });
+                    }
+                    if wayland::is_wayland() {
+                        let xwayland = if wayland::has_xwayland() { t!("通过 XWayland 模拟输入") } else { t!("没有 XWayland") };
+                        ui.label(t!("显示服务器: Wayland（{}）", xwayland));
+                    }
+                    self.input_backend_ui(ui);
+                    self.input_hook_ui(ui);
+                    ui.label(t!("支持的平台: Windows, macOS, Linux"));
+                    ui.label(t!("使用纯Rust实现，无需额外系统依赖"));
+                    ui.label(t!(
+                        "数据目录: {}{}",
+                        paths::data_dir().map_or(t!("未知").to_string(), |dir| dir.display().to_string()),
+                        if paths::is_portable() { t!("（便携模式）") } else { "" }
+                    ));
+                    ui.label(t!("系统语言: {}", self.system_locale.as_deref().unwrap_or(t!("未知"))));
+                    self.font_script_ui(ui);
+                    self.theme_ui(ui);
+                    self.language_ui(ui);
+                    self.log_level_ui(ui);
+
+                    ui.separator();
+                    ui.checkbox(&mut self.show_debug_info, t!("显示输入事件检查器"));
+
+                    if self.show_debug_info {
+                        self.input_inspector_ui(ui);
+                        ui.separator();
+                        self.button_calibration_ui(ui);
+                    }
+                });
 
-            // 说明文字
-            ui.collapsing("使用说明", |ui| {
-                ui.label("1. 设置要点击的坐标位置（基于屏幕左上角为原点）");
-                ui.label("2. 选择点击类型（左键/右键/中键）");
-                ui.label("3. 可以进行单次点击或开启自动点击模式");
-                ui.label("4. 自动模式下可以设置点击间隔和次数");
-                ui.label("5. 点击过程中可以随时停止");
-                ui.label("6. 使用「捕捉坐标」按钮：点击按钮后在屏幕任意位置点击鼠标中键，坐标会自动填入");
-                ui.label("7. 使用「获取当前位置」按钮：直接获取鼠标当前位置坐标");
-                ui.label("💡 提示：使用中键捕捉坐标可以避免与界面左键点击冲突");
                 ui.separator();
-                ui.colored_label(egui::Color32::RED, "⚠️ 请谨慎使用，避免对系统造成不必要的影响");
-                ui.colored_label(egui::Color32::GREEN, "✅ 跨平台纯Rust实现，支持Windows/macOS/Linux");
+
+                // 说明文字
+                ui.collapsing(t!("使用说明"), |ui| {
+                    ui.label(t!("1. 设置要点击的坐标位置（基于屏幕左上角为原点）"));
+                    ui.label(t!("2. 选择点击类型（左键/右键/中键）"));
+                    ui.label(t!("3. 可以进行单次点击或开启自动点击模式"));
+                    ui.label(t!("4. 自动模式下可以设置点击间隔和次数"));
+                    ui.label(t!("5. 点击过程中可以随时停止"));
+                    ui.label(t!("6. 使用「捕捉坐标」按钮：点击按钮后在屏幕任意位置点击鼠标中键，坐标会自动填入"));
+                    ui.label(t!("7. 使用「获取当前位置」按钮：直接获取鼠标当前位置坐标"));
+                    ui.label(t!("8. 自动模式下选择「A/B 两点交替」可在两个位置之间轮流点击，每个点的停留时间可单独设置"));
+                    ui.label(t!("9. 选择「多步骤序列」可编辑任意多个步骤，也可以导入 JSON 或 AutoHotkey 宏"));
+                    ui.label(t!("10. 序列中可以定义变量，坐标可以写成表达式，例如 X = x0 + 30 * i 表示每轮右移 30 像素"));
+                    ui.label(t!("11. 「添加循环」插入一对循环标记，拖动 ☰ 或用 ⬆⬇ 把步骤移到标记之间即可重复执行，循环可以嵌套"));
+                    ui.label(t!("12. 在「全局快捷键」中设置的快捷键在其他窗口处于前台时同样有效，默认 F8 开始/停止自动点击"));
+                    ui.label(t!("13. 「添加其他步骤」可插入等待、只移动不点击、滚轮滚动、按键和注释步骤"));
+                    ui.label(t!("14. 「完成后」可在点击全部完成时锁定电脑、关闭显示器、关闭程序或运行命令"));
+                    ui.label(t!("15. 开启「自适应间隔」后，每次点击后等反馈区域的像素变化再继续，最短和最长等待时间限制等待范围（仅 Windows）"));
+                    ui.label(t!("16. 勾选「显示回放轨迹」后，执行期间屏幕上会显示最近的移动路径和点击波纹，逐渐淡出"));
+                    ui.label(t!("17. 启用「托盘图标」后可以隐藏主窗口，通过托盘右键菜单开始、停止或显示窗口；也可以设为执行期间自动隐藏，结束后恢复（仅 Windows）"));
+                    ui.label(t!("18. 点击右上角「迷你模式」收起为置顶的小工具条，只显示开始/停止、目标坐标和剩余次数，按住空白处可拖动"));
+                    ui.label(t!("19. 「从截图选取」会截取整个屏幕，直接在截图上点击目标位置即可设置坐标；捕捉坐标时光标旁会显示放大镜"));
+                    ui.label(t!("20. 鼠标中键或右键被其他程序占用时，把鼠标移到目标位置后按「记录鼠标位置」快捷键（默认 F9）即可记录坐标"));
+                    ui.label(t!("21. 设置「开始前倒计时」后，开始执行要等倒计时结束才点击，期间可以切换到目标窗口；状态栏显示当前处于空闲、捕捉坐标、倒计时、执行、暂停还是停止中"));
+                    ui.label(t!("22. 使用很快的点击间隔之前，可以先用「点击速度测试」测出本机实际能达到的点击速率和延迟"));
+                    ui.label(t!("23. 目标程序收不到模拟的点击或按键时，可以在「平台信息」里切换输入后端：Windows 上可选扫描码 SendInput，Linux 上可选 XTest 或 uinput（Wayland 会话中也有效）"));
+                    ui.label(t!("24. 执行中需要切换到其他窗口时，在「目标窗口」中选择要点击的程序，开始执行时或每次点击前会先把它切回前台"));
+                    ui.label(t!("25. 默认在执行期间阻止系统睡眠、关闭显示器和启动屏幕保护，长时间挂机不会被打断；不需要时可以在设置中关闭"));
+                    ui.label(t!("26. 「防离开」每隔一段时间微动一下鼠标或按一下 F15，让聊天软件保持「在线」状态，可以限定只在每天的某个时间段内生效"));
+                    ui.label(t!("27. 勾选开始按钮旁的「模拟」后再开始，只会列出序列将要执行的每个动作和所需时间，不会真的移动鼠标或点击，适合先检查有风险的自动化"));
+                    ui.label(t!("28. 把插件（.dll、.dylib 或 .so）放进数据目录的 plugins 文件夹后重新启动，插件提供的步骤会出现在「添加其他步骤」菜单中，例如读取串口的数值或等待外部信号"));
+                    ui.label(t!("💡 提示：使用中键捕捉坐标可以避免与界面左键点击冲突"));
+                    ui.separator();
+                    ui.colored_label(egui::Color32::RED, t!("⚠️ 请谨慎使用，避免对系统造成不必要的影响"));
+                    ui.colored_label(egui::Color32::GREEN, t!("✅ 跨平台纯Rust实现，支持Windows/macOS/Linux"));
+                });
             });
         });
 
-        // 在捕捉模式下更频繁地刷新以检测点击，并添加闪烁效果
-        if self.is_picking_position {
+        // 没有钩子时在捕捉模式下更频繁地刷新以检测点击；有钩子时点击和鼠标移动会主动唤醒界面
+        if self.is_picking() && self.input_hook.is_none() {
             ctx.request_repaint_after(Duration::from_millis(16)); // ~60 FPS 用于流畅的视觉反馈
         } else {
             ctx.request_repaint_after(Duration::from_millis(100));
@@ -595,18 +5252,106 @@ impl eframe::App for MouseClickerApp {
 }
 
 fn main() -> Result<(), eframe::Error> {
+    i18n::register_english(translations::ENGLISH);
+    display::enable_dpi_awareness();
+    wayland::prefer_xwayland();
+    paths::init(std::env::args().skip(1).any(|arg| arg == "--portable"));
+    // 日志级别保存在界面的设置中，读到之前先按默认级别记录
+    logging::init(logging::LogLevel::default());
+
+    // 带 --run 参数时直接执行宏，不打开界面
+    match runner::parse_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => {
+            if wayland::has_xwayland()
+                && let Some(message) = wayland::diagnostic()
+            {
+                eprintln!("{}", message);
+            }
+            println!("执行宏: {}（共 {} 次点击）", options.macro_path, options.count);
+            let result = runner::run(&options).and_then(|report| {
+                println!("完成: 成功 {} 次，失败 {} 次", report.clicks_performed, report.errors);
+                report.aborted.map_or(Ok(()), |reason| Err(format!("执行中止: {}", reason)))
+            });
+            if let Err(e) = result {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    }
+
+    let launch = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(launch) => launch,
+        Err(e) => {
+            eprintln!("{}", e);
+            eprintln!("{}", cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+    let config = match load_config() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+
+    // 已有实例在运行时把参数交给它并切换到它的窗口，不再打开第二个窗口
+    let instance = match single_instance::acquire(&std::env::args().skip(1).collect::<Vec<_>>()) {
+        Ok(Some(instance)) => instance,
+        Ok(None) => return Ok(()),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
-            .with_inner_size([480.0, 650.0])
-            .with_min_inner_size([450.0, 600.0])
+            .with_inner_size(WINDOW_SIZE)
+            .with_min_inner_size(MIN_WINDOW_SIZE)
             .with_resizable(true)
-            .with_title("跨平台鼠标点击工具"),
+            .with_title(paths::APP_NAME),
+        // 恢复上次关闭时的窗口大小和位置
+        persist_window: true,
+        persistence_path: paths::storage_file(),
         ..Default::default()
     };
 
     eframe::run_native(
-        "跨平台鼠标点击工具",
+        paths::APP_NAME,
         options,
-        Box::new(|cc| Ok(Box::new(MouseClickerApp::new(cc)))),
+        Box::new(|cc| Ok(Box::new(MouseClickerApp::new(cc, config, launch, instance)))),
     )
-}
\ No newline at end of file
+}
+
+// 读取 `--config` 指定的配置文件，没有指定时读取默认位置（不存在时跳过）
+fn load_config() -> Result<Option<(PathBuf, toml::Table)>, String> {
+    let (path, required) = match config::path_from_args(std::env::args().skip(1))? {
+        Some(path) => (path, true),
+        None => match config::default_path() {
+            Some(path) => (path, false),
+            None => return Ok(None),
+        },
+    };
+    Ok(config::load(&path, required)?.map(|config| (path, config)))
+}
+
+// 找图步骤的模板图片：显示文件名，悬停显示完整路径，可以重新选择
+fn template_file_ui(ui: &mut egui::Ui, image: &mut String) {
+    let name = std::path::Path::new(image.as_str()).file_name().map(|name| name.to_string_lossy().into_owned());
+    let response = ui.label(name.unwrap_or_else(|| t!("（未选择）").to_string()));
+    if !image.is_empty() {
+        response.on_hover_text(image.as_str());
+    }
+    if ui.button(t!("选择…")).clicked()
+        && let Some(path) = rfd::FileDialog::new().add_filter(t!("图片"), &["png", "jpg", "jpeg", "bmp"]).pick_file()
+    {
+        *image = path.display().to_string();
+    }
+}
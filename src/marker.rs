@@ -0,0 +1,57 @@
+// 目标位置标记
+//
+// 在将要点击的位置叠加显示十字准星，两点和序列模式下附带点位名称或步骤序号，
+// 开始前就能确认点击会落在哪里。和回放轨迹一样画在透明、置顶、不接收鼠标的叠加层上。
+
+use eframe::egui;
+
+use crate::sequence::Sequence;
+
+const MARKER_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 64, 64);
+// 十字准星的半径（逻辑像素）
+const MARKER_RADIUS: f32 = 12.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Target {
+    pub x: i32,
+    pub y: i32,
+    pub label: String,
+}
+
+impl Target {
+    pub fn new(x: i32, y: i32, label: impl Into<String>) -> Self {
+        Self { x, y, label: label.into() }
+    }
+}
+
+/// 序列中带坐标的步骤，以步骤序号作为名称。使用表达式的坐标在执行时才能确定，不显示
+pub fn sequence_targets(sequence: &Sequence) -> Vec<Target> {
+    sequence
+        .steps
+        .iter()
+        .enumerate()
+        .filter(|(_, step)| step.kind.has_target() && step.x_expr.is_empty() && step.y_expr.is_empty())
+        .map(|(i, step)| Target::new(step.x, step.y, (i + 1).to_string()))
+        .collect()
+}
+
+pub fn paint(painter: &egui::Painter, targets: &[Target], pixels_per_point: f32) {
+    let stroke = egui::Stroke::new(2.0, MARKER_COLOR);
+    // 深色描边让准星在浅色背景上也清楚
+    let shadow = egui::Stroke::new(4.0, egui::Color32::from_black_alpha(160));
+    for target in targets {
+        let center = egui::pos2(target.x as f32, target.y as f32) / pixels_per_point;
+        for stroke in [shadow, stroke] {
+            painter.circle_stroke(center, MARKER_RADIUS * 0.6, stroke);
+            painter.line_segment([center - egui::vec2(MARKER_RADIUS, 0.0), center + egui::vec2(MARKER_RADIUS, 0.0)], stroke);
+            painter.line_segment([center - egui::vec2(0.0, MARKER_RADIUS), center + egui::vec2(0.0, MARKER_RADIUS)], stroke);
+        }
+        if !target.label.is_empty() {
+            let pos = center + egui::vec2(MARKER_RADIUS + 2.0, -MARKER_RADIUS - 2.0);
+            let galley = painter.layout_no_wrap(target.label.clone(), egui::FontId::proportional(14.0), egui::Color32::WHITE);
+            let rect = egui::Rect::from_min_size(pos, galley.size()).expand(2.0);
+            painter.rect_filled(rect, 3.0, MARKER_COLOR);
+            painter.galley(pos, galley, egui::Color32::WHITE);
+        }
+    }
+}
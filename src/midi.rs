@@ -0,0 +1,162 @@
+// MIDI 触发
+//
+// 通过 midir 接收所有 MIDI 输入设备的音符和控制变化（CC）消息，打击垫、控制器上绑定的按键
+// 可以开始/停止自动点击、开始/停止指定的配置或在设定的坐标点击一次。
+// 音符在按下（Note On 且力度大于 0）时触发；CC 在数值从 64 以下变为 64 及以上时触发，
+// 按下发 127、松开发 0 的按钮每按一次只触发一次。
+//
+// midir 在自己的线程中回调，消息经通道交给界面线程处理并唤醒界面。
+// Linux 上通过 ALSA 接收，构建需要 ALSA 开发包（如 libasound2-dev）。
+
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver};
+
+use eframe::egui;
+use midir::{MidiInput, MidiInputConnection};
+use serde::{Deserialize, Serialize};
+
+use mousetool_core::i18n::t;
+
+const CLIENT_NAME: &str = "mouseTOOL";
+// CC 数值达到这个值视为按下
+const CC_PRESSED: u8 = 64;
+const NOTE_NAMES: [&str; 12] = ["C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageKind {
+    Note,
+    ControlChange,
+}
+
+/// 触发操作的一种消息，通道从 0 开始
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MidiTrigger {
+    pub kind: MessageKind,
+    pub channel: u8,
+    pub number: u8,
+}
+
+impl MidiTrigger {
+    // 解析一条消息，不是会触发操作的按下时返回 None
+    fn parse(message: &[u8], cc_values: &mut HashMap<(u8, u8), u8>) -> Option<Self> {
+        let [status, number, value, ..] = *message else {
+            return None;
+        };
+        let channel = status & 0x0F;
+        match status & 0xF0 {
+            0x90 if value > 0 => Some(Self { kind: MessageKind::Note, channel, number }),
+            0xB0 => {
+                let previous = cc_values.insert((channel, number), value).unwrap_or(0);
+                (previous < CC_PRESSED && value >= CC_PRESSED)
+                    .then_some(Self { kind: MessageKind::ControlChange, channel, number })
+            }
+            _ => None,
+        }
+    }
+
+    pub fn display(&self) -> String {
+        match self.kind {
+            MessageKind::Note => {
+                // 中央 C（60）记作 C4
+                let name = NOTE_NAMES[(self.number % 12) as usize];
+                let octave = i32::from(self.number / 12) - 1;
+                t!("音符 {}{}（通道 {}）", name, octave, self.channel + 1)
+            }
+            MessageKind::ControlChange => t!("CC {}（通道 {}）", self.number, self.channel + 1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MidiAction {
+    /// 开始 / 停止自动点击
+    ToggleClicking,
+    /// 在设定的坐标点击一次
+    SingleClick,
+    /// 切换到指定名称的配置后开始，执行中时停止
+    ToggleProfile(String),
+}
+
+impl MidiAction {
+    pub fn label(&self) -> String {
+        match self {
+            MidiAction::ToggleClicking => t!("开始/停止自动点击").to_string(),
+            MidiAction::SingleClick => t!("单次点击").to_string(),
+            MidiAction::ToggleProfile(name) => t!("开始/停止配置「{}」", name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MidiBinding {
+    pub trigger: MidiTrigger,
+    pub action: MidiAction,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MidiSettings {
+    pub enabled: bool,
+    pub bindings: Vec<MidiBinding>,
+}
+
+/// 连接到的全部 MIDI 输入设备，析构时断开
+pub struct MidiInputs {
+    _connections: Vec<MidiInputConnection<()>>,
+    ports: Vec<String>,
+    receiver: Receiver<MidiTrigger>,
+}
+
+impl MidiInputs {
+    /// 连接当前所有的输入设备；之后插上的设备要重新连接才能收到
+    pub fn connect(ctx: &egui::Context) -> Result<Self, String> {
+        let input = MidiInput::new(CLIENT_NAME).map_err(|e| t!("无法初始化 MIDI: {}", e))?;
+        let count = input.ports().len();
+        let (sender, receiver) = mpsc::channel();
+        let mut connections = Vec::new();
+        let mut ports = Vec::new();
+        // 每次连接都会消耗一个 MidiInput
+        for index in 0..count {
+            let input = MidiInput::new(CLIENT_NAME).map_err(|e| t!("无法初始化 MIDI: {}", e))?;
+            let Some(port) = input.ports().into_iter().nth(index) else {
+                continue;
+            };
+            let name = input.port_name(&port).unwrap_or_else(|_| t!("未知设备").to_string());
+            let sender = sender.clone();
+            let ctx = ctx.clone();
+            let mut cc_values = HashMap::new();
+            let connection = input.connect(
+                &port,
+                CLIENT_NAME,
+                move |_, message, _| {
+                    if let Some(trigger) = MidiTrigger::parse(message, &mut cc_values)
+                        && sender.send(trigger).is_ok()
+                    {
+                        ctx.request_repaint();
+                    }
+                },
+                (),
+            );
+            match connection {
+                Ok(connection) => {
+                    connections.push(connection);
+                    ports.push(name);
+                }
+                Err(e) => tracing::warn!(port = %name, error = %e, "无法连接 MIDI 输入设备"),
+            }
+        }
+        Ok(Self { _connections: connections, ports, receiver })
+    }
+
+    /// 已连接的设备名称
+    pub fn ports(&self) -> &[String] {
+        &self.ports
+    }
+
+    /// 取出一条待处理的触发消息
+    pub fn try_recv(&self) -> Option<MidiTrigger> {
+        self.receiver.try_recv().ok()
+    }
+}
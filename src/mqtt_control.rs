@@ -0,0 +1,221 @@
+// MQTT 控制接口
+//
+// 连接到 MQTT 代理（例如 Home Assistant 的 Mosquitto），家庭自动化可以通过消息开始和停止配置：
+//
+//   <主题>/command       订阅，消息内容是与命名管道相同的命令（见 `control::TextCommand`），
+//                        例如 `toggle 签到`
+//   <主题>/reply         每条命令的结果：{"ok": true}、{"error": "原因"}，status 回复状态
+//   <主题>/state         运行状态，例如 idle、running，保留消息，状态变化时更新
+//   <主题>/availability  online 或 offline，保留消息，程序意外断开时由代理发布 offline
+//
+// 能向代理发布消息的客户端都能控制本程序，访问控制交给代理的用户名、密码和 ACL。
+// 连接不加密，只在可信的网络中使用。断开后每隔 `RECONNECT_DELAY` 自动重连。
+//
+// 和 `http_api` 一样作为后台任务运行在 `tasks` 的运行时上，开始和停止交给界面线程处理。
+
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use eframe::egui;
+use rumqttc::{AsyncClient, LastWill, MqttOptions, Packet, QoS};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use mousetool_core::app_state::{AppState, SharedState};
+use mousetool_core::events::{self, Event};
+use mousetool_core::i18n::t;
+use mousetool_core::tasks;
+
+use crate::control::{self, Pending, TextCommand};
+use crate::event_log::{self, SharedLog};
+
+pub const DEFAULT_PORT: u16 = 1883;
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+const KEEP_ALIVE: Duration = Duration::from_secs(30);
+// 等待发送的消息数，代理长时间无响应时多出的消息被丢弃
+const QUEUE_CAPACITY: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MqttSettings {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    /// 留空时匿名连接
+    pub username: String,
+    pub password: String,
+    /// 主题前缀，各个主题都在它下面
+    pub topic: String,
+}
+
+impl Default for MqttSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: "localhost".to_string(),
+            port: DEFAULT_PORT,
+            username: String::new(),
+            password: String::new(),
+            topic: "mousetool".to_string(),
+        }
+    }
+}
+
+/// 服务器读取的共享状态
+pub struct Shared {
+    pub state: SharedState,
+    pub total_clicks: Arc<Mutex<u32>>,
+    pub log: SharedLog,
+}
+
+/// 与代理的连接状态，显示在界面上
+#[derive(Debug, Clone, PartialEq)]
+pub enum Connection {
+    Connecting,
+    Connected,
+    /// 连接失败或断开，正在等待重连
+    Failed(String),
+}
+
+impl Connection {
+    pub fn label(&self) -> String {
+        match self {
+            Connection::Connecting => t!("正在连接").to_string(),
+            Connection::Connected => t!("已连接").to_string(),
+            Connection::Failed(e) => t!("连接失败，稍后重试: {}", e),
+        }
+    }
+}
+
+struct Mqtt {
+    shared: Shared,
+    requests: Sender<Pending>,
+    ctx: egui::Context,
+    client: AsyncClient,
+    topic: String,
+    connection: Arc<Mutex<Connection>>,
+}
+
+/// 正在运行的客户端，析构时断开
+pub struct Server {
+    connection: Arc<Mutex<Connection>>,
+    requests: Receiver<Pending>,
+    _task: tasks::Task,
+}
+
+impl Server {
+    pub fn start(settings: &MqttSettings, shared: Shared, ctx: &egui::Context) -> Result<Self, String> {
+        let host = settings.host.trim();
+        let topic = settings.topic.trim().trim_end_matches('/').to_string();
+        if host.is_empty() {
+            return Err(t!("代理地址不能为空").to_string());
+        }
+        if topic.is_empty() || topic.contains(['+', '#']) {
+            return Err(t!("主题前缀不能为空，也不能包含通配符").to_string());
+        }
+
+        // 客户端 ID 相同的连接会互相踢下线，带上进程号区分多个实例
+        let mut options = MqttOptions::new(format!("mouseTOOL-{}", std::process::id()), host, settings.port);
+        options.set_keep_alive(KEEP_ALIVE);
+        if !settings.username.is_empty() {
+            options.set_credentials(settings.username.clone(), settings.password.clone());
+        }
+        options.set_last_will(LastWill::new(format!("{}/availability", topic), "offline", QoS::AtLeastOnce, true));
+        let (client, eventloop) = AsyncClient::new(options, QUEUE_CAPACITY);
+
+        let (sender, requests) = mpsc::channel();
+        let connection = Arc::new(Mutex::new(Connection::Connecting));
+        let mqtt = Arc::new(Mqtt { shared, requests: sender, ctx: ctx.clone(), client, topic, connection: connection.clone() });
+        let task = tasks::spawn(move |token| run(mqtt, eventloop, token));
+        Ok(Self { connection, requests, _task: task })
+    }
+
+    pub fn connection(&self) -> Connection {
+        self.connection.lock().unwrap().clone()
+    }
+
+    /// 取出一个等待界面线程处理的请求
+    pub fn try_recv(&self) -> Option<Pending> {
+        self.requests.try_recv().ok()
+    }
+}
+
+async fn run(mqtt: Arc<Mqtt>, mut eventloop: rumqttc::EventLoop, token: tasks::CancellationToken) {
+    let mut events = events::subscribe();
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+            event = events.recv() => match event {
+                Ok(Event::State { state }) => mqtt.publish_state(state),
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            notification = eventloop.poll() => match notification {
+                Ok(rumqttc::Event::Incoming(Packet::ConnAck(_))) => mqtt.connected(),
+                Ok(rumqttc::Event::Incoming(Packet::Publish(publish))) => {
+                    let line = String::from_utf8_lossy(&publish.payload).into_owned();
+                    // 等待界面处理时不能阻塞事件循环，否则心跳会超时
+                    tokio::spawn(mqtt.clone().execute(line));
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::warn!(error = %e, "MQTT 连接断开");
+                    *mqtt.connection.lock().unwrap() = Connection::Failed(e.to_string());
+                    mqtt.ctx.request_repaint();
+                    // 下一次 poll 会重新连接
+                    tokio::select! {
+                        _ = token.cancelled() => break,
+                        _ = tokio::time::sleep(RECONNECT_DELAY) => {}
+                    }
+                }
+            },
+        }
+    }
+    // 正常退出时主动发布离线，代理不会再发布遗嘱
+    let _ = mqtt.client.try_publish(mqtt.topic("availability"), QoS::AtLeastOnce, true, "offline");
+    let _ = mqtt.client.try_disconnect();
+    let _ = tokio::time::timeout(Duration::from_secs(1), eventloop.poll()).await;
+    tracing::info!("MQTT 控制接口已停止");
+}
+
+impl Mqtt {
+    fn topic(&self, name: &str) -> String {
+        format!("{}/{}", self.topic, name)
+    }
+
+    // 每次连接（包括重连）后重新订阅并发布当前状态
+    fn connected(&self) {
+        tracing::info!(topic = %self.topic, "MQTT 控制接口已连接");
+        *self.connection.lock().unwrap() = Connection::Connected;
+        self.ctx.request_repaint();
+        let _ = self.client.try_subscribe(self.topic("command"), QoS::AtLeastOnce);
+        let _ = self.client.try_publish(self.topic("availability"), QoS::AtLeastOnce, true, "online");
+        self.publish_state(self.shared.state.get());
+    }
+
+    fn publish_state(&self, state: AppState) {
+        let state = serde_json::to_value(state).ok().and_then(|value| value.as_str().map(str::to_string)).unwrap_or_default();
+        let _ = self.client.try_publish(self.topic("state"), QoS::AtLeastOnce, true, state);
+    }
+
+    // 执行一条命令并把结果发布到 reply 主题
+    async fn execute(self: Arc<Self>, line: String) {
+        let line = line.trim();
+        let reply = match TextCommand::parse(line) {
+            Ok(TextCommand::Status) => serde_json::json!({
+                "state": self.shared.state.get(),
+                "total_clicks": *self.shared.total_clicks.lock().unwrap(),
+            }),
+            Ok(TextCommand::Request(request)) => {
+                self.shared.log.lock().unwrap().push(event_log::EntryKind::Status, t!("MQTT 控制接口: {}", line));
+                match control::forward(&self.requests, &self.ctx, request).await {
+                    Ok(()) => serde_json::json!({ "ok": true }),
+                    Err(message) => serde_json::json!({ "error": message.to_string() }),
+                }
+            }
+            Err(message) => serde_json::json!({ "error": message }),
+        };
+        let _ = self.client.try_publish(self.topic("reply"), QoS::AtLeastOnce, false, reply.to_string());
+    }
+}
@@ -0,0 +1,98 @@
+// 桌面通知
+//
+// 自动点击完成或被条件中止时发送一条系统通知，人不在窗口前也能知道执行已经结束。
+// Windows 临时添加一个通知区域图标并弹出气泡（Windows 10 起显示为系统通知），
+// 几秒后删除图标；macOS 通过 osascript 发送；Linux 使用 notify-send。
+// 发送在后台线程中进行，失败时静默忽略。
+
+use std::thread;
+
+pub fn send(title: &str, body: &str) {
+    let (title, body) = (title.to_string(), body.to_string());
+    thread::spawn(move || platform::send(&title, &body));
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::time::Duration;
+    use windows_sys::Win32::UI::Shell::{
+        NIF_ICON, NIF_INFO, NIF_TIP, NIIF_INFO, NIM_ADD, NIM_DELETE, NOTIFYICONDATAW, Shell_NotifyIconW,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::{CreateWindowExW, DestroyWindow, IDI_INFORMATION, LoadIconW};
+
+    // 图标保留的时长，删除图标会同时收起还在显示的气泡
+    const DISPLAY_TIME: Duration = Duration::from_secs(8);
+
+    fn copy_text(target: &mut [u16], text: &str) {
+        let text: Vec<u16> = text.encode_utf16().collect();
+        let len = text.len().min(target.len() - 1);
+        target[..len].copy_from_slice(&text[..len]);
+    }
+
+    pub fn send(title: &str, body: &str) {
+        unsafe {
+            // 通知区域图标必须属于某个窗口，借用系统的 STATIC 类创建一个不可见窗口即可
+            let class: Vec<u16> = "STATIC".encode_utf16().chain(Some(0)).collect();
+            let hwnd = CreateWindowExW(
+                0,
+                class.as_ptr(),
+                std::ptr::null(),
+                0,
+                0,
+                0,
+                0,
+                0,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                std::ptr::null(),
+            );
+            if hwnd.is_null() {
+                return;
+            }
+            let mut data: NOTIFYICONDATAW = std::mem::zeroed();
+            data.cbSize = std::mem::size_of::<NOTIFYICONDATAW>() as u32;
+            data.hWnd = hwnd;
+            data.uID = 1;
+            data.uFlags = NIF_ICON | NIF_TIP | NIF_INFO;
+            data.hIcon = LoadIconW(std::ptr::null_mut(), IDI_INFORMATION);
+            data.dwInfoFlags = NIIF_INFO;
+            copy_text(&mut data.szTip, crate::paths::APP_NAME);
+            copy_text(&mut data.szInfoTitle, title);
+            copy_text(&mut data.szInfo, body);
+            if Shell_NotifyIconW(NIM_ADD, &data) != 0 {
+                std::thread::sleep(DISPLAY_TIME);
+                Shell_NotifyIconW(NIM_DELETE, &data);
+            }
+            DestroyWindow(hwnd);
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::process::{Command, Stdio};
+
+    // AppleScript 字符串中的反斜杠和引号需要转义
+    fn quote(text: &str) -> String {
+        format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+
+    pub fn send(title: &str, body: &str) {
+        let script = format!("display notification {} with title {}", quote(body), quote(title));
+        let _ = Command::new("osascript").args(["-e", &script]).stdout(Stdio::null()).stderr(Stdio::null()).status();
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    use std::process::{Command, Stdio};
+
+    pub fn send(title: &str, body: &str) {
+        let _ = Command::new("notify-send")
+            .args(["--app-name", crate::paths::APP_NAME, title, body])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    }
+}
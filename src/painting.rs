@@ -0,0 +1,117 @@
+// 库中数据的界面绘制
+//
+// 运行状态、点击热力图和回放轨迹由执行引擎记录在不依赖界面的库中，这里把它们画到 egui 上。
+
+use std::time::Duration;
+
+use eframe::egui;
+
+use mousetool_core::app_state::AppState;
+use mousetool_core::i18n::t;
+
+use crate::heatmap::Heatmap;
+use crate::trail::Trail;
+
+// 热力图横向的格子数，纵向按屏幕比例计算
+const GRID_COLUMNS: usize = 64;
+
+// 点击波纹扩散的时长和最大半径（点）
+const RIPPLE_DURATION: Duration = Duration::from_millis(600);
+const RIPPLE_RADIUS: f32 = 24.0;
+
+const TRAIL_COLOR: egui::Color32 = egui::Color32::from_rgb(0, 170, 255);
+const RIPPLE_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 80, 80);
+
+pub fn state_color(state: AppState) -> egui::Color32 {
+    match state {
+        AppState::Idle => egui::Color32::GRAY,
+        AppState::Picking | AppState::Countdown => egui::Color32::from_rgb(255, 165, 0),
+        AppState::Running => egui::Color32::GREEN,
+        AppState::Paused => egui::Color32::YELLOW,
+        AppState::Stopping => egui::Color32::LIGHT_RED,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HeatmapView {
+    #[default]
+    Heatmap,
+    Scatter,
+}
+
+impl HeatmapView {
+    pub const ALL: [HeatmapView; 2] = [HeatmapView::Heatmap, HeatmapView::Scatter];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HeatmapView::Heatmap => t!("热力图"),
+            HeatmapView::Scatter => t!("散点"),
+        }
+    }
+}
+
+/// 把屏幕（`screen` 为像素宽高）缩放到 `rect` 中绘制
+pub fn heatmap(painter: &egui::Painter, rect: egui::Rect, heatmap: &Heatmap, screen: (i32, i32), view: HeatmapView) {
+    painter.rect_filled(rect, 2.0, egui::Color32::from_gray(24));
+    let (width, height) = (screen.0.max(1) as f32, screen.1.max(1) as f32);
+    let to_pos =
+        |x: i32, y: i32| rect.min + egui::vec2(x as f32 / width * rect.width(), y as f32 / height * rect.height());
+
+    match view {
+        HeatmapView::Scatter => {
+            for &(x, y) in heatmap.points() {
+                painter.circle_filled(to_pos(x, y), 2.0, egui::Color32::from_rgba_unmultiplied(255, 80, 80, 160));
+            }
+        }
+        HeatmapView::Heatmap => {
+            let columns = GRID_COLUMNS;
+            let (rows, cells) = heatmap.grid(screen, columns);
+            let max = cells.iter().copied().max().unwrap_or(0);
+            if max == 0 {
+                return;
+            }
+            let cell = egui::vec2(rect.width() / columns as f32, rect.height() / rows as f32);
+            for (index, &count) in cells.iter().enumerate() {
+                if count == 0 {
+                    continue;
+                }
+                let min = rect.min + egui::vec2((index % columns) as f32 * cell.x, (index / columns) as f32 * cell.y);
+                painter.rect_filled(egui::Rect::from_min_size(min, cell), 0.0, heat_color(count as f32 / max as f32));
+            }
+        }
+    }
+}
+
+// 从蓝色经黄色到红色，次数越多越红越不透明
+fn heat_color(t: f32) -> egui::Color32 {
+    let low = egui::Color32::from_rgb(40, 90, 255);
+    let middle = egui::Color32::from_rgb(255, 220, 0);
+    let high = egui::Color32::from_rgb(255, 40, 40);
+    let color = if t < 0.5 { low.lerp_to_gamma(middle, t * 2.0) } else { middle.lerp_to_gamma(high, t * 2.0 - 1.0) };
+    color.gamma_multiply(0.35 + 0.65 * t)
+}
+
+/// 在叠加层上绘制回放轨迹。坐标是屏幕像素，按叠加层的缩放比例换算成点
+pub fn trail(painter: &egui::Painter, trail: &Trail, pixels_per_point: f32) {
+    let to_pos = |x: i32, y: i32| egui::pos2(x as f32, y as f32) / pixels_per_point;
+
+    // 越旧的线段越透明越细
+    for (from, to) in trail.points().zip(trail.points().skip(1)) {
+        let alpha = to.fade();
+        painter.line_segment(
+            [to_pos(from.x, from.y), to_pos(to.x, to.y)],
+            egui::Stroke::new(1.0 + 3.0 * alpha, TRAIL_COLOR.gamma_multiply(alpha)),
+        );
+    }
+
+    for point in trail.points().filter(|point| point.click) {
+        let progress = point.at.elapsed().as_secs_f32() / RIPPLE_DURATION.as_secs_f32();
+        if progress < 1.0 {
+            painter.circle_stroke(
+                to_pos(point.x, point.y),
+                4.0 + RIPPLE_RADIUS * progress,
+                egui::Stroke::new(3.0, RIPPLE_COLOR.gamma_multiply(1.0 - progress)),
+            );
+        }
+    }
+}
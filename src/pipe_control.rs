@@ -0,0 +1,200 @@
+// 命名管道控制接口（Windows）
+//
+// AutoHotkey、PowerShell 等脚本不必启用 HTTP 也能控制正在运行的实例：连接管道
+// `\\.\pipe\mouseTOOL`，每行写一条与命令行相同风格的命令（见 `control::TextCommand`），每条命令回复一行 JSON：
+//
+//   status               {"state": "idle", "total_clicks": 42}
+//   start                按界面中的当前设置开始自动点击，成功时回复 {"ok": true}
+//   stop                 停止执行
+//   run-profile <名称>   切换到指定配置后开始
+//   toggle [名称]        执行中时停止，否则开始，指定配置时先切换到该配置
+//
+// 失败时回复 {"error": "原因"}。管道拒绝远程客户端，使用系统默认的访问权限，
+// 只有启动本程序的用户和管理员能写入，因此不需要令牌。一个连接中可以连续发送多条命令。
+//
+// PowerShell 示例：
+//
+//   $pipe = [System.IO.Pipes.NamedPipeClientStream]::new(".", "mouseTOOL", "InOut")
+//   $pipe.Connect(1000)
+//   $writer = [System.IO.StreamWriter]::new($pipe); $writer.AutoFlush = $true
+//   $writer.WriteLine("run-profile 签到")
+//   [System.IO.StreamReader]::new($pipe).ReadLine()
+//
+// 和 `http_api` 一样作为后台任务运行在 `tasks` 的运行时上，开始和停止交给界面线程处理。
+
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+
+use eframe::egui;
+
+use mousetool_core::app_state::SharedState;
+use mousetool_core::tasks;
+
+use crate::control::Pending;
+use crate::event_log::SharedLog;
+
+pub const PIPE_NAME: &str = r"\\.\pipe\mouseTOOL";
+
+// 服务器读取的共享状态，其他平台上没有管道服务器读取它们
+#[cfg_attr(not(windows), allow(dead_code))]
+pub struct Shared {
+    pub state: SharedState,
+    pub total_clicks: Arc<Mutex<u32>>,
+    pub log: SharedLog,
+}
+
+/// 正在运行的服务器，析构时停止
+pub struct Server {
+    requests: Receiver<Pending>,
+    _task: tasks::Task,
+}
+
+impl Server {
+    pub fn start(shared: Shared, ctx: &egui::Context) -> Result<Self, String> {
+        let (sender, requests) = mpsc::channel();
+        let task = platform::start(shared, sender, ctx.clone())?;
+        Ok(Self { requests, _task: task })
+    }
+
+    /// 取出一个等待界面线程处理的请求
+    pub fn try_recv(&self) -> Option<Pending> {
+        self.requests.try_recv().ok()
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::sync::Arc;
+    use std::sync::mpsc::{self, Sender};
+
+    use eframe::egui;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::windows::named_pipe::{NamedPipeServer, ServerOptions};
+
+    use crate::event_log;
+    use mousetool_core::i18n::t;
+    use mousetool_core::tasks;
+
+    use super::{PIPE_NAME, Shared};
+    use crate::control::{self, Pending, TextCommand};
+
+    struct Pipe {
+        shared: Shared,
+        requests: Sender<Pending>,
+        ctx: egui::Context,
+    }
+
+    impl Pipe {
+        // 执行一行命令，返回回复的 JSON
+        async fn execute(&self, line: &str) -> serde_json::Value {
+            let result = match TextCommand::parse(line) {
+                Ok(TextCommand::Status) => {
+                    return serde_json::json!({
+                        "state": self.shared.state.get(),
+                        "total_clicks": *self.shared.total_clicks.lock().unwrap(),
+                    });
+                }
+                Ok(TextCommand::Request(request)) => {
+                    self.log(line.trim());
+                    control::forward(&self.requests, &self.ctx, request).await.map_err(String::from)
+                }
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(()) => serde_json::json!({ "ok": true }),
+                Err(message) => serde_json::json!({ "error": message }),
+            }
+        }
+
+        fn log(&self, command: &str) {
+            self.shared.log.lock().unwrap().push(event_log::EntryKind::Status, t!("命名管道控制接口: {}", command));
+        }
+    }
+
+    fn create(first: bool) -> std::io::Result<NamedPipeServer> {
+        ServerOptions::new().first_pipe_instance(first).reject_remote_clients(true).create(PIPE_NAME)
+    }
+
+    pub fn start(shared: Shared, requests: Sender<Pending>, ctx: egui::Context) -> Result<tasks::Task, String> {
+        // 管道只能在运行时中创建，等任务报告创建结果，管道名被占用时能立即告诉界面
+        let (ready, created) = mpsc::channel();
+        let task = tasks::spawn(move |token| async move {
+            let server = match create(true) {
+                Ok(server) => server,
+                Err(e) => {
+                    let _ = ready.send(Err(t!("无法创建命名管道 {}: {}", PIPE_NAME, e)));
+                    return;
+                }
+            };
+            let _ = ready.send(Ok(()));
+            serve(server, Arc::new(Pipe { shared, requests, ctx }), token).await;
+        });
+        created.recv().map_err(|_| t!("命名管道控制接口没有启动").to_string())??;
+        Ok(task)
+    }
+
+    async fn serve(mut server: NamedPipeServer, pipe: Arc<Pipe>, token: tasks::CancellationToken) {
+        tracing::info!(pipe = PIPE_NAME, "命名管道控制接口已启动");
+        loop {
+            tokio::select! {
+                _ = token.cancelled() => break,
+                connected = server.connect() => {
+                    if let Err(e) = connected {
+                        tracing::warn!(error = %e, "命名管道客户端连接失败");
+                        continue;
+                    }
+                }
+            }
+            // 先创建下一个实例再处理连接，其他客户端不会在间隙中找不到管道
+            let next = match create(false) {
+                Ok(next) => next,
+                Err(e) => {
+                    tracing::error!(error = %e, "命名管道控制接口异常退出");
+                    break;
+                }
+            };
+            let client = std::mem::replace(&mut server, next);
+            tokio::spawn(session(client, pipe.clone(), token.clone()));
+        }
+        tracing::info!("命名管道控制接口已停止");
+    }
+
+    // 一个客户端连接：逐行读取命令并回复，直到客户端断开或服务器停止
+    async fn session(client: NamedPipeServer, pipe: Arc<Pipe>, token: tasks::CancellationToken) {
+        let (reader, mut writer) = tokio::io::split(client);
+        let mut lines = BufReader::new(reader).lines();
+        loop {
+            let line = tokio::select! {
+                _ = token.cancelled() => break,
+                line = lines.next_line() => match line {
+                    Ok(Some(line)) => line,
+                    Ok(None) | Err(_) => break,
+                },
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let reply = format!("{}\n", pipe.execute(&line).await);
+            if writer.write_all(reply.as_bytes()).await.is_err() {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    use std::sync::mpsc::Sender;
+
+    use eframe::egui;
+
+    use mousetool_core::i18n::t;
+    use mousetool_core::tasks;
+
+    use super::Shared;
+    use crate::control::Pending;
+
+    pub fn start(_shared: Shared, _requests: Sender<Pending>, _ctx: egui::Context) -> Result<tasks::Task, String> {
+        Err(t!("命名管道控制接口只支持 Windows").to_string())
+    }
+}
@@ -0,0 +1,234 @@
+// 命名配置
+//
+// 每个配置保存一整套设置和多步骤序列，例如「游戏挂机」「填表」，
+// 可以在界面顶部的下拉框中切换。切换时当前的修改会先写回正在使用的配置。
+//
+// 单个配置可以导出为 JSON 文件分享给同事，在另一台电脑上导入后得到完全相同的设置。
+// 导入时检查设置和序列中的数值，超出界面允许的范围时拒绝导入。结束后的操作会关闭程序或运行命令，
+// 不随配置导入，需要时在本机重新设置。
+// 配置可以附带标题、说明、作者和各语言的显示名称，使用不同语言的同事拿到文件后也能看懂用途：
+//
+// ```json
+// {
+//   "version": 1,
+//   "app_version": "0.2.0",
+//   "profile": {
+//     "name": "填表",
+//     "info": { "title": "填写日报", "author": "小王", "names": { "en": "Fill in daily report" } },
+//     "settings": { ... },
+//     "sequence": { "steps": [ ... ] }
+//   }
+// }
+// ```
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::end_actions::EndAction;
+use crate::hotkey::Hotkey;
+use crate::i18n::t;
+use crate::sequence::Sequence;
+use crate::settings::Settings;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    #[serde(default)]
+    pub info: ProfileInfo,
+    /// 全局快捷键：按下后切换到该配置并开始执行，执行中再按一次停止
+    #[serde(default)]
+    pub hotkey: Option<Hotkey>,
+    #[serde(default)]
+    pub settings: Settings,
+    #[serde(default)]
+    pub sequence: Sequence,
+}
+
+// 配置的说明信息，只用于显示，不影响执行
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfileInfo {
+    pub title: String,
+    pub description: String,
+    pub author: String,
+    /// 各语言的显示名称，键为语言标签（如 `en`、`zh-TW`）
+    pub names: BTreeMap<String, String>,
+}
+
+// 统一语言标签的写法：小写，用 `-` 分隔
+fn normalize_tag(tag: &str) -> String {
+    tag.trim().to_ascii_lowercase().replace('_', "-")
+}
+
+impl Profile {
+    /// 按语言选择显示名称：先找完全匹配的语言标签，再找同一语言，
+    /// 都没有时使用标题，最后使用配置名称
+    pub fn display_name(&self, locale: Option<&str>) -> &str {
+        if let Some(locale) = locale {
+            // 去掉 `.UTF-8` 之类的编码后缀
+            let locale = normalize_tag(locale.split(['.', '@']).next().unwrap_or(""));
+            let language = locale.split('-').next().unwrap_or("");
+            let find = |wanted: &str| {
+                self.info
+                    .names
+                    .iter()
+                    .find(|(tag, name)| normalize_tag(tag) == wanted && !name.trim().is_empty())
+                    .map(|(_, name)| name.as_str())
+            };
+            if let Some(name) = find(&locale).or_else(|| find(language)) {
+                return name;
+            }
+        }
+        if !self.info.title.trim().is_empty() {
+            return &self.info.title;
+        }
+        &self.name
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profiles {
+    pub profiles: Vec<Profile>,
+    /// 正在使用的配置的下标
+    pub active: usize,
+}
+
+const DEFAULT_PROFILE_NAME: &str = "默认";
+
+impl Default for Profiles {
+    fn default() -> Self {
+        Self::with_settings(Settings::default())
+    }
+}
+
+impl Profiles {
+    /// 只有一个默认配置，用于首次启动或从没有配置功能的旧版本升级
+    pub fn with_settings(settings: Settings) -> Self {
+        Self {
+            profiles: vec![Profile {
+                name: DEFAULT_PROFILE_NAME.to_string(),
+                info: ProfileInfo::default(),
+                hotkey: None,
+                settings,
+                sequence: Sequence::default(),
+            }],
+            active: 0,
+        }
+    }
+
+    pub fn active(&self) -> &Profile {
+        // 存储被手动改坏时 active 可能越界，退回第一个配置
+        self.profiles.get(self.active).unwrap_or(&self.profiles[0])
+    }
+
+    pub fn active_mut(&mut self) -> &mut Profile {
+        if self.active >= self.profiles.len() {
+            self.active = 0;
+        }
+        &mut self.profiles[self.active]
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        self.profiles.iter().any(|profile| profile.name == name)
+    }
+
+    /// 在 `base` 后面加序号，得到一个还没有被使用的名称
+    pub fn unique_name(&self, base: &str) -> String {
+        if !self.contains(base) {
+            return base.to_string();
+        }
+        (2..)
+            .map(|n| format!("{} {}", base, n))
+            .find(|name| !self.contains(name))
+            .unwrap()
+    }
+
+    /// 添加配置并返回它的下标，重名时自动加序号
+    pub fn add(&mut self, mut profile: Profile) -> usize {
+        profile.name = self.unique_name(profile.name.trim());
+        // 复制或导入的配置不沿用快捷键，避免两个配置抢同一个按键
+        profile.hotkey = None;
+        self.profiles.push(profile);
+        self.profiles.len() - 1
+    }
+
+    pub fn rename(&mut self, index: usize, name: &str) -> Result<(), String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(t!("配置名称不能为空").to_string());
+        }
+        if self.profiles.iter().enumerate().any(|(i, p)| i != index && p.name == name) {
+            return Err(t!("已经有名为「{}」的配置", name));
+        }
+        let profile = self.profiles.get_mut(index).ok_or(t!("配置不存在"))?;
+        profile.name = name.to_string();
+        Ok(())
+    }
+
+    pub fn delete(&mut self, index: usize) -> Result<Profile, String> {
+        if self.profiles.len() <= 1 {
+            return Err(t!("至少需要保留一个配置").to_string());
+        }
+        if index >= self.profiles.len() {
+            return Err(t!("配置不存在").to_string());
+        }
+        let removed = self.profiles.remove(index);
+        if self.active > index || self.active >= self.profiles.len() {
+            self.active = self.active.saturating_sub(1);
+        }
+        Ok(removed)
+    }
+}
+
+// 配置文件的格式版本，修改结构时提升并在 `import` 中兼容旧版本
+pub const FILE_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ProfileFile {
+    version: u32,
+    /// 导出该文件的程序版本
+    #[serde(default)]
+    app_version: String,
+    profile: Profile,
+}
+
+pub fn export(profile: &Profile, path: impl AsRef<Path>) -> Result<(), String> {
+    let mut file = ProfileFile {
+        version: FILE_FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        // 快捷键是本机的习惯，不随配置分享
+        profile: Profile { hotkey: None, ..profile.clone() },
+    };
+    // 结束后的操作同样只属于本机，导入时也会去掉
+    file.profile.settings.end_action = EndAction::default();
+    let content = serde_json::to_string_pretty(&file).map_err(|e| e.to_string())?;
+    std::fs::write(path, content).map_err(|e| t!("无法写入文件: {}", e))
+}
+
+/// 导入的配置
+pub struct Imported {
+    pub profile: Profile,
+    /// 文件中设置了、但没有导入的结束后操作
+    pub dropped_end_action: Option<EndAction>,
+}
+
+pub fn import(path: impl AsRef<Path>) -> Result<Imported, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| t!("无法读取文件: {}", e))?;
+    let mut file: ProfileFile = serde_json::from_str(&content).map_err(|e| t!("配置文件格式错误: {}", e))?;
+    if file.version > FILE_FORMAT_VERSION {
+        return Err(t!(
+            "配置文件由更新的程序（{}）导出，格式版本 {} 比当前程序支持的版本 {} 更新，请升级程序",
+            file.app_version, file.version, FILE_FORMAT_VERSION
+        ));
+    }
+    // 与启动配置文件相同，超出界面允许范围的数值会让执行线程出错，导入前就拒绝
+    file.profile.settings.validate().map_err(|e| t!("配置中的设置有误: {}", e))?;
+    file.profile.sequence.validate()?;
+    // 别人的文件可以借结束后的操作在本机运行任意命令，导入时去掉
+    let end_action = std::mem::take(&mut file.profile.settings.end_action);
+    let dropped_end_action = (!end_action.is_none()).then_some(end_action);
+    Ok(Imported { profile: file.profile, dropped_end_action })
+}
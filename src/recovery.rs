@@ -0,0 +1,97 @@
+// 意外退出后的恢复
+//
+// 执行期间定期把执行位置（剩余点击数、当前步骤、循环进度和变量）写入数据目录下的
+// recovery.json，执行结束（完成、停止或中止）时删除。如果下次启动时文件还在，
+// 说明上次是崩溃或被强制结束的，此时询问是否从中断处继续。
+// 恢复后会从保存时所在的步骤重新开始，最后几秒内的点击可能会重复一次。
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::history::RunKind;
+use crate::paths;
+use crate::sequence::{RunPosition, Sequence};
+
+const RECOVERY_FILE: &str = "recovery.json";
+// 两次保存之间的最短间隔
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunSnapshot {
+    /// 保存时间（Unix 时间戳，秒）
+    pub saved_at: u64,
+    /// 执行时使用的配置
+    pub profile: String,
+    pub kind: RunKind,
+    pub max_clicks: u32,
+    pub sequence: Sequence,
+    pub position: RunPosition,
+}
+
+fn recovery_path() -> Option<PathBuf> {
+    paths::data_dir().map(|dir| dir.join(RECOVERY_FILE))
+}
+
+/// 上次意外中断的执行，没有时返回 None
+pub fn load() -> Option<RunSnapshot> {
+    let content = std::fs::read_to_string(recovery_path()?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// 删除保存的位置；执行正常结束或用户放弃恢复时调用
+pub fn clear() {
+    if let Some(path) = recovery_path() {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+// 由执行线程定期调用，写入当前的执行位置
+#[derive(Debug)]
+pub struct Checkpointer {
+    snapshot: Mutex<RunSnapshot>,
+    last_saved: Mutex<Option<Instant>>,
+}
+
+impl Checkpointer {
+    pub fn new(profile: &str, kind: RunKind, max_clicks: u32, sequence: Sequence) -> Self {
+        Self {
+            snapshot: Mutex::new(RunSnapshot {
+                saved_at: 0,
+                profile: profile.to_string(),
+                kind,
+                max_clicks,
+                sequence,
+                position: RunPosition::default(),
+            }),
+            last_saved: Mutex::new(None),
+        }
+    }
+
+    pub fn is_due(&self) -> bool {
+        self.last_saved.lock().unwrap().is_none_or(|at| at.elapsed() >= CHECKPOINT_INTERVAL)
+    }
+
+    pub fn save(&self, position: RunPosition) {
+        *self.last_saved.lock().unwrap() = Some(Instant::now());
+        let Some(path) = recovery_path() else {
+            return;
+        };
+        let mut snapshot = self.snapshot.lock().unwrap();
+        snapshot.saved_at = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        snapshot.position = position;
+        let Ok(content) = serde_json::to_string(&*snapshot) else {
+            return;
+        };
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        // 先写临时文件再替换，避免在写入过程中崩溃留下半个文件
+        let temp = path.with_extension("json.tmp");
+        if std::fs::write(&temp, content).is_ok() {
+            let _ = std::fs::rename(temp, path);
+        }
+    }
+}
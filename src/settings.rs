@@ -0,0 +1,138 @@
+// 跨会话保存的界面设置
+//
+// 通过 eframe 的持久化存储保存，下次启动时恢复。窗口大小和位置由 eframe 自行保存。
+// 字段都有默认值，旧版本保存的设置缺少的字段按默认值补齐。
+// 配置文件和导入的配置不经过界面，用 `Settings::validate` 检查数值是否在界面允许的范围内。
+
+use std::ops::RangeInclusive;
+
+use serde::{Deserialize, Serialize};
+
+use crate::end_actions::EndAction;
+use crate::i18n::t;
+use crate::feedback::AdaptiveInterval;
+use crate::jiggler::JigglerSettings;
+use crate::power::PowerGuard;
+use crate::sequence::SequenceStep;
+use crate::sound::SoundSettings;
+use crate::target_window::{FocusMode, WindowTarget};
+use crate::{AutoClickMode, CaptureButtonType, ClickMultiplicity, ClickType, HoldKey, MoveMode};
+
+/// 点击间隔和两点交替的停留时间（秒）
+pub const INTERVAL_RANGE: RangeInclusive<f64> = 0.001..=10.0;
+/// 回放速度倍数
+pub const SPEED_RANGE: RangeInclusive<f64> = 0.25..=4.0;
+/// 平滑移动的耗时（毫秒）
+pub const SMOOTH_MOVE_RANGE: RangeInclusive<u64> = 20..=5000;
+/// 自定义的多击间隔（毫秒）
+pub const MULTI_CLICK_GAP_RANGE: RangeInclusive<u64> = 1..=2000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub x: i32,
+    pub y: i32,
+    pub click_interval: f64,
+    pub click_count: u32,
+    /// 开始执行前倒计时的秒数
+    pub start_delay_secs: u32,
+    pub click_type: ClickType,
+    pub click_multiplicity: ClickMultiplicity,
+    /// 多击间隔（毫秒），None 表示跟随系统双击时间
+    pub multi_click_gap_ms: Option<u64>,
+    pub capture_button: CaptureButtonType,
+    pub move_mode: MoveMode,
+    pub smooth_move_ms: u64,
+    pub auto_click_mode: AutoClickMode,
+    pub point_a: SequenceStep,
+    pub point_b: SequenceStep,
+    pub hold_key_enabled: bool,
+    pub hold_key: HoldKey,
+    pub playback_speed: f64,
+    /// 自动点击正常完成后执行的操作
+    pub end_action: EndAction,
+    pub adaptive_interval: AdaptiveInterval,
+    /// 执行期间在屏幕上显示回放轨迹
+    pub show_trail: bool,
+    pub power_guard: PowerGuard,
+    /// 点击和执行结束时的提示音
+    pub sound: SoundSettings,
+    /// 执行完成或被条件中止时发送桌面通知
+    pub notify_on_finish: bool,
+    /// 坐标相对的显示器名称，None 表示使用全局坐标
+    pub display: Option<String>,
+    /// 执行前切换到前台的窗口，以及切换的时机
+    pub focus_window: Option<WindowTarget>,
+    pub focus_mode: FocusMode,
+    /// 执行期间阻止系统睡眠和屏幕保护
+    pub keep_awake: bool,
+    /// 防离开的发送方式、间隔和生效时间段
+    pub jiggler: JigglerSettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            x: 100,
+            y: 100,
+            click_interval: 1.0,
+            click_count: 10,
+            start_delay_secs: 0,
+            click_type: ClickType::Left,
+            click_multiplicity: ClickMultiplicity::Single,
+            multi_click_gap_ms: None,
+            capture_button: CaptureButtonType::MiddleButton,
+            move_mode: MoveMode::Teleport,
+            smooth_move_ms: 200,
+            auto_click_mode: AutoClickMode::SinglePoint,
+            point_a: SequenceStep { x: 100, y: 100, dwell: 1.0, ..Default::default() },
+            point_b: SequenceStep { x: 200, y: 100, dwell: 1.0, ..Default::default() },
+            hold_key_enabled: false,
+            hold_key: HoldKey::W,
+            playback_speed: 1.0,
+            end_action: EndAction::default(),
+            adaptive_interval: AdaptiveInterval::default(),
+            show_trail: false,
+            power_guard: PowerGuard::default(),
+            sound: SoundSettings::default(),
+            notify_on_finish: false,
+            display: None,
+            focus_window: None,
+            focus_mode: FocusMode::default(),
+            keep_awake: true,
+            jiggler: JigglerSettings::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// 检查数值是否在界面允许的范围内，出错时返回带字段名的错误信息。
+    /// 速度为 0 或间隔为负数时执行线程会出错，所以配置文件和导入的配置都要先检查
+    pub fn validate(&self) -> Result<(), String> {
+        let numbers = [
+            ("click_interval", self.click_interval, &INTERVAL_RANGE),
+            ("point_a.dwell", self.point_a.dwell, &INTERVAL_RANGE),
+            ("point_b.dwell", self.point_b.dwell, &INTERVAL_RANGE),
+            ("playback_speed", self.playback_speed, &SPEED_RANGE),
+        ];
+        for (name, value, range) in numbers {
+            // NaN 不在任何范围内
+            if !range.contains(&value) {
+                return Err(t!("{} 的范围是 {} 到 {}，不能是 {}", name, range.start(), range.end(), value));
+            }
+        }
+        let mut millis = vec![("smooth_move_ms", self.smooth_move_ms, &SMOOTH_MOVE_RANGE)];
+        if let Some(gap) = self.multi_click_gap_ms {
+            millis.push(("multi_click_gap_ms", gap, &MULTI_CLICK_GAP_RANGE));
+        }
+        for (name, value, range) in millis {
+            if !range.contains(&value) {
+                return Err(t!("{} 的范围是 {} 到 {}，不能是 {}", name, range.start(), range.end(), value));
+            }
+        }
+        if self.click_count == 0 {
+            return Err(t!("click_count 至少为 1").to_string());
+        }
+        Ok(())
+    }
+}
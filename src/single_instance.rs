@@ -0,0 +1,316 @@
+// 单实例
+//
+// 启动界面前先找已经在运行的实例：找到时把命令行参数转交给它然后退出，由它应用参数并把
+// 窗口切换到前台；找不到时自己成为主实例，监听之后启动的实例。不同数据目录（例如便携模式）
+// 的实例互不影响。
+//
+// Unix 上用数据目录下的套接字文件，连得上说明已有实例；Windows 上用命名互斥体判断，
+// 参数通过名称由数据目录决定的命名管道转交。管道拒绝远程客户端，使用系统默认的访问权限，
+// 只有启动本程序的用户和管理员能写入，其他用户的进程无法让主实例加载配置文件或开始执行。
+// 每个连接只发送一行 JSON 字符串数组，每个连接在单独的线程中读取，不发数据的连接不会挡住后面的实例。
+//
+// 以管理员身份重新启动时新实例带 `--replace` 参数，等旧实例退出后接替它，而不是转交参数。
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+
+use eframe::egui;
+
+use crate::paths;
+
+// 接替正在运行的实例，而不是把参数转交给它（见 `secure_desktop::relaunch_elevated`）
+const REPLACE_ARG: &str = "--replace";
+// 接替时等待旧实例退出的时间和检查间隔
+const REPLACE_TIMEOUT: Duration = Duration::from_secs(10);
+const REPLACE_POLL: Duration = Duration::from_millis(100);
+// 一次转交的参数的最大长度，超出的部分不再读取
+const MAX_MESSAGE_LEN: u64 = 64 * 1024;
+
+/// 作为主实例持有的监听端，转交来的参数要等界面创建后才开始接收
+pub struct Instance {
+    listener: platform::Listener,
+}
+
+/// 接收其他实例转交的命令行参数
+pub struct Forwarded {
+    receiver: Receiver<Vec<String>>,
+}
+
+/// 成为主实例时返回 Some；已有实例在运行时把参数转交给它并返回 None
+pub fn acquire(args: &[String]) -> Result<Option<Instance>, String> {
+    // 没有数据目录或无法监听时无法和其他实例约定位置，不做限制
+    let unrestricted = || Instance { listener: platform::Listener::none() };
+    let Some(dir) = paths::data_dir() else {
+        return Ok(Some(unrestricted()));
+    };
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        tracing::warn!(error = %e, "无法创建数据目录，不限制单实例");
+        return Ok(Some(unrestricted()));
+    }
+    let mut acquired = platform::acquire(&dir);
+    if args.iter().any(|arg| arg == REPLACE_ARG) {
+        let deadline = Instant::now() + REPLACE_TIMEOUT;
+        while matches!(acquired, Ok(None)) && Instant::now() < deadline {
+            std::thread::sleep(REPLACE_POLL);
+            acquired = platform::acquire(&dir);
+        }
+    }
+    match acquired {
+        Ok(Some(listener)) => Ok(Some(Instance { listener })),
+        Ok(None) => {
+            let message = serde_json::to_string(&absolute_paths(args)).map_err(|e| e.to_string())?;
+            platform::forward(&dir, &message)?;
+            tracing::info!("已把命令行参数转交给正在运行的实例");
+            Ok(None)
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "无法监听其他实例，不限制单实例");
+            Ok(Some(unrestricted()))
+        }
+    }
+}
+
+impl Instance {
+    /// 在后台线程中接收转交来的参数，收到时唤醒界面
+    pub fn listen(self, ctx: &egui::Context) -> Forwarded {
+        let (sender, receiver) = mpsc::channel();
+        let ctx = ctx.clone();
+        self.listener.spawn(move |stream| {
+            let mut line = String::new();
+            if BufReader::new(stream.take(MAX_MESSAGE_LEN)).read_line(&mut line).is_err() {
+                return;
+            }
+            match serde_json::from_str::<Vec<String>>(&line) {
+                Ok(args) => {
+                    if sender.send(args).is_ok() {
+                        ctx.request_repaint();
+                    }
+                }
+                Err(e) => tracing::warn!(error = %e, "无法解析其他实例转交的参数"),
+            }
+        });
+        Forwarded { receiver }
+    }
+}
+
+impl Forwarded {
+    /// 取出一组待处理的命令行参数
+    pub fn try_recv(&self) -> Option<Vec<String>> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+// 主实例的工作目录可能不同，`--config` 的相对路径在转交前按当前目录展开
+fn absolute_paths(args: &[String]) -> Vec<String> {
+    let mut forwarded = Vec::with_capacity(args.len());
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        forwarded.push(arg.clone());
+        if arg == "--config"
+            && let Some(path) = args.next()
+        {
+            let path = std::path::absolute(path).unwrap_or_else(|_| PathBuf::from(path));
+            forwarded.push(path.to_string_lossy().into_owned());
+        }
+    }
+    forwarded
+}
+
+fn write_message(mut stream: impl Write, message: &str) -> Result<(), String> {
+    writeln!(stream, "{}", message)
+        .and_then(|_| stream.flush())
+        .map_err(|e| format!("无法把参数转交给正在运行的实例: {}", e))
+}
+
+#[cfg(unix)]
+mod platform {
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::Path;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    const SOCKET_FILE: &str = "instance.sock";
+    // 连接后迟迟不发送参数时放弃读取
+    const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+    pub struct Listener(Option<UnixListener>);
+
+    impl Listener {
+        pub fn none() -> Self {
+            Self(None)
+        }
+
+        pub fn spawn(self, handle: impl Fn(UnixStream) + Send + Sync + 'static) {
+            let Some(listener) = self.0 else {
+                return;
+            };
+            let handle = Arc::new(handle);
+            std::thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+                    let handle = handle.clone();
+                    std::thread::spawn(move || handle(stream));
+                }
+            });
+        }
+    }
+
+    pub fn acquire(dir: &Path) -> Result<Option<Listener>, String> {
+        let path = dir.join(SOCKET_FILE);
+        if UnixStream::connect(&path).is_ok() {
+            return Ok(None);
+        }
+        // 连不上说明是上次意外退出留下的文件
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).map_err(|e| format!("无法创建 {}: {}", path.display(), e))?;
+        Ok(Some(Listener(Some(listener))))
+    }
+
+    pub fn forward(dir: &Path, message: &str) -> Result<(), String> {
+        let stream = UnixStream::connect(dir.join(SOCKET_FILE))
+            .map_err(|e| format!("无法连接正在运行的实例: {}", e))?;
+        super::write_message(stream, message)
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::fs::{File, OpenOptions};
+    use std::hash::{DefaultHasher, Hash, Hasher};
+    use std::os::windows::io::{AsRawHandle, FromRawHandle, OwnedHandle};
+    use std::path::Path;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use windows_sys::Win32::Foundation::{
+        CloseHandle, ERROR_ALREADY_EXISTS, ERROR_PIPE_BUSY, ERROR_PIPE_CONNECTED, GetLastError, INVALID_HANDLE_VALUE,
+    };
+    use windows_sys::Win32::Storage::FileSystem::{FILE_FLAG_FIRST_PIPE_INSTANCE, PIPE_ACCESS_INBOUND};
+    use windows_sys::Win32::System::Pipes::{
+        ConnectNamedPipe, CreateNamedPipeW, PIPE_REJECT_REMOTE_CLIENTS, PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES,
+        PIPE_WAIT,
+    };
+    use windows_sys::Win32::System::Threading::CreateMutexW;
+    use windows_sys::Win32::UI::WindowsAndMessaging::{ASFW_ANY, AllowSetForegroundWindow};
+
+    // 主实例刚启动、还没创建好管道，或者管道实例都在使用中时等待的次数和间隔
+    const FORWARD_ATTEMPTS: u32 = 20;
+    const FORWARD_RETRY: Duration = Duration::from_millis(100);
+
+    pub struct Listener(Option<Pipe>);
+
+    pub struct Pipe {
+        name: Vec<u16>,
+        // 等待下一个连接的管道实例
+        server: OwnedHandle,
+    }
+
+    impl Listener {
+        pub fn none() -> Self {
+            Self(None)
+        }
+
+        pub fn spawn(self, handle: impl Fn(File) + Send + Sync + 'static) {
+            let Some(Pipe { name, mut server }) = self.0 else {
+                return;
+            };
+            let handle = Arc::new(handle);
+            std::thread::spawn(move || {
+                loop {
+                    let connected = unsafe { ConnectNamedPipe(server.as_raw_handle() as _, std::ptr::null_mut()) } != 0
+                        || unsafe { GetLastError() } == ERROR_PIPE_CONNECTED;
+                    // 先创建下一个实例再处理连接，其他实例不会在间隙中找不到管道
+                    let next = match create(&name, false) {
+                        Ok(next) => next,
+                        Err(e) => {
+                            tracing::error!(error = %e, "单实例管道异常退出");
+                            return;
+                        }
+                    };
+                    let client = std::mem::replace(&mut server, next);
+                    if connected {
+                        let handle = handle.clone();
+                        std::thread::spawn(move || handle(File::from(client)));
+                    }
+                }
+            });
+        }
+    }
+
+    // 互斥体和管道的名称包含数据目录，便携版和安装版可以同时运行
+    fn instance_name(dir: &Path) -> String {
+        let mut hasher = DefaultHasher::new();
+        dir.hash(&mut hasher);
+        format!("mouseTOOL-{:016x}", hasher.finish())
+    }
+
+    fn pipe_name(dir: &Path) -> String {
+        format!(r"\\.\pipe\{}", instance_name(dir))
+    }
+
+    fn wide(name: &str) -> Vec<u16> {
+        name.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    fn create(name: &[u16], first: bool) -> std::io::Result<OwnedHandle> {
+        // 第一个实例要求管道还不存在，其他进程抢先创建同名管道时不会收到转交的参数
+        let first = if first { FILE_FLAG_FIRST_PIPE_INSTANCE } else { 0 };
+        let handle = unsafe {
+            CreateNamedPipeW(
+                name.as_ptr(),
+                PIPE_ACCESS_INBOUND | first,
+                PIPE_TYPE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+                PIPE_UNLIMITED_INSTANCES,
+                0,
+                super::MAX_MESSAGE_LEN as u32,
+                0,
+                std::ptr::null(),
+            )
+        };
+        if handle == INVALID_HANDLE_VALUE {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(unsafe { OwnedHandle::from_raw_handle(handle as _) })
+    }
+
+    pub fn acquire(dir: &Path) -> Result<Option<Listener>, String> {
+        let name = wide(&format!("Local\\{}", instance_name(dir)));
+        // 创建成功的句柄一直持有到进程退出，由系统释放
+        let mutex = unsafe { CreateMutexW(std::ptr::null(), 0, name.as_ptr()) };
+        if mutex.is_null() {
+            return Err(format!("无法创建互斥体: {}", std::io::Error::last_os_error()));
+        }
+        if unsafe { GetLastError() } == ERROR_ALREADY_EXISTS {
+            // 不关闭的话，旧实例退出后互斥体仍然存在，接替时永远等不到
+            unsafe { CloseHandle(mutex) };
+            return Ok(None);
+        }
+        let name = wide(&pipe_name(dir));
+        let server = create(&name, true).map_err(|e| format!("无法创建命名管道: {}", e))?;
+        Ok(Some(Listener(Some(Pipe { name, server }))))
+    }
+
+    pub fn forward(dir: &Path, message: &str) -> Result<(), String> {
+        // 只有前台进程能把其他进程的窗口切换到前台，这里允许主实例这样做
+        unsafe { AllowSetForegroundWindow(ASFW_ANY) };
+        let name = pipe_name(dir);
+        let mut error = String::new();
+        for _ in 0..FORWARD_ATTEMPTS {
+            match OpenOptions::new().write(true).open(&name) {
+                Ok(pipe) => return super::write_message(pipe, message),
+                Err(e) => {
+                    // 管道不存在时主实例还没创建好，所有实例都忙时稍后再试，其他错误不再重试
+                    let busy = e.raw_os_error() == Some(ERROR_PIPE_BUSY as i32);
+                    if !busy && e.kind() != std::io::ErrorKind::NotFound {
+                        return Err(format!("无法连接正在运行的实例: {}", e));
+                    }
+                    error = e.to_string();
+                }
+            }
+            std::thread::sleep(FORWARD_RETRY);
+        }
+        Err(format!("无法连接正在运行的实例: {}", error))
+    }
+}
@@ -0,0 +1,98 @@
+// 提示音
+//
+// 看着另一台显示器时也能听出点击器在工作：每次模拟点击响一声短促的提示音，
+// 执行结束时再播放一次系统提示音。每个配置分别设置。
+// Windows 使用系统的蜂鸣和提示音接口；macOS 用 afplay 播放系统自带的声音；
+// Linux 依次尝试 canberra-gtk-play 和 paplay，都没有时不发声。
+
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+// 点击很密集时两次点击提示音之间的最短间隔，避免声音连成一片
+const MIN_CLICK_GAP: Duration = Duration::from_millis(80);
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SoundSettings {
+    /// 每次模拟点击时响一声
+    pub on_click: bool,
+    /// 执行结束（完成或被条件中止）时播放提示音，手动停止时不播放
+    pub on_finish: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sound {
+    Click,
+    Finish,
+}
+
+/// 在后台播放提示音，不阻塞调用的线程
+pub fn play(sound: Sound) {
+    if sound == Sound::Click {
+        static LAST_CLICK: Mutex<Option<Instant>> = Mutex::new(None);
+        let mut last = LAST_CLICK.lock().unwrap_or_else(|e| e.into_inner());
+        if last.is_some_and(|at| at.elapsed() < MIN_CLICK_GAP) {
+            return;
+        }
+        *last = Some(Instant::now());
+    }
+    thread::spawn(move || platform::play(sound));
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::Sound;
+    use windows_sys::Win32::System::Diagnostics::Debug::{Beep, MessageBeep};
+    use windows_sys::Win32::UI::WindowsAndMessaging::MB_ICONASTERISK;
+
+    pub fn play(sound: Sound) {
+        unsafe {
+            match sound {
+                Sound::Click => {
+                    Beep(1200, 20);
+                }
+                Sound::Finish => {
+                    MessageBeep(MB_ICONASTERISK);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::Sound;
+    use std::process::{Command, Stdio};
+
+    pub fn play(sound: Sound) {
+        let file = match sound {
+            Sound::Click => "/System/Library/Sounds/Tink.aiff",
+            Sound::Finish => "/System/Library/Sounds/Glass.aiff",
+        };
+        let _ = Command::new("afplay").arg(file).stdout(Stdio::null()).stderr(Stdio::null()).status();
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    use super::Sound;
+    use std::process::{Command, Stdio};
+
+    pub fn play(sound: Sound) {
+        // freedesktop 声音主题中的事件名称和对应的文件
+        let (event, file) = match sound {
+            Sound::Click => ("button-pressed", "/usr/share/sounds/freedesktop/stereo/button-pressed.oga"),
+            Sound::Finish => ("complete", "/usr/share/sounds/freedesktop/stereo/complete.oga"),
+        };
+        let attempts: [(&str, &[&str]); 2] = [("canberra-gtk-play", &["-i", event]), ("paplay", &[file])];
+        for (program, args) in attempts {
+            let status = Command::new(program).args(args).stdout(Stdio::null()).stderr(Stdio::null()).status();
+            if status.is_ok_and(|status| status.success()) {
+                return;
+            }
+        }
+    }
+}
@@ -0,0 +1,93 @@
+// 目标预览缩略图
+//
+// 捕捉坐标后截取目标周围的一小块画面，显示在坐标输入框旁边，保存书签时一起保存，
+// 过一段时间再看也能认出这个坐标指的是什么。Windows 直接读取屏幕像素；
+// 其他平台截取目标周围的区域，无法截图时没有缩略图。
+
+use std::collections::HashMap;
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::{capture, display, feedback};
+
+// 目标两侧各截取的像素数
+const RADIUS: i32 = 16;
+// 界面上显示的边长（逻辑像素）
+pub const DISPLAY_SIZE: f32 = 40.0;
+
+fn span() -> i32 {
+    RADIUS * 2
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Thumbnail {
+    /// 截取时的时间戳（毫秒），用于区分同一坐标的不同截图
+    pub id: u64,
+    pub x: i32,
+    pub y: i32,
+    /// 逐行排列的 RGB 像素，边长为 `RADIUS * 2`
+    pub rgb: Vec<u8>,
+}
+
+impl Thumbnail {
+    /// 截取 (x, y) 周围的画面
+    pub fn capture(x: i32, y: i32) -> Option<Self> {
+        let rgb = match feedback::read_pixels(x - RADIUS, y - RADIUS, span(), span()) {
+            // COLORREF 为 0x00BBGGRR
+            Some(pixels) => pixels.iter().flat_map(|&p| [p as u8, (p >> 8) as u8, (p >> 16) as u8]).collect(),
+            None => capture_around(x, y)?,
+        };
+        let id = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+        Some(Self { id, x, y, rgb })
+    }
+
+    fn to_image(&self) -> egui::ColorImage {
+        let side = span() as usize;
+        if self.rgb.len() != side * side * 3 {
+            return egui::ColorImage::new([side, side], egui::Color32::BLACK);
+        }
+        egui::ColorImage::from_rgb([side, side], &self.rgb)
+    }
+}
+
+// 截取目标周围的区域，屏幕外的部分为黑色
+fn capture_around(x: i32, y: i32) -> Option<Vec<u8>> {
+    let (gx, gy) = display::to_global(x, y);
+    let frame = capture::capture_region(capture::Region::new(gx - RADIUS, gy - RADIUS, span(), span())).ok()?;
+    Some(frame.rgba.chunks_exact(4).flat_map(|pixel| [pixel[0], pixel[1], pixel[2]]).collect())
+}
+
+/// 缩略图对应的纹理，按截图 id 缓存，不必每帧重新上传
+#[derive(Default)]
+pub struct TextureCache {
+    textures: HashMap<u64, egui::TextureHandle>,
+}
+
+impl TextureCache {
+    pub fn texture(&mut self, ctx: &egui::Context, thumbnail: &Thumbnail) -> egui::TextureHandle {
+        self.textures
+            .entry(thumbnail.id)
+            .or_insert_with(|| {
+                let name = format!("thumbnail_{}", thumbnail.id);
+                ctx.load_texture(name, thumbnail.to_image(), egui::TextureOptions::NEAREST)
+            })
+            .clone()
+    }
+
+    /// 显示缩略图，中心画一个十字标出目标位置，悬停时显示放大图
+    pub fn show(&mut self, ui: &mut egui::Ui, thumbnail: &Thumbnail) -> egui::Response {
+        let texture = self.texture(ui.ctx(), thumbnail);
+        let response = ui.add(egui::Image::new(&texture).fit_to_exact_size(egui::Vec2::splat(DISPLAY_SIZE)));
+        let center = response.rect.center();
+        let stroke = egui::Stroke::new(1.0, egui::Color32::RED);
+        ui.painter().hline(center.x - 4.0..=center.x + 4.0, center.y, stroke);
+        ui.painter().vline(center.x, center.y - 4.0..=center.y + 4.0, stroke);
+        response.on_hover_ui(|ui| {
+            ui.add(egui::Image::new(&texture).fit_to_exact_size(egui::Vec2::splat(DISPLAY_SIZE * 4.0)));
+            ui.label(format!("({}, {})", thumbnail.x, thumbnail.y));
+        })
+    }
+}
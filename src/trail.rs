@@ -0,0 +1,53 @@
+// 回放轨迹
+//
+// 执行期间在屏幕上叠加显示最近的鼠标移动路径和点击波纹，路径随时间淡出，
+// 方便旁观的人看清自动化正在做什么。执行线程在这里记录移动和点击，界面在一个透明、置顶、
+// 不接收鼠标、覆盖主显示器的窗口中绘制（见 painting.rs）。
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// 路径保留的时长
+const TRAIL_DURATION: Duration = Duration::from_millis(1500);
+
+#[derive(Debug, Clone, Copy)]
+pub struct TrailPoint {
+    pub x: i32,
+    pub y: i32,
+    pub at: Instant,
+    pub click: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct Trail {
+    points: VecDeque<TrailPoint>,
+}
+
+pub type SharedTrail = Arc<Mutex<Trail>>;
+
+impl Trail {
+    /// 记录一次移动（`click` 为 true 时同时画出点击波纹）
+    pub fn push(&mut self, x: i32, y: i32, click: bool) {
+        self.points.push_back(TrailPoint { x, y, at: Instant::now(), click });
+    }
+
+    /// 去掉已经完全淡出的点，返回是否还有需要显示的内容
+    pub fn prune(&mut self) -> bool {
+        while self.points.front().is_some_and(|point| point.at.elapsed() > TRAIL_DURATION) {
+            self.points.pop_front();
+        }
+        !self.points.is_empty()
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = &TrailPoint> {
+        self.points.iter()
+    }
+}
+
+impl TrailPoint {
+    /// 还剩多少没有淡出：刚记录时为 1，超过保留时长后为 0
+    pub fn fade(&self) -> f32 {
+        1.0 - (self.at.elapsed().as_secs_f32() / TRAIL_DURATION.as_secs_f32()).min(1.0)
+    }
+}
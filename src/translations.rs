@@ -0,0 +1,755 @@
+// 界面程序的英文译文
+//
+// 与 mousetool-core 的界面文字一样以中文原文作为键，启动时通过 `i18n::register_english` 注册，
+// 之后 `t!` 先查这里，再查 mousetool-core 自带的译文。
+
+// 英文译文，按模块分组
+pub const ENGLISH: &[(&str, &str)] = &[
+    // main.rs
+    ("空格", "Space"),
+    ("准备就绪", "Ready"),
+    ("已加载配置文件: {}", "Loaded config file: {}"),
+    ("⚠️ 配置文件 {} 有误: {}", "⚠️ Config file {} is invalid: {}"),
+    ("自动点击进行中，请先停止再切换配置", "Auto-click is running; stop it before switching profiles"),
+    ("请先停止自动点击再捕捉坐标", "Stop auto-clicking before capturing coordinates"),
+    ("已切换到配置「{}」", "Switched to profile \"{}\""),
+    ("配置:", "Profile:"),
+    ("确定", "OK"),
+    ("配置已重命名为「{}」", "Profile renamed to \"{}\""),
+    ("取消", "Cancel"),
+    ("作者: {}", "Author: {}"),
+    ("新建", "New"),
+    ("以当前设置新建一个配置", "Create a profile from the current settings"),
+    ("新配置", "New profile"),
+    ("重命名", "Rename"),
+    ("删除", "Delete"),
+    ("已删除配置「{}」，切换到「{}」", "Deleted profile \"{}\", switched to \"{}\""),
+    ("导出…", "Export…"),
+    ("把当前配置保存为可分享的文件", "Save the current profile as a shareable file"),
+    ("导入…", "Import…"),
+    ("配置说明", "Profile details"),
+    ("标题:", "Title:"),
+    ("作者:", "Author:"),
+    ("说明:", "Description:"),
+    ("各语言的显示名称（按系统语言显示，没有对应语言时显示标题）:", "Display names per language (shown by system language, falling back to the title):"),
+    ("语言标签，如 en、zh-TW、ja", "Language tag, e.g. en, zh-TW, ja"),
+    ("添加语言", "Add language"),
+    ("配置文件", "Profile file"),
+    ("配置「{}」已导出到: {}", "Profile \"{}\" exported to: {}"),
+    ("导出配置失败: {}", "Failed to export profile: {}"),
+    ("已导入配置「{}」", "Imported profile \"{}\""),
+    ("已导入配置「{}」，文件中的结束后操作（{}）没有导入，需要时请重新设置", "Imported profile \"{}\". The end action in the file ({}) was not imported; set it again if needed"),
+    ("导入配置失败: {}", "Failed to import profile: {}"),
+    ("鼠标中键（滚轮键）", "Middle mouse button (wheel)"),
+    ("鼠标右键", "Right mouse button"),
+    ("坐标捕捉模式已激活！请在屏幕任意位置点击{}...", "Coordinate capture active! Click {} anywhere on the screen..."),
+    ("⚠️ 已取消坐标捕捉: {}", "⚠️ Coordinate capture cancelled: {}"),
+    ("✅ 坐标捕捉成功！已设置为: ({}, {}) [使用{}捕捉]", "✅ Coordinates captured: ({}, {}) [using {}]"),
+    ("⚠️ 坐标已设置为 ({}, {})，但{}", "⚠️ Coordinates set to ({}, {}), but {}"),
+    ("⚠️ 鼠标控制器访问失败，请重试", "⚠️ Failed to access the mouse controller, please try again"),
+    ("已获取当前鼠标位置: ({}, {})", "Current mouse position: ({}, {})"),
+    ("屏幕尺寸: {}x{}", "Screen size: {}x{}"),
+    ("屏幕尺寸: {}x{}，虚拟桌面: {}x{}，左上角 ({}, {})", "Screen size: {}x{}, virtual desktop: {}x{} with its top-left at ({}, {})"),
+    ("获取屏幕信息失败: {}", "Failed to get screen info: {}"),
+    ("无法开始: {}", "Cannot start: {}"),
+    ("无法开始: 步骤 {}: {}", "Cannot start: step {}: {}"),
+    ("自动点击中...", "Auto-clicking..."),
+    ("{} 秒后开始自动点击...", "Auto-clicking starts in {} s..."),
+    ("正在捕捉坐标，请先完成或取消捕捉", "Capturing coordinates; finish or cancel the capture first"),
+    ("正在等待上一次执行停止，请稍后再试", "Waiting for the previous run to stop; try again shortly"),
+    ("{}（{} 秒）", "{} ({} s)"),
+    ("⚠️ 自动点击已中止: {}", "⚠️ Auto-click aborted: {}"),
+    ("自动点击完成，已{}", "Auto-click finished, then: {}"),
+    ("⚠️ 自动点击完成，但{}失败: {}", "⚠️ Auto-click finished, but \"{}\" failed: {}"),
+    ("⚠️ 反馈区域有 {} 次在最长等待时间内没有变化，请检查区域位置是否正确", "⚠️ The feedback region did not change within the maximum wait {} times; check that the region is in the right place"),
+    ("♻️ 恢复上次的执行", "♻️ Resume the last run"),
+    ("上次的自动点击没有正常结束，可能是程序崩溃或被强制关闭。", "The last auto-click run did not finish normally; the program may have crashed or been killed."),
+    ("配置: {}（{}）", "Profile: {} ({})"),
+    ("进度: 已完成 {}/{} 次点击，停在第 {} 个步骤（{}保存）", "Progress: {}/{} clicks done, stopped at step {} (saved {})"),
+    ("继续时从该步骤重新开始，最后几秒内的点击可能会重复一次", "Resuming restarts from that step; clicks from the last few seconds may be repeated once"),
+    ("从中断处继续", "Resume where it stopped"),
+    ("放弃", "Discard"),
+    ("⚠️ 确认开始自动点击", "⚠️ Confirm auto-click"),
+    ("模式: {}", "Mode: {}"),
+    ("点击次数: {}", "Click count: {}"),
+    ("估算速率: 约每秒 {} 次", "Estimated rate: about {} clicks per second"),
+    ("速率超过每秒 {} 次，目标程序或系统可能来不及响应", "The rate exceeds {} clicks per second; the target program or the system may not keep up"),
+    ("点击次数超过 {} 次", "More than {} clicks"),
+    ("执行中可以随时按 {} 停止", "Press {} at any time to stop the run"),
+    ("确认开始", "Start"),
+    ("已取消自动点击", "Auto-click cancelled"),
+    ("宏文件", "Macro file"),
+    ("AutoHotkey 脚本", "AutoHotkey script"),
+    ("，跳过 {} 行无法识别的内容", ", skipped {} unrecognized lines"),
+    ("已导入 {} 个步骤{}", "Imported {} steps{}"),
+    ("导入失败: {}", "Import failed: {}"),
+    ("JSON 宏文件", "JSON macro file"),
+    ("TOML 宏文件", "TOML macro file"),
+    ("宏已保存到: {}", "Macro saved to: {}"),
+    ("保存宏失败: {}", "Failed to save macro: {}"),
+    ("停留(秒):", "Dwell (s):"),
+    ("⏳ 等待", "⏳ Wait"),
+    (" 秒", " s"),
+    ("➡ 移动到", "➡ Move to"),
+    ("🔃 滚动", "🔃 Scroll"),
+    ("格（正数向下）停留(秒):", "notches (positive = down)  Dwell (s):"),
+    ("⌨ 按键", "⌨ Key"),
+    ("如 Enter、F5、a", "e.g. Enter, F5, a"),
+    ("无法识别的按键", "Unrecognized key"),
+    ("🎨 等待颜色", "🎨 Wait for color"),
+    ("读取该坐标当前的颜色", "Read the current color at these coordinates"),
+    ("容差:", "Tolerance:"),
+    ("RGB 下是每个通道允许的差，HSV 下是色相允许的差（100% 为 180°）", "In RGB, the allowed difference per channel; in HSV, the allowed hue difference (100% = 180°)"),
+    ("🖼 点击图片", "🖼 Click image"),
+    ("🖼 点击所有匹配", "🖼 Click all matches"),
+    ("点击间隔(秒):", "Click interval (s):"),
+    ("相邻两次点击之间等待的时间", "Time to wait between consecutive clicks"),
+    ("（未选择）", "(none selected)"),
+    ("选择…", "Choose…"),
+    ("图片", "Images"),
+    ("相似度阈值:", "Similarity threshold:"),
+    ("多尺度", "Multi-scale"),
+    ("模板在缩放比例不同的显示器上截取时开启，查找耗时成倍增加", "Enable when the template was captured on a display with a different scale; searching takes several times longer"),
+    ("测试", "Test"),
+    ("立即在屏幕上查找一次，显示位置、相似度和耗时", "Search the screen once now and show the position, similarity and time taken"),
+    ("限定搜索区域", "Restrict search region"),
+    ("只在这块区域中查找，屏幕越大、区域越小，查找越快", "Only search this region; the smaller the region compared to the screen, the faster the search"),
+    ("宽: ", "W: "),
+    ("高: ", "H: "),
+    ("找到图片，中心 ({}, {})，相似度 {}，用时 {} 毫秒", "Image found, center ({}, {}), similarity {}, took {} ms"),
+    ("没有找到相似度达到阈值的位置，用时 {} 毫秒", "No position reached the similarity threshold, took {} ms"),
+    ("找到 {} 处，用时 {} 毫秒", "Found {} matches, took {} ms"),
+    ("⌛ 等待图片", "⌛ Wait for image"),
+    ("超时(秒):", "Timeout (s):"),
+    ("0 表示一直等到图片出现", "0 waits until the image appears"),
+    ("注释，执行时跳过", "Comment, skipped when running"),
+    ("🔁 重复", "🔁 Repeat"),
+    ("次，轮次变量:", "times, round variable:"),
+    ("可选", "optional"),
+    ("⏹ 循环结束", "⏹ End loop"),
+    ("⛔ 不支持", "⛔ Unsupported"),
+    ("如 x0 + 30 * i", "e.g. x0 + 30 * i"),
+    ("点击后:", "After click:"),
+    ("如 k = k + 1", "e.g. k = k + 1"),
+    ("拖动调整顺序", "Drag to reorder"),
+    ("复制此步骤，循环开始会连同整个循环一起复制", "Duplicate this step; duplicating a loop start copies the whole loop"),
+    ("序列为空，请添加步骤或导入宏文件", "The sequence is empty; add steps or import a macro file"),
+    ("添加步骤（使用当前坐标）", "Add step (at current coordinates)"),
+    ("添加其他步骤", "Add other step"),
+    ("添加循环", "Add loop"),
+    ("清空", "Clear"),
+    ("执行前检查", "Pre-run check"),
+    ("检查目标位置是否允许模拟点击", "Check whether the target positions accept simulated clicks"),
+    ("✅ 检查通过", "✅ Check passed"),
+    ("⚠️ 有 {} 个步骤无法执行", "⚠️ {} steps cannot run"),
+    ("序列无法执行: {}", "Sequence cannot run: {}"),
+    ("变量与坐标表达式", "Variables and coordinate expressions"),
+    ("名称:", "Name:"),
+    ("描述:", "Description:"),
+    ("打开宏…", "Open macro…"),
+    ("保存宏…", "Save macro…"),
+    ("支持宏文件（.json / .toml）和 AutoHotkey 的 Click/MouseMove/Sleep 语句（.ahk）", "Supports macro files (.json / .toml) and AutoHotkey Click/MouseMove/Sleep statements (.ahk)"),
+    ("内置变量: {}（第几轮，从 0 开始）、{}（已点击次数）。坐标表达式为空时使用上面的固定坐标", "Built-in variables: {} (round number, from 0), {} (clicks so far). Empty coordinate expressions use the fixed coordinates above"),
+    ("初始值:", "Initial value:"),
+    ("添加计数器", "Add counter"),
+    ("当前坐标存为变量", "Save current coordinates as variables"),
+    ("使用电池且电量低于", "When on battery and below"),
+    ("时暂停", ", pause"),
+    ("当前电量 {}%，{}", "Battery {}%, {}"),
+    ("使用电池", "on battery"),
+    ("已接通电源", "plugged in"),
+    ("未检测到电池", "No battery detected"),
+    ("CPU 占用连续", "Sustained CPU usage for"),
+    ("高于", "above"),
+    ("当前平台无法读取 CPU 占用，该条件不会生效", "This platform cannot read CPU usage; this condition has no effect"),
+    ("条件恢复后自动继续；每个配置分别设置", "Resumes automatically once conditions recover; set separately for each profile"),
+    ("自适应间隔：等待反馈区域变化后再点击下一下", "Adaptive interval: wait for the feedback region to change before the next click"),
+    ("点击后观察反馈区域，界面有反应就立即继续，代替固定的点击间隔", "After each click, watch the feedback region and continue as soon as the screen reacts, instead of a fixed interval"),
+    ("反馈区域中心:", "Feedback region center:"),
+    ("边长:", "Size:"),
+    ("使用当前坐标", "Use current coordinates"),
+    ("等待时间:", "Wait time:"),
+    ("最短 ", "min "),
+    (" 毫秒", " ms"),
+    ("最长 ", "max "),
+    ("导出运行包失败: {}", "Failed to export run bundle: {}"),
+    ("运行包已导出到: {}", "Run bundle exported to: {}"),
+    ("Shell 脚本", "Shell script"),
+    ("导出脚本失败: {}", "Failed to export script: {}"),
+    ("已导出 xdotool 脚本: {}", "Exported xdotool script: {}"),
+    ("🆕 新功能", "🆕 What's new"),
+    ("版本 {}", "Version {}"),
+    ("前往设置", "Go to setting"),
+    ("知道了", "Got it"),
+    ("🔒 统计数据只从本机的历史记录计算，不会上传或发送到任何地方", "🔒 Statistics are computed only from local history and are never uploaded or sent anywhere"),
+    ("刷新统计", "Refresh statistics"),
+    ("清除历史记录", "Clear history"),
+    ("历史记录已清除", "History cleared"),
+    ("清除历史记录失败: {}", "Failed to clear history: {}"),
+    ("暂无历史记录", "No history yet"),
+    ("执行次数: {}", "Runs: {}"),
+    ("累计点击: {}，失败: {}", "Total clicks: {}, failed: {}"),
+    ("自动点击平均每次 {} 下，耗时 {} 秒，{}% 被手动停止", "Auto-click runs average {} clicks and {} s; {}% were stopped manually"),
+    ("最近的执行:", "Recent runs:"),
+    ("  {} {}: {}/{} 下，{}{}", "  {} {}: {}/{} clicks, {}{}"),
+    ("，手动停止", ", stopped manually"),
+    ("功能使用次数:", "Feature usage:"),
+    ("最常点击的位置:", "Most clicked positions:"),
+    ("  ({}, {}): {} 次", "  ({}, {}): {} times"),
+    ("出错最多的位置:", "Positions with the most failures:"),
+    ("  ({}, {}): 失败 {} 次 / 成功 {} 次", "  ({}, {}): {} failed / {} succeeded"),
+    ("回放轨迹", "Playback trail"),
+    ("正在截取屏幕...", "Capturing the screen..."),
+    ("请在截图上点击目标位置，按 Esc 取消", "Click the target position on the screenshot, press Esc to cancel"),
+    ("截图失败: {}", "Screenshot failed: {}"),
+    ("从截图选取坐标 - 点击目标位置，Esc 取消", "Pick from screenshot - click the target position, Esc to cancel"),
+    ("✅ 已从截图选取坐标: ({}, {})", "✅ Coordinates picked from screenshot: ({}, {})"),
+    ("已取消从截图选取坐标", "Cancelled picking from screenshot"),
+    ("放大镜", "Magnifier"),
+    ("目标位置", "Target position"),
+    ("上次", "Last"),
+    ("本次", "This run"),
+    ("｜{}: {} 下，{}", " | {}: {} clicks, {}"),
+    ("计算中", "calculating"),
+    ("{}/{} 下", "{}/{} clicks"),
+    ("已用时 {}，预计还需 {}", "Elapsed {}, about {} remaining"),
+    ("点击间隔统计", "Click interval statistics"),
+    ("平均 {} ms，最短 {} ms，最长 {} ms", "Mean {} ms, min {} ms, max {} ms"),
+    ("抖动（标准差）: ±{} ms", "Jitter (standard deviation): ±{} ms"),
+    ("共统计 {} 个间隔", "{} intervals measured"),
+    ("开始自动点击后统计相邻两次点击的实际间隔", "Measures the actual time between consecutive clicks once auto-click starts"),
+    ("设定间隔: {} ms（不含移动和点击本身的耗时）", "Configured interval: {} ms (excluding the time spent moving and clicking)"),
+    ("共 {} 次点击（仅主显示器，退出后清空）", "{} clicks (primary display only, cleared on exit)"),
+    ("已通过鼠标按钮紧急停止，所有按键和按钮已释放", "Emergency stop via mouse button; all keys and buttons released"),
+    ("已取消录制快捷键", "Hotkey recording cancelled"),
+    ("不支持把该按键设为快捷键", "This key cannot be used as a hotkey"),
+    ("「{}」的快捷键已设置为 {}", "Hotkey for \"{}\" set to {}"),
+    ("配置「{}」的快捷键已设置为 {}", "Hotkey for profile \"{}\" set to {}"),
+    ("请按下新的快捷键（Esc 取消）...", "Press the new hotkey (Esc to cancel)..."),
+    ("未设置", "Not set"),
+    ("录制", "Record"),
+    ("清除", "Clear"),
+    ("快捷键按键盘上的物理位置记录，切换键盘布局（如 AZERTY、Dvorak）后仍对应同一个按键", "Hotkeys are recorded by physical key position and keep working after switching keyboard layouts (e.g. AZERTY, Dvorak)"),
+    ("各配置的快捷键（按下后切换到该配置并开始执行，执行中再按一次停止）:", "Profile hotkeys (switch to the profile and start; press again while running to stop):"),
+    ("⚠️ 快捷键 {} 被设置了多次，按下时会同时触发", "⚠️ Hotkey {} is assigned more than once; pressing it triggers all of them"),
+    ("紧急停止鼠标按钮:", "Emergency stop mouse button:"),
+    ("当前平台无法全局检测鼠标侧键", "This platform cannot detect mouse side buttons globally"),
+    ("执行期间按下该按钮立即停止并释放所有按键，适合键盘被全屏游戏独占的场合", "Pressing this button during a run stops immediately and releases all keys, useful when a fullscreen game grabs the keyboard"),
+    ("⚠️ 中键同时用于捕捉坐标，只在执行期间作为紧急停止", "⚠️ The middle button is also used to capture coordinates, so it only acts as an emergency stop during runs"),
+    ("外部程序访问控制接口时需要携带令牌，令牌的权限范围决定它能执行哪些操作", "External programs must present a token to use the control interface; its scope decides what it may do"),
+    ("（未命名）", "(unnamed)"),
+    ("复制", "Copy"),
+    ("令牌已复制到剪贴板", "Token copied to clipboard"),
+    ("吊销", "Revoke"),
+    ("还没有发放任何令牌", "No tokens issued yet"),
+    ("令牌已吊销", "Token revoked"),
+    ("吊销令牌失败: {}", "Failed to revoke token: {}"),
+    ("如 仪表盘", "e.g. dashboard"),
+    ("发放令牌", "Issue token"),
+    ("已发放「{}」令牌并复制到剪贴板", "Issued token \"{}\" and copied it to the clipboard"),
+    ("发放令牌失败: {}", "Failed to issue token: {}"),
+    ("多击间隔:", "Multi-click gap:"),
+    ("自定义", "Custom"),
+    ("跟随系统（{} ms）", "Follow system ({} ms)"),
+    ("⚠️ 间隔不短于系统双击时间 {} ms，双击会被识别为两次单击", "⚠️ The gap is not shorter than the system double-click time {} ms; a double click will register as two single clicks"),
+    ("无法读取系统双击时间，按 {} ms 估算", "Cannot read the system double-click time; assuming {} ms"),
+    ("使用", "Use"),
+    ("已使用书签「{}」: ({}, {})", "Used bookmark \"{}\": ({}, {})"),
+    ("还没有书签，先捕捉坐标再保存", "No bookmarks yet; capture coordinates first, then save them"),
+    ("书签名称", "Bookmark name"),
+    ("保存当前坐标 ({}, {})", "Save current coordinates ({}, {})"),
+    ("已保存书签「{}」", "Saved bookmark \"{}\""),
+    ("登录系统时自动启动", "Start automatically at login"),
+    ("启动时最小化窗口", "Minimize window on start"),
+    ("已设置登录时自动启动", "Enabled start at login"),
+    ("已取消登录时自动启动", "Disabled start at login"),
+    ("设置自动启动失败: {}", "Failed to set start at login: {}"),
+    ("只对当前用户生效；移动程序位置后需要重新勾选", "Applies to the current user only; re-check after moving the program"),
+    ("显示托盘图标", "Show tray icon"),
+    ("无法显示托盘图标: {}", "Cannot show the tray icon: {}"),
+    ("开始执行时隐藏到托盘，执行结束后恢复窗口", "Hide to tray when a run starts and restore the window when it ends"),
+    ("隐藏到托盘", "Hide to tray"),
+    ("图标灰色表示空闲、绿色表示正在执行、橙色表示已暂停；右键菜单可以开始、停止、显示窗口和退出，双击显示窗口", "Grey means idle, green running and orange paused; the right-click menu can start, stop, show the window and quit, and double-click shows the window"),
+    ("🎯 序列 {} 步", "🎯 Sequence, {} steps"),
+    ("剩余 {} 次", "{} left"),
+    ("共 {} 次", "{} total"),
+    ("⏹ 停止", "⏹ Stop"),
+    ("▶ 开始", "▶ Start"),
+    ("展开完整窗口", "Expand to full window"),
+    ("自动（{}）", "Auto ({})"),
+    ("字体偏好:", "Font preference:"),
+    ("优先使用系统字体", "Prefer system fonts"),
+    ("繁体中文、日文和韩文使用系统字体能显示本地的字形；找不到系统字体时仍使用内置字体", "System fonts show the local glyph forms for Traditional Chinese, Japanese and Korean; the built-in font is still used when none is found"),
+    ("界面主题:", "Theme:"),
+    ("跟随系统", "Follow system"),
+    ("深色", "Dark"),
+    ("浅色", "Light"),
+    ("界面语言:", "Language:"),
+    ("日志级别:", "Log level:"),
+    ("报告问题时请附上日志文件，需要更详细的信息时选择「调试」", "Attach the log files when reporting a problem; choose \"Debug\" for more detail"),
+    ("日志目录: {}", "Log folder: {}"),
+    ("⛔ 有 {} 次输入注入失败，点击可能没有生效: {}", "⛔ {} input injections failed, clicks may not have taken effect: {}"),
+    ("查看错误", "View errors"),
+    ("⛔ 输入错误", "⛔ Input errors"),
+    ("当前输入后端: {}", "Current input backend: {}"),
+    ("没有注入失败", "No injection failures"),
+    ("目标程序收不到输入时，可以在「平台信息」中换一个输入后端，或以管理员身份运行", "If the target program does not receive the input, choose another input backend under \"Platform info\" or run as administrator"),
+    ("已停止", "Stopped"),
+    ("🖱️ 跨平台鼠标点击工具", "🖱️ Cross-platform mouse clicker"),
+    ("🗕 迷你模式", "🗕 Mini mode"),
+    ("收起为置顶的小工具条", "Collapse into a small always-on-top toolbar"),
+    ("🎯 坐标捕捉模式激活中", "🎯 Coordinate capture active"),
+    ("请在屏幕任意位置点击鼠标中键（滚轮键）来捕捉坐标", "Click the middle mouse button (wheel) anywhere on the screen to capture coordinates"),
+    ("点击坐标:", "Click coordinates:"),
+    ("清除记录", "Clear list"),
+    ("最近使用的坐标", "Recently used coordinates"),
+    ("在屏幕上标记将要点击的位置（仅主显示器）", "Mark the positions to be clicked on screen (primary display only)"),
+    ("复制坐标（鼠标在这一行上时也可以按 Ctrl+C）", "Copy the coordinates (or press Ctrl+C with the mouse over this row)"),
+    ("粘贴 x,y 或 (x, y) 格式的坐标（鼠标在这一行上时也可以按 Ctrl+V）", "Paste coordinates written as x,y or (x, y) (or press Ctrl+V with the mouse over this row)"),
+    ("已复制坐标 {}", "Copied coordinates {}"),
+    ("已粘贴坐标 ({}, {})", "Pasted coordinates ({}, {})"),
+    ("无法粘贴坐标: {}", "Cannot paste coordinates: {}"),
+    ("👈 坐标将自动填入这里", "👈 Coordinates will be filled in here"),
+    ("捕捉坐标", "Capture coordinates"),
+    ("从截图选取", "Pick from screenshot"),
+    ("截取整个屏幕，在截图上点击目标位置", "Capture the whole screen and click the target position on the screenshot"),
+    ("获取当前位置", "Get current position"),
+    ("获取屏幕信息", "Get screen info"),
+    ("等待{}点击中，请在屏幕任意位置点击鼠标{}...", "Waiting for a {} click; click the {} anywhere on the screen..."),
+    ("取消捕捉", "Cancel capture"),
+    ("已取消坐标捕捉", "Coordinate capture cancelled"),
+    ("捕捉按钮:", "Capture button:"),
+    ("（也可以随时按 {} 记录鼠标所在位置）", "(or press {} at any time to record the mouse position)"),
+    ("📌 坐标书签（{}）", "📌 Coordinate bookmarks ({})"),
+    ("点击类型:", "Click type:"),
+    ("点击次数:", "Click count:"),
+    ("速率过高或次数很多时开始前先确认", "Confirm before starting very fast or very long runs"),
+    ("每秒超过 {} 次或点击次数超过 {} 次时，开始前显示将要执行的内容", "Shows what is about to run before starting when the rate exceeds {} clicks per second or the count exceeds {}"),
+    ("移动方式:", "Movement:"),
+    ("瞬移", "Instant"),
+    ("平滑", "Smooth"),
+    ("平滑移动使用绝对坐标逐步注入，不受系统指针速度和加速度设置影响", "Smooth movement injects absolute coordinates step by step and ignores the system pointer speed and acceleration settings"),
+    ("执行单次点击", "Click once"),
+    ("自动点击模式", "Auto-click mode"),
+    ("点击方式:", "Click mode:"),
+    ("单点", "Single point"),
+    ("点击间隔(秒):", "Click interval (s):"),
+    ("点{}:", "Point {}:"),
+    ("回放速度:", "Playback speed:"),
+    ("点击期间按住按键:", "Hold key while clicking:"),
+    ("完成后:", "When finished:"),
+    ("进程名，如 game.exe", "Process name, e.g. game.exe"),
+    ("命令", "Command"),
+    ("只在全部点击正常完成后执行，手动停止或中止时不执行", "Only runs after all clicks complete normally, not when stopped manually or aborted"),
+    ("电量和 CPU 保护", "Battery and CPU protection"),
+    ("执行时在屏幕上显示回放轨迹", "Show the playback trail on screen while running"),
+    ("提示音:", "Sounds:"),
+    ("每次点击", "Each click"),
+    ("执行结束", "Run finished"),
+    ("全部完成或被条件中止时播放，手动停止时不播放", "Plays when all clicks complete or a condition aborts the run, not when stopped manually"),
+    ("执行结束时发送桌面通知", "Send a desktop notification when a run finishes"),
+    ("目标窗口", "Target window"),
+    ("切换到窗口:", "Bring to front:"),
+    ("不切换", "Don't switch"),
+    ("切换到其他窗口后，点击会落在挡在前面的窗口上；选择目标窗口后执行时会先把它切到前台", "After switching to another window, clicks land on whatever window is in front; with a target window selected, runs bring it to the front first"),
+    ("切换时机:", "Switch:"),
+    ("窗口已经在前台时不重复切换；找不到窗口时中止执行", "Windows already in front are not switched again; the run aborts if the window is gone"),
+    ("🎯 立即切换", "🎯 Switch now"),
+    ("已切换到「{}」", "Switched to \"{}\""),
+    ("防离开（鼠标微动）", "Anti-idle (mouse jiggler)"),
+    ("⏹ 停止防离开", "⏹ Stop anti-idle"),
+    ("▶ 开始防离开", "▶ Start anti-idle"),
+    ("已停止防离开", "Anti-idle stopped"),
+    ("已开始防离开", "Anti-idle started"),
+    ("发送失败: {}", "Sending failed: {}"),
+    ("上次发送: {}", "Last sent: {}"),
+    ("运行中", "Running"),
+    ("未开启", "Off"),
+    ("方式:", "Method:"),
+    ("每隔", "Every"),
+    ("发送一次", "send once"),
+    ("只在每天的这段时间内生效:", "Only during these hours each day:"),
+    ("鼠标被动过或正在执行自动点击时跳过这一次；结束时间早于开始时间时跨过午夜", "Skipped when the mouse has moved or an auto-click run is active; an end time before the start time spans midnight"),
+    ("开始前倒计时(秒):", "Countdown before start (s):"),
+    ("开始后先等待这么多秒再点击，留出时间切换到目标窗口；用快捷键开始时同样生效", "Wait this many seconds after starting before clicking, leaving time to switch to the target window; also applies when starting with a hotkey"),
+    ("全部完成或被条件中止时通知点击次数，手动停止时不通知", "Reports the click count when all clicks complete or a condition aborts the run, not when stopped manually"),
+    ("执行期间阻止系统睡眠和屏幕保护", "Keep the system awake and the screensaver off during a run"),
+    ("执行结束或停止后恢复系统原来的睡眠设置", "The system's usual sleep behaviour returns once the run finishes or stops"),
+    ("自动点击已中止（{}），共点击 {} 次", "Auto-click aborted ({}) after {} clicks"),
+    ("自动点击完成，共点击 {} 次", "Auto-click finished: {} clicks"),
+    ("绘制最近的移动路径和点击波纹，方便旁观者看清正在执行的操作（仅主显示器）", "Draw the recent movement path and click ripples so onlookers can follow what is happening (primary display only)"),
+    ("开始自动点击", "Start auto-click"),
+    ("停止点击", "Stop clicking"),
+    ("开始模拟执行", "Start simulation"),
+    ("🧪 模拟", "🧪 Simulate"),
+    ("不移动鼠标也不点击，立即算出序列会执行的每个动作和真实执行所需的时间", "Neither moves the mouse nor clicks; instantly works out every action the sequence would perform and how long a real run would take"),
+    ("正在模拟执行...", "Simulating..."),
+    ("模拟执行完成: {} 次点击，{} 个动作，真实执行约需 {}", "Simulation finished: {} clicks, {} actions, a real run would take about {}"),
+    ("🧪 模拟执行结果", "🧪 Simulation result"),
+    ("点击 {} 次，失败 {} 次", "{} clicks, {} failed"),
+    ("共 {} 个动作，真实执行约需 {}", "{} actions in total, a real run would take about {}"),
+    ("⚠️ 执行会中止: {}", "⚠️ The run would abort: {}"),
+    ("⚠️ 动作超过 {} 个，只模拟了前面的部分", "⚠️ More than {} actions; only the first part was simulated"),
+    ("模拟被手动停止，结果不完整", "The simulation was stopped manually; the result is incomplete"),
+    ("模拟时没有切换目标窗口", "The target window was not brought to the front during the simulation"),
+    ("模拟时自适应间隔按固定的停留时间等待", "The adaptive interval waited for the fixed dwell time during the simulation"),
+    ("真实执行完成后还会{}", "After a real run finishes it would also {}"),
+    ("导出脚本", "Export script"),
+    ("导出独立运行包：包含本程序、宏文件和启动脚本，对方无需使用界面即可执行", "Export a standalone run bundle with this program, the macro file and a launch script, so others can run it without the UI"),
+    ("导出运行包…", "Export run bundle…"),
+    ("导出为 bash + xdotool 脚本，可在 Linux 无界面或脚本环境中执行", "Export as a bash + xdotool script that runs on headless Linux or in scripts"),
+    ("导出 xdotool 脚本…", "Export xdotool script…"),
+    ("复制到剪贴板", "Copy to clipboard"),
+    ("xdotool 脚本已复制到剪贴板", "xdotool script copied to clipboard"),
+    ("状态:", "Status:"),
+    ("事件日志", "Event log"),
+    ("显示每次点击", "Show each click"),
+    ("⏸ 已暂停: {}，恢复后自动继续", "⏸ Paused: {}; resumes automatically afterwards"),
+    ("累计点击次数:", "Total clicks:"),
+    ("重启后继续累加，点击「重置计数器」清零", "Keeps counting across restarts; click \"Reset counter\" to clear it"),
+    ("重置计数器", "Reset counter"),
+    ("计数器已重置", "Counter reset"),
+    ("释放所有按键", "Release all keys"),
+    ("已释放所有由本工具按下的按键和按钮", "Released all keys and buttons pressed by this tool"),
+    ("⚠️ 有按键处于按住状态", "⚠️ Some keys are held down"),
+    ("全局快捷键", "Global hotkeys"),
+    ("控制接口", "Control interface"),
+    ("使用统计（仅本地）", "Usage statistics (local only)"),
+    ("点击热力图", "Click heatmap"),
+    ("无法获取屏幕尺寸", "Cannot get the screen size"),
+    ("⚡ 点击速度测试", "⚡ Click speed test"),
+    ("把鼠标移到下面的目标上尽快连续点击左键，测出当前平台的模拟点击能达到多快；测试中请不要移动鼠标", "Moves the mouse onto the target below and left-clicks as fast as possible to measure how fast simulated clicks can go on this platform; do not move the mouse during the test"),
+    ("开始测试", "Start test"),
+    ("点击速度测试中...", "Running the click speed test..."),
+    ("⚠️ 测试提前停止: {}", "⚠️ Test stopped early: {}"),
+    ("发出 {} 次，每秒 {} 次", "Sent {}, {} per second"),
+    ("界面收到 {} 次，每秒 {} 次", "Received {}, {} per second"),
+    ("延迟: 平均 {} ms，最长 {} ms（包含等待界面下一帧的时间）", "Latency: {} ms average, {} ms max (includes waiting for the next frame)"),
+    ("⚠️ 有 {} 次点击没有收到", "⚠️ {} clicks were not received"),
+    ("⚠️ 有 {} 次点击发送失败", "⚠️ {} clicks failed to send"),
+    ("找不到显示器 {}，暂时使用全局坐标", "Display {} not found; using global coordinates for now"),
+    ("全局坐标（原点在主显示器左上角）", "Global coordinates (origin at the top-left of the primary display)"),
+    ("坐标相对于:", "Coordinates relative to:"),
+    ("重新检测显示器", "Detect displays again"),
+    ("虚拟桌面 {}x{}，左上角 ({}, {})，主显示器左边或上方的坐标为负数", "Virtual desktop {}x{} with its top-left at ({}, {}); screens left of or above the main display have negative coordinates"),
+    ("🎨 光标处颜色", "🎨 Color under cursor"),
+    ("已复制 ({}, {}) 处的颜色 {}", "Copied the color {2} at ({0}, {1})"),
+    ("无法读取 ({}, {}) 处的颜色", "Cannot read the color at ({}, {})"),
+    ("实时读取像素颜色仅支持 Windows，其他平台可以用快捷键取色", "Live pixel color readout is Windows only; on other platforms pick colors with the hotkey"),
+    ("按 {} 读取光标处的颜色并复制到剪贴板", "Press {} to read the color under the cursor and copy it to the clipboard"),
+    ("在「全局快捷键」中设置取色快捷键后，按下即可读取光标处的颜色", "Set a pick-color hotkey under \"Global hotkeys\" to read the color under the cursor"),
+    ("复制十六进制颜色", "Copy the hex color"),
+    ("复制 RGB 数值", "Copy the RGB values"),
+    ("开机启动", "Start at login"),
+    ("托盘图标", "Tray icon"),
+    ("平台信息", "Platform info"),
+    ("操作系统: {}", "Operating system: {}"),
+    ("架构: {}", "Architecture: {}"),
+    ("显示服务器: Wayland（{}）", "Display server: Wayland ({})"),
+    ("⚠️ 缺少 macOS 权限: {}，点击和快捷键可能无效", "⚠️ Missing macOS permissions: {}; clicks and hotkeys may not work"),
+    ("⚠️ 目标坐标处的窗口以管理员权限运行，会忽略本工具发出的点击", "⚠️ The window at the target position runs as administrator and ignores clicks sent by this tool"),
+    ("🛡 以管理员身份重新启动", "🛡 Restart as administrator"),
+    ("运行权限: 管理员", "Privileges: administrator"),
+    ("运行权限: 普通用户", "Privileges: standard user"),
+    ("🔐 设置权限", "🔐 Set up permissions"),
+    ("✅ 已获得所需的 macOS 权限", "✅ The required macOS permissions are granted"),
+    ("🔐 需要 macOS 权限", "🔐 macOS permissions required"),
+    ("macOS 会静默丢弃没有权限的程序模拟的输入。请在系统设置中允许本工具:", "macOS silently drops input simulated by programs without permission. Allow this tool in System Settings:"),
+    ("打开系统设置", "Open System Settings"),
+    ("勾选后这里会自动更新；如果一直没有变化，请退出并重新打开本工具", "This updates automatically once enabled; if nothing changes, quit and reopen the tool"),
+    ("正在等待授权…", "Waiting for permission…"),
+    ("稍后", "Later"),
+    ("⚠️ 无法使用 {} 后端，继续使用 enigo: {}", "⚠️ Cannot use the {} backend, staying on enigo: {}"),
+    ("输入后端:", "Input backend:"),
+    ("在终端中执行以下命令允许当前用户使用 uinput，然后注销并重新登录:", "Run these commands in a terminal to let the current user use uinput, then log out and back in:"),
+    ("📋 复制命令", "📋 Copy commands"),
+    ("🔄 重试", "🔄 Retry"),
+    ("鼠标按钮检测: 系统钩子", "Mouse button detection: system hook"),
+    ("鼠标按钮检测: 逐帧查询（可能漏掉很快的点击）", "Mouse button detection: per-frame polling (may miss very fast clicks)"),
+    ("通过 XWayland 模拟输入", "input simulated through XWayland"),
+    ("没有 XWayland", "no XWayland"),
+    ("支持的平台: Windows, macOS, Linux", "Supported platforms: Windows, macOS, Linux"),
+    ("使用纯Rust实现，无需额外系统依赖", "Written in pure Rust with no extra system dependencies"),
+    ("数据目录: {}{}", "Data directory: {}{}"),
+    ("未知", "unknown"),
+    ("（便携模式）", " (portable mode)"),
+    ("系统语言: {}", "System language: {}"),
+    ("显示输入事件检查器", "Show the input event inspector"),
+    ("按钮数组: {}  光标: ({}, {})", "Button array: {}  cursor: ({}, {})"),
+    ("{}（索引{}）", "{} (index {})"),
+    ("显示:", "Show:"),
+    ("⏸ 暂停", "⏸ Pause"),
+    ("按钮映射: 左键 = 索引{}，右键 = 索引{}，中键 = 索引{}", "Button mapping: left = index {}, right = index {}, middle = index {}"),
+    ("校准按钮映射", "Calibrate button mapping"),
+    ("捕捉坐标用的按钮不起作用时，重新识别各个按钮", "Identify each button again when the capture button does not respond"),
+    ("恢复默认", "Restore defaults"),
+    ("{}: 索引{}", "{}: index {}"),
+    ("请在空白处按一下鼠标{}", "Press the {} once on an empty area"),
+    ("取消校准", "Cancel calibration"),
+    ("按钮映射已校准: 左键 = 索引{}，右键 = 索引{}，中键 = 索引{}", "Button mapping calibrated: left = index {}, right = index {}, middle = index {}"),
+    ("使用说明", "Instructions"),
+    ("1. 设置要点击的坐标位置（基于屏幕左上角为原点）", "1. Set the coordinates to click (origin at the top-left corner of the screen)"),
+    ("2. 选择点击类型（左键/右键/中键）", "2. Choose the click type (left/right/middle)"),
+    ("3. 可以进行单次点击或开启自动点击模式", "3. Click once or turn on auto-click mode"),
+    ("4. 自动模式下可以设置点击间隔和次数", "4. In auto mode you can set the click interval and count"),
+    ("5. 点击过程中可以随时停止", "5. You can stop at any time while clicking"),
+    ("6. 使用「捕捉坐标」按钮：点击按钮后在屏幕任意位置点击鼠标中键，坐标会自动填入", "6. \"Capture coordinates\": after pressing the button, click the middle button anywhere on the screen and the coordinates are filled in"),
+    ("7. 使用「获取当前位置」按钮：直接获取鼠标当前位置坐标", "7. \"Get current position\": reads the current mouse position directly"),
+    ("8. 自动模式下选择「A/B 两点交替」可在两个位置之间轮流点击，每个点的停留时间可单独设置", "8. In auto mode, \"A/B alternating points\" clicks two positions in turn, each with its own dwell time"),
+    ("9. 选择「多步骤序列」可编辑任意多个步骤，也可以导入 JSON 或 AutoHotkey 宏", "9. \"Multi-step sequence\" lets you edit any number of steps and import JSON or AutoHotkey macros"),
+    ("10. 序列中可以定义变量，坐标可以写成表达式，例如 X = x0 + 30 * i 表示每轮右移 30 像素", "10. Sequences can define variables and use expressions as coordinates, e.g. X = x0 + 30 * i moves 30 pixels right each round"),
+    ("11. 「添加循环」插入一对循环标记，拖动 ☰ 或用 ⬆⬇ 把步骤移到标记之间即可重复执行，循环可以嵌套", "11. \"Add loop\" inserts a pair of loop markers; drag steps between them by ☰ or move them with ⬆⬇ to repeat them, and loops can be nested"),
+    ("12. 在「全局快捷键」中设置的快捷键在其他窗口处于前台时同样有效，默认 F8 开始/停止自动点击", "12. Hotkeys set under \"Global hotkeys\" also work while other windows are in front; F8 starts/stops auto-click by default"),
+    ("13. 「添加其他步骤」可插入等待、只移动不点击、滚轮滚动、按键和注释步骤", "13. \"Add other step\" inserts waits, moves without clicking, wheel scrolls, key presses and comments"),
+    ("14. 「完成后」可在点击全部完成时锁定电脑、关闭显示器、关闭程序或运行命令", "14. \"When finished\" can lock the computer, turn off the display, close a program or run a command once all clicks complete"),
+    ("15. 开启「自适应间隔」后，每次点击后等反馈区域的像素变化再继续，最短和最长等待时间限制等待范围（仅 Windows）", "15. With \"Adaptive interval\" on, each click waits for the feedback region's pixels to change before continuing, within the minimum and maximum wait (Windows only)"),
+    ("16. 勾选「显示回放轨迹」后，执行期间屏幕上会显示最近的移动路径和点击波纹，逐渐淡出", "16. With \"Show the playback trail\" checked, the recent movement path and click ripples appear on screen during a run and fade out"),
+    ("17. 启用「托盘图标」后可以隐藏主窗口，通过托盘右键菜单开始、停止或显示窗口；也可以设为执行期间自动隐藏，结束后恢复（仅 Windows）", "17. With \"Tray icon\" enabled you can hide the main window and start, stop or show it from the tray menu; it can also hide automatically during runs and come back afterwards (Windows only)"),
+    ("18. 点击右上角「迷你模式」收起为置顶的小工具条，只显示开始/停止、目标坐标和剩余次数，按住空白处可拖动", "18. Click \"Mini mode\" at the top right to collapse into a small always-on-top toolbar showing only start/stop, the target and the remaining count; drag it by the empty area"),
+    ("19. 「从截图选取」会截取整个屏幕，直接在截图上点击目标位置即可设置坐标；捕捉坐标时光标旁会显示放大镜", "19. \"Pick from screenshot\" captures the whole screen so you can click the target directly on the screenshot; a magnifier follows the cursor while capturing coordinates"),
+    ("20. 鼠标中键或右键被其他程序占用时，把鼠标移到目标位置后按「记录鼠标位置」快捷键（默认 F9）即可记录坐标", "20. When another program intercepts the middle or right button, move the mouse to the target and press the \"Record mouse position\" hotkey (F9 by default) to record the coordinates"),
+    ("21. 设置「开始前倒计时」后，开始执行要等倒计时结束才点击，期间可以切换到目标窗口；状态栏显示当前处于空闲、捕捉坐标、倒计时、执行、暂停还是停止中", "21. With a countdown before start, clicking begins only when the countdown ends, leaving time to switch to the target window; the status bar shows whether the tool is idle, capturing, counting down, running, paused or stopping"),
+    ("22. 使用很快的点击间隔之前，可以先用「点击速度测试」测出本机实际能达到的点击速率和延迟", "22. Before using very short click intervals, run the \"Click speed test\" to measure the click rate and latency this computer can actually reach"),
+    ("23. 目标程序收不到模拟的点击或按键时，可以在「平台信息」里切换输入后端：Windows 上可选扫描码 SendInput，Linux 上可选 XTest 或 uinput（Wayland 会话中也有效）", "23. When the target program ignores the simulated clicks or keys, switch the input backend under \"Platform info\": SendInput scan codes on Windows, XTest or uinput on Linux (uinput also works in Wayland sessions)"),
+    ("24. 执行中需要切换到其他窗口时，在「目标窗口」中选择要点击的程序，开始执行时或每次点击前会先把它切回前台", "24. If you need other windows during a run, choose the program to click under \"Target window\"; it is brought back to the front when the run starts or before every click"),
+    ("25. 默认在执行期间阻止系统睡眠、关闭显示器和启动屏幕保护，长时间挂机不会被打断；不需要时可以在设置中关闭", "25. By default the system will not sleep, turn off the display or start the screensaver during a run, so long unattended runs are not interrupted; turn this off in the settings if you don't need it"),
+    ("26. 「防离开」每隔一段时间微动一下鼠标或按一下 F15，让聊天软件保持「在线」状态，可以限定只在每天的某个时间段内生效", "26. \"Anti-idle\" nudges the mouse or presses F15 at an interval so chat apps keep showing you as online; it can be limited to certain hours of the day"),
+    ("27. 勾选开始按钮旁的「模拟」后再开始，只会列出序列将要执行的每个动作和所需时间，不会真的移动鼠标或点击，适合先检查有风险的自动化", "27. Tick \"Simulate\" next to the start button before starting to list every action the sequence would perform and how long it would take, without actually moving the mouse or clicking; useful for checking risky automations first"),
+    ("28. 把插件（.dll、.dylib 或 .so）放进数据目录的 plugins 文件夹后重新启动，插件提供的步骤会出现在「添加其他步骤」菜单中，例如读取串口的数值或等待外部信号", "28. Put plugins (.dll, .dylib or .so) into the plugins folder of the data directory and restart; the steps they provide appear in the \"Add other step\" menu, for example reading a value from a serial port or waiting for an external signal"),
+    ("💡 提示：使用中键捕捉坐标可以避免与界面左键点击冲突", "💡 Tip: capturing with the middle button avoids clashing with left clicks on the UI"),
+    ("⚠️ 请谨慎使用，避免对系统造成不必要的影响", "⚠️ Use with care to avoid unintended effects on your system"),
+    ("✅ 跨平台纯Rust实现，支持Windows/macOS/Linux", "✅ Cross-platform pure Rust implementation for Windows/macOS/Linux"),
+    ("🧩 {}（触发）", "🧩 {} (trigger)"),
+    ("已加载插件 {}（{} 种步骤）", "Loaded plugin {} ({} step types)"),
+    ("⚠️ 无法加载插件 {}: {}", "⚠️ Cannot load plugin {}: {}"),
+    ("⚠️ 另一个实例的命令行参数有误: {}", "⚠️ Invalid command-line arguments from another instance: {}"),
+    ("已应用另一个实例转交的命令行参数", "Applied command-line arguments forwarded by another instance"),
+    ("⚠️ 确认退出", "⚠️ Confirm exit"),
+    ("自动点击正在执行，退出前会先停止执行", "Auto clicking is running and will be stopped before exiting"),
+    ("停止并退出", "Stop and exit"),
+    ("启用后其他程序和脚本可以通过 HTTP 请求或 WebSocket 查看状态、开始、停止和坐标点击，请求需要携带下面发放的令牌", "When enabled, other programs and scripts can view the status, start, stop and click at coordinates through HTTP requests or a WebSocket, which must carry a token issued below"),
+    ("启用 HTTP 控制接口", "Enable the HTTP control interface"),
+    ("端口:", "Port:"),
+    ("允许局域网内的其他电脑访问", "Allow access from other computers on the LAN"),
+    ("⚠️ 请求不加密，只在可信的网络中开启，并只把令牌交给可信的客户端", "⚠️ Requests are not encrypted; only enable this on trusted networks and only give tokens to trusted clients"),
+    ("正在监听:", "Listening on:"),
+    ("HTTP 控制接口已在 {} 上启动", "HTTP control interface started on {}"),
+    ("⚠️ 无法启动 HTTP 控制接口: {}", "⚠️ Cannot start the HTTP control interface: {}"),
+    ("已经在执行", "Already running"),
+    ("启用后 AutoHotkey、PowerShell 等本机脚本可以通过命名管道发送 status、start、stop、toggle、run-profile <名称> 命令，不需要令牌", "When enabled, local scripts such as AutoHotkey or PowerShell can send status, start, stop, toggle and run-profile <name> commands through a named pipe without a token"),
+    ("启用命名管道控制接口", "Enable the named-pipe control interface"),
+    ("管道名称:", "Pipe name:"),
+    ("⚠️ 无法启动命名管道控制接口: {}", "⚠️ Cannot start the named-pipe control interface: {}"),
+    ("找不到配置「{}」", "Profile \"{}\" not found"),
+    ("启用后 Stream Deck 等外部按键可以向本机端口发送「<令牌> toggle <配置名称>」这样的 UDP 数据报开始和停止配置，发送「<令牌> subscribe」订阅运行状态", "When enabled, external buttons such as a Stream Deck can start and stop profiles by sending UDP datagrams like \"<token> toggle <profile name>\" to a local port, and subscribe to the run state with \"<token> subscribe\""),
+    ("启用 UDP 控制接口", "Enable the UDP control interface"),
+    ("⚠️ 无法启动 UDP 控制接口: {}", "⚠️ Cannot start the UDP control interface: {}"),
+    ("启用后连接到 MQTT 代理，Home Assistant 等家庭自动化可以向 <主题>/command 发送 start、stop、toggle <配置名称> 等命令，运行状态发布到 <主题>/state", "When enabled, connects to an MQTT broker so home automation such as Home Assistant can send commands like start, stop or toggle <profile name> to <topic>/command; the run state is published to <topic>/state"),
+    ("启用 MQTT 控制接口", "Enable the MQTT control interface"),
+    ("代理地址:", "Broker host:"),
+    ("用户名:", "Username:"),
+    ("密码:", "Password:"),
+    ("主题前缀:", "Topic prefix:"),
+    ("⚠️ 连接不加密，能向代理发布消息的客户端都能控制本程序，请在代理上设置用户名和访问控制", "⚠️ The connection is not encrypted and any client that can publish to the broker can control this program; set up users and access control on the broker"),
+    ("连接状态:", "Connection:"),
+    ("⚠️ 无法启动 MQTT 控制接口: {}", "⚠️ Cannot start the MQTT control interface: {}"),
+    ("手柄按钮", "Gamepad buttons"),
+    ("MIDI 触发", "MIDI triggers"),
+    ("「{}」已绑定到 MIDI {}", "\"{}\" is now bound to MIDI {}"),
+    ("⚠️ MIDI 绑定的配置「{}」已不存在", "⚠️ The profile \"{}\" bound to MIDI no longer exists"),
+    ("打击垫、MIDI 控制器上的音符或 CC 按钮可以开始和停止自动点击、配置或点击一次", "Notes or CC buttons on pads and MIDI controllers can start and stop auto clicking or a profile, or click once"),
+    ("启用 MIDI 触发", "Enable MIDI triggers"),
+    ("重新连接设备", "Reconnect devices"),
+    ("没有检测到 MIDI 输入设备", "No MIDI input device detected"),
+    ("请按下控制器上的按键...", "Press a key on the controller..."),
+    ("学习", "Learn"),
+    ("点击后按下控制器上要绑定的按键", "Click, then press the key on the controller to bind"),
+    ("「{}」已绑定到手柄按钮 {}", "\"{}\" is now bound to gamepad button {}"),
+    ("⚠️ 无法读取手柄: {}", "⚠️ Cannot read gamepads: {}"),
+    ("没有检测到手柄", "No gamepad detected"),
+    ("已连接: {}", "Connected: {}"),
+    ("请按下手柄上的按钮...", "Press a button on the gamepad..."),
+    ("窗口不在前台时也能触发，适合键盘不在手边的场合；Windows 上只支持 Xbox 兼容手柄", "Works even when the window is in the background, handy when the keyboard is out of reach; on Windows only Xbox-compatible gamepads are supported"),
+    ("Webhook 通知", "Webhook notifications"),
+    ("执行开始、结束时向指定地址发送 JSON，可以直接填写 Slack 或 Discord 的 Webhook 地址", "Sends JSON to the given URL when a run starts or ends; Slack and Discord webhook URLs work directly"),
+    ("启用 Webhook 通知", "Enable webhook notifications"),
+    ("地址:", "URL:"),
+    ("发送测试", "Send test"),
+    ("正在发送测试通知，结果见事件日志", "Sending a test notification, see the event log for the result"),
+    ("发送时机:", "Send on:"),
+    ("完成", "Complete"),
+    ("出错或被条件中止", "Error or aborted by a condition"),
+    ("手动停止", "Manual stop"),
+    ("⚠️ 无法启用 Webhook 通知: {}", "⚠️ Cannot enable webhook notifications: {}"),
+    ("点击 ({}, {})", "Click at ({}, {})"),
+    // autostart.rs
+    ("无法写入注册表（错误码 {}）", "Cannot write to the registry (error code {})"),
+    ("无法删除注册表项（错误码 {}）", "Cannot delete the registry entry (error code {})"),
+    ("找不到用户目录", "Cannot find the home directory"),
+    ("无法写入 {}: {}", "Cannot write {}: {}"),
+    ("无法删除 {}: {}", "Cannot delete {}: {}"),
+    // benchmark.rs
+    ("无法移动鼠标", "Cannot move the mouse"),
+    ("测试目标已移动或不再显示", "The test target moved or is no longer shown"),
+    ("鼠标离开了测试目标", "The mouse left the test target"),
+    // bundle.rs
+    ("无法创建文件夹: {}", "Cannot create the folder: {}"),
+    ("找不到当前程序: {}", "Cannot find the running program: {}"),
+    ("复制程序失败: {}", "Failed to copy the program: {}"),
+    ("步骤 {}: 找不到模板图片 {}", "Step {}: template image {} not found"),
+    ("无法复制模板图片 {}: {}", "Cannot copy the template image {}: {}"),
+    // changelog.rs
+    ("平滑移动：逐步以绝对坐标移动到目标，不受系统指针速度和加速度影响", "Smooth movement: moves to the target step by step with absolute coordinates, unaffected by pointer speed and acceleration"),
+    ("A/B 两点交替点击，每个点可单独设置停留时间", "A/B alternating clicks, with a separate dwell time for each point"),
+    ("多步骤序列编辑器，支持打开/保存宏文件（JSON/TOML）和导入 AutoHotkey 脚本", "Multi-step sequence editor that opens/saves macro files (JSON/TOML) and imports AutoHotkey scripts"),
+    ("自动点击期间持续按住指定按键", "Hold a chosen key down during auto-click"),
+    ("把序列导出为 bash + xdotool 脚本", "Export sequences as bash + xdotool scripts"),
+    ("停止、退出或异常时自动释放本工具按下的所有按键和按钮", "Release every key and button pressed by this tool on stop, exit or errors"),
+    ("回放速度倍率、变量和计数器、嵌套循环，以及等待、移动、滚动、按键和注释步骤", "Playback speed multiplier, variables and counters, nested loops, and wait, move, scroll, key and comment steps"),
+    ("等待颜色、点击图片、点击所有匹配和等待图片步骤，可设置相似度、缩放和查找区域", "Wait for color, click image, click all matches and wait for image steps, with similarity, scaling and search region options"),
+    ("命名配置，可以导入导出；设置在两次启动之间保存，也可以用 --config 配置文件和命令行参数启动", "Named profiles that can be imported and exported; settings persist between launches and can come from a --config file or command-line flags"),
+    ("坐标书签、最近使用的坐标、从截图选取坐标、放大镜和快捷键捕捉坐标", "Coordinate bookmarks, recent coordinates, picking from a screenshot, a magnifier loupe and hotkey capture"),
+    ("执行结束后锁定电脑、关闭显示器、关闭程序或运行命令，并可发送通知和播放提示音", "After a run, lock the computer, turn off the display, close an app or run a command, with optional notifications and sounds"),
+    ("自适应点击间隔、电量和 CPU 保护，执行期间阻止系统睡眠，意外退出后可以从中断处继续", "Adaptive click interval, battery and CPU guards, keep-awake while running, and resuming after an unexpected exit"),
+    ("事件日志、点击间隔统计、点击热力图、回放轨迹和执行进度", "Event log, click timing statistics, click heatmap, playback trail and run progress"),
+    ("系统托盘、迷你工具条、深色/浅色主题和英文界面", "System tray, compact toolbar, dark/light themes and an English interface"),
+    ("多显示器选择、鼠标按钮校准、输入事件查看器，以及 SendInput、XTest、uinput 和 Interception 输入后端", "Display selection, mouse button calibration, an input event inspector, and SendInput, XTest, uinput and Interception input backends"),
+    ("执行前切换到目标窗口、防离开（鼠标微动）和模拟执行", "Focusing the target window before running, an anti-idle jiggler and dry-run simulation"),
+    ("插件提供的自定义步骤", "Custom steps provided by plugins"),
+    ("HTTP/WebSocket、命名管道、UDP 和 MQTT 控制接口，手柄和 MIDI 触发，以及 Webhook 通知", "HTTP/WebSocket, named pipe, UDP and MQTT control interfaces, gamepad and MIDI triggers, and webhook notifications"),
+    ("命令行程序 mousetool-cli，以及把序列导出为可以单独运行的运行包", "The mousetool-cli command-line program, and exporting a sequence as a standalone runner bundle"),
+    // control.rs
+    ("只读状态", "Read-only status"),
+    ("开始/停止", "Start/stop"),
+    ("完全控制", "Full control"),
+    ("查看状态", "View status"),
+    ("开始", "Start"),
+    ("停止", "Stop"),
+    ("坐标点击", "Click at coordinates"),
+    ("无法生成随机令牌: {}", "Cannot generate a random token: {}"),
+    ("找不到数据目录", "Cannot find the data directory"),
+    ("无法保存令牌: {}", "Cannot save tokens: {}"),
+    ("run-profile 缺少配置名称", "run-profile is missing the profile name"),
+    ("未知命令: {}", "Unknown command: {}"),
+    // coordinate_text.rs
+    ("「{}」不是 x,y 格式的坐标", "\"{}\" is not a coordinate in x,y form"),
+    ("「{}」不是有效的坐标值", "\"{}\" is not a valid coordinate value"),
+    // end_actions.rs
+    ("无", "None"),
+    ("锁定电脑", "Lock computer"),
+    ("关闭显示器", "Turn off display"),
+    ("关闭程序", "Close program"),
+    ("运行命令", "Run command"),
+    ("关闭程序 {}", "Close program {}"),
+    ("运行命令 {}", "Run command {}"),
+    ("没有填写要关闭的程序", "No program to close was entered"),
+    ("没有填写要运行的命令", "No command to run was entered"),
+    ("无法运行命令: {}", "Cannot run the command: {}"),
+    ("无法运行 {}: {}", "Cannot run {}: {}"),
+    ("{} 执行失败（{}）", "{} failed ({})"),
+    ("无法锁定电脑", "Cannot lock the computer"),
+    ("无法关闭显示器", "Cannot turn off the display"),
+    // gamepad.rs
+    ("方向键上", "D-pad up"),
+    ("方向键下", "D-pad down"),
+    ("方向键左", "D-pad left"),
+    ("方向键右", "D-pad right"),
+    // history.rs
+    ("单次点击", "Single click"),
+    ("单点自动点击", "Single-point auto-click"),
+    ("A/B 两点交替", "A/B alternating points"),
+    ("多步骤序列", "Multi-step sequence"),
+    ("平滑移动", "Smooth movement"),
+    ("按住按键", "Hold key"),
+    ("调整回放速度", "Playback speed adjustment"),
+    ("刚刚", "just now"),
+    ("{} 分钟前", "{} min ago"),
+    ("{} 小时前", "{} h ago"),
+    ("{} 天前", "{} days ago"),
+    ("{} 小时 {} 分", "{} h {} min"),
+    ("{} 分 {} 秒", "{} min {} s"),
+    ("{} 秒", "{} s"),
+    // hotkey.rs
+    ("开始/停止自动点击", "Start/stop auto-click"),
+    ("记录鼠标位置", "Record mouse position"),
+    ("取色并复制", "Pick color and copy"),
+    ("侧键 4（后退）", "Side button 4 (back)"),
+    ("侧键 5（前进）", "Side button 5 (forward)"),
+    // http_api.rs
+    ("无法监听端口 {}: {}", "Cannot listen on port {}: {}"),
+    ("令牌无效", "Invalid token"),
+    ("令牌的权限范围不包含「{}」", "The token's scope does not include \"{}\""),
+    ("HTTP 控制接口（{}）: {}", "HTTP control interface ({}): {}"),
+    ("界面已关闭", "The window has been closed"),
+    ("界面没有及时响应", "The window did not respond in time"),
+    ("正在执行，不能同时坐标点击", "A run is in progress; cannot click at coordinates at the same time"),
+    ("{}点击 ({}, {})", "{} click ({}, {})"),
+    ("无法解析命令: {}", "Cannot parse the command: {}"),
+    ("点击 ({}, {}) 失败: {}", "Click at ({}, {}) failed: {}"),
+    // input_hook.rs
+    ("钩子线程意外退出", "The hook thread exited unexpectedly"),
+    ("鼠标钩子已经安装", "The mouse hook is already installed"),
+    ("安装鼠标钩子失败: {}", "Failed to install the mouse hook: {}"),
+    ("无法监听鼠标事件，请在系统设置中允许「输入监控」权限", "Cannot listen to mouse events. Allow \"Input Monitoring\" in System Settings"),
+    ("X 服务器不支持 XInput2: {}", "The X server does not support XInput2: {}"),
+    // input_inspector.rs
+    ("按钮", "Button"),
+    ("未映射", "unmapped"),
+    ("索引{} {}（{}） @ ({}, {})", "Index {} {} ({}) @ ({}, {})"),
+    ("{} 按下", "{} down"),
+    ("{} 松开", "{} up"),
+    // locale.rs
+    ("西文（English 等）", "Latin (English, etc.)"),
+    // loupe.rs
+    ("放大镜仅支持 Windows", "The magnifier is only supported on Windows"),
+    // midi.rs
+    ("音符 {}{}（通道 {}）", "Note {}{} (channel {})"),
+    ("CC {}（通道 {}）", "CC {} (channel {})"),
+    ("开始/停止配置「{}」", "Start/stop profile \"{}\""),
+    ("无法初始化 MIDI: {}", "Cannot initialize MIDI: {}"),
+    ("未知设备", "Unknown device"),
+    // mqtt_control.rs
+    ("正在连接", "Connecting"),
+    ("已连接", "Connected"),
+    ("连接失败，稍后重试: {}", "Connection failed, retrying shortly: {}"),
+    ("代理地址不能为空", "The broker host cannot be empty"),
+    ("主题前缀不能为空，也不能包含通配符", "The topic prefix cannot be empty or contain wildcards"),
+    ("MQTT 控制接口: {}", "MQTT control interface: {}"),
+    // painting.rs
+    ("热力图", "Heatmap"),
+    ("散点", "Scatter"),
+    // pipe_control.rs
+    ("命名管道控制接口: {}", "Named-pipe control interface: {}"),
+    ("无法创建命名管道 {}: {}", "Cannot create the named pipe {}: {}"),
+    ("命名管道控制接口没有启动", "The named-pipe control interface did not start"),
+    ("命名管道控制接口只支持 Windows", "The named-pipe control interface is only supported on Windows"),
+    // profile.rs
+    ("配置名称不能为空", "Profile name cannot be empty"),
+    ("已经有名为「{}」的配置", "A profile named \"{}\" already exists"),
+    ("配置不存在", "Profile not found"),
+    ("至少需要保留一个配置", "At least one profile must be kept"),
+    ("配置文件格式错误: {}", "Invalid profile file: {}"),
+    ("配置文件由更新的程序（{}）导出，格式版本 {} 比当前程序支持的版本 {} 更新，请升级程序", "The profile was exported by a newer program ({}); format version {} is newer than the supported version {}, please update the program"),
+    ("配置中的设置有误: {}", "Invalid settings in profile: {}"),
+    // settings.rs
+    ("{} 的范围是 {} 到 {}，不能是 {}", "{} must be between {} and {}, not {}"),
+    ("click_count 至少为 1", "click_count must be at least 1"),
+    // tray.rs
+    ("托盘线程意外退出", "The tray thread exited unexpectedly"),
+    ("显示窗口", "Show window"),
+    ("退出", "Quit"),
+    ("无法创建托盘窗口", "Cannot create the tray window"),
+    ("无法添加托盘图标", "Cannot add the tray icon"),
+    ("当前平台暂不支持托盘图标", "Tray icons are not supported on this platform yet"),
+    // udp_control.rs
+    ("UDP 控制接口（{}）: {}", "UDP control interface ({}): {}"),
+    // webhook.rs
+    ("请填写 Webhook 地址", "Please enter a webhook URL"),
+    ("Webhook 地址必须以 http:// 或 https:// 开头", "The webhook URL must start with http:// or https://"),
+    ("自动点击已开始", "Auto clicking started"),
+    ("自动点击已停止，共点击 {} 次", "Auto clicking stopped after {} clicks"),
+    ("这是一条测试通知", "This is a test notification"),
+    ("Webhook 测试通知已发送", "Webhook test notification sent"),
+    ("后台任务已停止", "Background tasks have stopped"),
+    ("⚠️ Webhook 发送失败: {}", "⚠️ Webhook delivery failed: {}"),
+];
@@ -0,0 +1,346 @@
+// 系统托盘图标
+//
+// 托盘图标的颜色表示当前状态（灰色空闲、绿色执行中、橙色暂停），右键菜单可以开始、停止、
+// 显示窗口和退出，主窗口隐藏后仍能控制。托盘运行在单独的线程中，菜单命令通过通道交给界面线程，
+// 同时唤醒界面，窗口隐藏时也能及时处理。
+//
+// 目前只支持 Windows。
+
+use std::sync::mpsc::{self, Receiver};
+
+use eframe::egui;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayState {
+    Idle,
+    Running,
+    /// 执行中，但被电量或 CPU 保护暂停
+    Paused,
+}
+
+// 其他平台上没有托盘菜单发送这些命令
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayCommand {
+    Start,
+    Stop,
+    /// 显示并激活主窗口
+    Show,
+    Quit,
+}
+
+pub const UNSUPPORTED_MESSAGE: &str = "当前平台暂不支持托盘图标";
+
+pub struct Tray {
+    handle: platform::Handle,
+    commands: Receiver<TrayCommand>,
+    state: TrayState,
+}
+
+impl Tray {
+    pub fn is_supported() -> bool {
+        cfg!(target_os = "windows")
+    }
+
+    pub fn new(ctx: &egui::Context) -> Result<Self, String> {
+        let (sender, commands) = mpsc::channel();
+        let handle = platform::create(sender, ctx.clone())?;
+        Ok(Self { handle, commands, state: TrayState::Idle })
+    }
+
+    /// 更新图标和提示文字，状态没有变化时不做任何事
+    pub fn set_state(&mut self, state: TrayState) {
+        if state != self.state {
+            self.state = state;
+            self.handle.set_state(state);
+        }
+    }
+
+    /// 取出一个待处理的菜单命令
+    pub fn try_recv(&self) -> Option<TrayCommand> {
+        self.commands.try_recv().ok()
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{TrayCommand, TrayState};
+    use crate::i18n::t;
+    use eframe::egui;
+    use std::cell::RefCell;
+    use std::sync::mpsc::{self, Sender};
+    use windows_sys::Win32::Foundation::{HINSTANCE, HWND, LPARAM, LRESULT, POINT, WPARAM};
+    use windows_sys::Win32::System::LibraryLoader::GetModuleHandleW;
+    use windows_sys::Win32::UI::Shell::{
+        NIF_ICON, NIF_MESSAGE, NIF_TIP, NIM_ADD, NIM_DELETE, NIM_MODIFY, NOTIFY_ICON_MESSAGE, NOTIFYICONDATAW,
+        Shell_NotifyIconW,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        AppendMenuW, CreateIcon, CreatePopupMenu, CreateWindowExW, DefWindowProcW, DestroyIcon, DestroyMenu,
+        DestroyWindow, DispatchMessageW, GetCursorPos, GetMessageW, HICON, MF_GRAYED, MF_SEPARATOR, MF_STRING, MSG,
+        PostMessageW, PostQuitMessage, RegisterClassW, RegisterWindowMessageW, SetForegroundWindow, TPM_NONOTIFY,
+        TPM_RETURNCMD, TPM_RIGHTBUTTON, TrackPopupMenu, TranslateMessage, WM_APP, WM_CLOSE, WM_CONTEXTMENU, WM_DESTROY,
+        WM_LBUTTONDBLCLK, WM_NULL, WM_RBUTTONUP, WNDCLASSW,
+    };
+
+    // 托盘图标的鼠标事件
+    const WM_TRAY: u32 = WM_APP + 1;
+    // 界面线程通知状态变化，wparam 为 `TrayState` 的序号
+    const WM_SET_STATE: u32 = WM_APP + 2;
+
+    const MENU_START: i32 = 1;
+    const MENU_STOP: i32 = 2;
+    const MENU_SHOW: i32 = 3;
+    const MENU_QUIT: i32 = 4;
+
+    const STATES: [TrayState; 3] = [TrayState::Idle, TrayState::Running, TrayState::Paused];
+
+    fn wide(text: &str) -> Vec<u16> {
+        text.encode_utf16().chain(Some(0)).collect()
+    }
+
+    fn state_label(state: TrayState) -> &'static str {
+        match state {
+            TrayState::Idle => t!("空闲"),
+            TrayState::Running => t!("正在执行"),
+            TrayState::Paused => t!("已暂停"),
+        }
+    }
+
+    // 托盘线程中窗口过程需要用到的数据
+    struct Window {
+        sender: Sender<TrayCommand>,
+        ctx: egui::Context,
+        state: TrayState,
+        icons: [HICON; 3],
+        // 资源管理器重启后广播的消息，收到后需要重新添加图标
+        taskbar_created: u32,
+    }
+
+    thread_local! {
+        static WINDOW: RefCell<Option<Window>> = const { RefCell::new(None) };
+    }
+
+    pub struct Handle {
+        // HWND 是裸指针，不能跨线程传递，保存为整数
+        hwnd: usize,
+    }
+
+    impl Handle {
+        pub fn set_state(&self, state: TrayState) {
+            let index = STATES.iter().position(|s| *s == state).unwrap_or(0);
+            unsafe { PostMessageW(self.hwnd as HWND, WM_SET_STATE, index, 0) };
+        }
+    }
+
+    impl Drop for Handle {
+        fn drop(&mut self) {
+            // 窗口销毁时删除图标并结束托盘线程
+            unsafe { PostMessageW(self.hwnd as HWND, WM_CLOSE, 0, 0) };
+        }
+    }
+
+    pub fn create(sender: Sender<TrayCommand>, ctx: egui::Context) -> Result<Handle, String> {
+        let (ready, created) = mpsc::channel();
+        std::thread::spawn(move || run(sender, ctx, ready));
+        let hwnd = created.recv().map_err(|_| t!("托盘线程意外退出").to_string())??;
+        Ok(Handle { hwnd })
+    }
+
+    // 16×16 的圆点图标
+    fn create_icon(instance: HINSTANCE, (r, g, b): (u8, u8, u8)) -> HICON {
+        const SIZE: usize = 16;
+        let mut color = Vec::with_capacity(SIZE * SIZE * 4);
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                let (dx, dy) = (x as f32 + 0.5 - 8.0, y as f32 + 0.5 - 8.0);
+                let alpha = (7.5 - (dx * dx + dy * dy).sqrt()).clamp(0.0, 1.0);
+                color.extend([b, g, r, (alpha * 255.0) as u8]);
+            }
+        }
+        // 带透明通道时掩码全部为 0
+        let mask = [0u8; SIZE * SIZE / 8];
+        unsafe { CreateIcon(instance, SIZE as i32, SIZE as i32, 1, 32, mask.as_ptr(), color.as_ptr()) }
+    }
+
+    fn notify(hwnd: HWND, message: NOTIFY_ICON_MESSAGE) -> bool {
+        let Some((state, icon)) = WINDOW.with(|window| {
+            window.borrow().as_ref().map(|window| {
+                let index = STATES.iter().position(|s| *s == window.state).unwrap_or(0);
+                (window.state, window.icons[index])
+            })
+        }) else {
+            return false;
+        };
+        let mut data: NOTIFYICONDATAW = unsafe { std::mem::zeroed() };
+        data.cbSize = std::mem::size_of::<NOTIFYICONDATAW>() as u32;
+        data.hWnd = hwnd;
+        data.uID = 1;
+        data.uFlags = NIF_ICON | NIF_MESSAGE | NIF_TIP;
+        data.uCallbackMessage = WM_TRAY;
+        data.hIcon = icon;
+        let tip: Vec<u16> = format!("{} - {}", crate::paths::APP_NAME, state_label(state)).encode_utf16().collect();
+        let len = tip.len().min(data.szTip.len() - 1);
+        data.szTip[..len].copy_from_slice(&tip[..len]);
+        unsafe { Shell_NotifyIconW(message, &data) != 0 }
+    }
+
+    fn send(command: TrayCommand) {
+        WINDOW.with(|window| {
+            if let Some(window) = window.borrow().as_ref() {
+                let _ = window.sender.send(command);
+                window.ctx.request_repaint();
+            }
+        });
+    }
+
+    fn show_menu(hwnd: HWND) {
+        let running = WINDOW.with(|window| window.borrow().as_ref().is_some_and(|w| w.state != TrayState::Idle));
+        let item = |menu, enabled: bool, id: i32, text: &str| {
+            let text = wide(text);
+            let flags = if enabled { MF_STRING } else { MF_STRING | MF_GRAYED };
+            unsafe { AppendMenuW(menu, flags, id as usize, text.as_ptr()) };
+        };
+        let selected = unsafe {
+            let menu = CreatePopupMenu();
+            item(menu, !running, MENU_START, t!("开始"));
+            item(menu, running, MENU_STOP, t!("停止"));
+            AppendMenuW(menu, MF_SEPARATOR, 0, std::ptr::null());
+            item(menu, true, MENU_SHOW, t!("显示窗口"));
+            item(menu, true, MENU_QUIT, t!("退出"));
+
+            let mut point = POINT { x: 0, y: 0 };
+            GetCursorPos(&mut point);
+            // 不先激活窗口的话，点击菜单外部时菜单不会关闭
+            SetForegroundWindow(hwnd);
+            let selected = TrackPopupMenu(
+                menu,
+                TPM_RETURNCMD | TPM_NONOTIFY | TPM_RIGHTBUTTON,
+                point.x,
+                point.y,
+                0,
+                hwnd,
+                std::ptr::null(),
+            );
+            PostMessageW(hwnd, WM_NULL, 0, 0);
+            DestroyMenu(menu);
+            selected
+        };
+        let command = match selected {
+            MENU_START => TrayCommand::Start,
+            MENU_STOP => TrayCommand::Stop,
+            MENU_SHOW => TrayCommand::Show,
+            MENU_QUIT => TrayCommand::Quit,
+            _ => return,
+        };
+        send(command);
+    }
+
+    unsafe extern "system" fn window_proc(hwnd: HWND, message: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        match message {
+            WM_TRAY => match lparam as u32 {
+                WM_LBUTTONDBLCLK => send(TrayCommand::Show),
+                WM_RBUTTONUP | WM_CONTEXTMENU => show_menu(hwnd),
+                _ => {}
+            },
+            WM_SET_STATE => {
+                WINDOW.with(|window| {
+                    if let Some(window) = window.borrow_mut().as_mut() {
+                        window.state = STATES.get(wparam).copied().unwrap_or(TrayState::Idle);
+                    }
+                });
+                notify(hwnd, NIM_MODIFY);
+            }
+            WM_DESTROY => {
+                notify(hwnd, NIM_DELETE);
+                unsafe { PostQuitMessage(0) };
+            }
+            _ if WINDOW.with(|window| window.borrow().as_ref().is_some_and(|w| w.taskbar_created == message)) => {
+                notify(hwnd, NIM_ADD);
+            }
+            _ => return unsafe { DefWindowProcW(hwnd, message, wparam, lparam) },
+        }
+        0
+    }
+
+    fn run(sender: Sender<TrayCommand>, ctx: egui::Context, ready: Sender<Result<usize, String>>) {
+        unsafe {
+            let instance = GetModuleHandleW(std::ptr::null());
+            let class_name = wide("mouseTOOL_tray");
+            let class = WNDCLASSW {
+                lpfnWndProc: Some(window_proc),
+                hInstance: instance,
+                lpszClassName: class_name.as_ptr(),
+                ..std::mem::zeroed()
+            };
+            // 重新启用托盘时类已经注册过，忽略失败
+            RegisterClassW(&class);
+            // 不可见的普通窗口，只用来接收托盘消息
+            let hwnd = CreateWindowExW(
+                0,
+                class_name.as_ptr(),
+                std::ptr::null(),
+                0,
+                0,
+                0,
+                0,
+                0,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                instance,
+                std::ptr::null(),
+            );
+            if hwnd.is_null() {
+                let _ = ready.send(Err(t!("无法创建托盘窗口").to_string()));
+                return;
+            }
+
+            let icons = [
+                create_icon(instance, (128, 128, 128)),
+                create_icon(instance, (46, 160, 67)),
+                create_icon(instance, (230, 150, 30)),
+            ];
+            let taskbar_created = RegisterWindowMessageW(wide("TaskbarCreated").as_ptr());
+            WINDOW.with(|window| {
+                *window.borrow_mut() = Some(Window { sender, ctx, state: TrayState::Idle, icons, taskbar_created })
+            });
+
+            if notify(hwnd, NIM_ADD) {
+                let _ = ready.send(Ok(hwnd as usize));
+                let mut message: MSG = std::mem::zeroed();
+                while GetMessageW(&mut message, std::ptr::null_mut(), 0, 0) > 0 {
+                    TranslateMessage(&message);
+                    DispatchMessageW(&message);
+                }
+            } else {
+                DestroyWindow(hwnd);
+                let _ = ready.send(Err(t!("无法添加托盘图标").to_string()));
+            }
+
+            if let Some(window) = WINDOW.with(|window| window.borrow_mut().take()) {
+                for icon in window.icons {
+                    DestroyIcon(icon);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    use super::{TrayCommand, TrayState, UNSUPPORTED_MESSAGE};
+    use crate::i18n;
+    use eframe::egui;
+    use std::sync::mpsc::Sender;
+
+    pub struct Handle;
+
+    impl Handle {
+        pub fn set_state(&self, _state: TrayState) {}
+    }
+
+    pub fn create(_sender: Sender<TrayCommand>, _ctx: egui::Context) -> Result<Handle, String> {
+        Err(i18n::tr(UNSUPPORTED_MESSAGE).to_string())
+    }
+}
@@ -0,0 +1,211 @@
+// UDP 控制接口
+//
+// 给 Stream Deck 这类外部按键使用：按键插件向本机端口发送一个 UDP 数据报
+// `<令牌> <命令>`，命令与命名管道相同（见 `control::TextCommand`），例如
+//
+//   3f9a… toggle 签到      执行中时停止，否则切换到配置「签到」后开始
+//   3f9a… stop
+//
+// 每个数据报回复一个 JSON 数据报：{"ok": true}、{"error": "原因"}，status 回复
+// {"state": "running", "total_clicks": 42}。令牌和权限范围与 HTTP 控制接口相同。
+//
+// 按键上要显示运行状态时发送 `<令牌> subscribe`：回复当前状态，之后运行状态每次变化都向
+// 发送方推送 {"type": "state", "state": "idle"}。订阅在 `SUBSCRIPTION_TTL` 后失效，
+// 插件需要定期重新订阅，退出后不会一直收到推送。
+//
+// 只监听本机回环地址。和 `http_api` 一样作为后台任务运行在 `tasks` 的运行时上，
+// 开始和停止交给界面线程处理。
+
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use mousetool_core::app_state::SharedState;
+use mousetool_core::events::{self, Event};
+use mousetool_core::i18n::t;
+use mousetool_core::tasks;
+
+use crate::control::{self, Command, Denied, Pending, SharedTokens, TextCommand};
+use crate::event_log::{self, SharedLog};
+
+pub const DEFAULT_PORT: u16 = 8766;
+// 订阅运行状态的有效期
+const SUBSCRIPTION_TTL: Duration = Duration::from_secs(60);
+// 命令数据报的最大长度，超出部分被截断
+const MAX_DATAGRAM: usize = 1024;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UdpSettings {
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for UdpSettings {
+    fn default() -> Self {
+        Self { enabled: false, port: DEFAULT_PORT }
+    }
+}
+
+/// 服务器读取的共享状态
+pub struct Shared {
+    pub tokens: SharedTokens,
+    pub state: SharedState,
+    pub total_clicks: Arc<Mutex<u32>>,
+    pub log: SharedLog,
+}
+
+// 命令成功后的回复
+enum Reply {
+    Ok,
+    /// 回复当前状态
+    Status,
+}
+
+struct Udp {
+    shared: Shared,
+    requests: Sender<Pending>,
+    ctx: egui::Context,
+    /// 订阅运行状态的客户端和订阅时间
+    subscribers: Mutex<HashMap<SocketAddr, Instant>>,
+}
+
+/// 正在运行的服务器，析构时停止
+pub struct Server {
+    address: SocketAddr,
+    requests: Receiver<Pending>,
+    _task: tasks::Task,
+}
+
+impl Server {
+    pub fn start(settings: &UdpSettings, shared: Shared, ctx: &egui::Context) -> Result<Self, String> {
+        // 在这里同步绑定端口，端口被占用时能立即告诉界面
+        let socket = std::net::UdpSocket::bind((Ipv4Addr::LOCALHOST, settings.port))
+            .and_then(|socket| socket.set_nonblocking(true).map(|_| socket))
+            .map_err(|e| t!("无法监听端口 {}: {}", settings.port, e))?;
+        let address = socket.local_addr().map_err(|e| e.to_string())?;
+
+        let (sender, requests) = mpsc::channel();
+        let udp = Arc::new(Udp { shared, requests: sender, ctx: ctx.clone(), subscribers: Mutex::new(HashMap::new()) });
+        let task = tasks::spawn(move |token| serve(socket, udp, token));
+        Ok(Self { address, requests, _task: task })
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// 取出一个等待界面线程处理的请求
+    pub fn try_recv(&self) -> Option<Pending> {
+        self.requests.try_recv().ok()
+    }
+}
+
+async fn serve(socket: std::net::UdpSocket, udp: Arc<Udp>, token: tasks::CancellationToken) {
+    let socket = match tokio::net::UdpSocket::from_std(socket) {
+        Ok(socket) => Arc::new(socket),
+        Err(e) => {
+            tracing::error!(error = %e, "UDP 控制接口无法接收数据");
+            return;
+        }
+    };
+    tracing::info!(address = ?socket.local_addr().ok(), "UDP 控制接口已启动");
+    let mut events = events::subscribe();
+    let mut buffer = [0u8; MAX_DATAGRAM];
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+            event = events.recv() => match event {
+                Ok(Event::State { state }) => {
+                    let subscribers: Vec<SocketAddr> = {
+                        let mut subscribers = udp.subscribers.lock().unwrap();
+                        subscribers.retain(|_, subscribed| subscribed.elapsed() < SUBSCRIPTION_TTL);
+                        subscribers.keys().copied().collect()
+                    };
+                    let message = serde_json::json!({ "type": "state", "state": state }).to_string();
+                    for address in subscribers {
+                        let _ = socket.send_to(message.as_bytes(), address).await;
+                    }
+                }
+                // 积压太多时跳过丢失的事件，下一次状态变化会再推送
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            received = socket.recv_from(&mut buffer) => {
+                // Windows 上之前发送的数据报无人接收时这里会返回错误，忽略即可
+                let Ok((length, from)) = received else {
+                    continue;
+                };
+                let datagram = String::from_utf8_lossy(&buffer[..length]).into_owned();
+                // 等待界面处理时不能阻塞接收，否则其他客户端的命令和状态推送都要排队
+                tokio::spawn(udp.clone().reply(socket.clone(), datagram, from));
+            }
+        }
+    }
+    tracing::info!("UDP 控制接口已停止");
+}
+
+impl Udp {
+    // 执行一个数据报中的命令并把结果回复给发送方
+    async fn reply(self: Arc<Self>, socket: Arc<tokio::net::UdpSocket>, datagram: String, from: SocketAddr) {
+        let reply = self.execute(&datagram, from).await;
+        let _ = socket.send_to(reply.to_string().as_bytes(), from).await;
+    }
+
+    // 执行一个数据报中的命令，返回回复的 JSON
+    async fn execute(&self, datagram: &str, from: SocketAddr) -> serde_json::Value {
+        let datagram = datagram.trim();
+        let (token, line) = datagram.split_once(char::is_whitespace).unwrap_or((datagram, ""));
+        let line = line.trim();
+        let result = if line == "subscribe" {
+            self.authorize(token, Command::Status).map(|_| {
+                self.subscribers.lock().unwrap().insert(from, Instant::now());
+                Reply::Status
+            })
+        } else {
+            match TextCommand::parse(line) {
+                Ok(command) => match self.authorize(token, command.command()) {
+                    Ok(client) => self.run(&client, line, command).await,
+                    Err(e) => Err(e),
+                },
+                Err(e) => Err(e),
+            }
+        };
+        match result {
+            Ok(Reply::Ok) => serde_json::json!({ "ok": true }),
+            // 查询和订阅都回复当前状态
+            Ok(Reply::Status) => serde_json::json!({
+                "state": self.shared.state.get(),
+                "total_clicks": *self.shared.total_clicks.lock().unwrap(),
+            }),
+            Err(message) => serde_json::json!({ "error": message }),
+        }
+    }
+
+    // 校验令牌，通过时返回令牌的名称，用于记录日志
+    fn authorize(&self, token: &str, command: Command) -> Result<String, String> {
+        let tokens = self.shared.tokens.lock().unwrap();
+        match tokens.authorize(token, command) {
+            Ok(token) => Ok(token.name.clone()),
+            Err(Denied::Unauthorized) => Err(t!("令牌无效").to_string()),
+            Err(Denied::Forbidden) => Err(t!("令牌的权限范围不包含「{}」", command.label())),
+        }
+    }
+
+    // 执行通过校验的命令
+    async fn run(&self, client: &str, line: &str, command: TextCommand) -> Result<Reply, String> {
+        let request = match command {
+            TextCommand::Status => return Ok(Reply::Status),
+            TextCommand::Request(request) => request,
+        };
+        let client = if client.is_empty() { t!("（未命名）") } else { client };
+        self.shared.log.lock().unwrap().push(event_log::EntryKind::Status, t!("UDP 控制接口（{}）: {}", client, line));
+        control::forward(&self.requests, &self.ctx, request).await.map(|_| Reply::Ok).map_err(String::from)
+    }
+}
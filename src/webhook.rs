@@ -0,0 +1,187 @@
+// Webhook 通知
+//
+// 执行开始、完成、中止（出错或被条件停止）和手动停止时向设置的 URL 发送 POST 请求，内容如
+//
+//   {"event": "run_completed", "text": "自动点击完成，共点击 120 次", "content": "…",
+//    "clicks": 120, "errors": 0, "reason": null, "timestamp": 1760000000}
+//
+// event 为 run_started、run_completed、run_aborted 或 run_stopped。text 和 content 是同一段说明，
+// 分别是 Slack 和 Discord 的 incoming webhook 显示的字段，两者的地址都可以直接填进来。
+//
+// 监听 `events` 中的事件，作为后台任务运行在 `tasks` 的运行时上，请求在阻塞线程中发送，
+// 失败时记录到事件日志，不影响执行。
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use mousetool_core::app_state::AppState;
+use mousetool_core::events::{self, Event};
+use mousetool_core::i18n::t;
+use mousetool_core::tasks;
+
+use crate::event_log::{self, SharedLog};
+
+// 单个请求的最长耗时，包括连接和等待响应
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhookSettings {
+    pub enabled: bool,
+    pub url: String,
+    pub on_start: bool,
+    pub on_complete: bool,
+    /// 出错或被条件中止
+    pub on_abort: bool,
+    /// 手动、快捷键或控制接口停止
+    pub on_stop: bool,
+}
+
+impl Default for WebhookSettings {
+    fn default() -> Self {
+        Self { enabled: false, url: String::new(), on_start: false, on_complete: true, on_abort: true, on_stop: false }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum RunEvent {
+    RunStarted,
+    RunCompleted,
+    RunAborted,
+    RunStopped,
+    /// 界面上的「发送测试」
+    Test,
+}
+
+#[derive(Debug, Serialize)]
+struct Payload {
+    event: RunEvent,
+    text: String,
+    content: String,
+    clicks: Option<u32>,
+    errors: Option<u32>,
+    reason: Option<String>,
+    timestamp: u64,
+}
+
+impl Payload {
+    fn new(event: RunEvent, text: String) -> Self {
+        Self {
+            event,
+            content: text.clone(),
+            text,
+            clicks: None,
+            errors: None,
+            reason: None,
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+        }
+    }
+}
+
+impl WebhookSettings {
+    fn wants(&self, event: RunEvent) -> bool {
+        match event {
+            RunEvent::RunStarted => self.on_start,
+            RunEvent::RunCompleted => self.on_complete,
+            RunEvent::RunAborted => self.on_abort,
+            RunEvent::RunStopped => self.on_stop,
+            RunEvent::Test => true,
+        }
+    }
+}
+
+/// 检查 URL，发送失败时才报告的错误尽量在设置时就提示
+pub fn validate(url: &str) -> Result<(), String> {
+    let url = url.trim();
+    if url.is_empty() {
+        return Err(t!("请填写 Webhook 地址").to_string());
+    }
+    if !url.starts_with("https://") && !url.starts_with("http://") {
+        return Err(t!("Webhook 地址必须以 http:// 或 https:// 开头").to_string());
+    }
+    Ok(())
+}
+
+/// 按设置监听执行事件并发送通知，析构时停止
+pub fn start(settings: WebhookSettings, log: SharedLog) -> Result<tasks::Task, String> {
+    validate(&settings.url)?;
+    Ok(tasks::spawn(move |token| async move {
+        let mut events = events::subscribe();
+        // 暂停后恢复也会进入执行中，只有从空闲或倒计时进入时才算开始
+        let mut previous = AppState::Idle;
+        loop {
+            let event = tokio::select! {
+                _ = token.cancelled() => break,
+                event = events.recv() => match event {
+                    Ok(event) => event,
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+            };
+            let payload = match event {
+                Event::State { state } => {
+                    let started = state == AppState::Running && !matches!(previous, AppState::Running | AppState::Paused);
+                    previous = state;
+                    if !started {
+                        continue;
+                    }
+                    Payload::new(RunEvent::RunStarted, t!("自动点击已开始").to_string())
+                }
+                Event::RunFinished { clicks, errors, stopped, aborted } => {
+                    let mut payload = match (&aborted, stopped) {
+                        (Some(reason), _) => Payload::new(RunEvent::RunAborted, t!("自动点击已中止（{}），共点击 {} 次", reason, clicks)),
+                        (None, true) => Payload::new(RunEvent::RunStopped, t!("自动点击已停止，共点击 {} 次", clicks)),
+                        (None, false) => Payload::new(RunEvent::RunCompleted, t!("自动点击完成，共点击 {} 次", clicks)),
+                    };
+                    payload.clicks = Some(clicks);
+                    payload.errors = Some(errors);
+                    payload.reason = aborted;
+                    payload
+                }
+                _ => continue,
+            };
+            if settings.wants(payload.event) {
+                send(&settings.url, payload, &log).await;
+            }
+        }
+    }))
+}
+
+/// 发送一条测试通知，结果记录到事件日志
+pub fn send_test(url: &str, log: SharedLog) -> Result<(), String> {
+    validate(url)?;
+    let url = url.to_string();
+    // 任务不理会取消，丢弃句柄后照样发送完
+    let _ = tasks::spawn(move |_| async move {
+        let payload = Payload::new(RunEvent::Test, t!("这是一条测试通知").to_string());
+        if send(&url, payload, &log).await {
+            log.lock().unwrap().push(event_log::EntryKind::Status, t!("Webhook 测试通知已发送").to_string());
+        }
+    });
+    Ok(())
+}
+
+// 发送成功时返回 true
+async fn send(url: &str, payload: Payload, log: &SharedLog) -> bool {
+    let url = url.trim().to_string();
+    let result = tasks::blocking(move || {
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .timeout_global(Some(REQUEST_TIMEOUT))
+            .tls_config(ureq::tls::TlsConfig::builder().provider(ureq::tls::TlsProvider::NativeTls).build())
+            .build()
+            .into();
+        agent.post(&url).send_json(&payload).map(|_| ())
+    })
+    .await;
+    let error = match result {
+        Some(Ok(())) => return true,
+        Some(Err(e)) => e.to_string(),
+        None => t!("后台任务已停止").to_string(),
+    };
+    tracing::warn!(error = %error, "Webhook 发送失败");
+    log.lock().unwrap().push(event_log::EntryKind::Status, t!("⚠️ Webhook 发送失败: {}", error));
+    false
+}